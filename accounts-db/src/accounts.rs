@@ -852,6 +852,7 @@ mod tests {
                 inner_instructions: None,
                 durable_nonce_fee: nonce.map(DurableNonceFee::from),
                 return_data: None,
+                invocation_trace: None,
                 executed_units: 0,
                 accounts_data_len_delta: 0,
             },
@@ -6419,23 +6419,30 @@ impl AccountsDb {
     where
         P: Iterator<Item = u64>,
     {
-        txn_iter
-            .enumerate()
-            .map(|(i, txn)| {
-                let account = accounts_and_meta_to_store
+        let txns: Vec<_> = txn_iter.collect();
+        let accounts: Vec<_> = (0..txns.len())
+            .map(|i| {
+                accounts_and_meta_to_store
                     .account_default_if_zero_lamport(i)
                     .map(|account| account.to_account_shared_data())
-                    .unwrap_or_default();
-                let account_info = AccountInfo::new(StorageLocation::Cached, account.lamports());
-
-                self.notify_account_at_accounts_update(
-                    slot,
-                    &account,
-                    txn,
-                    accounts_and_meta_to_store.pubkey(i),
-                    &mut write_version_producer,
-                );
+                    .unwrap_or_default()
+            })
+            .collect();
+        self.notify_accounts_at_accounts_update(
+            slot,
+            accounts
+                .iter()
+                .zip(txns)
+                .enumerate()
+                .map(|(i, (account, txn))| (accounts_and_meta_to_store.pubkey(i), account, txn)),
+            &mut write_version_producer,
+        );
 
+        accounts
+            .into_iter()
+            .enumerate()
+            .map(|(i, account)| {
+                let account_info = AccountInfo::new(StorageLocation::Cached, account.lamports());
                 let cached_account =
                     self.accounts_cache
                         .store(slot, accounts_and_meta_to_store.pubkey(i), account);
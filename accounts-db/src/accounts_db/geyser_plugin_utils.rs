@@ -2,6 +2,7 @@ use {
     crate::{
         account_storage::meta::{StoredAccountMeta, StoredMeta},
         accounts_db::AccountsDb,
+        accounts_update_notifier_interface::AccountUpdate,
     },
     solana_measure::measure::Measure,
     solana_metrics::*,
@@ -60,24 +61,31 @@ impl AccountsDb {
         notify_stats.report();
     }
 
-    pub fn notify_account_at_accounts_update<P>(
+    /// Notifies the accounts of one store, in a single call to the notifier
+    pub fn notify_accounts_at_accounts_update<'a, P>(
         &self,
         slot: Slot,
-        account: &AccountSharedData,
-        txn: &Option<&SanitizedTransaction>,
-        pubkey: &Pubkey,
+        accounts: impl Iterator<
+            Item = (
+                &'a Pubkey,
+                &'a AccountSharedData,
+                &'a Option<&'a SanitizedTransaction>,
+            ),
+        >,
         write_version_producer: &mut P,
     ) where
         P: Iterator<Item = u64>,
     {
         if let Some(accounts_update_notifier) = &self.accounts_update_notifier {
-            accounts_update_notifier.notify_account_update(
-                slot,
-                account,
-                txn,
-                pubkey,
-                write_version_producer.next().unwrap(),
-            );
+            let updates: Vec<_> = accounts
+                .map(|(pubkey, account, txn)| AccountUpdate {
+                    pubkey,
+                    account,
+                    txn,
+                    write_version: write_version_producer.next().unwrap(),
+                })
+                .collect();
+            accounts_update_notifier.notify_account_updates(slot, &updates);
         }
     }
 
@@ -116,35 +124,40 @@ impl AccountsDb {
         &self,
         slot: Slot,
         notified_accounts: &mut HashSet<Pubkey>,
-        mut accounts_to_stream: HashMap<Pubkey, StoredAccountMeta>,
+        accounts_to_stream: HashMap<Pubkey, StoredAccountMeta>,
         notify_stats: &mut GeyserPluginNotifyAtSnapshotRestoreStats,
     ) {
         let notifier = self.accounts_update_notifier.as_ref().unwrap();
         let mut measure_notify = Measure::start("accountsdb-plugin-notifying-accounts");
         let local_write_version = 0;
-        for (_, mut account) in accounts_to_stream.drain() {
-            // We do not need to rely on the specific write_version read from the append vec.
-            // So, overwrite the write_version with something that works.
-            // 'accounts_to_stream' is already a hashmap, so there is already only entry per pubkey.
-            // write_version is only used to order multiple entries with the same pubkey, so it doesn't matter what value it gets here.
-            // Passing 0 for everyone's write_version is sufficiently correct.
-            let meta = StoredMeta {
+        let mut accounts: Vec<_> = accounts_to_stream.into_values().collect();
+        // We do not need to rely on the specific write_version read from the append vec.
+        // So, overwrite the write_version with something that works.
+        // 'accounts_to_stream' is already a hashmap, so there is already only entry per pubkey.
+        // write_version is only used to order multiple entries with the same pubkey, so it doesn't matter what value it gets here.
+        // Passing 0 for everyone's write_version is sufficiently correct.
+        let metas: Vec<_> = accounts
+            .iter()
+            .map(|account| StoredMeta {
                 write_version_obsolete: local_write_version,
                 ..*account.meta()
-            };
-            account.set_meta(&meta);
-            let mut measure_pure_notify = Measure::start("accountsdb-plugin-notifying-accounts");
-            notifier.notify_account_restore_from_snapshot(slot, &account);
-            measure_pure_notify.stop();
-
-            notify_stats.total_pure_notify += measure_pure_notify.as_us() as usize;
-
-            let mut measure_bookkeep = Measure::start("accountsdb-plugin-notifying-bookeeeping");
-            notified_accounts.insert(*account.pubkey());
-            measure_bookkeep.stop();
-            notify_stats.total_pure_bookeeping += measure_bookkeep.as_us() as usize;
+            })
+            .collect();
+        for (account, meta) in accounts.iter_mut().zip(&metas) {
+            account.set_meta(meta);
         }
-        notify_stats.notified_accounts += accounts_to_stream.len();
+        let mut measure_pure_notify = Measure::start("accountsdb-plugin-notifying-accounts");
+        notifier.notify_accounts_restore_from_snapshot(slot, &accounts);
+        measure_pure_notify.stop();
+
+        notify_stats.total_pure_notify += measure_pure_notify.as_us() as usize;
+
+        let mut measure_bookkeep = Measure::start("accountsdb-plugin-notifying-bookeeeping");
+        notified_accounts.extend(accounts.iter().map(|account| *account.pubkey()));
+        measure_bookkeep.stop();
+        notify_stats.total_pure_bookeeping += measure_bookkeep.as_us() as usize;
+
+        notify_stats.notified_accounts += accounts.len();
         measure_notify.stop();
         notify_stats.elapsed_notifying_us += measure_notify.as_us() as usize;
     }
@@ -100,6 +100,11 @@ pub struct ScanConfig {
     /// true to allow return of all matching items and allow them to be unsorted.
     /// This is more efficient.
     pub collect_all_unsorted: bool,
+
+    /// when set, only return accounts whose latest version visible to the scan
+    /// was written in a slot greater than this one. Accounts removed since
+    /// that slot are not visible to the scan, so they are not returned.
+    pub changed_since_slot: Option<Slot>,
 }
 
 impl ScanConfig {
@@ -122,9 +127,17 @@ impl ScanConfig {
         ScanConfig {
             abort: Some(self.abort.as_ref().map(Arc::clone).unwrap_or_default()),
             collect_all_unsorted: self.collect_all_unsorted,
+            changed_since_slot: self.changed_since_slot,
         }
     }
 
+    /// true if an account version written in `slot` should be returned by the scan
+    pub fn includes_slot(&self, slot: Slot) -> bool {
+        self.changed_since_slot
+            .map(|changed_since_slot| slot > changed_since_slot)
+            .unwrap_or(true)
+    }
+
     /// true if scan should abort
     pub fn is_aborted(&self) -> bool {
         if let Some(abort) = self.abort.as_ref() {
@@ -1027,10 +1040,12 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                 if let Some(index) = self.latest_slot(Some(ancestors), list_r, max_root) {
                     latest_slot_timer.stop();
                     latest_slot_elapsed += latest_slot_timer.as_us();
-                    let mut load_account_timer = Measure::start("load_account");
-                    func(&pubkey, (&list_r[index].1, list_r[index].0));
-                    load_account_timer.stop();
-                    load_account_elapsed += load_account_timer.as_us();
+                    if config.includes_slot(list_r[index].0) {
+                        let mut load_account_timer = Measure::start("load_account");
+                        func(&pubkey, (&list_r[index].1, list_r[index].0));
+                        load_account_timer.stop();
+                        load_account_elapsed += load_account_timer.as_us();
+                    }
                 }
                 if config.is_aborted() {
                     return;
@@ -1076,7 +1091,11 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                     &entry,
                     Some(ancestors),
                     max_root,
-                    |(slot, account_info)| func(&pubkey, (&account_info, slot)),
+                    |(slot, account_info)| {
+                        if config.includes_slot(slot) {
+                            func(&pubkey, (&account_info, slot))
+                        }
+                    },
                 );
             };
         }
@@ -4217,5 +4236,58 @@ pub mod tests {
 
         let config = config.recreate_with_abort();
         assert!(config.is_aborted());
+
+        let config = ScanConfig {
+            changed_since_slot: Some(5),
+            ..ScanConfig::default()
+        };
+        assert!(!config.includes_slot(4));
+        assert!(!config.includes_slot(5));
+        assert!(config.includes_slot(6));
+        let config = config.recreate_with_abort();
+        assert_eq!(config.changed_since_slot, Some(5));
+        assert!(ScanConfig::default().includes_slot(0));
+    }
+
+    #[test]
+    fn test_scan_accounts_changed_since_slot() {
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        let old_key = solana_sdk::pubkey::new_rand();
+        let new_key = solana_sdk::pubkey::new_rand();
+        let mut gc = Vec::new();
+        for (slot, key) in [(1, &old_key), (1, &new_key), (3, &new_key)] {
+            index.upsert(
+                slot,
+                slot,
+                key,
+                &AccountSharedData::default(),
+                &AccountSecondaryIndexes::default(),
+                true,
+                &mut gc,
+                UPSERT_POPULATE_RECLAIMS,
+            );
+        }
+        let ancestors = vec![(1, 0), (3, 0)].into_iter().collect();
+
+        let mut scanned = vec![];
+        index.unchecked_scan_accounts(
+            "",
+            &ancestors,
+            |pubkey, (_account_info, slot)| scanned.push((*pubkey, slot)),
+            &ScanConfig {
+                changed_since_slot: Some(2),
+                ..ScanConfig::default()
+            },
+        );
+        assert_eq!(scanned, vec![(new_key, 3)]);
+
+        let mut num = 0;
+        index.unchecked_scan_accounts(
+            "",
+            &ancestors,
+            |_pubkey, _index| num += 1,
+            &ScanConfig::default(),
+        );
+        assert_eq!(num, 2);
     }
 }
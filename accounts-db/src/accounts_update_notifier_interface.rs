@@ -6,6 +6,15 @@ use {
     std::sync::Arc,
 };
 
+/// An account updated at runtime, see `AccountsUpdateNotifierInterface::notify_account_updates`
+#[derive(Debug)]
+pub struct AccountUpdate<'a> {
+    pub pubkey: &'a Pubkey,
+    pub account: &'a AccountSharedData,
+    pub txn: &'a Option<&'a SanitizedTransaction>,
+    pub write_version: u64,
+}
+
 pub trait AccountsUpdateNotifierInterface: std::fmt::Debug {
     /// Notified when an account is updated at runtime, due to transaction activities
     fn notify_account_update(
@@ -21,6 +30,28 @@ pub trait AccountsUpdateNotifierInterface: std::fmt::Debug {
     /// from a snapshot.
     fn notify_account_restore_from_snapshot(&self, slot: Slot, account: &StoredAccountMeta);
 
+    /// Notified of the accounts updated at runtime by one store. Notifiers with per call
+    /// overhead override it to pay it once per store; the default notifies each account.
+    fn notify_account_updates(&self, slot: Slot, updates: &[AccountUpdate]) {
+        for update in updates {
+            self.notify_account_update(
+                slot,
+                update.account,
+                update.txn,
+                update.pubkey,
+                update.write_version,
+            );
+        }
+    }
+
+    /// Notified of the accounts of a slot when the AccountsDb is restored from a snapshot; the
+    /// default notifies each account.
+    fn notify_accounts_restore_from_snapshot(&self, slot: Slot, accounts: &[StoredAccountMeta]) {
+        for account in accounts {
+            self.notify_account_restore_from_snapshot(slot, account);
+        }
+    }
+
     /// Notified when all accounts have been notified when restoring from a snapshot.
     fn notify_end_of_restore_from_snapshot(&self);
 }
@@ -194,6 +194,7 @@ fn simulate_transaction(
         units_consumed,
         return_data,
         inner_instructions,
+        invocation_trace: _,
     } = bank.simulate_transaction_unchecked(&sanitized_transaction, false);
 
     let simulation_details = TransactionSimulationDetails {
@@ -2,7 +2,12 @@ use {
     super::leader_slot_timing_metrics::LeaderExecuteAndCommitTimings,
     itertools::Itertools,
     solana_ledger::{
-        blockstore_processor::TransactionStatusSender, token_balances::collect_token_balances,
+        blockstore_processor::TransactionStatusSender,
+        token_balances::collect_token_balances,
+        transaction_account_states::{
+            collect_writable_accounts, pair_account_states, AccountStatesPrograms,
+            TransactionWritableAccounts,
+        },
     },
     solana_measure::measure_us,
     solana_runtime::{
@@ -20,7 +25,10 @@ use {
         token_balances::TransactionTokenBalancesSet, TransactionTokenBalance,
     },
     solana_vote::vote_sender_types::ReplayVoteSender,
-    std::{collections::HashMap, sync::Arc},
+    std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    },
 };
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -34,6 +42,9 @@ pub(super) struct PreBalanceInfo {
     pub native: Vec<Vec<u64>>,
     pub token: Vec<Vec<TransactionTokenBalance>>,
     pub mint_decimals: HashMap<Pubkey, u8>,
+    /// The owner programs of the account states to send, read once for the batch, with the
+    /// writable accounts before its execution
+    pub accounts: Option<(Arc<HashSet<Pubkey>>, TransactionWritableAccounts)>,
 }
 
 #[derive(Clone)]
@@ -60,6 +71,14 @@ impl Committer {
         self.transaction_status_sender.is_some()
     }
 
+    /// The owner programs of the account states currently wanted with the transaction statuses
+    pub(super) fn account_states_programs(&self) -> Option<Arc<HashSet<Pubkey>>> {
+        self.transaction_status_sender
+            .as_ref()
+            .and_then(|sender| sender.account_states_programs.as_ref())
+            .and_then(AccountStatesPrograms::get)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(super) fn commit_transactions(
         &self,
@@ -159,6 +178,14 @@ impl Committer {
                     }
                 })
                 .collect();
+            let account_states =
+                pre_balance_info
+                    .accounts
+                    .take()
+                    .map(|(programs, pre_accounts)| {
+                        let post_accounts = collect_writable_accounts(bank, batch);
+                        pair_account_states(pre_accounts, post_accounts, &programs)
+                    });
             transaction_status_sender.send_transaction_status_batch(
                 bank.clone(),
                 txs,
@@ -173,6 +200,7 @@ impl Committer {
                 ),
                 tx_results.rent_debits,
                 batch_transaction_indexes,
+                account_states,
             );
         }
     }
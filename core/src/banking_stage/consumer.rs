@@ -9,7 +9,10 @@ use {
         BankingStageStats,
     },
     itertools::Itertools,
-    solana_ledger::token_balances::collect_token_balances,
+    solana_ledger::{
+        token_balances::collect_token_balances,
+        transaction_account_states::collect_writable_accounts,
+    },
     solana_measure::{measure::Measure, measure_us},
     solana_poh::poh_recorder::{
         BankStart, PohRecorderError, RecordTransactionsSummary, RecordTransactionsTimings,
@@ -572,7 +575,11 @@ impl Consumer {
             if transaction_status_sender_enabled {
                 pre_balance_info.native = bank.collect_balances(batch);
                 pre_balance_info.token =
-                    collect_token_balances(bank, batch, &mut pre_balance_info.mint_decimals)
+                    collect_token_balances(bank, batch, &mut pre_balance_info.mint_decimals);
+                if let Some(programs) = self.committer.account_states_programs() {
+                    pre_balance_info.accounts =
+                        Some((programs, collect_writable_accounts(bank, batch)));
+                }
             }
         });
         execute_and_commit_timings.collect_balances_us = collect_balances_us;
@@ -1944,6 +1951,8 @@ mod tests {
                 None,
                 blockstore.clone(),
                 false,
+                None,
+                false,
                 Arc::new(AtomicBool::new(false)),
             );
 
@@ -1951,6 +1960,7 @@ mod tests {
             let committer = Committer::new(
                 Some(TransactionStatusSender {
                     sender: transaction_status_sender,
+                    account_states_programs: None,
                 }),
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
@@ -2089,6 +2099,8 @@ mod tests {
                 None,
                 blockstore.clone(),
                 false,
+                None,
+                false,
                 Arc::new(AtomicBool::new(false)),
             );
 
@@ -2096,6 +2108,7 @@ mod tests {
             let committer = Committer::new(
                 Some(TransactionStatusSender {
                     sender: transaction_status_sender,
+                    account_states_programs: None,
                 }),
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
@@ -2,6 +2,7 @@
 
 use {
     crossbeam_channel::Receiver,
+    solana_geyser_plugin_manager::block_cost_notifier_interface::BlockCostNotifierArc,
     solana_ledger::blockstore::Blockstore,
     solana_runtime::bank::Bank,
     std::{
@@ -20,11 +21,15 @@ pub struct CostUpdateService {
 }
 
 impl CostUpdateService {
-    pub fn new(blockstore: Arc<Blockstore>, cost_update_receiver: CostUpdateReceiver) -> Self {
+    pub fn new(
+        blockstore: Arc<Blockstore>,
+        cost_update_receiver: CostUpdateReceiver,
+        block_cost_notifier: Option<BlockCostNotifierArc>,
+    ) -> Self {
         let thread_hdl = Builder::new()
             .name("solCostUpdtSvc".to_string())
             .spawn(move || {
-                Self::service_loop(blockstore, cost_update_receiver);
+                Self::service_loop(blockstore, cost_update_receiver, block_cost_notifier);
             })
             .unwrap();
 
@@ -35,11 +40,25 @@ impl CostUpdateService {
         self.thread_hdl.join()
     }
 
-    fn service_loop(_blockstore: Arc<Blockstore>, cost_update_receiver: CostUpdateReceiver) {
+    fn service_loop(
+        _blockstore: Arc<Blockstore>,
+        cost_update_receiver: CostUpdateReceiver,
+        block_cost_notifier: Option<BlockCostNotifierArc>,
+    ) {
         for cost_update in cost_update_receiver.iter() {
             match cost_update {
                 CostUpdate::FrozenBank { bank } => {
-                    bank.read_cost_tracker().unwrap().report_stats(bank.slot());
+                    let cost_tracker = bank.read_cost_tracker().unwrap();
+                    cost_tracker.report_stats(bank.slot());
+                    // Replay only fills the cost tracker once `apply_cost_tracker_during_replay`
+                    // is active; before, the costs of replayed blocks are unknown
+                    let is_tracked = cost_tracker.transaction_count() > 0
+                        || bank.executed_transaction_count() == 0;
+                    if let Some(block_cost_notifier) = &block_cost_notifier {
+                        if is_tracked {
+                            block_cost_notifier.notify_block_cost(bank.slot(), &cost_tracker);
+                        }
+                    }
                 }
             }
         }
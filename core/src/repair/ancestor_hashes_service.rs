@@ -183,6 +183,7 @@ impl AncestorHashesService {
                         quic_endpoint_response_receiver,
                         response_sender,
                         Recycler::default(),
+                        false, // record_receive_times
                         exit,
                     )
                 })
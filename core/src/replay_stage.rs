@@ -55,6 +55,7 @@ use {
     solana_rpc::{
         optimistically_confirmed_bank_tracker::{BankNotification, BankNotificationSenderConfig},
         rpc_subscriptions::RpcSubscriptions,
+        slot_status_notifier_interface::SlotStatusNotifier,
     },
     solana_rpc_client_api::response::SlotUpdate,
     solana_runtime::{
@@ -285,6 +286,7 @@ pub struct ReplayStageConfig {
     pub cache_block_meta_sender: Option<CacheBlockMetaSender>,
     pub entry_notification_sender: Option<EntryNotifierSender>,
     pub bank_notification_sender: Option<BankNotificationSenderConfig>,
+    pub slot_status_notifier: Option<SlotStatusNotifier>,
     pub wait_for_vote_to_start_leader: bool,
     pub ancestor_hashes_replay_update_sender: AncestorHashesReplayUpdateSender,
     pub tower_storage: Arc<dyn TowerStorage>,
@@ -570,6 +572,7 @@ impl ReplayStage {
             cache_block_meta_sender,
             entry_notification_sender,
             bank_notification_sender,
+            slot_status_notifier,
             wait_for_vote_to_start_leader,
             ancestor_hashes_replay_update_sender,
             tower_storage,
@@ -692,6 +695,7 @@ impl ReplayStage {
                     &bank_forks,
                     &leader_schedule_cache,
                     &rpc_subscriptions,
+                    &slot_status_notifier,
                     &mut progress,
                     &mut replay_timing,
                 );
@@ -719,6 +723,7 @@ impl ReplayStage {
                     &bank_notification_sender,
                     &rewards_recorder_sender,
                     &rpc_subscriptions,
+                    &slot_status_notifier,
                     &mut duplicate_slots_tracker,
                     &duplicate_confirmed_slots,
                     &mut epoch_slots_frozen_slots,
@@ -1140,6 +1145,7 @@ impl ReplayStage {
                         &poh_recorder,
                         &leader_schedule_cache,
                         &rpc_subscriptions,
+                        &slot_status_notifier,
                         &mut progress,
                         &retransmit_slots_sender,
                         &mut skipped_slots_info,
@@ -1999,6 +2005,7 @@ impl ReplayStage {
         poh_recorder: &Arc<RwLock<PohRecorder>>,
         leader_schedule_cache: &Arc<LeaderScheduleCache>,
         rpc_subscriptions: &Arc<RpcSubscriptions>,
+        slot_status_notifier: &Option<SlotStatusNotifier>,
         progress_map: &mut ProgressMap,
         retransmit_slots_sender: &Sender<Slot>,
         skipped_slots_info: &mut SkippedSlotsInfo,
@@ -2121,6 +2128,7 @@ impl ReplayStage {
                 root_slot,
                 my_pubkey,
                 rpc_subscriptions,
+                slot_status_notifier,
                 NewBankOptions { vote_only_bank },
             );
             // make sure parent is frozen for finalized hashes via the above
@@ -2184,6 +2192,7 @@ impl ReplayStage {
         root: Slot,
         err: &BlockstoreProcessorError,
         rpc_subscriptions: &Arc<RpcSubscriptions>,
+        slot_status_notifier: &Option<SlotStatusNotifier>,
         duplicate_slots_tracker: &mut DuplicateSlotsTracker,
         duplicate_confirmed_slots: &DuplicateConfirmedSlots,
         epoch_slots_frozen_slots: &mut EpochSlotsFrozenSlots,
@@ -2224,9 +2233,17 @@ impl ReplayStage {
 
         blockstore.slots_stats.mark_dead(slot);
 
+        let dead_error = format!("error: {err:?}");
+        if let Some(slot_status_notifier) = slot_status_notifier {
+            slot_status_notifier.read().unwrap().notify_slot_dead(
+                slot,
+                bank.parent_slot(),
+                dead_error.clone(),
+            );
+        }
         rpc_subscriptions.notify_slot_update(SlotUpdate::Dead {
             slot,
-            err: format!("error: {err:?}"),
+            err: dead_error,
             timestamp: timestamp(),
         });
         let dead_state = DeadState::new_from_state(
@@ -2923,6 +2940,7 @@ impl ReplayStage {
         bank_notification_sender: &Option<BankNotificationSenderConfig>,
         rewards_recorder_sender: &Option<RewardsRecorderSender>,
         rpc_subscriptions: &Arc<RpcSubscriptions>,
+        slot_status_notifier: &Option<SlotStatusNotifier>,
         duplicate_slots_tracker: &mut DuplicateSlotsTracker,
         duplicate_confirmed_slots: &DuplicateConfirmedSlots,
         epoch_slots_frozen_slots: &mut EpochSlotsFrozenSlots,
@@ -2961,6 +2979,7 @@ impl ReplayStage {
                             bank_forks.read().unwrap().root(),
                             err,
                             rpc_subscriptions,
+                            slot_status_notifier,
                             duplicate_slots_tracker,
                             duplicate_confirmed_slots,
                             epoch_slots_frozen_slots,
@@ -3004,6 +3023,7 @@ impl ReplayStage {
                             bank_forks.read().unwrap().root(),
                             &BlockstoreProcessorError::InvalidTransaction(err),
                             rpc_subscriptions,
+                            slot_status_notifier,
                             duplicate_slots_tracker,
                             duplicate_confirmed_slots,
                             epoch_slots_frozen_slots,
@@ -3112,6 +3132,13 @@ impl ReplayStage {
                 blockstore_processor::cache_block_meta(bank, cache_block_meta_sender);
 
                 let bank_hash = bank.hash();
+                if let Some(slot_status_notifier) = slot_status_notifier {
+                    slot_status_notifier.read().unwrap().notify_frozen(
+                        bank.slot(),
+                        bank.parent_slot(),
+                        &bank_hash,
+                    );
+                }
                 if let Some(new_frozen_voters) =
                     unfrozen_gossip_verified_vote_hashes.remove_slot_hash(bank.slot(), &bank_hash)
                 {
@@ -3181,6 +3208,7 @@ impl ReplayStage {
         bank_notification_sender: &Option<BankNotificationSenderConfig>,
         rewards_recorder_sender: &Option<RewardsRecorderSender>,
         rpc_subscriptions: &Arc<RpcSubscriptions>,
+        slot_status_notifier: &Option<SlotStatusNotifier>,
         duplicate_slots_tracker: &mut DuplicateSlotsTracker,
         duplicate_confirmed_slots: &DuplicateConfirmedSlots,
         epoch_slots_frozen_slots: &mut EpochSlotsFrozenSlots,
@@ -3263,6 +3291,7 @@ impl ReplayStage {
             bank_notification_sender,
             rewards_recorder_sender,
             rpc_subscriptions,
+            slot_status_notifier,
             duplicate_slots_tracker,
             duplicate_confirmed_slots,
             epoch_slots_frozen_slots,
@@ -4179,6 +4208,7 @@ impl ReplayStage {
         bank_forks: &RwLock<BankForks>,
         leader_schedule_cache: &Arc<LeaderScheduleCache>,
         rpc_subscriptions: &Arc<RpcSubscriptions>,
+        slot_status_notifier: &Option<SlotStatusNotifier>,
         progress: &mut ProgressMap,
         replay_timing: &mut ReplayLoopTiming,
     ) {
@@ -4233,6 +4263,7 @@ impl ReplayStage {
                     forks.root(),
                     &leader,
                     rpc_subscriptions,
+                    slot_status_notifier,
                     NewBankOptions::default(),
                 );
                 let empty: Vec<Pubkey> = vec![];
@@ -4280,9 +4311,16 @@ impl ReplayStage {
         root_slot: u64,
         leader: &Pubkey,
         rpc_subscriptions: &Arc<RpcSubscriptions>,
+        slot_status_notifier: &Option<SlotStatusNotifier>,
         new_bank_options: NewBankOptions,
     ) -> Bank {
         rpc_subscriptions.notify_slot(slot, parent.slot(), root_slot);
+        if let Some(slot_status_notifier) = slot_status_notifier {
+            slot_status_notifier
+                .read()
+                .unwrap()
+                .notify_created_bank(slot, parent.slot());
+        }
         Bank::new_from_parent_with_options(parent, leader, slot, new_bank_options)
     }
 
@@ -4345,6 +4383,7 @@ pub(crate) mod tests {
         solana_rpc::{
             optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
             rpc::{create_test_transaction_entries, populate_blockstore_for_tests},
+            slot_status_notifier_interface::SlotStatusNotifierInterface,
         },
         solana_runtime::{
             accounts_background_service::AbsRequestSender,
@@ -4370,12 +4409,56 @@ pub(crate) mod tests {
         std::{
             fs::remove_dir_all,
             iter,
-            sync::{atomic::AtomicU64, Arc, RwLock},
+            sync::{atomic::AtomicU64, Arc, Mutex, RwLock},
         },
         tempfile::tempdir,
         trees::{tr, Tree},
     };
 
+    /// Records the slot statuses replay notifies of, as (status, slot, parent)
+    #[derive(Default)]
+    struct RecordingSlotStatusNotifier {
+        statuses: Mutex<Vec<(&'static str, Slot, Option<Slot>)>>,
+        dead_errors: Mutex<Vec<String>>,
+    }
+
+    impl RecordingSlotStatusNotifier {
+        fn record(&self, status: &'static str, slot: Slot, parent: Option<Slot>) {
+            self.statuses.lock().unwrap().push((status, slot, parent));
+        }
+    }
+
+    impl SlotStatusNotifierInterface for RecordingSlotStatusNotifier {
+        fn notify_slot_confirmed(&self, slot: Slot, parent: Option<Slot>) {
+            self.record("confirmed", slot, parent);
+        }
+
+        fn notify_slot_processed(&self, slot: Slot, parent: Option<Slot>) {
+            self.record("processed", slot, parent);
+        }
+
+        fn notify_slot_rooted(&self, slot: Slot, parent: Option<Slot>) {
+            self.record("rooted", slot, parent);
+        }
+
+        fn notify_created_bank(&self, slot: Slot, parent: Slot) {
+            self.record("createdbank", slot, Some(parent));
+        }
+
+        fn notify_completed(&self, slot: Slot, parent: Option<Slot>) {
+            self.record("completed", slot, parent);
+        }
+
+        fn notify_frozen(&self, slot: Slot, parent: Slot, _bank_hash: &Hash) {
+            self.record("frozen", slot, Some(parent));
+        }
+
+        fn notify_slot_dead(&self, slot: Slot, parent: Slot, error: String) {
+            self.record("dead", slot, Some(parent));
+            self.dead_errors.lock().unwrap().push(error);
+        }
+    }
+
     fn new_bank_from_parent_with_bank_forks(
         bank_forks: &RwLock<BankForks>,
         parent: Arc<Bank>,
@@ -4570,12 +4653,15 @@ pub(crate) mod tests {
             .unwrap()
             .get(NUM_CONSECUTIVE_LEADER_SLOTS)
             .is_none());
+        let recording_notifier = Arc::new(RwLock::new(RecordingSlotStatusNotifier::default()));
+        let slot_status_notifier: SlotStatusNotifier = recording_notifier.clone();
         let mut replay_timing = ReplayLoopTiming::default();
         ReplayStage::generate_new_bank_forks(
             &blockstore,
             &bank_forks,
             &leader_schedule_cache,
             &rpc_subscriptions,
+            &Some(slot_status_notifier),
             &mut progress,
             &mut replay_timing,
         );
@@ -4584,6 +4670,10 @@ pub(crate) mod tests {
             .unwrap()
             .get(NUM_CONSECUTIVE_LEADER_SLOTS)
             .is_some());
+        assert_eq!(
+            *recording_notifier.read().unwrap().statuses.lock().unwrap(),
+            vec![("createdbank", NUM_CONSECUTIVE_LEADER_SLOTS, Some(1))]
+        );
 
         // Insert shreds for slot 2 * NUM_CONSECUTIVE_LEADER_SLOTS,
         // chaining to slot 1
@@ -4604,6 +4694,7 @@ pub(crate) mod tests {
             &bank_forks,
             &leader_schedule_cache,
             &rpc_subscriptions,
+            &None,
             &mut progress,
             &mut replay_timing,
         );
@@ -5078,6 +5169,8 @@ pub(crate) mod tests {
             ));
             let (ancestor_hashes_replay_update_sender, _ancestor_hashes_replay_update_receiver) =
                 unbounded();
+            let recording_notifier = Arc::new(RwLock::new(RecordingSlotStatusNotifier::default()));
+            let slot_status_notifier: SlotStatusNotifier = recording_notifier.clone();
             if let Err(err) = &res {
                 ReplayStage::mark_dead_slot(
                     &blockstore,
@@ -5085,6 +5178,7 @@ pub(crate) mod tests {
                     0,
                     err,
                     &rpc_subscriptions,
+                    &Some(slot_status_notifier),
                     &mut DuplicateSlotsTracker::default(),
                     &DuplicateConfirmedSlots::new(),
                     &mut EpochSlotsFrozenSlots::default(),
@@ -5104,6 +5198,17 @@ pub(crate) mod tests {
 
             // Check that the erroring bank was marked as dead in blockstore
             assert!(blockstore.is_dead(bank1.slot()));
+
+            // Check that the slot status notifier was told the slot is dead
+            let recording_notifier = recording_notifier.read().unwrap();
+            assert_eq!(
+                *recording_notifier.statuses.lock().unwrap(),
+                vec![("dead", bank1.slot(), Some(0))]
+            );
+            assert_eq!(
+                *recording_notifier.dead_errors.lock().unwrap(),
+                vec![format!("error: {:?}", res.as_ref().unwrap_err())]
+            );
             res.map(|_| ())
         };
         let _ignored = remove_dir_all(ledger_path);
@@ -6466,6 +6571,7 @@ pub(crate) mod tests {
             &bank_forks,
             &leader_schedule_cache,
             &rpc_subscriptions,
+            &None,
             &mut progress,
             &mut replay_timing,
         );
@@ -6495,6 +6601,7 @@ pub(crate) mod tests {
             &bank_forks,
             &leader_schedule_cache,
             &rpc_subscriptions,
+            &None,
             &mut progress,
             &mut replay_timing,
         );
@@ -6525,6 +6632,7 @@ pub(crate) mod tests {
             &bank_forks,
             &leader_schedule_cache,
             &rpc_subscriptions,
+            &None,
             &mut progress,
             &mut replay_timing,
         );
@@ -6554,6 +6662,7 @@ pub(crate) mod tests {
             &bank_forks,
             &leader_schedule_cache,
             &rpc_subscriptions,
+            &None,
             &mut progress,
             &mut replay_timing,
         );
@@ -24,7 +24,7 @@ use {
             Arc, RwLock,
         },
         thread::{self, Builder, JoinHandle},
-        time::{Duration, Instant},
+        time::{Duration, Instant, SystemTime},
     },
 };
 
@@ -159,13 +159,19 @@ impl ShredFetchStage {
         flags: PacketFlags,
         repair_context: Option<(Arc<UdpSocket>, Arc<ClusterInfo>)>,
         turbine_disabled: Arc<AtomicBool>,
+        record_receive_times: bool,
     ) -> (Vec<JoinHandle<()>>, JoinHandle<()>) {
         let (packet_sender, packet_receiver) = unbounded();
         let streamers = sockets
             .into_iter()
             .enumerate()
             .map(|(i, socket)| {
-                streamer::receiver(
+                let receiver = if record_receive_times {
+                    streamer::receiver_with_receive_times
+                } else {
+                    streamer::receiver
+                };
+                receiver(
                     format!("{receiver_thread_name}{i:02}"),
                     socket,
                     exit.clone(),
@@ -210,6 +216,8 @@ impl ShredFetchStage {
         bank_forks: Arc<RwLock<BankForks>>,
         cluster_info: Arc<ClusterInfo>,
         turbine_disabled: Arc<AtomicBool>,
+        // Whether to record when shreds were read off the sockets, for shred notifications
+        record_receive_times: bool,
         exit: Arc<AtomicBool>,
     ) -> Self {
         let recycler = PacketBatchRecycler::warmed(100, 1024);
@@ -227,6 +235,7 @@ impl ShredFetchStage {
             PacketFlags::empty(),
             None, // repair_context
             turbine_disabled.clone(),
+            record_receive_times,
         );
 
         let (repair_receiver, repair_handler) = Self::packet_modifier(
@@ -242,6 +251,7 @@ impl ShredFetchStage {
             PacketFlags::REPAIR,
             Some((repair_socket, cluster_info)),
             turbine_disabled.clone(),
+            record_receive_times,
         );
 
         tvu_threads.extend(repair_receiver);
@@ -263,6 +273,7 @@ impl ShredFetchStage {
                             repair_quic_endpoint_receiver,
                             packet_sender,
                             recycler,
+                            record_receive_times,
                             exit,
                         )
                     })
@@ -294,6 +305,7 @@ impl ShredFetchStage {
                         turbine_quic_endpoint_receiver,
                         packet_sender,
                         recycler,
+                        record_receive_times,
                         exit,
                     )
                 })
@@ -331,6 +343,7 @@ fn receive_quic_datagrams(
     turbine_quic_endpoint_receiver: Receiver<(Pubkey, SocketAddr, Bytes)>,
     sender: Sender<PacketBatch>,
     recycler: PacketBatchRecycler,
+    record_receive_times: bool,
     exit: Arc<AtomicBool>,
 ) {
     const RECV_TIMEOUT: Duration = Duration::from_secs(1);
@@ -350,10 +363,14 @@ fn receive_quic_datagrams(
             std::iter::repeat_with(|| turbine_quic_endpoint_receiver.recv_deadline(deadline).ok())
                 .while_some(),
         );
+        let mut receive_times = Vec::new();
         let size = entries
             .filter(|(_, _, bytes)| bytes.len() <= PACKET_DATA_SIZE)
             .zip(packet_batch.iter_mut())
             .map(|((_pubkey, addr, bytes), packet)| {
+                if record_receive_times {
+                    receive_times.push(SystemTime::now());
+                }
                 *packet.meta_mut() = Meta {
                     size: bytes.len(),
                     addr: addr.ip(),
@@ -365,6 +382,9 @@ fn receive_quic_datagrams(
             .count();
         if size > 0 {
             packet_batch.truncate(size);
+            for (index, time) in receive_times.into_iter().enumerate() {
+                packet_batch.set_receive_time(index..index + 1, time);
+            }
             if sender.send(packet_batch).is_err() {
                 return;
             }
@@ -376,6 +396,7 @@ pub(crate) fn receive_repair_quic_packets(
     repair_quic_endpoint_receiver: Receiver<(SocketAddr, Vec<u8>)>,
     sender: Sender<PacketBatch>,
     recycler: PacketBatchRecycler,
+    record_receive_times: bool,
     exit: Arc<AtomicBool>,
 ) {
     const RECV_TIMEOUT: Duration = Duration::from_secs(1);
@@ -395,10 +416,14 @@ pub(crate) fn receive_repair_quic_packets(
             std::iter::repeat_with(|| repair_quic_endpoint_receiver.recv_deadline(deadline).ok())
                 .while_some(),
         );
+        let mut receive_times = Vec::new();
         let size = entries
             .filter(|(_, bytes)| bytes.len() <= PACKET_DATA_SIZE)
             .zip(packet_batch.iter_mut())
             .map(|((addr, bytes), packet)| {
+                if record_receive_times {
+                    receive_times.push(SystemTime::now());
+                }
                 *packet.meta_mut() = Meta {
                     size: bytes.len(),
                     addr: addr.ip(),
@@ -410,6 +435,9 @@ pub(crate) fn receive_repair_quic_packets(
             .count();
         if size > 0 {
             packet_batch.truncate(size);
+            for (index, time) in receive_times.into_iter().enumerate() {
+                packet_batch.set_receive_time(index..index + 1, time);
+            }
             if sender.send(packet_batch).is_err() {
                 return; // The receiver end of the channel is disconnected.
             }
@@ -28,7 +28,10 @@ use {
     bytes::Bytes,
     crossbeam_channel::{unbounded, Receiver, Sender},
     solana_client::connection_cache::ConnectionCache,
-    solana_geyser_plugin_manager::block_metadata_notifier_interface::BlockMetadataNotifierArc,
+    solana_geyser_plugin_manager::{
+        block_cost_notifier_interface::BlockCostNotifierArc,
+        block_metadata_notifier_interface::BlockMetadataNotifierArc,
+    },
     solana_gossip::{
         cluster_info::ClusterInfo, duplicate_shred_handler::DuplicateShredHandler,
         duplicate_shred_listener::DuplicateShredListener,
@@ -36,12 +39,12 @@ use {
     solana_ledger::{
         blockstore::Blockstore, blockstore_cleanup_service::BlockstoreCleanupService,
         blockstore_processor::TransactionStatusSender, entry_notifier_service::EntryNotifierSender,
-        leader_schedule_cache::LeaderScheduleCache,
+        leader_schedule_cache::LeaderScheduleCache, shred_notifier_interface::ShredNotifierArc,
     },
     solana_poh::poh_recorder::PohRecorder,
     solana_rpc::{
         max_slots::MaxSlots, optimistically_confirmed_bank_tracker::BankNotificationSenderConfig,
-        rpc_subscriptions::RpcSubscriptions,
+        rpc_subscriptions::RpcSubscriptions, slot_status_notifier_interface::SlotStatusNotifier,
     },
     solana_runtime::{
         accounts_background_service::AbsRequestSender, bank_forks::BankForks,
@@ -84,6 +87,7 @@ pub struct TvuSockets {
 #[derive(Default)]
 pub struct TvuConfig {
     pub max_ledger_shreds: Option<u64>,
+    pub transaction_history_retention_slots: Option<u64>,
     pub shred_version: u16,
     // Validators from which repairs are requested
     pub repair_validators: Option<HashSet<Pubkey>>,
@@ -132,6 +136,9 @@ impl Tvu {
         tvu_config: TvuConfig,
         max_slots: &Arc<MaxSlots>,
         block_metadata_notifier: Option<BlockMetadataNotifierArc>,
+        shred_notifier: Option<ShredNotifierArc>,
+        slot_status_notifier: Option<SlotStatusNotifier>,
+        block_cost_notifier: Option<BlockCostNotifierArc>,
         wait_to_vote_slot: Option<Slot>,
         accounts_background_request_sender: AbsRequestSender,
         log_messages_bytes_limit: Option<usize>,
@@ -169,6 +176,7 @@ impl Tvu {
             bank_forks.clone(),
             cluster_info.clone(),
             turbine_disabled,
+            shred_notifier.is_some(), // record_receive_times
             exit.clone(),
         );
 
@@ -235,6 +243,7 @@ impl Tvu {
                 dumped_slots_receiver,
                 popular_pruned_forks_sender,
                 outstanding_repair_requests,
+                shred_notifier,
             )
         };
 
@@ -261,6 +270,7 @@ impl Tvu {
             cache_block_meta_sender,
             entry_notification_sender,
             bank_notification_sender,
+            slot_status_notifier,
             wait_for_vote_to_start_leader: tvu_config.wait_for_vote_to_start_leader,
             ancestor_hashes_replay_update_sender,
             tower_storage: tower_storage.clone(),
@@ -287,7 +297,11 @@ impl Tvu {
             None
         };
         let (cost_update_sender, cost_update_receiver) = unbounded();
-        let cost_update_service = CostUpdateService::new(blockstore.clone(), cost_update_receiver);
+        let cost_update_service = CostUpdateService::new(
+            blockstore.clone(),
+            cost_update_receiver,
+            block_cost_notifier,
+        );
 
         let (drop_bank_sender, drop_bank_receiver) = unbounded();
 
@@ -322,7 +336,12 @@ impl Tvu {
         )?;
 
         let blockstore_cleanup_service = tvu_config.max_ledger_shreds.map(|max_ledger_shreds| {
-            BlockstoreCleanupService::new(blockstore.clone(), max_ledger_shreds, exit.clone())
+            BlockstoreCleanupService::new(
+                blockstore.clone(),
+                max_ledger_shreds,
+                tvu_config.transaction_history_retention_slots,
+                exit.clone(),
+            )
         });
 
         let duplicate_shred_listener = DuplicateShredListener::new(
@@ -491,6 +510,9 @@ pub mod tests {
             &Arc::new(MaxSlots::default()),
             None,
             None,
+            None,
+            None,
+            None,
             AbsRequestSender::default(),
             None,
             &Arc::new(ConnectionCache::new("connection_cache_test")),
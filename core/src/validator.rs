@@ -86,7 +86,7 @@ use {
         rpc_service::JsonRpcService,
         rpc_subscriptions::RpcSubscriptions,
         transaction_notifier_interface::TransactionNotifierArc,
-        transaction_status_service::TransactionStatusService,
+        transaction_status_service::{TransactionLogsStorageConfig, TransactionStatusService},
     },
     solana_runtime::{
         accounts_background_service::{
@@ -216,6 +216,9 @@ pub struct ValidatorConfig {
     pub pubsub_config: PubSubConfig,
     pub snapshot_config: SnapshotConfig,
     pub max_ledger_shreds: Option<u64>,
+    /// Keep the transaction history for this many slots behind the latest root, independently of
+    /// the shreds limited by `max_ledger_shreds`
+    pub transaction_history_retention_slots: Option<u64>,
     pub broadcast_stage_type: BroadcastStageType,
     pub turbine_disabled: Arc<AtomicBool>,
     pub enforce_ulimit_nofile: bool,
@@ -279,6 +282,7 @@ impl Default for ValidatorConfig {
             expected_shred_version: None,
             voting_disabled: false,
             max_ledger_shreds: None,
+            transaction_history_retention_slots: None,
             account_paths: Vec::new(),
             account_snapshot_paths: Vec::new(),
             rpc_config: JsonRpcConfig::default(),
@@ -658,6 +662,18 @@ impl Validator {
             .as_ref()
             .and_then(|geyser_plugin_service| geyser_plugin_service.get_block_metadata_notifier());
 
+        let shred_notifier = geyser_plugin_service
+            .as_ref()
+            .and_then(|geyser_plugin_service| geyser_plugin_service.get_shred_notifier());
+
+        let slot_status_notifier = geyser_plugin_service
+            .as_ref()
+            .and_then(|geyser_plugin_service| geyser_plugin_service.get_slot_status_notifier());
+
+        let block_cost_notifier = geyser_plugin_service
+            .as_ref()
+            .and_then(|geyser_plugin_service| geyser_plugin_service.get_block_cost_notifier());
+
         info!(
             "Geyser plugin: accounts_update_notifier: {}, \
             transaction_notifier: {}, \
@@ -1149,7 +1165,9 @@ impl Validator {
 
         let rpc_completed_slots_service = RpcCompletedSlotsService::spawn(
             completed_slots_receiver,
+            blockstore.clone(),
             rpc_subscriptions.clone(),
+            slot_status_notifier.clone(),
             exit.clone(),
         );
 
@@ -1301,6 +1319,7 @@ impl Validator {
             duplicate_confirmed_slots_receiver,
             TvuConfig {
                 max_ledger_shreds: config.max_ledger_shreds,
+                transaction_history_retention_slots: config.transaction_history_retention_slots,
                 shred_version: node.info.shred_version(),
                 repair_validators: config.repair_validators.clone(),
                 repair_whitelist: config.repair_whitelist.clone(),
@@ -1309,6 +1328,9 @@ impl Validator {
             },
             &max_slots,
             block_metadata_notifier,
+            shred_notifier,
+            slot_status_notifier,
+            block_cost_notifier,
             config.wait_to_vote_slot,
             accounts_background_request_sender,
             config.runtime_config.log_messages_bytes_limit,
@@ -1406,6 +1428,7 @@ impl Validator {
             repair_socket: Arc::new(node.sockets.repair),
             outstanding_repair_requests,
             cluster_slots,
+            blockstore: blockstore.clone(),
         });
 
         Ok(Self {
@@ -1847,6 +1870,8 @@ fn load_blockstore(
                 exit.clone(),
                 enable_rpc_transaction_history,
                 config.rpc_config.enable_extended_tx_metadata_storage,
+                config.rpc_config.transaction_logs_storage,
+                config.rpc_config.enable_program_signatures_index,
                 transaction_notifier,
             )
         } else {
@@ -2232,12 +2257,18 @@ fn initialize_rpc_transaction_history_services(
     exit: Arc<AtomicBool>,
     enable_rpc_transaction_history: bool,
     enable_extended_tx_metadata_storage: bool,
+    transaction_logs_storage: Option<TransactionLogsStorageConfig>,
+    enable_program_signatures_index: bool,
     transaction_notifier: Option<TransactionNotifierArc>,
 ) -> TransactionHistoryServices {
     let max_complete_transaction_status_slot = Arc::new(AtomicU64::new(blockstore.max_root()));
     let (transaction_status_sender, transaction_status_receiver) = unbounded();
+    let account_states_programs = transaction_notifier
+        .as_ref()
+        .and_then(|transaction_notifier| transaction_notifier.account_states_programs());
     let transaction_status_sender = Some(TransactionStatusSender {
         sender: transaction_status_sender,
+        account_states_programs,
     });
     let transaction_status_service = Some(TransactionStatusService::new(
         transaction_status_receiver,
@@ -2246,6 +2277,8 @@ fn initialize_rpc_transaction_history_services(
         transaction_notifier,
         blockstore.clone(),
         enable_extended_tx_metadata_storage,
+        transaction_logs_storage,
+        enable_program_signatures_index,
         exit.clone(),
     ));
 
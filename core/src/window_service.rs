@@ -24,6 +24,7 @@ use {
         blockstore::{Blockstore, BlockstoreInsertionMetrics, PossibleDuplicateShred},
         leader_schedule_cache::LeaderScheduleCache,
         shred::{self, Nonce, ReedSolomonCache, Shred},
+        shred_notifier_interface::ShredNotifierArc,
     },
     solana_measure::measure::Measure,
     solana_metrics::inc_new_counter_error,
@@ -44,7 +45,7 @@ use {
             Arc, RwLock,
         },
         thread::{self, Builder, JoinHandle},
-        time::{Duration, Instant},
+        time::{Duration, Instant, SystemTime},
     },
     tokio::sync::mpsc::Sender as AsyncSender,
 };
@@ -149,6 +150,7 @@ fn run_check_duplicate(
     shred_receiver: &Receiver<PossibleDuplicateShred>,
     duplicate_slots_sender: &DuplicateSlotSender,
     bank_forks: &RwLock<BankForks>,
+    shred_notifier: Option<&ShredNotifierArc>,
 ) -> Result<()> {
     let mut root_bank = bank_forks.read().unwrap().root_bank();
     let mut last_updated = Instant::now();
@@ -215,6 +217,10 @@ fn run_check_duplicate(
             }
         };
 
+        // Notify plugins first so that an error pushing the proof does not drop it
+        if let Some(shred_notifier) = shred_notifier {
+            shred_notifier.notify_duplicate_shred(shred_slot, shred1.payload(), &shred2);
+        }
         // Propagate duplicate proof through gossip
         cluster_info.push_duplicate_shred(&shred1, &shred2)?;
         // Notify duplicate consensus state machine
@@ -251,10 +257,12 @@ fn verify_repair(
 fn prune_shreds_by_repair_status(
     shreds: &mut Vec<Shred>,
     repair_infos: &mut Vec<Option<RepairMeta>>,
+    receive_times: &mut Vec<Option<SystemTime>>,
     outstanding_requests: &RwLock<OutstandingShredRepairs>,
     accept_repairs_only: bool,
 ) {
     assert_eq!(shreds.len(), repair_infos.len());
+    assert_eq!(shreds.len(), receive_times.len());
     let mut i = 0;
     let mut removed = HashSet::new();
     {
@@ -274,7 +282,10 @@ fn prune_shreds_by_repair_status(
     }
     i = 0;
     repair_infos.retain(|_repair_info| (!removed.contains(&i), i += 1).0);
+    i = 0;
+    receive_times.retain(|_receive_time| (!removed.contains(&i), i += 1).0);
     assert_eq!(shreds.len(), repair_infos.len());
+    assert_eq!(shreds.len(), receive_times.len());
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -291,6 +302,7 @@ fn run_insert<F>(
     outstanding_requests: &RwLock<OutstandingShredRepairs>,
     reed_solomon_cache: &ReedSolomonCache,
     accept_repairs_only: bool,
+    shred_notifier: Option<&ShredNotifierArc>,
 ) -> Result<()>
 where
     F: Fn(PossibleDuplicateShred),
@@ -319,12 +331,18 @@ where
         }
     };
     let now = Instant::now();
-    let (mut shreds, mut repair_infos): (Vec<_>, Vec<_>) = thread_pool.install(|| {
-        packets
-            .par_iter()
-            .flat_map_iter(|packets| packets.iter().filter_map(handle_packet))
-            .unzip()
-    });
+    let (mut shreds, (mut repair_infos, mut receive_times)): (Vec<_>, (Vec<_>, Vec<_>)) =
+        thread_pool.install(|| {
+            packets
+                .par_iter()
+                .flat_map_iter(|packets| {
+                    packets.iter().enumerate().filter_map(|(index, packet)| {
+                        let (shred, repair_info) = handle_packet(packet)?;
+                        Some((shred, (repair_info, packets.receive_time(index))))
+                    })
+                })
+                .unzip()
+        });
     ws_metrics.handle_packets_elapsed_us += now.elapsed().as_micros() as u64;
     ws_metrics.num_packets += packets.iter().map(PacketBatch::len).sum::<usize>();
     ws_metrics.num_repairs += repair_infos.iter().filter(|r| r.is_some()).count();
@@ -339,6 +357,7 @@ where
     prune_shreds_by_repair_status(
         &mut shreds,
         &mut repair_infos,
+        &mut receive_times,
         outstanding_requests,
         accept_repairs_only,
     );
@@ -350,6 +369,14 @@ where
     prune_shreds_elapsed.stop();
     ws_metrics.prune_shreds_elapsed_us += prune_shreds_elapsed.as_us();
 
+    if let Some(shred_notifier) = shred_notifier {
+        // Shreds from receivers which do not record the receive time are timed here.
+        let now = SystemTime::now();
+        for ((shred, is_repair), receive_time) in shreds.iter().zip(&repairs).zip(receive_times) {
+            shred_notifier.notify_shred(shred, *is_repair, receive_time.unwrap_or(now));
+        }
+    }
+
     let completed_data_sets = blockstore.insert_shreds_handle_duplicate(
         shreds,
         repairs,
@@ -395,6 +422,7 @@ impl WindowService {
         dumped_slots_receiver: DumpedSlotsReceiver,
         popular_pruned_forks_sender: PopularPrunedForksSender,
         outstanding_repair_requests: Arc<RwLock<OutstandingShredRepairs>>,
+        shred_notifier: Option<ShredNotifierArc>,
     ) -> WindowService {
         let cluster_info = repair_info.cluster_info.clone();
         let bank_forks = repair_info.bank_forks.clone();
@@ -427,6 +455,7 @@ impl WindowService {
             duplicate_receiver,
             duplicate_slots_sender,
             bank_forks,
+            shred_notifier.clone(),
         );
 
         let t_insert = Self::start_window_insert_thread(
@@ -439,6 +468,7 @@ impl WindowService {
             retransmit_sender,
             outstanding_repair_requests,
             accept_repairs_only,
+            shred_notifier,
         );
 
         WindowService {
@@ -455,6 +485,7 @@ impl WindowService {
         duplicate_receiver: Receiver<PossibleDuplicateShred>,
         duplicate_slots_sender: DuplicateSlotSender,
         bank_forks: Arc<RwLock<BankForks>>,
+        shred_notifier: Option<ShredNotifierArc>,
     ) -> JoinHandle<()> {
         let handle_error = || {
             inc_new_counter_error!("solana-check-duplicate-error", 1, 1);
@@ -469,6 +500,7 @@ impl WindowService {
                         &duplicate_receiver,
                         &duplicate_slots_sender,
                         &bank_forks,
+                        shred_notifier.as_ref(),
                     ) {
                         if Self::should_exit_on_error(e, &handle_error) {
                             break;
@@ -489,6 +521,7 @@ impl WindowService {
         retransmit_sender: Sender<Vec<ShredPayload>>,
         outstanding_requests: Arc<RwLock<OutstandingShredRepairs>>,
         accept_repairs_only: bool,
+        shred_notifier: Option<ShredNotifierArc>,
     ) -> JoinHandle<()> {
         let handle_error = || {
             inc_new_counter_error!("solana-window-insert-error", 1, 1);
@@ -522,6 +555,7 @@ impl WindowService {
                         &outstanding_requests,
                         &reed_solomon_cache,
                         accept_repairs_only,
+                        shred_notifier.as_ref(),
                     ) {
                         ws_metrics.record_error(&e);
                         if Self::should_exit_on_error(e, &handle_error) {
@@ -575,7 +609,8 @@ mod test {
             blockstore::{make_many_slot_entries, Blockstore},
             genesis_utils::create_genesis_config,
             get_tmp_ledger_path_auto_delete,
-            shred::{ProcessShredsStats, Shredder},
+            shred::{ProcessShredsStats, ShredFlags, Shredder},
+            shred_notifier_interface::ShredNotifier,
         },
         solana_runtime::bank::Bank,
         solana_sdk::{
@@ -584,8 +619,33 @@ mod test {
             timing::timestamp,
         },
         solana_streamer::socket::SocketAddrSpace,
+        std::{sync::Mutex, time::UNIX_EPOCH},
     };
 
+    #[derive(Default)]
+    struct RecordingShredNotifier {
+        shreds: Mutex<Vec<(Slot, u32, bool, SystemTime)>>,
+        duplicates: Mutex<Vec<(Slot, Vec<u8>, Vec<u8>)>>,
+    }
+
+    impl ShredNotifier for RecordingShredNotifier {
+        fn notify_shred(&self, shred: &Shred, is_repair: bool, arrival_time: SystemTime) {
+            self.shreds.lock().unwrap().push((
+                shred.slot(),
+                shred.index(),
+                is_repair,
+                arrival_time,
+            ));
+        }
+
+        fn notify_duplicate_shred(&self, slot: Slot, shred1: &[u8], shred2: &[u8]) {
+            self.duplicates
+                .lock()
+                .unwrap()
+                .push((slot, shred1.to_vec(), shred2.to_vec()));
+        }
+    }
+
     fn local_entries_to_shred(
         entries: &[Entry],
         slot: Slot,
@@ -653,12 +713,15 @@ mod test {
             Arc::new(keypair),
             SocketAddrSpace::Unspecified,
         );
+        let recording_notifier = Arc::new(RecordingShredNotifier::default());
+        let shred_notifier: ShredNotifierArc = recording_notifier.clone();
         run_check_duplicate(
             &cluster_info,
             &blockstore,
             &receiver,
             &duplicate_slot_sender,
             &bank_forks,
+            Some(&shred_notifier),
         )
         .unwrap();
 
@@ -667,6 +730,16 @@ mod test {
         assert_eq!(duplicate_proof.shred1, *original_shred.payload());
         assert_eq!(duplicate_proof.shred2, *duplicate_shred.payload());
 
+        // Make sure the proof was passed to the shred notifier
+        assert_eq!(
+            *recording_notifier.duplicates.lock().unwrap(),
+            vec![(
+                duplicate_shred_slot,
+                duplicate_shred.payload().clone(),
+                original_shred.payload().clone(),
+            )]
+        );
+
         // Make sure a duplicate signal was sent
         assert_eq!(
             duplicate_slot_receiver.try_recv().unwrap(),
@@ -674,6 +747,75 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_run_insert_notifies_receive_times() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let genesis_config = create_genesis_config(10_000).genesis_config;
+        let bank = Bank::new_for_tests(&genesis_config);
+        let leader_schedule_cache = LeaderScheduleCache::new_from_bank(&bank);
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let shreds: Vec<_> = (0..4)
+            .map(|index| {
+                Shred::new_from_data(
+                    1,     // slot
+                    index, // shred index
+                    1,     // parent offset
+                    &[],   // data
+                    ShredFlags::empty(),
+                    0, // reference_tick
+                    0, // version
+                    0, // fec_set_index
+                )
+            })
+            .collect();
+        let mut packet_batch = PacketBatch::new(vec![Packet::default(); shreds.len()]);
+        for (shred, packet) in shreds.iter().zip(packet_batch.iter_mut()) {
+            shred.copy_to_packet(packet);
+        }
+        // A packet which is not a shred is not notified.
+        packet_batch.push(Packet::default());
+        let first = UNIX_EPOCH + Duration::from_secs(1);
+        let second = UNIX_EPOCH + Duration::from_secs(2);
+        packet_batch.set_receive_time(0..2, first);
+        packet_batch.set_receive_time(2..packet_batch.len(), second);
+        let (verified_sender, verified_receiver) = unbounded();
+        verified_sender.send(vec![packet_batch]).unwrap();
+        let (completed_data_sets_sender, _completed_data_sets_receiver) = unbounded();
+        let (retransmit_sender, _retransmit_receiver) = unbounded();
+        let recording_notifier = Arc::new(RecordingShredNotifier::default());
+        let shred_notifier: ShredNotifierArc = recording_notifier.clone();
+        run_insert(
+            &thread_pool,
+            &verified_receiver,
+            &blockstore,
+            &leader_schedule_cache,
+            |_| (),
+            &mut BlockstoreInsertionMetrics::default(),
+            &mut WindowServiceMetrics::default(),
+            &completed_data_sets_sender,
+            &retransmit_sender,
+            &RwLock::new(OutstandingShredRepairs::default()),
+            &ReedSolomonCache::default(),
+            false, // accept_repairs_only
+            Some(&shred_notifier),
+        )
+        .unwrap();
+
+        let expected: Vec<_> = shreds
+            .iter()
+            .enumerate()
+            .map(|(i, shred)| {
+                let arrival_time = if i < 2 { first } else { second };
+                (shred.slot(), shred.index(), false, arrival_time)
+            })
+            .collect();
+        assert_eq!(*recording_notifier.shreds.lock().unwrap(), expected);
+    }
+
     #[test]
     fn test_store_duplicate_shreds_same_batch() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
@@ -699,6 +841,7 @@ mod test {
             duplicate_shred_receiver,
             duplicate_slot_sender,
             bank_forks,
+            None, // shred_notifier
         );
 
         let handle_duplicate = |shred| {
@@ -768,21 +911,38 @@ mod test {
             .add_request(repair_type, timestamp());
         let repair_meta1 = RepairMeta { nonce };
         let mut repair_infos = vec![None, Some(repair_meta), Some(repair_meta1)];
-        prune_shreds_by_repair_status(&mut shreds, &mut repair_infos, &outstanding_requests, false);
+        let receive_time = |secs| Some(UNIX_EPOCH + Duration::from_secs(secs));
+        let mut receive_times = vec![receive_time(0), receive_time(1), receive_time(2)];
+        prune_shreds_by_repair_status(
+            &mut shreds,
+            &mut repair_infos,
+            &mut receive_times,
+            &outstanding_requests,
+            false,
+        );
         assert_eq!(shreds.len(), 2);
         assert_eq!(repair_infos.len(), 2);
         assert!(repair_infos[0].is_none());
         assert_eq!(repair_infos[1].as_ref().unwrap().nonce, nonce);
+        assert_eq!(receive_times, vec![receive_time(0), receive_time(2)]);
 
         shreds = vec![shred.clone(), shred.clone(), shred];
         let repair_meta2 = RepairMeta { nonce: 0 };
         let repair_meta3 = RepairMeta { nonce };
         repair_infos = vec![None, Some(repair_meta2), Some(repair_meta3)];
+        receive_times = vec![receive_time(0), receive_time(1), receive_time(2)];
         // In wen_restart, we discard all Turbine shreds and only keep valid repair shreds.
-        prune_shreds_by_repair_status(&mut shreds, &mut repair_infos, &outstanding_requests, true);
+        prune_shreds_by_repair_status(
+            &mut shreds,
+            &mut repair_infos,
+            &mut receive_times,
+            &outstanding_requests,
+            true,
+        );
         assert_eq!(shreds.len(), 1);
         assert_eq!(repair_infos.len(), 1);
         assert!(repair_infos[0].is_some());
         assert_eq!(repair_infos[0].as_ref().unwrap().nonce, nonce);
+        assert_eq!(receive_times, vec![receive_time(2)]);
     }
 }
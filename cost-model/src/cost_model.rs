@@ -113,6 +113,7 @@ impl CostModel {
     ) {
         let mut programs_execution_costs = 0u64;
         let mut loaded_accounts_data_size_cost = 0u64;
+        let mut compute_unit_price = 0u64;
         let mut data_bytes_len_total = 0u64;
         let mut compute_unit_limit_is_set = false;
         let mut has_user_space_instructions = false;
@@ -147,6 +148,8 @@ impl CostModel {
         match process_compute_budget_instructions(transaction.message().program_instructions_iter())
         {
             Ok(compute_budget_limits) => {
+                compute_unit_price = compute_budget_limits.compute_unit_price;
+
                 // if tx contained user-space instructions and a more accurate estimate available correct it,
                 // where "user-space instructions" must be specifically checked by
                 // 'compute_unit_limit_is_set' flag, because compute_budget does not distinguish
@@ -172,6 +175,7 @@ impl CostModel {
 
         tx_cost.programs_execution_cost = programs_execution_costs;
         tx_cost.loaded_accounts_data_size_cost = loaded_accounts_data_size_cost;
+        tx_cost.compute_unit_price = compute_unit_price;
         tx_cost.data_bytes_cost = data_bytes_len_total / INSTRUCTION_DATA_BYTES_COST;
     }
 
@@ -9,7 +9,10 @@ use {
     solana_sdk::{
         clock::Slot, pubkey::Pubkey, saturating_add_assign, transaction::TransactionError,
     },
-    std::{cmp::Ordering, collections::HashMap},
+    std::{
+        cmp::{Ordering, Reverse},
+        collections::{BTreeMap, HashMap},
+    },
 };
 
 const WRITABLE_ACCOUNTS_PER_BLOCK: usize = 512;
@@ -61,6 +64,8 @@ pub struct CostTracker {
     transaction_signature_count: u64,
     secp256k1_instruction_signature_count: u64,
     ed25519_instruction_signature_count: u64,
+    /// number of non-vote transactions by compute unit price
+    transaction_count_by_compute_unit_price: BTreeMap<u64, u64>,
 }
 
 impl Default for CostTracker {
@@ -83,6 +88,7 @@ impl Default for CostTracker {
             transaction_signature_count: 0,
             secp256k1_instruction_signature_count: 0,
             ed25519_instruction_signature_count: 0,
+            transaction_count_by_compute_unit_price: BTreeMap::new(),
         }
     }
 }
@@ -141,6 +147,55 @@ impl CostTracker {
         self.transaction_count
     }
 
+    pub fn vote_cost(&self) -> u64 {
+        self.vote_cost
+    }
+
+    /// the `count` writable accounts with the highest cost, costliest first
+    pub fn costliest_writable_accounts(&self, count: usize) -> Vec<(Pubkey, u64)> {
+        let mut accounts: Vec<_> = self
+            .cost_by_writable_accounts
+            .iter()
+            .filter(|(_, &cost)| cost > 0)
+            .map(|(&pubkey, &cost)| (pubkey, cost))
+            .collect();
+        accounts.sort_unstable_by_key(|&(pubkey, cost)| (Reverse(cost), pubkey));
+        accounts.truncate(count);
+        accounts
+    }
+
+    pub fn non_vote_transaction_count(&self) -> u64 {
+        self.transaction_count_by_compute_unit_price.values().sum()
+    }
+
+    /// number of non-vote transactions paying a prioritization fee
+    pub fn prioritized_transaction_count(&self) -> u64 {
+        self.transaction_count_by_compute_unit_price
+            .range(1..)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// the compute unit price at `percentile` (0 to 100) of the non-vote transactions, using the
+    /// nearest-rank method; None if the block has no non-vote transaction
+    pub fn compute_unit_price_percentile(&self, percentile: u8) -> Option<u64> {
+        let count = self.non_vote_transaction_count();
+        if count == 0 {
+            return None;
+        }
+        let rank = (u64::from(percentile.min(100)) * count)
+            .div_ceil(100)
+            .max(1);
+        let mut seen = 0u64;
+        self.transaction_count_by_compute_unit_price
+            .iter()
+            .find(|(_, &price_count)| {
+                seen = seen.saturating_add(price_count);
+                seen >= rank
+            })
+            .map(|(&price, _)| price)
+    }
+
     pub fn report_stats(&self, bank_slot: Slot) {
         // skip reporting if block is empty
         if self.transaction_count == 0 {
@@ -246,6 +301,12 @@ impl CostTracker {
             self.ed25519_instruction_signature_count,
             tx_cost.num_ed25519_instruction_signatures()
         );
+        if !tx_cost.is_simple_vote() {
+            *self
+                .transaction_count_by_compute_unit_price
+                .entry(tx_cost.compute_unit_price())
+                .or_insert(0) += 1;
+        }
     }
 
     fn remove_transaction_cost(&mut self, tx_cost: &TransactionCost) {
@@ -264,6 +325,15 @@ impl CostTracker {
         self.ed25519_instruction_signature_count = self
             .ed25519_instruction_signature_count
             .saturating_sub(tx_cost.num_ed25519_instruction_signatures());
+        if !tx_cost.is_simple_vote() {
+            let price = tx_cost.compute_unit_price();
+            if let Some(count) = self.transaction_count_by_compute_unit_price.get_mut(&price) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    self.transaction_count_by_compute_unit_price.remove(&price);
+                }
+            }
+        }
     }
 
     /// Apply additional actual execution units to cost_tracker
@@ -864,4 +934,56 @@ mod tests {
         assert_eq!(0, cost_tracker.vote_cost);
         assert_eq!(0, cost_tracker.account_data_size);
     }
+
+    #[test]
+    fn test_block_cost_stats() {
+        let mut cost_tracker = CostTracker::default();
+        let (vote_acct, acct1, acct2) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        let vote_cost = TransactionCost::SimpleVote {
+            writable_accounts: vec![vote_acct],
+        };
+        cost_tracker.add_transaction_cost(&vote_cost);
+        assert_eq!(None, cost_tracker.compute_unit_price_percentile(50));
+
+        let tx_costs: Vec<_> = [
+            (vec![acct1], 0),
+            (vec![acct1, acct2], 10),
+            (vec![acct2], 30),
+        ]
+        .into_iter()
+        .map(|(writable_accounts, compute_unit_price)| {
+            TransactionCost::Transaction(UsageCostDetails {
+                writable_accounts,
+                programs_execution_cost: 100,
+                compute_unit_price,
+                ..UsageCostDetails::default()
+            })
+        })
+        .collect();
+        for tx_cost in &tx_costs {
+            cost_tracker.add_transaction_cost(tx_cost);
+        }
+        assert_eq!(vote_cost.sum(), cost_tracker.vote_cost());
+        assert_eq!(4, cost_tracker.transaction_count());
+        assert_eq!(3, cost_tracker.non_vote_transaction_count());
+        assert_eq!(2, cost_tracker.prioritized_transaction_count());
+        assert_eq!(Some(0), cost_tracker.compute_unit_price_percentile(0));
+        assert_eq!(Some(0), cost_tracker.compute_unit_price_percentile(25));
+        assert_eq!(Some(10), cost_tracker.compute_unit_price_percentile(50));
+        assert_eq!(Some(30), cost_tracker.compute_unit_price_percentile(100));
+        assert_eq!(3, cost_tracker.costliest_writable_accounts(10).len());
+
+        cost_tracker.remove_transaction_cost(&tx_costs[2]);
+        assert_eq!(1, cost_tracker.prioritized_transaction_count());
+        assert_eq!(Some(10), cost_tracker.compute_unit_price_percentile(100));
+        assert_eq!(
+            vec![(vote_acct, vote_cost.sum()), (acct1, 200)],
+            cost_tracker.costliest_writable_accounts(2)
+        );
+    }
 }
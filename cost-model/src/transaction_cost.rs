@@ -112,6 +112,13 @@ impl TransactionCost {
             Self::Transaction(usage_cost) => usage_cost.num_ed25519_instruction_signatures,
         }
     }
+
+    pub fn compute_unit_price(&self) -> u64 {
+        match self {
+            Self::SimpleVote { .. } => 0,
+            Self::Transaction(usage_cost) => usage_cost.compute_unit_price,
+        }
+    }
 }
 
 const MAX_WRITABLE_ACCOUNTS: usize = 256;
@@ -129,6 +136,8 @@ pub struct UsageCostDetails {
     pub num_transaction_signatures: u64,
     pub num_secp256k1_instruction_signatures: u64,
     pub num_ed25519_instruction_signatures: u64,
+    /// the prioritization fee per compute unit requested by the transaction, in micro-lamports
+    pub compute_unit_price: u64,
 }
 
 impl Default for UsageCostDetails {
@@ -144,6 +153,7 @@ impl Default for UsageCostDetails {
             num_transaction_signatures: 0u64,
             num_secp256k1_instruction_signatures: 0u64,
             num_ed25519_instruction_signatures: 0u64,
+            compute_unit_price: 0u64,
         }
     }
 }
@@ -165,6 +175,7 @@ impl PartialEq for UsageCostDetails {
             && self.num_secp256k1_instruction_signatures
                 == other.num_secp256k1_instruction_signatures
             && self.num_ed25519_instruction_signatures == other.num_ed25519_instruction_signatures
+            && self.compute_unit_price == other.compute_unit_price
             && to_hash_set(&self.writable_accounts) == to_hash_set(&other.writable_accounts)
     }
 }
@@ -5,11 +5,17 @@
 use {
     solana_sdk::{
         clock::{Slot, UnixTimestamp},
+        pubkey::Pubkey,
         signature::Signature,
         transaction::SanitizedTransaction,
     },
     solana_transaction_status::{Reward, TransactionStatusMeta},
-    std::{any::Any, error, io},
+    std::{
+        any::Any,
+        collections::HashSet,
+        error, io,
+        sync::{Arc, RwLock},
+    },
     thiserror::Error,
 };
 
@@ -159,6 +165,64 @@ pub struct ReplicaTransactionInfoV2<'a> {
     pub index: usize,
 }
 
+/// The state of an account. An account that doesn't exist has zero lamports and no data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ReplicaAccountState<'a> {
+    /// The lamports in the account.
+    pub lamports: u64,
+
+    /// The Pubkey of the owner program account.
+    pub owner: &'a [u8],
+
+    /// This account's data contains a loaded program (and is now read-only).
+    pub executable: bool,
+
+    /// The epoch at which this account will next owe rent.
+    pub rent_epoch: u64,
+
+    /// The data held in this account.
+    pub data: &'a [u8],
+}
+
+/// The states of a writable account of a transaction, before and after the transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ReplicaTransactionAccountState<'a> {
+    /// The Pubkey of the account.
+    pub pubkey: &'a [u8],
+
+    /// The state of the account before the transaction.
+    pub pre: ReplicaAccountState<'a>,
+
+    /// The state of the account after the transaction.
+    pub post: ReplicaAccountState<'a>,
+}
+
+/// Information about a transaction, including the states of its writable accounts
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaTransactionInfoV3<'a> {
+    /// The first signature of the transaction, used for identifying the transaction.
+    pub signature: &'a Signature,
+
+    /// Indicates if the transaction is a simple vote transaction.
+    pub is_vote: bool,
+
+    /// The sanitized transaction.
+    pub transaction: &'a SanitizedTransaction,
+
+    /// Metadata of the transaction status.
+    pub transaction_status_meta: &'a TransactionStatusMeta,
+
+    /// The transaction's index in the block
+    pub index: usize,
+
+    /// The states of the writable accounts owned, before or after the transaction, by one of the
+    /// programs returned by `GeyserPlugin::transaction_account_states_programs`
+    pub account_states: &'a [ReplicaTransactionAccountState<'a>],
+}
+
 /// A wrapper to future-proof ReplicaTransactionInfo handling.
 /// If there were a change to the structure of ReplicaTransactionInfo,
 /// there would be new enum entry for the newer version, forcing
@@ -167,6 +231,7 @@ pub struct ReplicaTransactionInfoV2<'a> {
 pub enum ReplicaTransactionInfoVersions<'a> {
     V0_0_1(&'a ReplicaTransactionInfo<'a>),
     V0_0_2(&'a ReplicaTransactionInfoV2<'a>),
+    V0_0_3(&'a ReplicaTransactionInfoV3<'a>),
 }
 
 #[derive(Clone, Debug)]
@@ -258,6 +323,150 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_3(&'a ReplicaBlockInfoV3<'a>),
 }
 
+/// The estimated cost of a writable account of a block, in compute units
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ReplicaWritableAccountCost<'a> {
+    /// The Pubkey of the account.
+    pub pubkey: &'a [u8],
+
+    /// The estimated compute units of the transactions writing to the account.
+    pub estimated_cost: u64,
+}
+
+/// A percentile of the compute unit prices paid by the non-vote transactions of a block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ReplicaComputeUnitPricePercentile {
+    /// The percentile, from 0 to 100.
+    pub percentile: u8,
+
+    /// The compute unit price at the percentile, in micro-lamports.
+    pub compute_unit_price: u64,
+}
+
+/// The compute units and prioritization fees of a block, as tracked by the cost tracker of its
+/// bank. The costs are the estimates of the cost model, which count the compute unit limit
+/// requested by the transactions rather than the compute units they consumed; the leader only
+/// replaces them with the consumed compute units before `apply_cost_tracker_during_replay` is
+/// active.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaBlockCostInfo<'a> {
+    pub slot: Slot,
+    /// The estimated compute units of all the transactions of the block
+    pub estimated_block_cost: u64,
+    /// The estimated compute units of the vote transactions of the block
+    pub estimated_vote_cost: u64,
+    /// The estimated compute units of the non-vote transactions of the block
+    pub estimated_non_vote_cost: u64,
+    pub transaction_count: u64,
+    pub non_vote_transaction_count: u64,
+    /// The number of non-vote transactions paying a prioritization fee
+    pub prioritized_transaction_count: u64,
+    /// The writable accounts of the block with the highest estimated cost, costliest first
+    pub costliest_writable_accounts: &'a [ReplicaWritableAccountCost<'a>],
+    /// The distribution of the compute unit prices of the non-vote transactions; empty when the
+    /// block has none
+    pub compute_unit_price_percentiles: &'a [ReplicaComputeUnitPricePercentile],
+}
+
+/// A wrapper to future-proof ReplicaBlockCostInfo handling. To make a change to the structure of
+/// ReplicaBlockCostInfo, add an new enum variant wrapping a newer version, which will force plugin
+/// implementations to handle the change.
+#[repr(u32)]
+pub enum ReplicaBlockCostInfoVersions<'a> {
+    V0_0_1(&'a ReplicaBlockCostInfo<'a>),
+}
+
+/// The type of a shred
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ReplicaShredType {
+    /// A data shred, carrying a piece of the serialized entries of a slot
+    Data,
+    /// A coding shred, carrying erasure coding for the data shreds of its FEC set
+    Code,
+}
+
+/// How a shred reached the node
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ShredOrigin {
+    /// Received from the cluster over turbine
+    Turbine,
+    /// Received in response to a repair request
+    Repair,
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaShredInfo {
+    /// The slot the shred belongs to
+    pub slot: Slot,
+    /// The shred's index in the slot, among shreds of the same type
+    pub index: u32,
+    /// The type of the shred
+    pub shred_type: ReplicaShredType,
+    /// The index of the first data shred of the shred's FEC set
+    pub fec_set_index: u32,
+    /// How the shred reached the node
+    pub origin: ShredOrigin,
+    /// When the packet carrying the shred was read off the socket, in microseconds since the
+    /// UNIX epoch
+    pub arrival_timestamp_us: u64,
+}
+
+/// A wrapper to future-proof ReplicaShredInfo handling. To make a change to the structure of
+/// ReplicaShredInfo, add an new enum variant wrapping a newer version, which will force plugin
+/// implementations to handle the change.
+#[repr(u32)]
+pub enum ReplicaShredInfoVersions<'a> {
+    V0_0_1(&'a ReplicaShredInfo),
+}
+
+/// Proof that the leader of a slot produced two conflicting shreds for it.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaDuplicateShredInfo<'a> {
+    /// The slot the conflicting shreds belong to
+    pub slot: Slot,
+    /// The payload of the first shred
+    pub shred1: &'a [u8],
+    /// The payload of the second shred, conflicting with the first
+    pub shred2: &'a [u8],
+}
+
+/// A wrapper to future-proof ReplicaDuplicateShredInfo handling.
+#[repr(u32)]
+pub enum ReplicaDuplicateShredInfoVersions<'a> {
+    V0_0_1(&'a ReplicaDuplicateShredInfo<'a>),
+}
+
+/// Selects the accounts a plugin is notified of. An account matches when it matches every
+/// criterion that is not empty; the default filter matches every account.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountNotificationFilter {
+    /// Addresses of the accounts
+    pub pubkeys: HashSet<Pubkey>,
+    /// Owner programs of the accounts
+    pub owners: HashSet<Pubkey>,
+    /// Sizes of the account data, in bytes
+    pub data_sizes: HashSet<usize>,
+}
+
+impl AccountNotificationFilter {
+    pub fn matches(&self, pubkey: &Pubkey, owner: &Pubkey, data_size: usize) -> bool {
+        (self.pubkeys.is_empty() || self.pubkeys.contains(pubkey))
+            && (self.owners.is_empty() || self.owners.contains(owner))
+            && (self.data_sizes.is_empty() || self.data_sizes.contains(&data_size))
+    }
+}
+
+/// An account notification filter shared by a plugin with the plugin manager. The plugin can
+/// change the filter at any time, and the manager applies it to the following notifications.
+pub type SharedAccountNotificationFilter = Arc<RwLock<AccountNotificationFilter>>;
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 #[repr(u32)]
@@ -315,6 +524,83 @@ impl SlotStatus {
     }
 }
 
+/// The status of a slot reported by `notify_slot_status`, a superset of `SlotStatus`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ReplicaSlotStatus {
+    /// The highest slot of the heaviest fork processed by the node, as in `SlotStatus::Processed`.
+    Processed,
+
+    /// The highest slot having reached max vote lockout.
+    Rooted,
+
+    /// The highest slot that has been voted on by supermajority of the cluster, ie. is confirmed.
+    Confirmed,
+
+    /// A bank was created for the slot, to replay it or to produce it as leader.
+    CreatedBank,
+
+    /// The blockstore received all the shreds of the slot.
+    Completed,
+
+    /// The bank of the slot was frozen; `bank_hash` is set.
+    Frozen,
+
+    /// Replay marked the slot dead; `dead_error` is set. Data notified for the slot, and for the
+    /// slots descending from it, is not part of the ledger.
+    Dead,
+}
+
+impl ReplicaSlotStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReplicaSlotStatus::Processed => "processed",
+            ReplicaSlotStatus::Rooted => "rooted",
+            ReplicaSlotStatus::Confirmed => "confirmed",
+            ReplicaSlotStatus::CreatedBank => "createdbank",
+            ReplicaSlotStatus::Completed => "completed",
+            ReplicaSlotStatus::Frozen => "frozen",
+            ReplicaSlotStatus::Dead => "dead",
+        }
+    }
+
+    /// The `SlotStatus` passed to `update_slot_status` for this status, if any
+    pub fn as_slot_status(&self) -> Option<SlotStatus> {
+        match self {
+            ReplicaSlotStatus::Processed => Some(SlotStatus::Processed),
+            ReplicaSlotStatus::Rooted => Some(SlotStatus::Rooted),
+            ReplicaSlotStatus::Confirmed => Some(SlotStatus::Confirmed),
+            ReplicaSlotStatus::CreatedBank
+            | ReplicaSlotStatus::Completed
+            | ReplicaSlotStatus::Frozen
+            | ReplicaSlotStatus::Dead => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct ReplicaSlotStatusInfo<'a> {
+    /// The slot
+    pub slot: Slot,
+    /// The parent of the slot, when known
+    pub parent: Option<Slot>,
+    /// The new status of the slot
+    pub status: ReplicaSlotStatus,
+    /// The bank hash of the slot, for `ReplicaSlotStatus::Frozen`
+    pub bank_hash: Option<&'a [u8]>,
+    /// Why the slot is dead, for `ReplicaSlotStatus::Dead`
+    pub dead_error: Option<&'a str>,
+}
+
+/// A wrapper to future-proof ReplicaSlotStatusInfo handling. To make a change to the structure of
+/// ReplicaSlotStatusInfo, add an new enum variant wrapping a newer version, which will force plugin
+/// implementations to handle the change.
+#[repr(u32)]
+pub enum ReplicaSlotStatusInfoVersions<'a> {
+    V0_0_1(&'a ReplicaSlotStatusInfo<'a>),
+}
+
 pub type Result<T> = std::result::Result<T, GeyserPluginError>;
 
 /// Defines a Geyser plugin, to stream data from the runtime.
@@ -366,6 +652,24 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     /// Used for doing cleanup before unload.
     fn on_unload(&mut self) {}
 
+    /// The callback called when the admin RPC `updatePluginConfig` asks the plugin to apply a
+    /// new config file without being unloaded, keeping its state and pending notifications.
+    /// The plugin manager is locked during the call, so the validator threads don't notify the
+    /// plugin meanwhile; a plugin configured with a `queue` still receives the notifications
+    /// already queued from its delivery thread. The account notification filter and the
+    /// transaction account states programs are fetched again after a successful update, and
+    /// apply to the following notifications.
+    /// Default is an error -- the plugin has to be reloaded to change its config.
+    fn on_config_update(&self, _config_file: &str) -> Result<()> {
+        Err(GeyserPluginError::Custom(
+            format!(
+                "plugin {} does not support config updates, reload it instead",
+                self.name()
+            )
+            .into(),
+        ))
+    }
+
     /// Called when an account is updated at a slot.
     /// When `is_startup` is true, it indicates the account is loaded from
     /// snapshots when the validator starts up. When `is_startup` is false,
@@ -396,6 +700,18 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called when a slot status is updated, including the statuses `update_slot_status` is not
+    /// called with. The default implementation forwards the statuses known to `SlotStatus` to
+    /// `update_slot_status`; plugins overriding it are not called with `update_slot_status`.
+    fn notify_slot_status(&self, slot_status: ReplicaSlotStatusInfoVersions) -> Result<()> {
+        match slot_status {
+            ReplicaSlotStatusInfoVersions::V0_0_1(info) => match info.status.as_slot_status() {
+                Some(status) => self.update_slot_status(info.slot, info.parent, status),
+                None => Ok(()),
+            },
+        }
+    }
+
     /// Called when a transaction is processed in a slot.
     #[allow(unused_variables)]
     fn notify_transaction(
@@ -418,6 +734,49 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called when a block is frozen, with the estimated cost of its transactions. Replayed
+    /// blocks are only notified once `apply_cost_tracker_during_replay` is active, as their
+    /// costs are not tracked before. Only called when `block_cost_notifications_enabled` returns
+    /// true.
+    #[allow(unused_variables)]
+    fn notify_block_cost(&self, block_cost: ReplicaBlockCostInfoVersions) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when the window service receives a shred, before it is inserted into the
+    /// blockstore. Only called when `shred_notifications_enabled` returns true.
+    #[allow(unused_variables)]
+    fn notify_shred(&self, shred: ReplicaShredInfoVersions) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when the window service detects that the leader of a slot produced conflicting
+    /// shreds. Proofs only received over gossip are not notified. Only called when
+    /// `shred_notifications_enabled` returns true.
+    #[allow(unused_variables)]
+    fn notify_duplicate_shred(
+        &self,
+        duplicate_shred: ReplicaDuplicateShredInfoVersions,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// The accounts the plugin wants `update_account` calls for. Called once after `on_load`;
+    /// the plugin can keep a clone of the returned filter to change it at runtime. Filtered out
+    /// accounts are skipped by the plugin manager without building their account info.
+    /// Default is None -- the plugin is notified of every account.
+    fn account_notification_filter(&self) -> Option<SharedAccountNotificationFilter> {
+        None
+    }
+
+    /// The owner programs of the writable accounts whose states before and after each
+    /// transaction the plugin wants in `ReplicaTransactionInfoV3::account_states`; an empty set
+    /// selects every writable account. Called once after `on_load`.
+    /// Default is None -- the plugin is not sent account states.
+    fn transaction_account_states_programs(&self) -> Option<HashSet<Pubkey>> {
+        None
+    }
+
     /// Check if the plugin is interested in account data
     /// Default is true -- if the plugin is not interested in
     /// account data, please return false.
@@ -425,6 +784,13 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         true
     }
 
+    /// Check if the plugin is interested in slot status updates
+    /// Default is true -- if the plugin is not interested in
+    /// slot status updates, please return false.
+    fn slot_status_notifications_enabled(&self) -> bool {
+        true
+    }
+
     /// Check if the plugin is interested in transaction data
     /// Default is false -- if the plugin is interested in
     /// transaction data, please return true.
@@ -438,4 +804,18 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     fn entry_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// Check if the plugin is interested in shred and duplicate shred notifications
+    /// Default is false -- if the plugin is interested in
+    /// shred data, return true.
+    fn shred_notifications_enabled(&self) -> bool {
+        false
+    }
+
+    /// Check if the plugin is interested in block cost data
+    /// Default is false -- if the plugin is interested in
+    /// block cost data, return true.
+    fn block_cost_notifications_enabled(&self) -> bool {
+        false
+    }
 }
@@ -4,7 +4,7 @@ use {
     log::*,
     solana_accounts_db::{
         account_storage::meta::StoredAccountMeta,
-        accounts_update_notifier_interface::AccountsUpdateNotifierInterface,
+        accounts_update_notifier_interface::{AccountUpdate, AccountsUpdateNotifierInterface},
     },
     solana_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaAccountInfoV3, ReplicaAccountInfoVersions,
@@ -33,30 +33,28 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         pubkey: &Pubkey,
         write_version: u64,
     ) {
-        if let Some(account_info) =
-            self.accountinfo_from_shared_account_data(account, txn, pubkey, write_version)
-        {
-            self.notify_plugins_of_account_update(account_info, slot, false);
-        }
+        self.notify_account_updates(
+            slot,
+            &[AccountUpdate {
+                pubkey,
+                account,
+                txn,
+                write_version,
+            }],
+        );
     }
 
     fn notify_account_restore_from_snapshot(&self, slot: Slot, account: &StoredAccountMeta) {
-        let mut measure_all = Measure::start("geyser-plugin-notify-account-restore-all");
-        let mut measure_copy = Measure::start("geyser-plugin-copy-stored-account-info");
-
-        let account = self.accountinfo_from_stored_account_meta(account);
-        measure_copy.stop();
+        self.notify_accounts_restore_from_snapshot(slot, std::slice::from_ref(account));
+    }
 
-        inc_new_counter_debug!(
-            "geyser-plugin-copy-stored-account-info-us",
-            measure_copy.as_us() as usize,
-            100000,
-            100000
-        );
+    fn notify_account_updates(&self, slot: Slot, updates: &[AccountUpdate]) {
+        self.notify_plugins_of_account_updates(updates, slot, false);
+    }
 
-        if let Some(account_info) = account {
-            self.notify_plugins_of_account_update(account_info, slot, true);
-        }
+    fn notify_accounts_restore_from_snapshot(&self, slot: Slot, accounts: &[StoredAccountMeta]) {
+        let mut measure_all = Measure::start("geyser-plugin-notify-account-restore-all");
+        self.notify_plugins_of_account_updates(accounts, slot, true);
         measure_all.stop();
 
         inc_new_counter_debug!(
@@ -104,44 +102,11 @@ impl AccountsUpdateNotifierImpl {
         AccountsUpdateNotifierImpl { plugin_manager }
     }
 
-    fn accountinfo_from_shared_account_data<'a>(
-        &self,
-        account: &'a AccountSharedData,
-        txn: &'a Option<&'a SanitizedTransaction>,
-        pubkey: &'a Pubkey,
-        write_version: u64,
-    ) -> Option<ReplicaAccountInfoV3<'a>> {
-        Some(ReplicaAccountInfoV3 {
-            pubkey: pubkey.as_ref(),
-            lamports: account.lamports(),
-            owner: account.owner().as_ref(),
-            executable: account.executable(),
-            rent_epoch: account.rent_epoch(),
-            data: account.data(),
-            write_version,
-            txn: *txn,
-        })
-    }
-
-    fn accountinfo_from_stored_account_meta<'a>(
-        &self,
-        stored_account_meta: &'a StoredAccountMeta,
-    ) -> Option<ReplicaAccountInfoV3<'a>> {
-        Some(ReplicaAccountInfoV3 {
-            pubkey: stored_account_meta.pubkey().as_ref(),
-            lamports: stored_account_meta.lamports(),
-            owner: stored_account_meta.owner().as_ref(),
-            executable: stored_account_meta.executable(),
-            rent_epoch: stored_account_meta.rent_epoch(),
-            data: stored_account_meta.data(),
-            write_version: stored_account_meta.write_version(),
-            txn: None,
-        })
-    }
-
-    fn notify_plugins_of_account_update(
+    /// Notifies the plugins whose account notification filter matches each account. The
+    /// account info is only built if a plugin wants the account.
+    fn notify_plugins_of_account_updates(
         &self,
-        account: ReplicaAccountInfoV3,
+        accounts: &[impl NotifiedAccount],
         slot: Slot,
         is_startup: bool,
     ) {
@@ -151,38 +116,45 @@ impl AccountsUpdateNotifierImpl {
         if plugin_manager.plugins.is_empty() {
             return;
         }
-        for plugin in plugin_manager.plugins.iter() {
-            let mut measure = Measure::start("geyser-plugin-update-account");
-            match plugin.update_account(
-                ReplicaAccountInfoVersions::V0_0_3(&account),
-                slot,
-                is_startup,
-            ) {
-                Err(err) => {
-                    error!(
-                        "Failed to update account {} at slot {}, error: {} to plugin {}",
-                        bs58::encode(account.pubkey).into_string(),
-                        slot,
-                        err,
-                        plugin.name()
-                    )
+        for account in accounts {
+            let mut account_info = None;
+            for plugin in plugin_manager.plugins.iter() {
+                if !plugin.wants_account(account.pubkey(), account.owner(), account.data_len()) {
+                    continue;
                 }
-                Ok(_) => {
-                    trace!(
-                        "Successfully updated account {} at slot {} to plugin {}",
-                        bs58::encode(account.pubkey).into_string(),
-                        slot,
-                        plugin.name()
-                    );
+                let account = account_info.get_or_insert_with(|| account.account_info());
+                let mut measure = Measure::start("geyser-plugin-update-account");
+                match plugin.update_account(
+                    ReplicaAccountInfoVersions::V0_0_3(account),
+                    slot,
+                    is_startup,
+                ) {
+                    Err(err) => {
+                        error!(
+                            "Failed to update account {} at slot {}, error: {} to plugin {}",
+                            bs58::encode(account.pubkey).into_string(),
+                            slot,
+                            err,
+                            plugin.name()
+                        )
+                    }
+                    Ok(_) => {
+                        trace!(
+                            "Successfully updated account {} at slot {} to plugin {}",
+                            bs58::encode(account.pubkey).into_string(),
+                            slot,
+                            plugin.name()
+                        );
+                    }
                 }
+                measure.stop();
+                inc_new_counter_debug!(
+                    "geyser-plugin-update-account-us",
+                    measure.as_us() as usize,
+                    100000,
+                    100000
+                );
             }
-            measure.stop();
-            inc_new_counter_debug!(
-                "geyser-plugin-update-account-us",
-                measure.as_us() as usize,
-                100000,
-                100000
-            );
         }
         measure2.stop();
         inc_new_counter_debug!(
@@ -193,3 +165,191 @@ impl AccountsUpdateNotifierImpl {
         );
     }
 }
+
+/// An account the plugins are notified of
+trait NotifiedAccount {
+    fn pubkey(&self) -> &Pubkey;
+    fn owner(&self) -> &Pubkey;
+    fn data_len(&self) -> usize;
+    fn account_info(&self) -> ReplicaAccountInfoV3<'_>;
+}
+
+impl NotifiedAccount for AccountUpdate<'_> {
+    fn pubkey(&self) -> &Pubkey {
+        self.pubkey
+    }
+
+    fn owner(&self) -> &Pubkey {
+        self.account.owner()
+    }
+
+    fn data_len(&self) -> usize {
+        self.account.data().len()
+    }
+
+    fn account_info(&self) -> ReplicaAccountInfoV3<'_> {
+        ReplicaAccountInfoV3 {
+            pubkey: self.pubkey.as_ref(),
+            lamports: self.account.lamports(),
+            owner: self.account.owner().as_ref(),
+            executable: self.account.executable(),
+            rent_epoch: self.account.rent_epoch(),
+            data: self.account.data(),
+            write_version: self.write_version,
+            txn: *self.txn,
+        }
+    }
+}
+
+impl NotifiedAccount for StoredAccountMeta<'_> {
+    fn pubkey(&self) -> &Pubkey {
+        StoredAccountMeta::pubkey(self)
+    }
+
+    fn owner(&self) -> &Pubkey {
+        ReadableAccount::owner(self)
+    }
+
+    fn data_len(&self) -> usize {
+        self.data().len()
+    }
+
+    fn account_info(&self) -> ReplicaAccountInfoV3<'_> {
+        let mut measure_copy = Measure::start("geyser-plugin-copy-stored-account-info");
+        let account_info = ReplicaAccountInfoV3 {
+            pubkey: StoredAccountMeta::pubkey(self).as_ref(),
+            lamports: self.lamports(),
+            owner: ReadableAccount::owner(self).as_ref(),
+            executable: self.executable(),
+            rent_epoch: self.rent_epoch(),
+            data: self.data(),
+            write_version: self.write_version(),
+            txn: None,
+        };
+        measure_copy.stop();
+
+        inc_new_counter_debug!(
+            "geyser-plugin-copy-stored-account-info-us",
+            measure_copy.as_us() as usize,
+            100000,
+            100000
+        );
+        account_info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::geyser_plugin_manager::LoadedGeyserPlugin,
+        solana_geyser_plugin_interface::geyser_plugin_interface::{
+            GeyserPlugin, Result as PluginResult, SharedAccountNotificationFilter,
+        },
+        std::{
+            cell::Cell,
+            sync::{Arc, Mutex},
+        },
+    };
+
+    #[derive(Debug, Default)]
+    struct RecordingPlugin {
+        filter: SharedAccountNotificationFilter,
+        accounts: Arc<Mutex<Vec<Pubkey>>>,
+    }
+
+    impl GeyserPlugin for RecordingPlugin {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn account_notification_filter(&self) -> Option<SharedAccountNotificationFilter> {
+            Some(self.filter.clone())
+        }
+
+        fn update_account(
+            &self,
+            account: ReplicaAccountInfoVersions,
+            _slot: Slot,
+            _is_startup: bool,
+        ) -> PluginResult<()> {
+            let ReplicaAccountInfoVersions::V0_0_3(account) = account else {
+                panic!("unexpected account info version");
+            };
+            self.accounts
+                .lock()
+                .unwrap()
+                .push(Pubkey::try_from(account.pubkey).unwrap());
+            Ok(())
+        }
+    }
+
+    /// Counts how many times its account info is built
+    struct CountingAccount {
+        pubkey: Pubkey,
+        owner: Pubkey,
+        built: Cell<usize>,
+    }
+
+    impl NotifiedAccount for CountingAccount {
+        fn pubkey(&self) -> &Pubkey {
+            &self.pubkey
+        }
+
+        fn owner(&self) -> &Pubkey {
+            &self.owner
+        }
+
+        fn data_len(&self) -> usize {
+            0
+        }
+
+        fn account_info(&self) -> ReplicaAccountInfoV3<'_> {
+            self.built.set(self.built.get() + 1);
+            ReplicaAccountInfoV3 {
+                pubkey: self.pubkey.as_ref(),
+                lamports: 1,
+                owner: self.owner.as_ref(),
+                executable: false,
+                rent_epoch: 0,
+                data: &[],
+                write_version: 0,
+                txn: None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_notify_plugins_of_account_updates() {
+        let (owner, other_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut plugin_manager = GeyserPluginManager::new();
+        let mut recorded = vec![];
+        for _ in 0..2 {
+            let plugin = RecordingPlugin::default();
+            plugin.filter.write().unwrap().owners.insert(owner);
+            recorded.push(plugin.accounts.clone());
+            let mut plugin = LoadedGeyserPlugin::new(Box::new(plugin), None);
+            plugin.load("", false).unwrap();
+            plugin_manager.plugins.push(plugin);
+        }
+        let notifier = AccountsUpdateNotifierImpl::new(Arc::new(RwLock::new(plugin_manager)));
+
+        let accounts = [owner, other_owner, owner].map(|owner| CountingAccount {
+            pubkey: Pubkey::new_unique(),
+            owner,
+            built: Cell::default(),
+        });
+        notifier.notify_plugins_of_account_updates(&accounts, 0, false);
+
+        // The account info is built once for all the plugins that want the account, and not at
+        // all for the account no plugin wants
+        let built: Vec<_> = accounts.iter().map(|account| account.built.get()).collect();
+        assert_eq!(built, [1, 0, 1]);
+        for recorded in recorded {
+            assert_eq!(
+                *recorded.lock().unwrap(),
+                [accounts[0].pubkey, accounts[2].pubkey]
+            );
+        }
+    }
+}
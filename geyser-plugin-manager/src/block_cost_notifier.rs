@@ -0,0 +1,323 @@
+/// Module responsible for notifying plugins of the estimated cost of frozen blocks
+use {
+    crate::{
+        block_cost_notifier_interface::BlockCostNotifier,
+        geyser_plugin_manager::GeyserPluginManager,
+    },
+    log::*,
+    solana_cost_model::cost_tracker::CostTracker,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaBlockCostInfo, ReplicaBlockCostInfoVersions, ReplicaComputeUnitPricePercentile,
+        ReplicaWritableAccountCost,
+    },
+    solana_measure::measure::Measure,
+    solana_metrics::*,
+    solana_sdk::{clock::Slot, pubkey::Pubkey},
+    std::sync::{Arc, RwLock},
+};
+
+/// The number of writable accounts with the highest estimated cost sent with each block
+const COSTLIEST_WRITABLE_ACCOUNTS: usize = 20;
+
+/// The percentiles of the compute unit prices sent with each block
+const COMPUTE_UNIT_PRICE_PERCENTILES: [u8; 9] = [0, 10, 25, 50, 75, 90, 95, 99, 100];
+
+pub(crate) struct BlockCostNotifierImpl {
+    plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+}
+
+impl BlockCostNotifier for BlockCostNotifierImpl {
+    fn notify_block_cost(&self, slot: Slot, cost_tracker: &CostTracker) {
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let costliest_writable_accounts =
+            cost_tracker.costliest_writable_accounts(COSTLIEST_WRITABLE_ACCOUNTS);
+        let costliest_writable_accounts =
+            Self::build_writable_account_costs(&costliest_writable_accounts);
+        let compute_unit_price_percentiles =
+            Self::build_compute_unit_price_percentiles(cost_tracker);
+        let block_cost_info = Self::build_replica_block_cost_info(
+            slot,
+            cost_tracker,
+            &costliest_writable_accounts,
+            &compute_unit_price_percentiles,
+        );
+
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.block_cost_notifications_enabled() {
+                continue;
+            }
+            let mut measure = Measure::start("geyser-plugin-notify-block-cost");
+            match plugin.notify_block_cost(ReplicaBlockCostInfoVersions::V0_0_1(&block_cost_info)) {
+                Err(err) => {
+                    error!(
+                        "Failed to notify block cost at slot {}, error: {} to plugin {}",
+                        slot,
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully notified block cost at slot {} to plugin {}",
+                        slot,
+                        plugin.name()
+                    );
+                }
+            }
+            measure.stop();
+            inc_new_counter_debug!(
+                "geyser-plugin-notify-block-cost-us",
+                measure.as_us() as usize,
+                1000,
+                1000
+            );
+        }
+    }
+}
+
+impl BlockCostNotifierImpl {
+    fn build_writable_account_costs(
+        accounts: &[(Pubkey, u64)],
+    ) -> Vec<ReplicaWritableAccountCost<'_>> {
+        accounts
+            .iter()
+            .map(|(pubkey, estimated_cost)| ReplicaWritableAccountCost {
+                pubkey: pubkey.as_ref(),
+                estimated_cost: *estimated_cost,
+            })
+            .collect()
+    }
+
+    fn build_compute_unit_price_percentiles(
+        cost_tracker: &CostTracker,
+    ) -> Vec<ReplicaComputeUnitPricePercentile> {
+        COMPUTE_UNIT_PRICE_PERCENTILES
+            .iter()
+            .filter_map(|&percentile| {
+                cost_tracker
+                    .compute_unit_price_percentile(percentile)
+                    .map(|compute_unit_price| ReplicaComputeUnitPricePercentile {
+                        percentile,
+                        compute_unit_price,
+                    })
+            })
+            .collect()
+    }
+
+    fn build_replica_block_cost_info<'a>(
+        slot: Slot,
+        cost_tracker: &CostTracker,
+        costliest_writable_accounts: &'a [ReplicaWritableAccountCost<'a>],
+        compute_unit_price_percentiles: &'a [ReplicaComputeUnitPricePercentile],
+    ) -> ReplicaBlockCostInfo<'a> {
+        ReplicaBlockCostInfo {
+            slot,
+            estimated_block_cost: cost_tracker.block_cost(),
+            estimated_vote_cost: cost_tracker.vote_cost(),
+            estimated_non_vote_cost: cost_tracker
+                .block_cost()
+                .saturating_sub(cost_tracker.vote_cost()),
+            transaction_count: cost_tracker.transaction_count(),
+            non_vote_transaction_count: cost_tracker.non_vote_transaction_count(),
+            prioritized_transaction_count: cost_tracker.prioritized_transaction_count(),
+            costliest_writable_accounts,
+            compute_unit_price_percentiles,
+        }
+    }
+
+    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
+        Self { plugin_manager }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::geyser_plugin_manager::LoadedGeyserPlugin,
+        solana_cost_model::transaction_cost::{TransactionCost, UsageCostDetails},
+        solana_geyser_plugin_interface::geyser_plugin_interface::{
+            GeyserPlugin, Result as PluginResult,
+        },
+        std::sync::Mutex,
+    };
+
+    /// A block cost notification, with the data it borrows copied
+    #[derive(Debug)]
+    struct BlockCost {
+        slot: Slot,
+        estimated_block_cost: u64,
+        estimated_vote_cost: u64,
+        estimated_non_vote_cost: u64,
+        transaction_count: u64,
+        non_vote_transaction_count: u64,
+        prioritized_transaction_count: u64,
+        costliest_writable_accounts: Vec<(Pubkey, u64)>,
+        compute_unit_price_percentiles: Vec<(u8, u64)>,
+    }
+
+    #[derive(Debug)]
+    struct RecordingPlugin {
+        name: &'static str,
+        enabled: bool,
+        notifications: Arc<Mutex<Vec<(&'static str, BlockCost)>>>,
+    }
+
+    impl GeyserPlugin for RecordingPlugin {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn notify_block_cost(&self, block_cost: ReplicaBlockCostInfoVersions) -> PluginResult<()> {
+            let ReplicaBlockCostInfoVersions::V0_0_1(info) = block_cost;
+            let block_cost = BlockCost {
+                slot: info.slot,
+                estimated_block_cost: info.estimated_block_cost,
+                estimated_vote_cost: info.estimated_vote_cost,
+                estimated_non_vote_cost: info.estimated_non_vote_cost,
+                transaction_count: info.transaction_count,
+                non_vote_transaction_count: info.non_vote_transaction_count,
+                prioritized_transaction_count: info.prioritized_transaction_count,
+                costliest_writable_accounts: info
+                    .costliest_writable_accounts
+                    .iter()
+                    .map(|account| {
+                        (
+                            Pubkey::try_from(account.pubkey).unwrap(),
+                            account.estimated_cost,
+                        )
+                    })
+                    .collect(),
+                compute_unit_price_percentiles: info
+                    .compute_unit_price_percentiles
+                    .iter()
+                    .map(|percentile| (percentile.percentile, percentile.compute_unit_price))
+                    .collect(),
+            };
+            self.notifications
+                .lock()
+                .unwrap()
+                .push((self.name, block_cost));
+            Ok(())
+        }
+
+        fn block_cost_notifications_enabled(&self) -> bool {
+            self.enabled
+        }
+    }
+
+    fn block_cost_notifier(
+        notifications: &Arc<Mutex<Vec<(&'static str, BlockCost)>>>,
+    ) -> BlockCostNotifierImpl {
+        let mut plugin_manager = GeyserPluginManager::new();
+        for (name, enabled) in [("disabled", false), ("enabled", true)] {
+            let mut plugin = LoadedGeyserPlugin::new(
+                Box::new(RecordingPlugin {
+                    name,
+                    enabled,
+                    notifications: notifications.clone(),
+                }),
+                None,
+            );
+            plugin.load("", false).unwrap();
+            plugin_manager.add_loaded_plugin(plugin);
+        }
+        BlockCostNotifierImpl::new(Arc::new(RwLock::new(plugin_manager)))
+    }
+
+    #[test]
+    fn test_notify_block_cost() {
+        let notifications = Arc::<Mutex<Vec<_>>>::default();
+        let notifier = block_cost_notifier(&notifications);
+
+        // A vote, and a transaction writing to its own account for each compute unit price from
+        // 1 to 25, costing 10_000 compute units per unit of price
+        let mut cost_tracker = CostTracker::default();
+        let vote_cost = TransactionCost::SimpleVote {
+            writable_accounts: vec![Pubkey::new_unique()],
+        };
+        cost_tracker.try_add(&vote_cost).unwrap();
+        let accounts: Vec<_> = (1..=25u64)
+            .map(|price| (Pubkey::new_unique(), price * 10_000))
+            .collect();
+        for (price, (account, cost)) in (1..).zip(&accounts) {
+            let tx_cost = TransactionCost::Transaction(UsageCostDetails {
+                writable_accounts: vec![*account],
+                programs_execution_cost: *cost,
+                compute_unit_price: price,
+                ..UsageCostDetails::default()
+            });
+            cost_tracker.try_add(&tx_cost).unwrap();
+        }
+        notifier.notify_block_cost(7, &cost_tracker);
+
+        let notifications = notifications.lock().unwrap();
+        assert_eq!(notifications.len(), 1);
+        let (name, block_cost) = &notifications[0];
+        assert_eq!(*name, "enabled");
+        assert_eq!(block_cost.slot, 7);
+        let non_vote_cost: u64 = accounts.iter().map(|(_, cost)| cost).sum();
+        assert_eq!(block_cost.estimated_vote_cost, vote_cost.sum());
+        assert_eq!(block_cost.estimated_non_vote_cost, non_vote_cost);
+        assert_eq!(
+            block_cost.estimated_block_cost,
+            non_vote_cost + vote_cost.sum()
+        );
+        assert_eq!(block_cost.transaction_count, 26);
+        assert_eq!(block_cost.non_vote_transaction_count, 25);
+        assert_eq!(block_cost.prioritized_transaction_count, 25);
+
+        // The 20 costliest accounts, costliest first
+        assert_eq!(
+            block_cost.costliest_writable_accounts,
+            accounts.iter().rev().take(20).copied().collect::<Vec<_>>()
+        );
+
+        // Nearest-rank percentiles of the prices 1 to 25
+        assert_eq!(
+            block_cost.compute_unit_price_percentiles,
+            vec![
+                (0, 1),
+                (10, 3),
+                (25, 7),
+                (50, 13),
+                (75, 19),
+                (90, 23),
+                (95, 24),
+                (99, 25),
+                (100, 25),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_notify_block_cost_without_non_vote_transactions() {
+        let notifications = Arc::<Mutex<Vec<_>>>::default();
+        let notifier = block_cost_notifier(&notifications);
+
+        let mut cost_tracker = CostTracker::default();
+        let vote_account = Pubkey::new_unique();
+        let vote_cost = TransactionCost::SimpleVote {
+            writable_accounts: vec![vote_account],
+        };
+        cost_tracker.try_add(&vote_cost).unwrap();
+        notifier.notify_block_cost(8, &cost_tracker);
+
+        let notifications = notifications.lock().unwrap();
+        let (_, block_cost) = &notifications[0];
+        assert_eq!(block_cost.estimated_block_cost, vote_cost.sum());
+        assert_eq!(block_cost.estimated_non_vote_cost, 0);
+        assert_eq!(block_cost.non_vote_transaction_count, 0);
+        assert_eq!(block_cost.prioritized_transaction_count, 0);
+        assert_eq!(
+            block_cost.costliest_writable_accounts,
+            vec![(vote_account, vote_cost.sum())]
+        );
+        // No percentile without non-vote transactions
+        assert!(block_cost.compute_unit_price_percentiles.is_empty());
+    }
+}
@@ -0,0 +1,9 @@
+use {solana_cost_model::cost_tracker::CostTracker, solana_sdk::clock::Slot, std::sync::Arc};
+
+/// Interface for notifying the cost of frozen blocks
+pub trait BlockCostNotifier {
+    /// Notify the cost tracked for the block of `slot` once the block is frozen
+    fn notify_block_cost(&self, slot: Slot, cost_tracker: &CostTracker);
+}
+
+pub type BlockCostNotifierArc = Arc<dyn BlockCostNotifier + Sync + Send>;
@@ -1,11 +1,18 @@
 use {
+    crate::queued_plugin::{PluginQueueConfig, QueuedGeyserPlugin},
     jsonrpc_core::{ErrorCode, Result as JsonRpcResult},
     libloading::Library,
     log::*,
-    solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, Result as PluginResult, SharedAccountNotificationFilter,
+    },
+    solana_ledger::transaction_account_states::AccountStatesPrograms,
+    solana_sdk::pubkey::Pubkey,
     std::{
+        collections::HashSet,
         ops::{Deref, DerefMut},
         path::Path,
+        sync::PoisonError,
     },
     tokio::sync::oneshot::Sender as OneShotSender,
 };
@@ -14,6 +21,8 @@ use {
 pub struct LoadedGeyserPlugin {
     name: String,
     plugin: Box<dyn GeyserPlugin>,
+    account_notification_filter: Option<SharedAccountNotificationFilter>,
+    transaction_account_states_programs: Option<HashSet<Pubkey>>,
 }
 
 impl LoadedGeyserPlugin {
@@ -21,12 +30,65 @@ impl LoadedGeyserPlugin {
         Self {
             name: name.unwrap_or_else(|| plugin.name().to_owned()),
             plugin,
+            account_notification_filter: None,
+            transaction_account_states_programs: None,
         }
     }
 
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Calls `on_load` on the plugin, then fetches its account notification filter and the
+    /// programs of the transaction account states it wants
+    pub fn load(&mut self, config_file: &str, is_reload: bool) -> PluginResult<()> {
+        self.plugin.on_load(config_file, is_reload)?;
+        self.fetch_settings();
+        Ok(())
+    }
+
+    /// Calls `on_config_update` on the plugin, then fetches its settings again
+    pub fn update_config(&mut self, config_file: &str) -> PluginResult<()> {
+        self.plugin.on_config_update(config_file)?;
+        self.fetch_settings();
+        Ok(())
+    }
+
+    fn fetch_settings(&mut self) {
+        self.account_notification_filter = self.plugin.account_notification_filter();
+        self.transaction_account_states_programs =
+            self.plugin.transaction_account_states_programs();
+    }
+
+    /// The owner programs of the transaction account states the plugin wants, see
+    /// `GeyserPlugin::transaction_account_states_programs`
+    pub fn transaction_account_states_programs(&self) -> Option<&HashSet<Pubkey>> {
+        self.transaction_account_states_programs.as_ref()
+    }
+
+    /// Whether the plugin's current account notification filter matches the account; true if
+    /// the plugin has no filter
+    pub fn wants_account(&self, pubkey: &Pubkey, owner: &Pubkey, data_size: usize) -> bool {
+        self.account_notification_filter
+            .as_ref()
+            .map_or(true, |filter| {
+                filter
+                    .read()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .matches(pubkey, owner, data_size)
+            })
+    }
+
+    /// Delivers the notifications to the plugin from a dedicated thread through a bounded queue
+    pub(crate) fn with_queue(self, config: PluginQueueConfig) -> Self {
+        let plugin = QueuedGeyserPlugin::new(self.name.clone(), self.plugin, config);
+        Self {
+            name: self.name,
+            plugin: Box::new(plugin),
+            account_notification_filter: self.account_notification_filter,
+            transaction_account_states_programs: self.transaction_account_states_programs,
+        }
+    }
 }
 
 impl Deref for LoadedGeyserPlugin {
@@ -46,7 +108,11 @@ impl DerefMut for LoadedGeyserPlugin {
 #[derive(Default, Debug)]
 pub struct GeyserPluginManager {
     pub plugins: Vec<LoadedGeyserPlugin>,
-    libs: Vec<Library>,
+    /// The library each plugin was loaded from; `None` for remote plugins
+    libs: Vec<Option<Library>>,
+    /// The union of the transaction account states programs of the plugins, updated as plugins
+    /// are loaded and unloaded
+    account_states_programs: AccountStatesPrograms,
 }
 
 impl GeyserPluginManager {
@@ -54,6 +120,7 @@ impl GeyserPluginManager {
         GeyserPluginManager {
             plugins: Vec::default(),
             libs: Vec::default(),
+            account_states_programs: AccountStatesPrograms::default(),
         }
     }
 
@@ -68,6 +135,7 @@ impl GeyserPluginManager {
         for lib in self.libs.drain(..) {
             drop(lib);
         }
+        self.update_account_states_programs();
     }
 
     /// Check if there is any plugin interested in account data
@@ -90,6 +158,48 @@ impl GeyserPluginManager {
         false
     }
 
+    /// The union of the programs of the transaction account states wanted by the plugins
+    /// interested in transaction data; an empty set selects every account
+    pub fn transaction_account_states_programs(&self) -> Option<HashSet<Pubkey>> {
+        let mut union: Option<HashSet<Pubkey>> = None;
+        for plugin in &self.plugins {
+            if !plugin.transaction_notifications_enabled() {
+                continue;
+            }
+            let Some(programs) = plugin.transaction_account_states_programs() else {
+                continue;
+            };
+            match &mut union {
+                // An empty set already selects every account
+                Some(union) if union.is_empty() => {}
+                Some(_) if programs.is_empty() => union = Some(HashSet::new()),
+                Some(union) => union.extend(programs),
+                None => union = Some(programs.clone()),
+            }
+        }
+        union
+    }
+
+    /// A handle to `transaction_account_states_programs`, which follows the loaded plugins
+    pub fn shared_account_states_programs(&self) -> AccountStatesPrograms {
+        self.account_states_programs.clone()
+    }
+
+    fn update_account_states_programs(&self) {
+        self.account_states_programs
+            .set(self.transaction_account_states_programs());
+    }
+
+    /// Check if there is any plugin interested in slot status updates
+    pub fn slot_status_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.slot_status_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
     /// Check if there is any plugin interested in entry data
     pub fn entry_notifications_enabled(&self) -> bool {
         for plugin in &self.plugins {
@@ -100,6 +210,26 @@ impl GeyserPluginManager {
         false
     }
 
+    /// Check if there is any plugin interested in shred data
+    pub fn shred_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.shred_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Check if there is any plugin interested in block cost data
+    pub fn block_cost_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.block_cost_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
     /// Admin RPC request handler
     pub(crate) fn list_plugins(&self) -> JsonRpcResult<Vec<String>> {
         Ok(self.plugins.iter().map(|p| p.name().to_owned()).collect())
@@ -147,7 +277,7 @@ impl GeyserPluginManager {
 
         // Call on_load and push plugin
         new_plugin
-            .on_load(new_config_file, false)
+            .load(new_config_file, false)
             .map_err(|on_load_err| jsonrpc_core::Error {
                 code: ErrorCode::InvalidRequest,
                 message: format!(
@@ -159,10 +289,18 @@ impl GeyserPluginManager {
         let name = new_plugin.name().to_string();
         self.plugins.push(new_plugin);
         self.libs.push(new_lib);
+        self.update_account_states_programs();
 
         Ok(name)
     }
 
+    /// Adds a plugin that is already loaded and is not backed by a library
+    pub(crate) fn add_loaded_plugin(&mut self, plugin: LoadedGeyserPlugin) {
+        self.plugins.push(plugin);
+        self.libs.push(None);
+        self.update_account_states_programs();
+    }
+
     pub(crate) fn unload_plugin(&mut self, name: &str) -> JsonRpcResult<()> {
         // Check if any plugin names match this one
         let Some(idx) = self
@@ -234,11 +372,12 @@ impl GeyserPluginManager {
         setup_logger_for_plugin(&*new_plugin.plugin)?;
 
         // Attempt to on_load with new plugin
-        match new_plugin.on_load(new_parsed_config_file, true) {
+        match new_plugin.load(new_parsed_config_file, true) {
             // On success, push plugin and library
             Ok(()) => {
                 self.plugins.push(new_plugin);
                 self.libs.push(new_lib);
+                self.update_account_states_programs();
             }
 
             // On failure, return error
@@ -256,6 +395,37 @@ impl GeyserPluginManager {
         Ok(())
     }
 
+    /// Admin RPC request handler
+    /// Passes a new config file to the plugin with the given `name`, which applies it without
+    /// being unloaded
+    pub(crate) fn update_plugin_config(
+        &mut self,
+        name: &str,
+        config_file: &str,
+    ) -> JsonRpcResult<()> {
+        let Some(plugin) = self
+            .plugins
+            .iter_mut()
+            .find(|plugin| plugin.name().eq(name))
+        else {
+            return Err(jsonrpc_core::error::Error {
+                code: ErrorCode::InvalidRequest,
+                message: String::from("The plugin you requested to update is not loaded"),
+                data: None,
+            });
+        };
+
+        plugin
+            .update_config(config_file)
+            .map_err(|err| jsonrpc_core::Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!("on_config_update method of plugin {name} failed: {err}"),
+                data: None,
+            })?;
+        self.update_account_states_programs();
+        Ok(())
+    }
+
     fn _drop_plugin(&mut self, idx: usize) {
         let current_lib = self.libs.remove(idx);
         let mut current_plugin = self.plugins.remove(idx);
@@ -264,6 +434,7 @@ impl GeyserPluginManager {
         // The plugin must be dropped before the library to avoid a crash.
         drop(current_plugin);
         drop(current_lib);
+        self.update_account_states_programs();
         info!("Unloaded plugin {name} at idx {idx}");
     }
 }
@@ -289,6 +460,11 @@ pub enum GeyserPluginManagerRequest {
         config_file: String,
         response_sender: OneShotSender<JsonRpcResult<()>>,
     },
+    UpdatePluginConfig {
+        name: String,
+        config_file: String,
+        response_sender: OneShotSender<JsonRpcResult<()>>,
+    },
     UnloadPlugin {
         name: String,
         response_sender: OneShotSender<JsonRpcResult<()>>,
@@ -327,6 +503,12 @@ pub enum GeyserPluginManagerError {
 
     #[error("The GeyserPlugin on_load method failed (error: {0})")]
     PluginStartError(String),
+
+    #[error("Invalid remote plugin config (error: {0})")]
+    InvalidRemotePluginConfig(String),
+
+    #[error("Invalid plugin queue config (error: {0})")]
+    InvalidQueueConfig(String),
 }
 
 /// # Safety
@@ -334,12 +516,13 @@ pub enum GeyserPluginManagerError {
 /// This function loads the dynamically linked library specified in the path. The library
 /// must do necessary initializations.
 ///
-/// This returns the geyser plugin, the dynamic library, and the parsed config file as a &str.
+/// This returns the geyser plugin, the dynamic library (`None` for remote plugins), and the
+/// parsed config file as a &str.
 /// (The geyser plugin interface requires a &str for the on_load method).
 #[cfg(not(test))]
 pub(crate) fn load_plugin_from_config(
     geyser_plugin_config_file: &Path,
-) -> Result<(LoadedGeyserPlugin, Library, &str), GeyserPluginManagerError> {
+) -> Result<(LoadedGeyserPlugin, Option<Library>, &str), GeyserPluginManagerError> {
     use std::{fs::File, io::Read, path::PathBuf};
     type PluginConstructor = unsafe fn() -> *mut dyn GeyserPlugin;
     use libloading::Symbol;
@@ -369,19 +552,6 @@ pub(crate) fn load_plugin_from_config(
         }
     };
 
-    let libpath = result["libpath"]
-        .as_str()
-        .ok_or(GeyserPluginManagerError::LibPathNotSet)?;
-    let mut libpath = PathBuf::from(libpath);
-    if libpath.is_relative() {
-        let config_dir = geyser_plugin_config_file.parent().ok_or_else(|| {
-            GeyserPluginManagerError::CannotOpenConfigFile(format!(
-                "Failed to resolve parent of {geyser_plugin_config_file:?}",
-            ))
-        })?;
-        libpath = config_dir.join(libpath);
-    }
-
     let plugin_name = result["name"].as_str().map(|s| s.to_owned());
 
     let config_file = geyser_plugin_config_file
@@ -389,20 +559,59 @@ pub(crate) fn load_plugin_from_config(
         .to_str()
         .ok_or(GeyserPluginManagerError::InvalidPluginPath)?;
 
-    let (plugin, lib) = unsafe {
-        let lib = Library::new(libpath)
-            .map_err(|e| GeyserPluginManagerError::PluginLoadError(e.to_string()))?;
-        let constructor: Symbol<PluginConstructor> = lib
-            .get(b"_create_plugin")
-            .map_err(|e| GeyserPluginManagerError::PluginLoadError(e.to_string()))?;
-        let plugin_raw = constructor();
-        (Box::from_raw(plugin_raw), lib)
+    // Remote plugins run in another process and are fed over a socket instead of a library
+    let (plugin, lib) = match result.get("remote") {
+        #[cfg(unix)]
+        Some(remote) => (
+            crate::remote_plugin::load_remote_plugin(
+                geyser_plugin_config_file,
+                plugin_name,
+                remote,
+            )?,
+            None,
+        ),
+        #[cfg(not(unix))]
+        Some(remote) => {
+            return Err(GeyserPluginManagerError::InvalidRemotePluginConfig(
+                format!("Remote plugins are not supported on this platform: {remote}"),
+            ));
+        }
+        None => {
+            let libpath = result["libpath"]
+                .as_str()
+                .ok_or(GeyserPluginManagerError::LibPathNotSet)?;
+            let mut libpath = PathBuf::from(libpath);
+            if libpath.is_relative() {
+                let config_dir = geyser_plugin_config_file.parent().ok_or_else(|| {
+                    GeyserPluginManagerError::CannotOpenConfigFile(format!(
+                        "Failed to resolve parent of {geyser_plugin_config_file:?}",
+                    ))
+                })?;
+                libpath = config_dir.join(libpath);
+            }
+
+            let (plugin, lib) = unsafe {
+                let lib = Library::new(libpath)
+                    .map_err(|e| GeyserPluginManagerError::PluginLoadError(e.to_string()))?;
+                let constructor: Symbol<PluginConstructor> = lib
+                    .get(b"_create_plugin")
+                    .map_err(|e| GeyserPluginManagerError::PluginLoadError(e.to_string()))?;
+                let plugin_raw = constructor();
+                (Box::from_raw(plugin_raw), lib)
+            };
+            (LoadedGeyserPlugin::new(plugin, plugin_name), Some(lib))
+        }
     };
-    Ok((
-        LoadedGeyserPlugin::new(plugin, plugin_name),
-        lib,
-        config_file,
-    ))
+
+    // Optionally deliver the notifications from a dedicated thread
+    let plugin = match result.get("queue") {
+        Some(queue) => plugin.with_queue(crate::queued_plugin::parse_queue_config(
+            geyser_plugin_config_file,
+            queue,
+        )?),
+        None => plugin,
+    };
+    Ok((plugin, lib, config_file))
 }
 
 #[cfg(test)]
@@ -418,7 +627,7 @@ const TESTPLUGIN2_CONFIG: &str = "TESTPLUGIN2_CONFIG";
 #[cfg(test)]
 pub(crate) fn load_plugin_from_config(
     geyser_plugin_config_file: &Path,
-) -> Result<(LoadedGeyserPlugin, Library, &str), GeyserPluginManagerError> {
+) -> Result<(LoadedGeyserPlugin, Option<Library>, &str), GeyserPluginManagerError> {
     if geyser_plugin_config_file.ends_with(TESTPLUGIN_CONFIG) {
         Ok(tests::dummy_plugin_and_library(
             tests::TestPlugin,
@@ -443,21 +652,27 @@ mod tests {
             GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
         },
         libloading::Library,
-        solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
-        std::sync::{Arc, RwLock},
+        solana_geyser_plugin_interface::geyser_plugin_interface::{
+            GeyserPlugin, Result as PluginResult, SharedAccountNotificationFilter,
+        },
+        solana_sdk::pubkey::Pubkey,
+        std::{
+            collections::HashSet,
+            sync::{Arc, RwLock},
+        },
     };
 
     pub(super) fn dummy_plugin_and_library<P: GeyserPlugin>(
         plugin: P,
         config_path: &'static str,
-    ) -> (LoadedGeyserPlugin, Library, &'static str) {
+    ) -> (LoadedGeyserPlugin, Option<Library>, &'static str) {
         #[cfg(unix)]
         let library = libloading::os::unix::Library::this();
         #[cfg(windows)]
         let library = libloading::os::windows::Library::this().unwrap();
         (
             LoadedGeyserPlugin::new(Box::new(plugin), None),
-            Library::from(library),
+            Some(Library::from(library)),
             config_path,
         )
     }
@@ -499,7 +714,7 @@ mod tests {
 
         // Mock having loaded plugin (TestPlugin)
         let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
-        plugin.on_load(config, false).unwrap();
+        plugin.load(config, false).unwrap();
         plugin_manager_lock.plugins.push(plugin);
         plugin_manager_lock.libs.push(lib);
         // plugin_manager_lock.libs.push(lib);
@@ -525,6 +740,74 @@ mod tests {
         assert!(!plugins.iter().any(|name| name.eq(DUMMY_NAME)));
     }
 
+    #[derive(Debug, Default)]
+    struct UpdatablePlugin {
+        config_file: RwLock<String>,
+    }
+
+    impl GeyserPlugin for UpdatablePlugin {
+        fn name(&self) -> &'static str {
+            DUMMY_NAME
+        }
+
+        fn on_config_update(&self, config_file: &str) -> PluginResult<()> {
+            *self.config_file.write().unwrap() = config_file.to_string();
+            Ok(())
+        }
+
+        fn transaction_account_states_programs(&self) -> Option<HashSet<Pubkey>> {
+            (*self.config_file.read().unwrap() == TESTPLUGIN2_CONFIG).then(HashSet::new)
+        }
+    }
+
+    #[test]
+    fn test_geyser_update_plugin_config() {
+        let mut plugin_manager = GeyserPluginManager::new();
+        let update_result = plugin_manager.update_plugin_config(DUMMY_NAME, TESTPLUGIN2_CONFIG);
+        assert_eq!(
+            update_result.unwrap_err().message,
+            "The plugin you requested to update is not loaded"
+        );
+
+        // Plugins not implementing on_config_update have to be reloaded
+        let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
+        plugin.load(config, false).unwrap();
+        plugin_manager.plugins.push(plugin);
+        plugin_manager.libs.push(lib);
+        let update_result = plugin_manager.update_plugin_config(DUMMY_NAME, TESTPLUGIN2_CONFIG);
+        assert!(update_result
+            .unwrap_err()
+            .message
+            .contains("does not support config updates"));
+        plugin_manager.unload_plugin(DUMMY_NAME).unwrap();
+
+        // The settings of the plugin are fetched again after the update
+        let (mut plugin, lib, config) =
+            dummy_plugin_and_library(UpdatablePlugin::default(), DUMMY_CONFIG);
+        plugin.load(config, false).unwrap();
+        plugin_manager.plugins.push(plugin);
+        plugin_manager.libs.push(lib);
+        plugin_manager.update_account_states_programs();
+        let account_states_programs = plugin_manager.shared_account_states_programs();
+        assert!(plugin_manager.plugins[0]
+            .transaction_account_states_programs()
+            .is_none());
+        assert!(account_states_programs.get().is_none());
+        plugin_manager
+            .update_plugin_config(DUMMY_NAME, TESTPLUGIN2_CONFIG)
+            .unwrap();
+        assert_eq!(
+            plugin_manager.plugins[0].transaction_account_states_programs(),
+            Some(&HashSet::new())
+        );
+        // The transaction status service sends the account states the plugin now wants
+        assert_eq!(
+            account_states_programs.get().as_deref(),
+            Some(&HashSet::new())
+        );
+        assert_eq!(plugin_manager.list_plugins().unwrap(), vec![DUMMY_NAME]);
+    }
+
     #[test]
     fn test_plugin_list() {
         // Initialize empty manager
@@ -534,12 +817,12 @@ mod tests {
         // Load two plugins
         // First
         let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin, TESTPLUGIN_CONFIG);
-        plugin.on_load(config, false).unwrap();
+        plugin.load(config, false).unwrap();
         plugin_manager_lock.plugins.push(plugin);
         plugin_manager_lock.libs.push(lib);
         // Second
         let (mut plugin, lib, config) = dummy_plugin_and_library(TestPlugin2, TESTPLUGIN2_CONFIG);
-        plugin.on_load(config, false).unwrap();
+        plugin.load(config, false).unwrap();
         plugin_manager_lock.plugins.push(plugin);
         plugin_manager_lock.libs.push(lib);
 
@@ -565,4 +848,154 @@ mod tests {
         assert!(unload_result.is_ok());
         assert_eq!(plugin_manager_lock.plugins.len(), 0);
     }
+
+    #[derive(Debug, Default)]
+    struct FilteredPlugin {
+        filter: SharedAccountNotificationFilter,
+    }
+
+    impl GeyserPlugin for FilteredPlugin {
+        fn name(&self) -> &'static str {
+            "filtered"
+        }
+
+        fn account_notification_filter(&self) -> Option<SharedAccountNotificationFilter> {
+            Some(self.filter.clone())
+        }
+    }
+
+    #[test]
+    fn test_account_notification_filter() {
+        let (pubkey, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let wanted = |plugin: &LoadedGeyserPlugin, pubkey, owner, data_size| {
+            plugin.wants_account(pubkey, owner, data_size)
+        };
+
+        // Plugins without a filter want every account
+        let (mut plugin, _lib, config) = dummy_plugin_and_library(TestPlugin, DUMMY_CONFIG);
+        plugin.load(config, false).unwrap();
+        assert!(wanted(&plugin, &pubkey, &owner, 0));
+
+        let filter = SharedAccountNotificationFilter::default();
+        filter.write().unwrap().owners.insert(owner);
+        let mut plugin = LoadedGeyserPlugin::new(
+            Box::new(FilteredPlugin {
+                filter: filter.clone(),
+            }),
+            None,
+        );
+        plugin.load(DUMMY_CONFIG, false).unwrap();
+        assert!(wanted(&plugin, &pubkey, &owner, 0));
+        assert!(!wanted(&plugin, &owner, &pubkey, 0));
+
+        // The plugin changes its filter at runtime
+        filter.write().unwrap().data_sizes.insert(165);
+        assert!(!wanted(&plugin, &pubkey, &owner, 0));
+        assert!(wanted(&plugin, &pubkey, &owner, 165));
+        filter.write().unwrap().pubkeys.insert(Pubkey::new_unique());
+        assert!(!wanted(&plugin, &pubkey, &owner, 165));
+
+        // A filter poisoned by a panicking plugin thread is still read
+        let _ = std::thread::spawn(move || {
+            let _guard = filter.write().unwrap();
+            panic!("poison the filter");
+        })
+        .join();
+        assert!(!wanted(&plugin, &pubkey, &owner, 165));
+        assert!(!wanted(&plugin, &pubkey, &owner, 0));
+    }
+
+    #[derive(Debug)]
+    struct AccountStatesPlugin {
+        programs: HashSet<Pubkey>,
+    }
+
+    impl GeyserPlugin for AccountStatesPlugin {
+        fn name(&self) -> &'static str {
+            "account-states"
+        }
+
+        fn transaction_account_states_programs(&self) -> Option<HashSet<Pubkey>> {
+            Some(self.programs.clone())
+        }
+
+        fn transaction_notifications_enabled(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_transaction_account_states_programs() {
+        let (program1, program2) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut plugin_manager = GeyserPluginManager::new();
+        // Taken before any plugin is loaded, as the validator does at startup
+        let account_states_programs = plugin_manager.shared_account_states_programs();
+        assert_eq!(account_states_programs.get(), None);
+        let mut add_plugin = |programs: HashSet<Pubkey>| {
+            let mut plugin =
+                LoadedGeyserPlugin::new(Box::new(AccountStatesPlugin { programs }), None);
+            plugin.load(DUMMY_CONFIG, false).unwrap();
+            plugin_manager.add_loaded_plugin(plugin);
+            let union = plugin_manager.transaction_account_states_programs();
+            assert_eq!(
+                account_states_programs.get().as_deref(),
+                union.as_ref(),
+                "the shared programs follow the loaded plugins"
+            );
+            union
+        };
+        assert_eq!(
+            add_plugin(HashSet::from([program1])),
+            Some(HashSet::from([program1]))
+        );
+        assert_eq!(
+            add_plugin(HashSet::from([program2])),
+            Some(HashSet::from([program1, program2]))
+        );
+        assert_eq!(add_plugin(HashSet::new()), Some(HashSet::new()));
+        assert_eq!(add_plugin(HashSet::from([program1])), Some(HashSet::new()));
+
+        plugin_manager.unload();
+        assert_eq!(account_states_programs.get(), None);
+    }
+
+    #[derive(Debug)]
+    struct SlotStatusPlugin {
+        slot_status_notifications_enabled: bool,
+    }
+
+    impl GeyserPlugin for SlotStatusPlugin {
+        fn name(&self) -> &'static str {
+            DUMMY_NAME
+        }
+
+        fn slot_status_notifications_enabled(&self) -> bool {
+            self.slot_status_notifications_enabled
+        }
+
+        fn account_data_notifications_enabled(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_slot_status_notifications_enabled() {
+        let mut plugin_manager = GeyserPluginManager::new();
+        assert!(!plugin_manager.slot_status_notifications_enabled());
+
+        // Slot statuses are wanted without any account, transaction or entry notifications
+        let mut add_plugin = |slot_status_notifications_enabled| {
+            let mut plugin = LoadedGeyserPlugin::new(
+                Box::new(SlotStatusPlugin {
+                    slot_status_notifications_enabled,
+                }),
+                None,
+            );
+            plugin.load(DUMMY_CONFIG, false).unwrap();
+            plugin_manager.plugins.push(plugin);
+            plugin_manager.slot_status_notifications_enabled()
+        };
+        assert!(!add_plugin(false));
+        assert!(add_plugin(true));
+    }
 }
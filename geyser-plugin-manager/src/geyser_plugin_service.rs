@@ -1,18 +1,25 @@
 use {
     crate::{
         accounts_update_notifier::AccountsUpdateNotifierImpl,
+        block_cost_notifier::BlockCostNotifierImpl,
+        block_cost_notifier_interface::BlockCostNotifierArc,
         block_metadata_notifier::BlockMetadataNotifierImpl,
         block_metadata_notifier_interface::BlockMetadataNotifierArc,
         entry_notifier::EntryNotifierImpl,
-        geyser_plugin_manager::{GeyserPluginManager, GeyserPluginManagerRequest},
-        slot_status_notifier::SlotStatusNotifierImpl,
+        geyser_plugin_manager::{
+            GeyserPluginManager, GeyserPluginManagerRequest, LoadedGeyserPlugin,
+        },
+        shred_notifier::ShredNotifierImpl,
+        slot_status_notifier::{SlotStatusNotifier, SlotStatusNotifierImpl},
         slot_status_observer::SlotStatusObserver,
         transaction_notifier::TransactionNotifierImpl,
     },
     crossbeam_channel::Receiver,
     log::*,
     solana_accounts_db::accounts_update_notifier_interface::AccountsUpdateNotifier,
-    solana_ledger::entry_notifier_interface::EntryNotifierArc,
+    solana_ledger::{
+        entry_notifier_interface::EntryNotifierArc, shred_notifier_interface::ShredNotifierArc,
+    },
     solana_rpc::{
         optimistically_confirmed_bank_tracker::SlotNotification,
         transaction_notifier_interface::TransactionNotifierArc,
@@ -36,7 +43,10 @@ pub struct GeyserPluginService {
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     transaction_notifier: Option<TransactionNotifierArc>,
     entry_notifier: Option<EntryNotifierArc>,
+    shred_notifier: Option<ShredNotifierArc>,
+    slot_status_notifier: Option<SlotStatusNotifier>,
     block_metadata_notifier: Option<BlockMetadataNotifierArc>,
+    block_cost_notifier: Option<BlockCostNotifierArc>,
 }
 
 impl GeyserPluginService {
@@ -77,10 +87,41 @@ impl GeyserPluginService {
             Self::load_plugin(&mut plugin_manager, geyser_plugin_config_file)?;
         }
 
+        Ok(Self::start(
+            plugin_manager,
+            confirmed_bank_receiver,
+            rpc_to_plugin_manager_receiver_and_exit,
+        ))
+    }
+
+    /// Creates the GeyserPluginService for plugins that are already loaded, for example by a
+    /// program that links them in rather than loading them from config files
+    pub fn new_with_plugins(
+        confirmed_bank_receiver: Receiver<SlotNotification>,
+        plugins: Vec<LoadedGeyserPlugin>,
+    ) -> Self {
+        let mut plugin_manager = GeyserPluginManager::new();
+        for plugin in plugins {
+            plugin_manager.add_loaded_plugin(plugin);
+        }
+        Self::start(plugin_manager, confirmed_bank_receiver, None)
+    }
+
+    fn start(
+        plugin_manager: GeyserPluginManager,
+        confirmed_bank_receiver: Receiver<SlotNotification>,
+        rpc_to_plugin_manager_receiver_and_exit: Option<(
+            Receiver<GeyserPluginManagerRequest>,
+            Arc<AtomicBool>,
+        )>,
+    ) -> Self {
         let account_data_notifications_enabled =
             plugin_manager.account_data_notifications_enabled();
+        let slot_status_notifications_enabled = plugin_manager.slot_status_notifications_enabled();
         let transaction_notifications_enabled = plugin_manager.transaction_notifications_enabled();
         let entry_notifications_enabled = plugin_manager.entry_notifications_enabled();
+        let shred_notifications_enabled = plugin_manager.shred_notifications_enabled();
+        let block_cost_notifications_enabled = plugin_manager.block_cost_notifications_enabled();
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
 
         let accounts_update_notifier: Option<AccountsUpdateNotifier> =
@@ -107,28 +148,51 @@ impl GeyserPluginService {
             None
         };
 
-        let (slot_status_observer, block_metadata_notifier): (
-            Option<SlotStatusObserver>,
-            Option<BlockMetadataNotifierArc>,
-        ) = if account_data_notifications_enabled
-            || transaction_notifications_enabled
-            || entry_notifications_enabled
+        let shred_notifier: Option<ShredNotifierArc> = if shred_notifications_enabled {
+            let shred_notifier = ShredNotifierImpl::new(plugin_manager.clone());
+            Some(Arc::new(shred_notifier))
+        } else {
+            None
+        };
+
+        let block_cost_notifier: Option<BlockCostNotifierArc> = if block_cost_notifications_enabled
         {
+            let block_cost_notifier = BlockCostNotifierImpl::new(plugin_manager.clone());
+            Some(Arc::new(block_cost_notifier))
+        } else {
+            None
+        };
+
+        let (slot_status_observer, slot_status_notifier): (
+            Option<SlotStatusObserver>,
+            Option<SlotStatusNotifier>,
+        ) = if slot_status_notifications_enabled {
             let slot_status_notifier = SlotStatusNotifierImpl::new(plugin_manager.clone());
-            let slot_status_notifier = Arc::new(RwLock::new(slot_status_notifier));
+            let slot_status_notifier: SlotStatusNotifier =
+                Arc::new(RwLock::new(slot_status_notifier));
             (
                 Some(SlotStatusObserver::new(
                     confirmed_bank_receiver,
-                    slot_status_notifier,
+                    slot_status_notifier.clone(),
                 )),
-                Some(Arc::new(BlockMetadataNotifierImpl::new(
-                    plugin_manager.clone(),
-                ))),
+                Some(slot_status_notifier),
             )
         } else {
             (None, None)
         };
 
+        let block_metadata_notifier: Option<BlockMetadataNotifierArc> =
+            if account_data_notifications_enabled
+                || transaction_notifications_enabled
+                || entry_notifications_enabled
+            {
+                Some(Arc::new(BlockMetadataNotifierImpl::new(
+                    plugin_manager.clone(),
+                )))
+            } else {
+                None
+            };
+
         // Initialize plugin manager rpc handler thread if needed
         if let Some((request_receiver, exit)) = rpc_to_plugin_manager_receiver_and_exit {
             let plugin_manager = plugin_manager.clone();
@@ -136,14 +200,17 @@ impl GeyserPluginService {
         };
 
         info!("Started GeyserPluginService");
-        Ok(GeyserPluginService {
+        GeyserPluginService {
             slot_status_observer,
             plugin_manager,
             accounts_update_notifier,
             transaction_notifier,
             entry_notifier,
+            shred_notifier,
+            slot_status_notifier,
             block_metadata_notifier,
-        })
+            block_cost_notifier,
+        }
     }
 
     fn load_plugin(
@@ -168,10 +235,22 @@ impl GeyserPluginService {
         self.entry_notifier.clone()
     }
 
+    pub fn get_shred_notifier(&self) -> Option<ShredNotifierArc> {
+        self.shred_notifier.clone()
+    }
+
+    pub fn get_slot_status_notifier(&self) -> Option<SlotStatusNotifier> {
+        self.slot_status_notifier.clone()
+    }
+
     pub fn get_block_metadata_notifier(&self) -> Option<BlockMetadataNotifierArc> {
         self.block_metadata_notifier.clone()
     }
 
+    pub fn get_block_cost_notifier(&self) -> Option<BlockCostNotifierArc> {
+        self.block_cost_notifier.clone()
+    }
+
     pub fn join(self) -> thread::Result<()> {
         if let Some(mut slot_status_observer) = self.slot_status_observer {
             slot_status_observer.join()?;
@@ -211,6 +290,20 @@ impl GeyserPluginService {
                                 .expect("Admin rpc service will be waiting for response");
                         }
 
+                        GeyserPluginManagerRequest::UpdatePluginConfig {
+                            ref name,
+                            ref config_file,
+                            response_sender,
+                        } => {
+                            let update_result = plugin_manager
+                                .write()
+                                .unwrap()
+                                .update_plugin_config(name, config_file);
+                            response_sender
+                                .send(update_result)
+                                .expect("Admin rpc service will be waiting for response");
+                        }
+
                         GeyserPluginManagerRequest::LoadPlugin {
                             ref config_file,
                             response_sender,
@@ -0,0 +1,1043 @@
+//! The `queued_plugin` module delivers the notifications of a plugin from a dedicated thread, so
+//! that a slow plugin doesn't slow down replay and accounts-db threads.
+//!
+//! A plugin is queued when its config file has a `queue` section:
+//!
+//! ```json
+//! "queue": {
+//!     "capacity": 10000,
+//!     "overflow": "dropOldest"
+//! }
+//! ```
+
+use {
+    crate::geyser_plugin_manager::GeyserPluginManagerError,
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError},
+    log::*,
+    serde_derive::Deserialize,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, GeyserPluginError, ReplicaAccountInfoV3, ReplicaAccountInfoVersions,
+        ReplicaAccountState, ReplicaBlockCostInfo, ReplicaBlockCostInfoVersions,
+        ReplicaBlockInfoV3, ReplicaBlockInfoVersions, ReplicaComputeUnitPricePercentile,
+        ReplicaDuplicateShredInfo, ReplicaDuplicateShredInfoVersions, ReplicaEntryInfoV2,
+        ReplicaEntryInfoVersions, ReplicaShredInfo, ReplicaShredInfoVersions, ReplicaSlotStatus,
+        ReplicaSlotStatusInfo, ReplicaSlotStatusInfoVersions, ReplicaTransactionAccountState,
+        ReplicaTransactionInfoV2, ReplicaTransactionInfoV3, ReplicaTransactionInfoVersions,
+        ReplicaWritableAccountCost, Result, SharedAccountNotificationFilter, SlotStatus,
+    },
+    solana_measure::measure::Measure,
+    solana_metrics::*,
+    solana_sdk::{
+        clock::{Slot, UnixTimestamp},
+        pubkey::Pubkey,
+        signature::Signature,
+        timing::AtomicInterval,
+        transaction::SanitizedTransaction,
+    },
+    solana_transaction_status::{Reward, TransactionStatusMeta},
+    std::{
+        collections::HashSet,
+        path::Path,
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc,
+        },
+        thread::{Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+const DEFAULT_QUEUE_CAPACITY: usize = 10_000;
+const RECV_TIMEOUT: Duration = Duration::from_millis(100);
+const METRICS_REPORT_INTERVAL_MS: u64 = 1_000;
+
+/// What to do with a notification when the queue of a plugin is full
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueOverflowPolicy {
+    /// Wait for the plugin to catch up
+    #[default]
+    Block,
+    /// Discard the oldest queued notification to make room
+    DropOldest,
+    /// Stop sending notifications to the plugin until it is reloaded
+    Disconnect,
+}
+
+/// The `queue` section of a geyser plugin config file
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PluginQueueConfig {
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+    #[serde(default)]
+    pub overflow: QueueOverflowPolicy,
+}
+
+impl Default for PluginQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_QUEUE_CAPACITY,
+            overflow: QueueOverflowPolicy::default(),
+        }
+    }
+}
+
+fn default_capacity() -> usize {
+    DEFAULT_QUEUE_CAPACITY
+}
+
+pub(crate) fn parse_queue_config(
+    config_file: &Path,
+    queue: &serde_json::Value,
+) -> std::result::Result<PluginQueueConfig, GeyserPluginManagerError> {
+    let config: PluginQueueConfig = serde_json::from_value(queue.clone()).map_err(|err| {
+        GeyserPluginManagerError::InvalidQueueConfig(format!(
+            "Invalid queue config in {config_file:?}, error: {err}"
+        ))
+    })?;
+    if config.capacity == 0 {
+        return Err(GeyserPluginManagerError::InvalidQueueConfig(
+            "capacity must be greater than zero".to_string(),
+        ));
+    }
+    Ok(config)
+}
+
+/// An owned copy of a notification, waiting in the queue of a plugin
+enum Notification {
+    UpdateAccount {
+        account: Box<AccountNotification>,
+        slot: Slot,
+        is_startup: bool,
+    },
+    EndOfStartup,
+    UpdateSlotStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: SlotStatus,
+    },
+    NotifySlotStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: ReplicaSlotStatus,
+        bank_hash: Option<Vec<u8>>,
+        dead_error: Option<String>,
+    },
+    NotifyTransaction {
+        transaction: Box<TransactionNotification>,
+        slot: Slot,
+    },
+    NotifyEntry(EntryNotification),
+    NotifyBlockMetadata(Box<BlockNotification>),
+    NotifyBlockCost(Box<BlockCostNotification>),
+    NotifyShred(ReplicaShredInfo),
+    NotifyDuplicateShred {
+        slot: Slot,
+        shred1: Vec<u8>,
+        shred2: Vec<u8>,
+    },
+}
+
+struct AccountNotification {
+    pubkey: Vec<u8>,
+    lamports: u64,
+    owner: Vec<u8>,
+    executable: bool,
+    rent_epoch: u64,
+    data: Vec<u8>,
+    write_version: u64,
+    txn: Option<SanitizedTransaction>,
+}
+
+struct TransactionNotification {
+    signature: Signature,
+    is_vote: bool,
+    transaction: SanitizedTransaction,
+    transaction_status_meta: TransactionStatusMeta,
+    index: usize,
+    /// None when the notification did not carry account states, to deliver it as it came
+    account_states: Option<Vec<TransactionAccountStateNotification>>,
+}
+
+struct TransactionAccountStateNotification {
+    pubkey: Vec<u8>,
+    pre: AccountStateNotification,
+    post: AccountStateNotification,
+}
+
+struct AccountStateNotification {
+    lamports: u64,
+    owner: Vec<u8>,
+    executable: bool,
+    rent_epoch: u64,
+    data: Vec<u8>,
+}
+
+impl From<&ReplicaTransactionAccountState<'_>> for TransactionAccountStateNotification {
+    fn from(account_state: &ReplicaTransactionAccountState) -> Self {
+        Self {
+            pubkey: account_state.pubkey.to_vec(),
+            pre: (&account_state.pre).into(),
+            post: (&account_state.post).into(),
+        }
+    }
+}
+
+impl From<&ReplicaAccountState<'_>> for AccountStateNotification {
+    fn from(account: &ReplicaAccountState) -> Self {
+        Self {
+            lamports: account.lamports,
+            owner: account.owner.to_vec(),
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            data: account.data.to_vec(),
+        }
+    }
+}
+
+impl AccountStateNotification {
+    fn as_replica(&self) -> ReplicaAccountState {
+        ReplicaAccountState {
+            lamports: self.lamports,
+            owner: &self.owner,
+            executable: self.executable,
+            rent_epoch: self.rent_epoch,
+            data: &self.data,
+        }
+    }
+}
+
+struct EntryNotification {
+    slot: Slot,
+    index: usize,
+    num_hashes: u64,
+    hash: Vec<u8>,
+    executed_transaction_count: u64,
+    starting_transaction_index: usize,
+}
+
+struct BlockNotification {
+    parent_slot: Slot,
+    parent_blockhash: String,
+    slot: Slot,
+    blockhash: String,
+    rewards: Vec<Reward>,
+    block_time: Option<UnixTimestamp>,
+    block_height: Option<u64>,
+    executed_transaction_count: u64,
+    entry_count: u64,
+}
+
+struct BlockCostNotification {
+    slot: Slot,
+    estimated_block_cost: u64,
+    estimated_vote_cost: u64,
+    estimated_non_vote_cost: u64,
+    transaction_count: u64,
+    non_vote_transaction_count: u64,
+    prioritized_transaction_count: u64,
+    costliest_writable_accounts: Vec<(Vec<u8>, u64)>,
+    compute_unit_price_percentiles: Vec<ReplicaComputeUnitPricePercentile>,
+}
+
+struct QueuedNotification {
+    notification: Notification,
+    queued_at: Instant,
+}
+
+/// A `GeyserPlugin` that queues the notifications for the wrapped plugin in a bounded queue and
+/// delivers them from a dedicated thread, applying the configured `QueueOverflowPolicy` when the
+/// queue is full.
+///
+/// Notifications are delivered with the latest version of each info struct, which is the only
+/// version the manager produces. Errors returned by the wrapped plugin are logged by the delivery
+/// thread.
+#[derive(Debug)]
+pub struct QueuedGeyserPlugin {
+    name: String,
+    plugin: Arc<dyn GeyserPlugin>,
+    config: PluginQueueConfig,
+    sender: Option<Sender<QueuedNotification>>,
+    // Used to discard the oldest notification with `QueueOverflowPolicy::DropOldest`
+    receiver: Option<Receiver<QueuedNotification>>,
+    worker: Option<JoinHandle<()>>,
+    disconnected: Arc<AtomicBool>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl QueuedGeyserPlugin {
+    pub fn new(name: String, plugin: Box<dyn GeyserPlugin>, config: PluginQueueConfig) -> Self {
+        Self {
+            name,
+            plugin: Arc::from(plugin),
+            config,
+            sender: None,
+            receiver: None,
+            worker: None,
+            disconnected: Arc::new(AtomicBool::new(false)),
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Number of notifications waiting to be delivered
+    pub fn queue_depth(&self) -> usize {
+        self.sender.as_ref().map_or(0, |sender| sender.len())
+    }
+
+    /// Number of notifications discarded with `QueueOverflowPolicy::DropOldest`
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Whether the plugin was disconnected with `QueueOverflowPolicy::Disconnect`
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::Relaxed)
+    }
+
+    fn enqueue(&self, notification: Notification) -> Result<()> {
+        // The overflow was reported once when the plugin was disconnected
+        if self.is_disconnected() {
+            return Ok(());
+        }
+        let (Some(sender), Some(receiver)) = (&self.sender, &self.receiver) else {
+            return Err(GeyserPluginError::Custom(
+                format!("plugin {} is not loaded", self.name).into(),
+            ));
+        };
+        let mut queued = QueuedNotification {
+            notification,
+            queued_at: Instant::now(),
+        };
+        loop {
+            let result = match self.config.overflow {
+                QueueOverflowPolicy::Block => sender.send(queued).map_err(TrySendError::from),
+                QueueOverflowPolicy::DropOldest | QueueOverflowPolicy::Disconnect => {
+                    sender.try_send(queued)
+                }
+            };
+            match result {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Full(rejected)) => match self.config.overflow {
+                    QueueOverflowPolicy::Block => unreachable!("send() blocks until there is room"),
+                    QueueOverflowPolicy::DropOldest => {
+                        if receiver.try_recv().is_ok() {
+                            self.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        queued = rejected;
+                    }
+                    QueueOverflowPolicy::Disconnect => {
+                        if !self.disconnected.swap(true, Ordering::Relaxed) {
+                            error!(
+                                "Disconnected plugin {}: its queue of {} notifications is full",
+                                self.name, self.config.capacity
+                            );
+                            datapoint_error!(
+                                "geyser-plugin-queue-disconnected",
+                                "plugin" => self.name,
+                                ("capacity", self.config.capacity, i64),
+                            );
+                        }
+                        return Ok(());
+                    }
+                },
+                Err(TrySendError::Disconnected(_)) => {
+                    return Err(GeyserPluginError::Custom(
+                        format!("delivery thread of plugin {} exited", self.name).into(),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        // The worker delivers the queued notifications, then exits once the sender is dropped
+        self.sender = None;
+        self.receiver = None;
+        if let Some(worker) = self.worker.take() {
+            if worker.join().is_err() {
+                error!("delivery thread of plugin {} panicked", self.name);
+            }
+        }
+    }
+
+    fn run_worker(
+        name: String,
+        plugin: Arc<dyn GeyserPlugin>,
+        receiver: Receiver<QueuedNotification>,
+        disconnected: Arc<AtomicBool>,
+        dropped: Arc<AtomicU64>,
+    ) {
+        let report_interval = AtomicInterval::default();
+        let mut delivered = 0;
+        let mut last_dropped = 0;
+        let mut total_queue_time_us = 0;
+        let mut max_queue_time_us = 0;
+        let mut total_notify_time_us = 0;
+        loop {
+            match receiver.recv_timeout(RECV_TIMEOUT) {
+                Ok(queued) => {
+                    if disconnected.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    let queue_time_us = queued.queued_at.elapsed().as_micros() as u64;
+                    let mut measure = Measure::start("geyser-plugin-queued-notify");
+                    if let Err(err) = Self::deliver(plugin.as_ref(), queued.notification) {
+                        error!("Failed to deliver notification to plugin {name}, error: {err}");
+                    }
+                    measure.stop();
+                    delivered += 1;
+                    total_queue_time_us += queue_time_us;
+                    max_queue_time_us = max_queue_time_us.max(queue_time_us);
+                    total_notify_time_us += measure.as_us();
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            if report_interval.should_update(METRICS_REPORT_INTERVAL_MS) {
+                let total_dropped = dropped.load(Ordering::Relaxed);
+                datapoint_info!(
+                    "geyser-plugin-queue",
+                    "plugin" => name,
+                    ("queue_depth", receiver.len(), i64),
+                    ("delivered", delivered, i64),
+                    ("dropped", total_dropped - last_dropped, i64),
+                    ("total_queue_time_us", total_queue_time_us, i64),
+                    ("max_queue_time_us", max_queue_time_us, i64),
+                    ("total_notify_time_us", total_notify_time_us, i64),
+                );
+                delivered = 0;
+                last_dropped = total_dropped;
+                total_queue_time_us = 0;
+                max_queue_time_us = 0;
+                total_notify_time_us = 0;
+            }
+        }
+    }
+
+    fn deliver(plugin: &dyn GeyserPlugin, notification: Notification) -> Result<()> {
+        match notification {
+            Notification::UpdateAccount {
+                account,
+                slot,
+                is_startup,
+            } => {
+                let account = ReplicaAccountInfoV3 {
+                    pubkey: &account.pubkey,
+                    lamports: account.lamports,
+                    owner: &account.owner,
+                    executable: account.executable,
+                    rent_epoch: account.rent_epoch,
+                    data: &account.data,
+                    write_version: account.write_version,
+                    txn: account.txn.as_ref(),
+                };
+                plugin.update_account(
+                    ReplicaAccountInfoVersions::V0_0_3(&account),
+                    slot,
+                    is_startup,
+                )
+            }
+            Notification::EndOfStartup => plugin.notify_end_of_startup(),
+            Notification::UpdateSlotStatus {
+                slot,
+                parent,
+                status,
+            } => plugin.update_slot_status(slot, parent, status),
+            Notification::NotifySlotStatus {
+                slot,
+                parent,
+                status,
+                bank_hash,
+                dead_error,
+            } => {
+                let slot_status = ReplicaSlotStatusInfo {
+                    slot,
+                    parent,
+                    status,
+                    bank_hash: bank_hash.as_deref(),
+                    dead_error: dead_error.as_deref(),
+                };
+                plugin.notify_slot_status(ReplicaSlotStatusInfoVersions::V0_0_1(&slot_status))
+            }
+            Notification::NotifyTransaction { transaction, slot } => {
+                let Some(account_states) = &transaction.account_states else {
+                    let transaction = ReplicaTransactionInfoV2 {
+                        signature: &transaction.signature,
+                        is_vote: transaction.is_vote,
+                        transaction: &transaction.transaction,
+                        transaction_status_meta: &transaction.transaction_status_meta,
+                        index: transaction.index,
+                    };
+                    return plugin.notify_transaction(
+                        ReplicaTransactionInfoVersions::V0_0_2(&transaction),
+                        slot,
+                    );
+                };
+                let account_states = account_states
+                    .iter()
+                    .map(|account_state| ReplicaTransactionAccountState {
+                        pubkey: &account_state.pubkey,
+                        pre: account_state.pre.as_replica(),
+                        post: account_state.post.as_replica(),
+                    })
+                    .collect::<Vec<_>>();
+                let transaction = ReplicaTransactionInfoV3 {
+                    signature: &transaction.signature,
+                    is_vote: transaction.is_vote,
+                    transaction: &transaction.transaction,
+                    transaction_status_meta: &transaction.transaction_status_meta,
+                    index: transaction.index,
+                    account_states: &account_states,
+                };
+                plugin
+                    .notify_transaction(ReplicaTransactionInfoVersions::V0_0_3(&transaction), slot)
+            }
+            Notification::NotifyEntry(entry) => {
+                let entry = ReplicaEntryInfoV2 {
+                    slot: entry.slot,
+                    index: entry.index,
+                    num_hashes: entry.num_hashes,
+                    hash: &entry.hash,
+                    executed_transaction_count: entry.executed_transaction_count,
+                    starting_transaction_index: entry.starting_transaction_index,
+                };
+                plugin.notify_entry(ReplicaEntryInfoVersions::V0_0_2(&entry))
+            }
+            Notification::NotifyBlockMetadata(block) => {
+                let block = ReplicaBlockInfoV3 {
+                    parent_slot: block.parent_slot,
+                    parent_blockhash: &block.parent_blockhash,
+                    slot: block.slot,
+                    blockhash: &block.blockhash,
+                    rewards: &block.rewards,
+                    block_time: block.block_time,
+                    block_height: block.block_height,
+                    executed_transaction_count: block.executed_transaction_count,
+                    entry_count: block.entry_count,
+                };
+                plugin.notify_block_metadata(ReplicaBlockInfoVersions::V0_0_3(&block))
+            }
+            Notification::NotifyBlockCost(block_cost) => {
+                let costliest_writable_accounts: Vec<_> = block_cost
+                    .costliest_writable_accounts
+                    .iter()
+                    .map(|(pubkey, estimated_cost)| ReplicaWritableAccountCost {
+                        pubkey,
+                        estimated_cost: *estimated_cost,
+                    })
+                    .collect();
+                let block_cost = ReplicaBlockCostInfo {
+                    slot: block_cost.slot,
+                    estimated_block_cost: block_cost.estimated_block_cost,
+                    estimated_vote_cost: block_cost.estimated_vote_cost,
+                    estimated_non_vote_cost: block_cost.estimated_non_vote_cost,
+                    transaction_count: block_cost.transaction_count,
+                    non_vote_transaction_count: block_cost.non_vote_transaction_count,
+                    prioritized_transaction_count: block_cost.prioritized_transaction_count,
+                    costliest_writable_accounts: &costliest_writable_accounts,
+                    compute_unit_price_percentiles: &block_cost.compute_unit_price_percentiles,
+                };
+                plugin.notify_block_cost(ReplicaBlockCostInfoVersions::V0_0_1(&block_cost))
+            }
+            Notification::NotifyShred(shred) => {
+                plugin.notify_shred(ReplicaShredInfoVersions::V0_0_1(&shred))
+            }
+            Notification::NotifyDuplicateShred {
+                slot,
+                shred1,
+                shred2,
+            } => {
+                let duplicate_shred = ReplicaDuplicateShredInfo {
+                    slot,
+                    shred1: &shred1,
+                    shred2: &shred2,
+                };
+                plugin.notify_duplicate_shred(ReplicaDuplicateShredInfoVersions::V0_0_1(
+                    &duplicate_shred,
+                ))
+            }
+        }
+    }
+}
+
+impl Drop for QueuedGeyserPlugin {
+    fn drop(&mut self) {
+        // The delivery thread calls into the plugin library, it must exit before the library is
+        // unloaded
+        self.stop();
+    }
+}
+
+impl GeyserPlugin for QueuedGeyserPlugin {
+    fn setup_logger(&self, logger: &'static dyn log::Log, level: log::LevelFilter) -> Result<()> {
+        self.plugin.setup_logger(logger, level)
+    }
+
+    fn name(&self) -> &'static str {
+        self.plugin.name()
+    }
+
+    fn on_load(&mut self, config_file: &str, is_reload: bool) -> Result<()> {
+        Arc::get_mut(&mut self.plugin)
+            .expect("the delivery thread is not running")
+            .on_load(config_file, is_reload)?;
+
+        let (sender, receiver) = bounded(self.config.capacity);
+        let name = self.name.clone();
+        let plugin = self.plugin.clone();
+        let worker_receiver = receiver.clone();
+        let disconnected = self.disconnected.clone();
+        let dropped = self.dropped.clone();
+        let worker = Builder::new()
+            .name("solGeyserQueue".to_string())
+            .spawn(move || Self::run_worker(name, plugin, worker_receiver, disconnected, dropped))
+            .map_err(|err| GeyserPluginError::Custom(Box::new(err)))?;
+        self.sender = Some(sender);
+        self.receiver = Some(receiver);
+        self.worker = Some(worker);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        self.stop();
+        match Arc::get_mut(&mut self.plugin) {
+            Some(plugin) => plugin.on_unload(),
+            None => error!("plugin {} is still in use, not unloading it", self.name),
+        }
+    }
+
+    /// The new config is applied to the plugin right away, ahead of the queued notifications.
+    /// The queue settings can only be changed by reloading the plugin.
+    fn on_config_update(&self, config_file: &str) -> Result<()> {
+        self.plugin.on_config_update(config_file)
+    }
+
+    fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: Slot,
+        is_startup: bool,
+    ) -> Result<()> {
+        let account = match account {
+            ReplicaAccountInfoVersions::V0_0_1(info) => AccountNotification {
+                pubkey: info.pubkey.to_vec(),
+                lamports: info.lamports,
+                owner: info.owner.to_vec(),
+                executable: info.executable,
+                rent_epoch: info.rent_epoch,
+                data: info.data.to_vec(),
+                write_version: info.write_version,
+                txn: None,
+            },
+            ReplicaAccountInfoVersions::V0_0_2(info) => AccountNotification {
+                pubkey: info.pubkey.to_vec(),
+                lamports: info.lamports,
+                owner: info.owner.to_vec(),
+                executable: info.executable,
+                rent_epoch: info.rent_epoch,
+                data: info.data.to_vec(),
+                write_version: info.write_version,
+                txn: None,
+            },
+            ReplicaAccountInfoVersions::V0_0_3(info) => AccountNotification {
+                pubkey: info.pubkey.to_vec(),
+                lamports: info.lamports,
+                owner: info.owner.to_vec(),
+                executable: info.executable,
+                rent_epoch: info.rent_epoch,
+                data: info.data.to_vec(),
+                write_version: info.write_version,
+                txn: info.txn.cloned(),
+            },
+        };
+        self.enqueue(Notification::UpdateAccount {
+            account: Box::new(account),
+            slot,
+            is_startup,
+        })
+    }
+
+    fn notify_end_of_startup(&self) -> Result<()> {
+        self.enqueue(Notification::EndOfStartup)
+    }
+
+    fn update_slot_status(
+        &self,
+        slot: Slot,
+        parent: Option<u64>,
+        status: SlotStatus,
+    ) -> Result<()> {
+        self.enqueue(Notification::UpdateSlotStatus {
+            slot,
+            parent,
+            status,
+        })
+    }
+
+    fn notify_slot_status(&self, slot_status: ReplicaSlotStatusInfoVersions) -> Result<()> {
+        let notification = match slot_status {
+            ReplicaSlotStatusInfoVersions::V0_0_1(info) => Notification::NotifySlotStatus {
+                slot: info.slot,
+                parent: info.parent,
+                status: info.status,
+                bank_hash: info.bank_hash.map(<[u8]>::to_vec),
+                dead_error: info.dead_error.map(str::to_string),
+            },
+        };
+        self.enqueue(notification)
+    }
+
+    fn notify_transaction(
+        &self,
+        transaction: ReplicaTransactionInfoVersions,
+        slot: Slot,
+    ) -> Result<()> {
+        let transaction = match transaction {
+            ReplicaTransactionInfoVersions::V0_0_1(info) => TransactionNotification {
+                signature: *info.signature,
+                is_vote: info.is_vote,
+                transaction: info.transaction.clone(),
+                transaction_status_meta: info.transaction_status_meta.clone(),
+                index: 0,
+                account_states: None,
+            },
+            ReplicaTransactionInfoVersions::V0_0_2(info) => TransactionNotification {
+                signature: *info.signature,
+                is_vote: info.is_vote,
+                transaction: info.transaction.clone(),
+                transaction_status_meta: info.transaction_status_meta.clone(),
+                index: info.index,
+                account_states: None,
+            },
+            ReplicaTransactionInfoVersions::V0_0_3(info) => TransactionNotification {
+                signature: *info.signature,
+                is_vote: info.is_vote,
+                transaction: info.transaction.clone(),
+                transaction_status_meta: info.transaction_status_meta.clone(),
+                index: info.index,
+                account_states: Some(info.account_states.iter().map(Into::into).collect()),
+            },
+        };
+        self.enqueue(Notification::NotifyTransaction {
+            transaction: Box::new(transaction),
+            slot,
+        })
+    }
+
+    fn notify_entry(&self, entry: ReplicaEntryInfoVersions) -> Result<()> {
+        let entry = match entry {
+            ReplicaEntryInfoVersions::V0_0_1(info) => EntryNotification {
+                slot: info.slot,
+                index: info.index,
+                num_hashes: info.num_hashes,
+                hash: info.hash.to_vec(),
+                executed_transaction_count: info.executed_transaction_count,
+                starting_transaction_index: 0,
+            },
+            ReplicaEntryInfoVersions::V0_0_2(info) => EntryNotification {
+                slot: info.slot,
+                index: info.index,
+                num_hashes: info.num_hashes,
+                hash: info.hash.to_vec(),
+                executed_transaction_count: info.executed_transaction_count,
+                starting_transaction_index: info.starting_transaction_index,
+            },
+        };
+        self.enqueue(Notification::NotifyEntry(entry))
+    }
+
+    fn notify_block_metadata(&self, blockinfo: ReplicaBlockInfoVersions) -> Result<()> {
+        let block = match blockinfo {
+            ReplicaBlockInfoVersions::V0_0_1(info) => BlockNotification {
+                parent_slot: 0,
+                parent_blockhash: String::default(),
+                slot: info.slot,
+                blockhash: info.blockhash.to_string(),
+                rewards: info.rewards.to_vec(),
+                block_time: info.block_time,
+                block_height: info.block_height,
+                executed_transaction_count: 0,
+                entry_count: 0,
+            },
+            ReplicaBlockInfoVersions::V0_0_2(info) => BlockNotification {
+                parent_slot: info.parent_slot,
+                parent_blockhash: info.parent_blockhash.to_string(),
+                slot: info.slot,
+                blockhash: info.blockhash.to_string(),
+                rewards: info.rewards.to_vec(),
+                block_time: info.block_time,
+                block_height: info.block_height,
+                executed_transaction_count: info.executed_transaction_count,
+                entry_count: 0,
+            },
+            ReplicaBlockInfoVersions::V0_0_3(info) => BlockNotification {
+                parent_slot: info.parent_slot,
+                parent_blockhash: info.parent_blockhash.to_string(),
+                slot: info.slot,
+                blockhash: info.blockhash.to_string(),
+                rewards: info.rewards.to_vec(),
+                block_time: info.block_time,
+                block_height: info.block_height,
+                executed_transaction_count: info.executed_transaction_count,
+                entry_count: info.entry_count,
+            },
+        };
+        self.enqueue(Notification::NotifyBlockMetadata(Box::new(block)))
+    }
+
+    fn notify_block_cost(&self, block_cost: ReplicaBlockCostInfoVersions) -> Result<()> {
+        let block_cost = match block_cost {
+            ReplicaBlockCostInfoVersions::V0_0_1(info) => BlockCostNotification {
+                slot: info.slot,
+                estimated_block_cost: info.estimated_block_cost,
+                estimated_vote_cost: info.estimated_vote_cost,
+                estimated_non_vote_cost: info.estimated_non_vote_cost,
+                transaction_count: info.transaction_count,
+                non_vote_transaction_count: info.non_vote_transaction_count,
+                prioritized_transaction_count: info.prioritized_transaction_count,
+                costliest_writable_accounts: info
+                    .costliest_writable_accounts
+                    .iter()
+                    .map(|account| (account.pubkey.to_vec(), account.estimated_cost))
+                    .collect(),
+                compute_unit_price_percentiles: info.compute_unit_price_percentiles.to_vec(),
+            },
+        };
+        self.enqueue(Notification::NotifyBlockCost(Box::new(block_cost)))
+    }
+
+    fn notify_shred(&self, shred: ReplicaShredInfoVersions) -> Result<()> {
+        let shred = match shred {
+            ReplicaShredInfoVersions::V0_0_1(info) => info.clone(),
+        };
+        self.enqueue(Notification::NotifyShred(shred))
+    }
+
+    fn notify_duplicate_shred(
+        &self,
+        duplicate_shred: ReplicaDuplicateShredInfoVersions,
+    ) -> Result<()> {
+        let notification = match duplicate_shred {
+            ReplicaDuplicateShredInfoVersions::V0_0_1(info) => Notification::NotifyDuplicateShred {
+                slot: info.slot,
+                shred1: info.shred1.to_vec(),
+                shred2: info.shred2.to_vec(),
+            },
+        };
+        self.enqueue(notification)
+    }
+
+    fn account_notification_filter(&self) -> Option<SharedAccountNotificationFilter> {
+        self.plugin.account_notification_filter()
+    }
+
+    fn transaction_account_states_programs(&self) -> Option<HashSet<Pubkey>> {
+        self.plugin.transaction_account_states_programs()
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        !self.is_disconnected() && self.plugin.account_data_notifications_enabled()
+    }
+
+    fn slot_status_notifications_enabled(&self) -> bool {
+        !self.is_disconnected() && self.plugin.slot_status_notifications_enabled()
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        !self.is_disconnected() && self.plugin.transaction_notifications_enabled()
+    }
+
+    fn entry_notifications_enabled(&self) -> bool {
+        !self.is_disconnected() && self.plugin.entry_notifications_enabled()
+    }
+
+    fn shred_notifications_enabled(&self) -> bool {
+        !self.is_disconnected() && self.plugin.shred_notifications_enabled()
+    }
+
+    fn block_cost_notifications_enabled(&self) -> bool {
+        !self.is_disconnected() && self.plugin.block_cost_notifications_enabled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::geyser_plugin_manager::LoadedGeyserPlugin,
+        std::sync::{Mutex, RwLock},
+    };
+
+    /// Records the slot status updates it receives, each one only after `gate` is opened
+    #[derive(Debug)]
+    struct GatedPlugin {
+        gate: Arc<RwLock<()>>,
+        slots: Arc<Mutex<Vec<Slot>>>,
+    }
+
+    impl GeyserPlugin for GatedPlugin {
+        fn name(&self) -> &'static str {
+            "gated"
+        }
+
+        fn update_slot_status(
+            &self,
+            slot: Slot,
+            _parent: Option<u64>,
+            _status: SlotStatus,
+        ) -> Result<()> {
+            let _gate = self.gate.read().unwrap();
+            self.slots.lock().unwrap().push(slot);
+            Ok(())
+        }
+    }
+
+    fn new_queued_plugin(
+        capacity: usize,
+        overflow: QueueOverflowPolicy,
+    ) -> (QueuedGeyserPlugin, Arc<RwLock<()>>, Arc<Mutex<Vec<Slot>>>) {
+        let gate = Arc::new(RwLock::new(()));
+        let slots = Arc::new(Mutex::new(vec![]));
+        let plugin = GatedPlugin {
+            gate: gate.clone(),
+            slots: slots.clone(),
+        };
+        let mut plugin = QueuedGeyserPlugin::new(
+            "gated".to_string(),
+            Box::new(plugin),
+            PluginQueueConfig { capacity, overflow },
+        );
+        plugin.on_load("", false).unwrap();
+        (plugin, gate, slots)
+    }
+
+    fn notify_slot(plugin: &QueuedGeyserPlugin, slot: Slot) -> Result<()> {
+        plugin.update_slot_status(slot, None, SlotStatus::Processed)
+    }
+
+    // Waits for the delivery thread to take every notification off the queue
+    fn wait_for_empty_queue(plugin: &QueuedGeyserPlugin) {
+        while plugin.queue_depth() > 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_parse_queue_config() {
+        let config_file = Path::new("config.json");
+        assert_eq!(
+            parse_queue_config(config_file, &serde_json::json!({})).unwrap(),
+            PluginQueueConfig::default()
+        );
+        assert_eq!(
+            parse_queue_config(
+                config_file,
+                &serde_json::json!({"capacity": 5, "overflow": "dropOldest"})
+            )
+            .unwrap(),
+            PluginQueueConfig {
+                capacity: 5,
+                overflow: QueueOverflowPolicy::DropOldest,
+            }
+        );
+        assert!(parse_queue_config(config_file, &serde_json::json!({"capacity": 0})).is_err());
+        assert!(
+            parse_queue_config(config_file, &serde_json::json!({"overflow": "ignore"})).is_err()
+        );
+    }
+
+    #[test]
+    fn test_queued_plugin_delivers_in_order() {
+        let (mut plugin, _gate, slots) = new_queued_plugin(4, QueueOverflowPolicy::Block);
+        for slot in 0..10 {
+            notify_slot(&plugin, slot).unwrap();
+        }
+        // Unloading delivers the queued notifications first
+        plugin.on_unload();
+        assert_eq!(*slots.lock().unwrap(), (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_queued_plugin_slot_status() {
+        let (mut plugin, _gate, slots) = new_queued_plugin(4, QueueOverflowPolicy::Block);
+        let bank_hash = [1u8; 32];
+        for (slot, status) in [
+            (1, ReplicaSlotStatus::CreatedBank),
+            (2, ReplicaSlotStatus::Completed),
+            (3, ReplicaSlotStatus::Frozen),
+            (4, ReplicaSlotStatus::Processed),
+            (5, ReplicaSlotStatus::Dead),
+            (6, ReplicaSlotStatus::Rooted),
+        ] {
+            let info = ReplicaSlotStatusInfo {
+                slot,
+                parent: Some(slot - 1),
+                status,
+                bank_hash: (status == ReplicaSlotStatus::Frozen).then_some(&bank_hash[..]),
+                dead_error: (status == ReplicaSlotStatus::Dead).then_some("error"),
+            };
+            plugin
+                .notify_slot_status(ReplicaSlotStatusInfoVersions::V0_0_1(&info))
+                .unwrap();
+        }
+        plugin.on_unload();
+        // The plugin only implements `update_slot_status`, which isn't called for the new statuses
+        assert_eq!(*slots.lock().unwrap(), vec![4, 6]);
+    }
+
+    #[test]
+    fn test_queued_plugin_drop_oldest() {
+        let (mut plugin, gate, slots) = new_queued_plugin(2, QueueOverflowPolicy::DropOldest);
+        let closed_gate = gate.write().unwrap();
+        notify_slot(&plugin, 0).unwrap();
+        wait_for_empty_queue(&plugin);
+        for slot in 1..5 {
+            notify_slot(&plugin, slot).unwrap();
+        }
+        assert_eq!(plugin.dropped_count(), 2);
+        assert_eq!(plugin.queue_depth(), 2);
+
+        drop(closed_gate);
+        plugin.on_unload();
+        assert_eq!(*slots.lock().unwrap(), vec![0, 3, 4]);
+    }
+
+    #[test]
+    fn test_queued_plugin_disconnect() {
+        let (mut plugin, gate, slots) = new_queued_plugin(1, QueueOverflowPolicy::Disconnect);
+        let closed_gate = gate.write().unwrap();
+        notify_slot(&plugin, 0).unwrap();
+        wait_for_empty_queue(&plugin);
+        notify_slot(&plugin, 1).unwrap();
+        // The overflow is reported once and the later notifications are discarded quietly
+        notify_slot(&plugin, 2).unwrap();
+        assert!(plugin.is_disconnected());
+        assert!(!plugin.account_data_notifications_enabled());
+        notify_slot(&plugin, 3).unwrap();
+
+        drop(closed_gate);
+        plugin.on_unload();
+        // The notification queued before the overflow is discarded too
+        assert_eq!(*slots.lock().unwrap(), vec![0]);
+    }
+
+    #[test]
+    fn test_loaded_plugin_with_queue() {
+        let (gate, slots) = (Arc::new(RwLock::new(())), Arc::new(Mutex::new(vec![])));
+        let plugin = GatedPlugin {
+            gate,
+            slots: slots.clone(),
+        };
+        let mut plugin = LoadedGeyserPlugin::new(Box::new(plugin), Some("queued".to_string()))
+            .with_queue(PluginQueueConfig::default());
+        assert_eq!(plugin.name(), "queued");
+        plugin.on_load("", false).unwrap();
+        plugin
+            .update_slot_status(7, None, SlotStatus::Rooted)
+            .unwrap();
+        plugin.on_unload();
+        assert_eq!(*slots.lock().unwrap(), vec![7]);
+    }
+}
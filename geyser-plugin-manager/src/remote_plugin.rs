@@ -0,0 +1,662 @@
+//! The `remote_plugin` module implements a `GeyserPlugin` that forwards notifications to a
+//! consumer running in another process.
+//!
+//! Notifications are serialized as newline-delimited JSON objects and written to a Unix domain
+//! socket the consumer listens on. Every connection starts with a `hello` message carrying the
+//! protocol version; consumers should refuse versions they don't know. The validator never reads
+//! from the socket, so the consumer can be restarted at any time: the validator reconnects and
+//! resumes with the notifications it has not written yet. Each message is written with a single
+//! write of its whole line, and a message whose write fails is written again in full on the next
+//! connection, so the only incomplete line a consumer can observe is the last one before the
+//! connection is lost, which it should discard.
+//!
+//! Notifications are queued in a bounded queue and written by a dedicated thread, so a slow or
+//! absent consumer only stalls the validator if the plugin is configured with the `block`
+//! overflow policy.
+
+use {
+    crate::geyser_plugin_manager::{GeyserPluginManagerError, LoadedGeyserPlugin},
+    base64::{prelude::BASE64_STANDARD, Engine},
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError},
+    log::*,
+    serde_derive::{Deserialize, Serialize},
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, ReplicaBlockInfoVersions,
+        ReplicaEntryInfoVersions, ReplicaTransactionInfoVersions, Result, SlotStatus,
+    },
+    solana_metrics::*,
+    solana_sdk::clock::Slot,
+    solana_transaction_status::{Rewards, UiTransactionStatusMeta},
+    std::{
+        io::Write,
+        os::unix::net::UnixStream,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+/// Version of the wire protocol, sent in the `hello` message of every connection
+pub const REMOTE_PLUGIN_PROTOCOL_VERSION: u32 = 1;
+
+const DEFAULT_QUEUE_CAPACITY: usize = 100_000;
+const RECONNECT_INTERVAL: Duration = Duration::from_millis(500);
+const RECV_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// What to do with a notification when the queue of a remote plugin is full
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RemotePluginOverflowPolicy {
+    /// Wait for the consumer to catch up, slowing down the validator
+    Block,
+    /// Discard the notification, so that a slow or absent consumer can't stall the validator
+    #[default]
+    Drop,
+}
+
+/// The `remote` section of a geyser plugin config file
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RemotePluginConfig {
+    /// Path of the Unix domain socket the consumer listens on. Relative paths are resolved
+    /// against the directory of the config file.
+    pub socket_path: PathBuf,
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+    #[serde(default)]
+    pub overflow: RemotePluginOverflowPolicy,
+    #[serde(default = "default_true")]
+    pub account_data_notifications: bool,
+    #[serde(default)]
+    pub transaction_notifications: bool,
+    #[serde(default)]
+    pub entry_notifications: bool,
+}
+
+fn default_queue_capacity() -> usize {
+    DEFAULT_QUEUE_CAPACITY
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A message of the remote plugin wire protocol. Pubkeys, signatures and hashes are base58
+/// encoded, account data and transactions base64 encoded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RemotePluginMessage {
+    #[serde(rename_all = "camelCase")]
+    Hello {
+        protocol_version: u32,
+        plugin: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    UpdateAccount {
+        slot: Slot,
+        is_startup: bool,
+        pubkey: String,
+        lamports: u64,
+        owner: String,
+        executable: bool,
+        rent_epoch: u64,
+        data: String,
+        write_version: u64,
+        txn_signature: Option<String>,
+    },
+    EndOfStartup,
+    #[serde(rename_all = "camelCase")]
+    UpdateSlotStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    NotifyTransaction {
+        slot: Slot,
+        signature: String,
+        is_vote: bool,
+        index: Option<usize>,
+        /// Bincode serialized `VersionedTransaction`
+        transaction: String,
+        meta: UiTransactionStatusMeta,
+    },
+    #[serde(rename_all = "camelCase")]
+    NotifyEntry {
+        slot: Slot,
+        index: usize,
+        num_hashes: u64,
+        hash: String,
+        executed_transaction_count: u64,
+        starting_transaction_index: Option<usize>,
+    },
+    #[serde(rename_all = "camelCase")]
+    NotifyBlockMetadata {
+        parent_slot: Option<Slot>,
+        parent_blockhash: Option<String>,
+        slot: Slot,
+        blockhash: String,
+        rewards: Rewards,
+        block_time: Option<i64>,
+        block_height: Option<u64>,
+        executed_transaction_count: Option<u64>,
+        entry_count: Option<u64>,
+    },
+}
+
+/// Loads the remote plugin described by the `remote` section of the config file at
+/// `config_file`. The plugin is named `name`, or after its socket path if no name is given.
+pub(crate) fn load_remote_plugin(
+    config_file: &Path,
+    name: Option<String>,
+    remote: &serde_json::Value,
+) -> std::result::Result<LoadedGeyserPlugin, GeyserPluginManagerError> {
+    let mut config: RemotePluginConfig = serde_json::from_value(remote.clone()).map_err(|err| {
+        GeyserPluginManagerError::InvalidRemotePluginConfig(format!(
+            "Invalid remote plugin config in {config_file:?}, error: {err}"
+        ))
+    })?;
+    if config.queue_capacity == 0 {
+        return Err(GeyserPluginManagerError::InvalidRemotePluginConfig(
+            "queueCapacity must be greater than zero".to_string(),
+        ));
+    }
+    if config.socket_path.is_relative() {
+        let config_dir = config_file.parent().ok_or_else(|| {
+            GeyserPluginManagerError::CannotOpenConfigFile(format!(
+                "Failed to resolve parent of {config_file:?}",
+            ))
+        })?;
+        config.socket_path = config_dir.join(&config.socket_path);
+    }
+    let name = name.unwrap_or_else(|| config.socket_path.display().to_string());
+    Ok(LoadedGeyserPlugin::new(
+        Box::new(RemoteGeyserPlugin::new(name.clone(), config)),
+        Some(name),
+    ))
+}
+
+/// A `GeyserPlugin` that queues notifications for a consumer listening on a Unix domain socket
+#[derive(Debug)]
+pub struct RemoteGeyserPlugin {
+    name: String,
+    config: RemotePluginConfig,
+    sender: Option<Sender<RemotePluginMessage>>,
+    writer: Option<JoinHandle<()>>,
+    exit: Arc<AtomicBool>,
+    dropped: AtomicU64,
+}
+
+impl RemoteGeyserPlugin {
+    pub fn new(name: String, config: RemotePluginConfig) -> Self {
+        Self {
+            name,
+            config,
+            sender: None,
+            writer: None,
+            exit: Arc::new(AtomicBool::new(false)),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of notifications discarded because the queue was full
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn send(&self, message: RemotePluginMessage) -> Result<()> {
+        let sender = self.sender.as_ref().ok_or_else(|| {
+            GeyserPluginError::Custom(format!("remote plugin {} is not loaded", self.name).into())
+        })?;
+        let result = match self.config.overflow {
+            RemotePluginOverflowPolicy::Block => sender.send(message).map_err(|_| ()),
+            RemotePluginOverflowPolicy::Drop => match sender.try_send(message) {
+                Err(TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    inc_new_counter_info!("geyser-plugin-remote-dropped", 1);
+                    Ok(())
+                }
+                result => result.map_err(|_| ()),
+            },
+        };
+        result.map_err(|_| {
+            GeyserPluginError::Custom(
+                format!("writer thread of remote plugin {} exited", self.name).into(),
+            )
+        })
+    }
+
+    fn run_writer(
+        name: String,
+        socket_path: PathBuf,
+        receiver: Receiver<RemotePluginMessage>,
+        exit: Arc<AtomicBool>,
+    ) {
+        let mut stream = None;
+        let mut pending = None;
+        while !exit.load(Ordering::Relaxed) {
+            let Some(writer) = stream.as_mut() else {
+                match Self::connect(&name, &socket_path) {
+                    Ok(writer) => {
+                        info!("remote plugin {name} connected to {socket_path:?}");
+                        stream = Some(writer);
+                    }
+                    Err(err) => {
+                        debug!("remote plugin {name} failed to connect to {socket_path:?}: {err}");
+                        thread::sleep(RECONNECT_INTERVAL);
+                    }
+                }
+                continue;
+            };
+
+            let message = match pending.take() {
+                Some(message) => message,
+                None => match receiver.recv_timeout(RECV_TIMEOUT) {
+                    Ok(message) => message,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                },
+            };
+            if let Err(err) = Self::write_message(writer, &message) {
+                warn!("remote plugin {name} lost connection to {socket_path:?}: {err}");
+                stream = None;
+                pending = Some(message);
+            }
+        }
+    }
+
+    fn connect(name: &str, socket_path: &Path) -> std::io::Result<UnixStream> {
+        let mut stream = UnixStream::connect(socket_path)?;
+        let hello = RemotePluginMessage::Hello {
+            protocol_version: REMOTE_PLUGIN_PROTOCOL_VERSION,
+            plugin: name.to_string(),
+        };
+        Self::write_message(&mut stream, &hello)?;
+        Ok(stream)
+    }
+
+    /// Writes `message` as a complete line in a single write, rather than through a buffer that
+    /// would lose the messages it holds if the connection fails
+    fn write_message(
+        writer: &mut impl Write,
+        message: &RemotePluginMessage,
+    ) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(message)?;
+        line.push(b'\n');
+        writer.write_all(&line)
+    }
+}
+
+impl GeyserPlugin for RemoteGeyserPlugin {
+    fn name(&self) -> &'static str {
+        "remote-geyser-plugin"
+    }
+
+    fn on_load(&mut self, _config_file: &str, _is_reload: bool) -> Result<()> {
+        let (sender, receiver) = bounded(self.config.queue_capacity);
+        let name = self.name.clone();
+        let socket_path = self.config.socket_path.clone();
+        let exit = self.exit.clone();
+        let writer = Builder::new()
+            .name("solGeyserRemote".to_string())
+            .spawn(move || Self::run_writer(name, socket_path, receiver, exit))
+            .map_err(|err| GeyserPluginError::Custom(Box::new(err)))?;
+        self.sender = Some(sender);
+        self.writer = Some(writer);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        self.exit.store(true, Ordering::Relaxed);
+        self.sender = None;
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                error!("writer thread of remote plugin {} panicked", self.name);
+            }
+        }
+    }
+
+    fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: Slot,
+        is_startup: bool,
+    ) -> Result<()> {
+        let (pubkey, lamports, owner, executable, rent_epoch, data, write_version, txn_signature) =
+            match account {
+                ReplicaAccountInfoVersions::V0_0_1(info) => (
+                    info.pubkey,
+                    info.lamports,
+                    info.owner,
+                    info.executable,
+                    info.rent_epoch,
+                    info.data,
+                    info.write_version,
+                    None,
+                ),
+                ReplicaAccountInfoVersions::V0_0_2(info) => (
+                    info.pubkey,
+                    info.lamports,
+                    info.owner,
+                    info.executable,
+                    info.rent_epoch,
+                    info.data,
+                    info.write_version,
+                    info.txn_signature.map(|signature| signature.to_string()),
+                ),
+                ReplicaAccountInfoVersions::V0_0_3(info) => (
+                    info.pubkey,
+                    info.lamports,
+                    info.owner,
+                    info.executable,
+                    info.rent_epoch,
+                    info.data,
+                    info.write_version,
+                    info.txn.map(|txn| txn.signature().to_string()),
+                ),
+            };
+        self.send(RemotePluginMessage::UpdateAccount {
+            slot,
+            is_startup,
+            pubkey: bs58::encode(pubkey).into_string(),
+            lamports,
+            owner: bs58::encode(owner).into_string(),
+            executable,
+            rent_epoch,
+            data: BASE64_STANDARD.encode(data),
+            write_version,
+            txn_signature,
+        })
+    }
+
+    fn notify_end_of_startup(&self) -> Result<()> {
+        self.send(RemotePluginMessage::EndOfStartup)
+    }
+
+    fn update_slot_status(
+        &self,
+        slot: Slot,
+        parent: Option<u64>,
+        status: SlotStatus,
+    ) -> Result<()> {
+        self.send(RemotePluginMessage::UpdateSlotStatus {
+            slot,
+            parent,
+            status: status.as_str().to_string(),
+        })
+    }
+
+    fn notify_transaction(
+        &self,
+        transaction: ReplicaTransactionInfoVersions,
+        slot: Slot,
+    ) -> Result<()> {
+        let (signature, is_vote, transaction, meta, index) = match transaction {
+            ReplicaTransactionInfoVersions::V0_0_1(info) => (
+                info.signature,
+                info.is_vote,
+                info.transaction,
+                info.transaction_status_meta,
+                None,
+            ),
+            ReplicaTransactionInfoVersions::V0_0_2(info) => (
+                info.signature,
+                info.is_vote,
+                info.transaction,
+                info.transaction_status_meta,
+                Some(info.index),
+            ),
+            ReplicaTransactionInfoVersions::V0_0_3(info) => (
+                info.signature,
+                info.is_vote,
+                info.transaction,
+                info.transaction_status_meta,
+                Some(info.index),
+            ),
+        };
+        let transaction =
+            bincode::serialize(&transaction.to_versioned_transaction()).map_err(|err| {
+                GeyserPluginError::TransactionUpdateError {
+                    msg: err.to_string(),
+                }
+            })?;
+        self.send(RemotePluginMessage::NotifyTransaction {
+            slot,
+            signature: signature.to_string(),
+            is_vote,
+            index,
+            transaction: BASE64_STANDARD.encode(transaction),
+            meta: meta.clone().into(),
+        })
+    }
+
+    fn notify_entry(&self, entry: ReplicaEntryInfoVersions) -> Result<()> {
+        let message = match entry {
+            ReplicaEntryInfoVersions::V0_0_1(info) => RemotePluginMessage::NotifyEntry {
+                slot: info.slot,
+                index: info.index,
+                num_hashes: info.num_hashes,
+                hash: bs58::encode(info.hash).into_string(),
+                executed_transaction_count: info.executed_transaction_count,
+                starting_transaction_index: None,
+            },
+            ReplicaEntryInfoVersions::V0_0_2(info) => RemotePluginMessage::NotifyEntry {
+                slot: info.slot,
+                index: info.index,
+                num_hashes: info.num_hashes,
+                hash: bs58::encode(info.hash).into_string(),
+                executed_transaction_count: info.executed_transaction_count,
+                starting_transaction_index: Some(info.starting_transaction_index),
+            },
+        };
+        self.send(message)
+    }
+
+    fn notify_block_metadata(&self, blockinfo: ReplicaBlockInfoVersions) -> Result<()> {
+        let message = match blockinfo {
+            ReplicaBlockInfoVersions::V0_0_1(info) => RemotePluginMessage::NotifyBlockMetadata {
+                parent_slot: None,
+                parent_blockhash: None,
+                slot: info.slot,
+                blockhash: info.blockhash.to_string(),
+                rewards: info.rewards.to_vec(),
+                block_time: info.block_time,
+                block_height: info.block_height,
+                executed_transaction_count: None,
+                entry_count: None,
+            },
+            ReplicaBlockInfoVersions::V0_0_2(info) => RemotePluginMessage::NotifyBlockMetadata {
+                parent_slot: Some(info.parent_slot),
+                parent_blockhash: Some(info.parent_blockhash.to_string()),
+                slot: info.slot,
+                blockhash: info.blockhash.to_string(),
+                rewards: info.rewards.to_vec(),
+                block_time: info.block_time,
+                block_height: info.block_height,
+                executed_transaction_count: Some(info.executed_transaction_count),
+                entry_count: None,
+            },
+            ReplicaBlockInfoVersions::V0_0_3(info) => RemotePluginMessage::NotifyBlockMetadata {
+                parent_slot: Some(info.parent_slot),
+                parent_blockhash: Some(info.parent_blockhash.to_string()),
+                slot: info.slot,
+                blockhash: info.blockhash.to_string(),
+                rewards: info.rewards.to_vec(),
+                block_time: info.block_time,
+                block_height: info.block_height,
+                executed_transaction_count: Some(info.executed_transaction_count),
+                entry_count: Some(info.entry_count),
+            },
+        };
+        self.send(message)
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        self.config.account_data_notifications
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        self.config.transaction_notifications
+    }
+
+    fn entry_notifications_enabled(&self) -> bool {
+        self.config.entry_notifications
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfoV3,
+        solana_sdk::pubkey::Pubkey,
+        std::{
+            io::{BufRead, BufReader},
+            os::unix::net::UnixListener,
+        },
+    };
+
+    fn new_config(socket_path: PathBuf) -> RemotePluginConfig {
+        RemotePluginConfig {
+            socket_path,
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            overflow: RemotePluginOverflowPolicy::Block,
+            account_data_notifications: true,
+            transaction_notifications: false,
+            entry_notifications: false,
+        }
+    }
+
+    #[test]
+    fn test_load_remote_plugin_config() {
+        let config_file = Path::new("/etc/geyser/remote.json");
+        let plugin = load_remote_plugin(
+            config_file,
+            None,
+            &serde_json::json!({
+                "socketPath": "geyser.sock",
+                "overflow": "drop",
+                "transactionNotifications": true,
+            }),
+        )
+        .unwrap();
+        assert_eq!(plugin.name(), "/etc/geyser/geyser.sock");
+        assert!(plugin.account_data_notifications_enabled());
+        assert!(plugin.transaction_notifications_enabled());
+        assert!(!plugin.entry_notifications_enabled());
+
+        let plugin = load_remote_plugin(
+            config_file,
+            Some("indexer".to_string()),
+            &serde_json::json!({"socketPath": "/run/geyser.sock"}),
+        )
+        .unwrap();
+        assert_eq!(plugin.name(), "indexer");
+
+        assert!(load_remote_plugin(
+            config_file,
+            None,
+            &serde_json::json!({"socketPath": "geyser.sock", "queueCapacity": 0}),
+        )
+        .is_err());
+        assert!(load_remote_plugin(
+            config_file,
+            None,
+            &serde_json::json!({"socketPath": "geyser.sock", "overflow": "panic"}),
+        )
+        .is_err());
+
+        // Notifications are dropped rather than stalling the validator unless `block` is set
+        let config: RemotePluginConfig =
+            serde_json::from_value(serde_json::json!({"socketPath": "geyser.sock"})).unwrap();
+        assert_eq!(config.overflow, RemotePluginOverflowPolicy::Drop);
+    }
+
+    #[test]
+    fn test_remote_plugin_notifications() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("geyser.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        let mut plugin = RemoteGeyserPlugin::new("test".to_string(), new_config(socket_path));
+        plugin.on_load("", false).unwrap();
+
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let account = ReplicaAccountInfoV3 {
+            pubkey: pubkey.as_ref(),
+            lamports: 42,
+            owner: owner.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data: &[1, 2, 3],
+            write_version: 7,
+            txn: None,
+        };
+        plugin
+            .update_account(ReplicaAccountInfoVersions::V0_0_3(&account), 5, true)
+            .unwrap();
+        plugin.notify_end_of_startup().unwrap();
+        plugin
+            .update_slot_status(5, Some(4), SlotStatus::Rooted)
+            .unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        let messages = BufReader::new(stream)
+            .lines()
+            .take(4)
+            .map(|line| serde_json::from_str::<RemotePluginMessage>(&line.unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                RemotePluginMessage::Hello {
+                    protocol_version: REMOTE_PLUGIN_PROTOCOL_VERSION,
+                    plugin: "test".to_string(),
+                },
+                RemotePluginMessage::UpdateAccount {
+                    slot: 5,
+                    is_startup: true,
+                    pubkey: pubkey.to_string(),
+                    lamports: 42,
+                    owner: owner.to_string(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: BASE64_STANDARD.encode([1, 2, 3]),
+                    write_version: 7,
+                    txn_signature: None,
+                },
+                RemotePluginMessage::EndOfStartup,
+                RemotePluginMessage::UpdateSlotStatus {
+                    slot: 5,
+                    parent: Some(4),
+                    status: "rooted".to_string(),
+                },
+            ]
+        );
+        plugin.on_unload();
+    }
+
+    #[test]
+    fn test_remote_plugin_drop_overflow() {
+        let dir = tempfile::tempdir().unwrap();
+        // Nobody listens on the socket, so the queue is never drained
+        let mut config = new_config(dir.path().join("geyser.sock"));
+        config.queue_capacity = 1;
+        config.overflow = RemotePluginOverflowPolicy::Drop;
+        let mut plugin = RemoteGeyserPlugin::new("test".to_string(), config);
+        plugin.on_load("", false).unwrap();
+
+        for slot in 0..3 {
+            plugin
+                .update_slot_status(slot, None, SlotStatus::Processed)
+                .unwrap();
+        }
+        assert_eq!(plugin.dropped_count(), 2);
+        plugin.on_unload();
+    }
+}
@@ -0,0 +1,129 @@
+/// Module responsible for notifying plugins about shreds
+use {
+    crate::geyser_plugin_manager::GeyserPluginManager,
+    log::*,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaDuplicateShredInfo, ReplicaDuplicateShredInfoVersions, ReplicaShredInfo,
+        ReplicaShredInfoVersions, ReplicaShredType, ShredOrigin,
+    },
+    solana_ledger::{
+        shred::{Shred, ShredType},
+        shred_notifier_interface::ShredNotifier,
+    },
+    solana_measure::measure::Measure,
+    solana_metrics::*,
+    solana_sdk::clock::Slot,
+    std::{
+        sync::{Arc, RwLock},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+pub(crate) struct ShredNotifierImpl {
+    plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+}
+
+impl ShredNotifier for ShredNotifierImpl {
+    fn notify_shred(&self, shred: &Shred, is_repair: bool, arrival_time: SystemTime) {
+        let mut measure = Measure::start("geyser-plugin-notify_plugins_of_shred_info");
+
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let shred_info = Self::build_replica_shred_info(shred, is_repair, arrival_time);
+
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.shred_notifications_enabled() {
+                continue;
+            }
+            match plugin.notify_shred(ReplicaShredInfoVersions::V0_0_1(&shred_info)) {
+                Err(err) => {
+                    error!(
+                        "Failed to notify shred, error: ({}) to plugin {}",
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!("Successfully notified shred to plugin {}", plugin.name());
+                }
+            }
+        }
+        measure.stop();
+        inc_new_counter_debug!(
+            "geyser-plugin-notify_plugins_of_shred_info-us",
+            measure.as_us() as usize,
+            10000,
+            10000
+        );
+    }
+
+    fn notify_duplicate_shred(&self, slot: Slot, shred1: &[u8], shred2: &[u8]) {
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let duplicate_shred_info = ReplicaDuplicateShredInfo {
+            slot,
+            shred1,
+            shred2,
+        };
+
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.shred_notifications_enabled() {
+                continue;
+            }
+            match plugin.notify_duplicate_shred(ReplicaDuplicateShredInfoVersions::V0_0_1(
+                &duplicate_shred_info,
+            )) {
+                Err(err) => {
+                    error!(
+                        "Failed to notify duplicate shred, error: ({}) to plugin {}",
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully notified duplicate shred to plugin {}",
+                        plugin.name()
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl ShredNotifierImpl {
+    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
+        Self { plugin_manager }
+    }
+
+    fn build_replica_shred_info(
+        shred: &Shred,
+        is_repair: bool,
+        arrival_time: SystemTime,
+    ) -> ReplicaShredInfo {
+        ReplicaShredInfo {
+            slot: shred.slot(),
+            index: shred.index(),
+            shred_type: match shred.shred_type() {
+                ShredType::Data => ReplicaShredType::Data,
+                ShredType::Code => ReplicaShredType::Code,
+            },
+            fec_set_index: shred.fec_set_index(),
+            origin: if is_repair {
+                ShredOrigin::Repair
+            } else {
+                ShredOrigin::Turbine
+            },
+            arrival_timestamp_us: arrival_time
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
+        }
+    }
+}
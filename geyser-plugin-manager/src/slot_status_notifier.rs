@@ -1,41 +1,61 @@
+pub use solana_rpc::slot_status_notifier_interface::{
+    SlotStatusNotifier, SlotStatusNotifierInterface,
+};
 use {
     crate::geyser_plugin_manager::GeyserPluginManager,
     log::*,
-    solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaSlotStatus, ReplicaSlotStatusInfo, ReplicaSlotStatusInfoVersions,
+    },
     solana_measure::measure::Measure,
     solana_metrics::*,
-    solana_sdk::clock::Slot,
+    solana_sdk::{clock::Slot, hash::Hash},
     std::sync::{Arc, RwLock},
 };
 
-pub trait SlotStatusNotifierInterface {
-    /// Notified when a slot is optimistically confirmed
-    fn notify_slot_confirmed(&self, slot: Slot, parent: Option<Slot>);
-
-    /// Notified when a slot is marked frozen.
-    fn notify_slot_processed(&self, slot: Slot, parent: Option<Slot>);
-
-    /// Notified when a slot is rooted.
-    fn notify_slot_rooted(&self, slot: Slot, parent: Option<Slot>);
-}
-
-pub type SlotStatusNotifier = Arc<RwLock<dyn SlotStatusNotifierInterface + Sync + Send>>;
-
 pub struct SlotStatusNotifierImpl {
     plugin_manager: Arc<RwLock<GeyserPluginManager>>,
 }
 
 impl SlotStatusNotifierInterface for SlotStatusNotifierImpl {
     fn notify_slot_confirmed(&self, slot: Slot, parent: Option<Slot>) {
-        self.notify_slot_status(slot, parent, SlotStatus::Confirmed);
+        self.notify_slot_status(slot, parent, ReplicaSlotStatus::Confirmed);
     }
 
     fn notify_slot_processed(&self, slot: Slot, parent: Option<Slot>) {
-        self.notify_slot_status(slot, parent, SlotStatus::Processed);
+        self.notify_slot_status(slot, parent, ReplicaSlotStatus::Processed);
     }
 
     fn notify_slot_rooted(&self, slot: Slot, parent: Option<Slot>) {
-        self.notify_slot_status(slot, parent, SlotStatus::Rooted);
+        self.notify_slot_status(slot, parent, ReplicaSlotStatus::Rooted);
+    }
+
+    fn notify_created_bank(&self, slot: Slot, parent: Slot) {
+        self.notify_slot_status(slot, Some(parent), ReplicaSlotStatus::CreatedBank);
+    }
+
+    fn notify_completed(&self, slot: Slot, parent: Option<Slot>) {
+        self.notify_slot_status(slot, parent, ReplicaSlotStatus::Completed);
+    }
+
+    fn notify_frozen(&self, slot: Slot, parent: Slot, bank_hash: &Hash) {
+        self.notify_plugins(&ReplicaSlotStatusInfo {
+            slot,
+            parent: Some(parent),
+            status: ReplicaSlotStatus::Frozen,
+            bank_hash: Some(bank_hash.as_ref()),
+            dead_error: None,
+        });
+    }
+
+    fn notify_slot_dead(&self, slot: Slot, parent: Slot, error: String) {
+        self.notify_plugins(&ReplicaSlotStatusInfo {
+            slot,
+            parent: Some(parent),
+            status: ReplicaSlotStatus::Dead,
+            bank_hash: None,
+            dead_error: Some(&error),
+        });
     }
 }
 
@@ -44,15 +64,35 @@ impl SlotStatusNotifierImpl {
         Self { plugin_manager }
     }
 
-    pub fn notify_slot_status(&self, slot: Slot, parent: Option<Slot>, slot_status: SlotStatus) {
+    pub fn notify_slot_status(
+        &self,
+        slot: Slot,
+        parent: Option<Slot>,
+        slot_status: ReplicaSlotStatus,
+    ) {
+        self.notify_plugins(&ReplicaSlotStatusInfo {
+            slot,
+            parent,
+            status: slot_status,
+            bank_hash: None,
+            dead_error: None,
+        });
+    }
+
+    fn notify_plugins(&self, slot_status_info: &ReplicaSlotStatusInfo) {
         let plugin_manager = self.plugin_manager.read().unwrap();
         if plugin_manager.plugins.is_empty() {
             return;
         }
 
+        let slot = slot_status_info.slot;
         for plugin in plugin_manager.plugins.iter() {
+            if !plugin.slot_status_notifications_enabled() {
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-update-slot");
-            match plugin.update_slot_status(slot, parent, slot_status) {
+            match plugin.notify_slot_status(ReplicaSlotStatusInfoVersions::V0_0_1(slot_status_info))
+            {
                 Err(err) => {
                     error!(
                         "Failed to update slot status at slot {}, error: {} to plugin {}",
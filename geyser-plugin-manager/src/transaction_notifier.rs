@@ -3,14 +3,25 @@ use {
     crate::geyser_plugin_manager::GeyserPluginManager,
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::{
-        ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions,
+        ReplicaAccountState, ReplicaTransactionAccountState, ReplicaTransactionInfoV2,
+        ReplicaTransactionInfoV3, ReplicaTransactionInfoVersions,
     },
+    solana_ledger::transaction_account_states::{AccountStatesPrograms, TransactionAccountState},
     solana_measure::measure::Measure,
     solana_metrics::*,
     solana_rpc::transaction_notifier_interface::TransactionNotifier,
-    solana_sdk::{clock::Slot, signature::Signature, transaction::SanitizedTransaction},
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        clock::Slot,
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::SanitizedTransaction,
+    },
     solana_transaction_status::TransactionStatusMeta,
-    std::sync::{Arc, RwLock},
+    std::{
+        collections::HashSet,
+        sync::{Arc, RwLock},
+    },
 };
 
 /// This implementation of TransactionNotifier is passed to the rpc's TransactionStatusService
@@ -29,15 +40,9 @@ impl TransactionNotifier for TransactionNotifierImpl {
         signature: &Signature,
         transaction_status_meta: &TransactionStatusMeta,
         transaction: &SanitizedTransaction,
+        account_states: &[TransactionAccountState],
     ) {
         let mut measure = Measure::start("geyser-plugin-notify_plugins_of_transaction_info");
-        let transaction_log_info = Self::build_replica_transaction_info(
-            index,
-            signature,
-            transaction_status_meta,
-            transaction,
-        );
-
         let plugin_manager = self.plugin_manager.read().unwrap();
 
         if plugin_manager.plugins.is_empty() {
@@ -48,10 +53,38 @@ impl TransactionNotifier for TransactionNotifierImpl {
             if !plugin.transaction_notifications_enabled() {
                 continue;
             }
-            match plugin.notify_transaction(
-                ReplicaTransactionInfoVersions::V0_0_2(&transaction_log_info),
-                slot,
-            ) {
+            // Plugins which did not ask for account states keep getting the previous version
+            let result = match plugin.transaction_account_states_programs() {
+                Some(programs) => {
+                    let replica_account_states =
+                        Self::build_replica_account_states(account_states, programs);
+                    let transaction_log_info = Self::build_replica_transaction_info(
+                        index,
+                        signature,
+                        transaction_status_meta,
+                        transaction,
+                        &replica_account_states,
+                    );
+                    plugin.notify_transaction(
+                        ReplicaTransactionInfoVersions::V0_0_3(&transaction_log_info),
+                        slot,
+                    )
+                }
+                None => {
+                    let transaction_log_info = ReplicaTransactionInfoV2 {
+                        index,
+                        signature,
+                        is_vote: transaction.is_simple_vote_transaction(),
+                        transaction,
+                        transaction_status_meta,
+                    };
+                    plugin.notify_transaction(
+                        ReplicaTransactionInfoVersions::V0_0_2(&transaction_log_info),
+                        slot,
+                    )
+                }
+            };
+            match result {
                 Err(err) => {
                     error!(
                         "Failed to notify transaction, error: ({}) to plugin {}",
@@ -75,6 +108,15 @@ impl TransactionNotifier for TransactionNotifierImpl {
             10000
         );
     }
+
+    fn account_states_programs(&self) -> Option<AccountStatesPrograms> {
+        Some(
+            self.plugin_manager
+                .read()
+                .unwrap()
+                .shared_account_states_programs(),
+        )
+    }
 }
 
 impl TransactionNotifierImpl {
@@ -87,13 +129,142 @@ impl TransactionNotifierImpl {
         signature: &'a Signature,
         transaction_status_meta: &'a TransactionStatusMeta,
         transaction: &'a SanitizedTransaction,
-    ) -> ReplicaTransactionInfoV2<'a> {
-        ReplicaTransactionInfoV2 {
+        account_states: &'a [ReplicaTransactionAccountState<'a>],
+    ) -> ReplicaTransactionInfoV3<'a> {
+        ReplicaTransactionInfoV3 {
             index,
             signature,
             is_vote: transaction.is_simple_vote_transaction(),
             transaction,
             transaction_status_meta,
+            account_states,
+        }
+    }
+
+    /// Selects the account states owned, before or after the transaction, by one of `programs`;
+    /// an empty set selects every account state
+    fn build_replica_account_states<'a>(
+        account_states: &'a [TransactionAccountState],
+        programs: &HashSet<Pubkey>,
+    ) -> Vec<ReplicaTransactionAccountState<'a>> {
+        let is_selected =
+            |account: &AccountSharedData| programs.is_empty() || programs.contains(account.owner());
+        account_states
+            .iter()
+            .filter(|account_state| {
+                is_selected(&account_state.pre) || is_selected(&account_state.post)
+            })
+            .map(|account_state| ReplicaTransactionAccountState {
+                pubkey: account_state.pubkey.as_ref(),
+                pre: Self::build_replica_account_state(&account_state.pre),
+                post: Self::build_replica_account_state(&account_state.post),
+            })
+            .collect()
+    }
+
+    fn build_replica_account_state(account: &AccountSharedData) -> ReplicaAccountState {
+        ReplicaAccountState {
+            lamports: account.lamports(),
+            owner: account.owner().as_ref(),
+            executable: account.executable(),
+            rent_epoch: account.rent_epoch(),
+            data: account.data(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::geyser_plugin_manager::LoadedGeyserPlugin,
+        solana_geyser_plugin_interface::geyser_plugin_interface::{
+            GeyserPlugin, Result as PluginResult,
+        },
+        solana_sdk::{hash::Hash, signature::Keypair, system_transaction},
+        std::sync::Mutex,
+    };
+
+    /// Records the version and the number of account states of the transactions it is notified of
+    #[derive(Debug)]
+    struct RecordingPlugin {
+        name: &'static str,
+        programs: Option<HashSet<Pubkey>>,
+        notifications: Arc<Mutex<Vec<(&'static str, usize)>>>,
+    }
+
+    impl GeyserPlugin for RecordingPlugin {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn notify_transaction(
+            &self,
+            transaction: ReplicaTransactionInfoVersions,
+            _slot: Slot,
+        ) -> PluginResult<()> {
+            let notification = match transaction {
+                ReplicaTransactionInfoVersions::V0_0_1(_) => ("v1", 0),
+                ReplicaTransactionInfoVersions::V0_0_2(_) => ("v2", 0),
+                ReplicaTransactionInfoVersions::V0_0_3(info) => ("v3", info.account_states.len()),
+            };
+            self.notifications.lock().unwrap().push(notification);
+            Ok(())
+        }
+
+        fn transaction_account_states_programs(&self) -> Option<HashSet<Pubkey>> {
+            self.programs.clone()
+        }
+
+        fn transaction_notifications_enabled(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_notify_transaction_versions() {
+        let notifications = Arc::<Mutex<Vec<_>>>::default();
+        let mut plugin_manager = GeyserPluginManager::new();
+        for (name, programs) in [
+            ("without-states", None),
+            ("with-states", Some(HashSet::new())),
+        ] {
+            let mut plugin = LoadedGeyserPlugin::new(
+                Box::new(RecordingPlugin {
+                    name,
+                    programs,
+                    notifications: notifications.clone(),
+                }),
+                None,
+            );
+            plugin.load("", false).unwrap();
+            plugin_manager.add_loaded_plugin(plugin);
+        }
+        let transaction_notifier =
+            TransactionNotifierImpl::new(Arc::new(RwLock::new(plugin_manager)));
+
+        let transaction =
+            SanitizedTransaction::from_transaction_for_tests(system_transaction::transfer(
+                &Keypair::new(),
+                &Pubkey::new_unique(),
+                1,
+                Hash::default(),
+            ));
+        let account_state = TransactionAccountState {
+            pubkey: Pubkey::new_unique(),
+            pre: AccountSharedData::default(),
+            post: AccountSharedData::new(1, 0, &Pubkey::new_unique()),
+        };
+        transaction_notifier.notify_transaction(
+            0,
+            0,
+            transaction.signature(),
+            &TransactionStatusMeta::default(),
+            &transaction,
+            &[account_state],
+        );
+
+        // Only the plugin asking for account states is sent the version carrying them
+        assert_eq!(*notifications.lock().unwrap(), vec![("v2", 0), ("v3", 1)]);
+    }
+}
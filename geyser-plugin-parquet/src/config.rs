@@ -0,0 +1,229 @@
+//! The configuration of the Parquet plugin, read from the `parquet` section of the plugin config
+//! file
+
+use {
+    serde_derive::Deserialize,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{GeyserPluginError, Result},
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        collections::HashSet,
+        fs,
+        path::{Path, PathBuf},
+        str::FromStr,
+    },
+};
+
+const DEFAULT_SLOTS_PER_PARTITION: u64 = 10_000;
+const DEFAULT_ROW_GROUP_SIZE: usize = 10_000;
+const DEFAULT_MAX_ROWS_PER_FILE: usize = 1_000_000;
+
+/// The plugin config file. Only the `parquet` section is read by the plugin, the other fields
+/// (`libpath`, `name`, `queue`...) are for the plugin manager.
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+    parquet: ParquetPluginConfig,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ParquetPluginConfig {
+    /// Directory the tables are written to, one subdirectory per table. Relative paths are
+    /// resolved against the directory of the config file.
+    pub output_dir: PathBuf,
+    /// Number of slots of each partition directory
+    #[serde(default = "default_slots_per_partition")]
+    pub slots_per_partition: u64,
+    /// Number of rows buffered in memory before being written as a row group
+    #[serde(default = "default_row_group_size")]
+    pub row_group_size: usize,
+    /// Number of rows after which a file is closed and the next one opened
+    #[serde(default = "default_max_rows_per_file")]
+    pub max_rows_per_file: usize,
+    #[serde(default = "default_true")]
+    pub accounts: bool,
+    /// Whether to also write the accounts notified while the validator loads its snapshot
+    #[serde(default)]
+    pub startup_accounts: bool,
+    #[serde(default = "default_true")]
+    pub transactions: bool,
+    #[serde(default = "default_true")]
+    pub blocks: bool,
+    /// Whether to write the slot status updates, which tell the rows of rooted slots from the
+    /// rows of slots on abandoned forks
+    #[serde(default = "default_true")]
+    pub slots: bool,
+    /// Writes the instructions parsed by `solana-transaction-status` when set
+    #[serde(default)]
+    pub instructions: Option<InstructionsConfig>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct InstructionsConfig {
+    /// Ids of the programs whose instructions are written; every parsable program when empty
+    #[serde(default)]
+    pub programs: Vec<String>,
+    #[serde(default = "default_true")]
+    pub inner_instructions: bool,
+    /// Columns extracted from the `info` object of the parsed instructions
+    #[serde(default)]
+    pub columns: Vec<InstructionColumnConfig>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct InstructionColumnConfig {
+    pub name: String,
+    /// JSON pointer into the `info` object of the parsed instruction, for example `/lamports`
+    pub path: String,
+    #[serde(rename = "type", default)]
+    pub column_type: InstructionColumnType,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstructionColumnType {
+    /// Strings are written as is, other values as JSON
+    #[default]
+    String,
+    /// Numbers and decimal strings, like token amounts
+    U64,
+    Bool,
+}
+
+fn default_slots_per_partition() -> u64 {
+    DEFAULT_SLOTS_PER_PARTITION
+}
+
+fn default_row_group_size() -> usize {
+    DEFAULT_ROW_GROUP_SIZE
+}
+
+fn default_max_rows_per_file() -> usize {
+    DEFAULT_MAX_ROWS_PER_FILE
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn config_error(msg: impl Into<String>) -> GeyserPluginError {
+    GeyserPluginError::ConfigFileReadError { msg: msg.into() }
+}
+
+impl ParquetPluginConfig {
+    /// Reads the `parquet` section of the plugin config file
+    pub fn load(config_file: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(config_file)?;
+        let ConfigFile {
+            parquet: mut config,
+        } = json5::from_str(&contents)
+            .map_err(|err| config_error(format!("The config file is not valid: {err}")))?;
+        if config.output_dir.is_relative() {
+            if let Some(config_dir) = config_file.parent() {
+                config.output_dir = config_dir.join(&config.output_dir);
+            }
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.slots_per_partition == 0 {
+            return Err(config_error("slotsPerPartition must be positive"));
+        }
+        if self.row_group_size == 0 || self.max_rows_per_file < self.row_group_size {
+            return Err(config_error(
+                "rowGroupSize must be positive and at most maxRowsPerFile",
+            ));
+        }
+        if let Some(instructions) = &self.instructions {
+            instructions.programs()?;
+            let mut names = HashSet::new();
+            for column in &instructions.columns {
+                if crate::tables::INSTRUCTION_COLUMNS.contains(&column.name.as_str())
+                    || !names.insert(&column.name)
+                {
+                    return Err(config_error(format!(
+                        "Duplicate instruction column {}",
+                        column.name
+                    )));
+                }
+                if !column.path.is_empty() && !column.path.starts_with('/') {
+                    return Err(config_error(format!(
+                        "The path of instruction column {} is not a JSON pointer",
+                        column.name
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl InstructionsConfig {
+    pub fn programs(&self) -> Result<HashSet<Pubkey>> {
+        self.programs
+            .iter()
+            .map(|program| {
+                Pubkey::from_str(program)
+                    .map_err(|err| config_error(format!("Invalid program id {program}: {err}")))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::Write, tempfile::NamedTempFile};
+
+    fn load(contents: &str) -> Result<ParquetPluginConfig> {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        ParquetPluginConfig::load(file.path())
+    }
+
+    #[test]
+    fn test_load_config() {
+        let config = load(
+            r#"{
+                "libpath": "libsolana_geyser_plugin_parquet.so",
+                "parquet": {
+                    "outputDir": "tables",
+                    "slotsPerPartition": 1000,
+                    "instructions": {
+                        "programs": ["11111111111111111111111111111111"],
+                        "columns": [{"name": "lamports", "path": "/lamports", "type": "u64"}]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(config.output_dir.is_absolute());
+        assert!(config.output_dir.ends_with("tables"));
+        assert_eq!(config.slots_per_partition, 1000);
+        assert_eq!(config.row_group_size, DEFAULT_ROW_GROUP_SIZE);
+        assert!(config.accounts && config.transactions && config.blocks && config.slots);
+        assert!(!config.startup_accounts);
+        let instructions = config.instructions.unwrap();
+        assert_eq!(
+            instructions.programs().unwrap(),
+            HashSet::from([solana_sdk::system_program::id()])
+        );
+        assert_eq!(
+            instructions.columns[0].column_type,
+            InstructionColumnType::U64
+        );
+
+        assert!(load(r#"{"parquet": {"outputDir": "/tmp", "slotsPerPartition": 0}}"#).is_err());
+        assert!(load(r#"{"parquet": {"outputDir": "/tmp", "unknown": 1}}"#).is_err());
+        assert!(load(
+            r#"{"parquet": {"outputDir": "/tmp", "instructions": {"programs": ["bad"]}}}"#
+        )
+        .is_err());
+        assert!(load(
+            r#"{"parquet": {"outputDir": "/tmp", "instructions": {"columns": [{"name": "slot", "path": "/a"}]}}}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,24 @@
+//! A Geyser plugin writing accounts, transactions, parsed instructions and blocks to rolling
+//! Parquet files, partitioned by slot range. It is the reference implementation of a plugin
+//! storing data, and produces files that can be queried offline by any Arrow-based tool.
+
+pub mod config;
+pub mod parquet_plugin;
+pub mod tables;
+pub mod writer;
+
+use {
+    crate::parquet_plugin::GeyserParquetPlugin,
+    solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
+};
+
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+/// # Safety
+///
+/// This function returns the GeyserParquetPlugin pointer as trait GeyserPlugin.
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    let plugin = GeyserParquetPlugin::default();
+    let plugin: Box<dyn GeyserPlugin> = Box::new(plugin);
+    Box::into_raw(plugin)
+}
@@ -0,0 +1,481 @@
+/// Main entry for the Parquet plugin
+use {
+    crate::{
+        config::ParquetPluginConfig,
+        tables::{
+            AccountRow, AccountRows, BlockRow, BlockRows, InstructionRows, SlotRow, SlotRows,
+            TableRows, TransactionRow, TransactionRows,
+        },
+        writer::{PartitionedWriter, WriterError},
+    },
+    log::*,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, ReplicaBlockInfoVersions,
+        ReplicaSlotStatusInfoVersions, ReplicaTransactionInfoVersions, Result,
+    },
+    solana_sdk::clock::Slot,
+    std::{
+        fmt::{self, Debug, Formatter},
+        path::Path,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// A table writer, present when the table is enabled in the config
+type TableWriter<R> = Option<Mutex<PartitionedWriter<R>>>;
+
+#[derive(Default)]
+pub struct GeyserParquetPlugin {
+    config: Option<ParquetPluginConfig>,
+    accounts: TableWriter<AccountRows>,
+    transactions: TableWriter<TransactionRows>,
+    instructions: TableWriter<InstructionRows>,
+    blocks: TableWriter<BlockRows>,
+    slots: TableWriter<SlotRows>,
+}
+
+impl Debug for GeyserParquetPlugin {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("GeyserParquetPlugin")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl From<WriterError> for GeyserPluginError {
+    fn from(err: WriterError) -> Self {
+        GeyserPluginError::Custom(Box::new(err))
+    }
+}
+
+fn append<R: TableRows>(
+    writer: &TableWriter<R>,
+    slot: Slot,
+    append: impl FnOnce(&mut R),
+) -> Result<()> {
+    match writer {
+        Some(writer) => Ok(writer.lock().unwrap().append(slot, append)?),
+        None => Ok(()),
+    }
+}
+
+fn close<R: TableRows>(writer: &mut TableWriter<R>) {
+    if let Some(writer) = writer.take() {
+        let mut writer = writer.into_inner().unwrap();
+        if let Err(err) = writer.close() {
+            error!(
+                "Failed to close the files of {}: {err}",
+                writer.table_dir().display()
+            );
+        }
+    }
+}
+
+impl GeyserParquetPlugin {
+    fn close(&mut self) {
+        close(&mut self.accounts);
+        close(&mut self.transactions);
+        close(&mut self.instructions);
+        close(&mut self.blocks);
+        close(&mut self.slots);
+    }
+}
+
+impl GeyserPlugin for GeyserParquetPlugin {
+    fn name(&self) -> &'static str {
+        "GeyserParquetPlugin"
+    }
+
+    /// The `parquet` section of the config file configures the plugin:
+    /// {
+    ///    "libpath": "/home/solana/target/release/libsolana_geyser_plugin_parquet.so",
+    ///    "parquet": {
+    ///       "outputDir": "/data/geyser",
+    ///       "slotsPerPartition": 10000,
+    ///       "rowGroupSize": 10000,
+    ///       "maxRowsPerFile": 1000000,
+    ///       "accounts": true,
+    ///       "startupAccounts": false,
+    ///       "transactions": true,
+    ///       "blocks": true,
+    ///       "slots": true,
+    ///       "instructions": {
+    ///          "programs": ["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"],
+    ///          "innerInstructions": true,
+    ///          "columns": [{"name": "amount", "path": "/amount", "type": "u64"}]
+    ///       }
+    ///    }
+    /// }
+    fn on_load(&mut self, config_file: &str, _is_reload: bool) -> Result<()> {
+        solana_logger::setup_with_default("info");
+        info!(
+            "Loading plugin {:?} from config_file {:?}",
+            self.name(),
+            config_file
+        );
+        let config = ParquetPluginConfig::load(Path::new(config_file))?;
+        if config.accounts {
+            self.accounts = Some(Mutex::new(PartitionedWriter::new(
+                &config,
+                AccountRows::default,
+            )));
+        }
+        if config.transactions {
+            self.transactions = Some(Mutex::new(PartitionedWriter::new(
+                &config,
+                TransactionRows::default,
+            )));
+        }
+        if let Some(instructions) = &config.instructions {
+            let programs = Arc::new(instructions.programs()?);
+            let instructions = instructions.clone();
+            self.instructions = Some(Mutex::new(PartitionedWriter::new(&config, move || {
+                InstructionRows::new(&instructions, programs.clone())
+            })));
+        }
+        if config.blocks {
+            self.blocks = Some(Mutex::new(PartitionedWriter::new(
+                &config,
+                BlockRows::default,
+            )));
+        }
+        if config.slots {
+            self.slots = Some(Mutex::new(PartitionedWriter::new(
+                &config,
+                SlotRows::default,
+            )));
+        }
+        self.config = Some(config);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        info!("Unloading plugin: {:?}", self.name());
+        self.close();
+    }
+
+    fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: Slot,
+        is_startup: bool,
+    ) -> Result<()> {
+        let startup_accounts = self
+            .config
+            .as_ref()
+            .is_some_and(|config| config.startup_accounts);
+        if is_startup && !startup_accounts {
+            return Ok(());
+        }
+        let row = match account {
+            ReplicaAccountInfoVersions::V0_0_1(account) => AccountRow {
+                slot,
+                pubkey: account.pubkey,
+                lamports: account.lamports,
+                owner: account.owner,
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data,
+                write_version: account.write_version,
+                txn_signature: None,
+                is_startup,
+            },
+            ReplicaAccountInfoVersions::V0_0_2(account) => AccountRow {
+                slot,
+                pubkey: account.pubkey,
+                lamports: account.lamports,
+                owner: account.owner,
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data,
+                write_version: account.write_version,
+                txn_signature: account.txn_signature,
+                is_startup,
+            },
+            ReplicaAccountInfoVersions::V0_0_3(account) => AccountRow {
+                slot,
+                pubkey: account.pubkey,
+                lamports: account.lamports,
+                owner: account.owner,
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data,
+                write_version: account.write_version,
+                txn_signature: account.txn.map(|txn| txn.signature()),
+                is_startup,
+            },
+        };
+        append(&self.accounts, slot, |rows| rows.append(&row))
+    }
+
+    fn notify_transaction(
+        &self,
+        transaction: ReplicaTransactionInfoVersions,
+        slot: Slot,
+    ) -> Result<()> {
+        let row = match transaction {
+            ReplicaTransactionInfoVersions::V0_0_1(transaction) => TransactionRow {
+                slot,
+                index: None,
+                signature: transaction.signature,
+                is_vote: transaction.is_vote,
+                transaction: transaction.transaction,
+                transaction_status_meta: transaction.transaction_status_meta,
+            },
+            ReplicaTransactionInfoVersions::V0_0_2(transaction) => TransactionRow {
+                slot,
+                index: Some(transaction.index),
+                signature: transaction.signature,
+                is_vote: transaction.is_vote,
+                transaction: transaction.transaction,
+                transaction_status_meta: transaction.transaction_status_meta,
+            },
+            ReplicaTransactionInfoVersions::V0_0_3(transaction) => TransactionRow {
+                slot,
+                index: Some(transaction.index),
+                signature: transaction.signature,
+                is_vote: transaction.is_vote,
+                transaction: transaction.transaction,
+                transaction_status_meta: transaction.transaction_status_meta,
+            },
+        };
+        append(&self.transactions, slot, |rows| rows.append(&row))?;
+        append(&self.instructions, slot, |rows| {
+            rows.append_transaction(&row)
+        })
+    }
+
+    fn notify_block_metadata(&self, blockinfo: ReplicaBlockInfoVersions) -> Result<()> {
+        let row = match blockinfo {
+            ReplicaBlockInfoVersions::V0_0_1(block) => BlockRow {
+                slot: block.slot,
+                parent_slot: None,
+                blockhash: block.blockhash,
+                parent_blockhash: None,
+                block_time: block.block_time,
+                block_height: block.block_height,
+                executed_transaction_count: None,
+                entry_count: None,
+            },
+            ReplicaBlockInfoVersions::V0_0_2(block) => BlockRow {
+                slot: block.slot,
+                parent_slot: Some(block.parent_slot),
+                blockhash: block.blockhash,
+                parent_blockhash: Some(block.parent_blockhash),
+                block_time: block.block_time,
+                block_height: block.block_height,
+                executed_transaction_count: Some(block.executed_transaction_count),
+                entry_count: None,
+            },
+            ReplicaBlockInfoVersions::V0_0_3(block) => BlockRow {
+                slot: block.slot,
+                parent_slot: Some(block.parent_slot),
+                blockhash: block.blockhash,
+                parent_blockhash: Some(block.parent_blockhash),
+                block_time: block.block_time,
+                block_height: block.block_height,
+                executed_transaction_count: Some(block.executed_transaction_count),
+                entry_count: Some(block.entry_count),
+            },
+        };
+        append(&self.blocks, row.slot, |rows| rows.append(&row))
+    }
+
+    /// Writes every status update, so that the rows of the other tables can be limited to the
+    /// rooted slots, and those of dead slots told apart
+    fn notify_slot_status(&self, slot_status: ReplicaSlotStatusInfoVersions) -> Result<()> {
+        let row = match slot_status {
+            ReplicaSlotStatusInfoVersions::V0_0_1(info) => SlotRow {
+                slot: info.slot,
+                parent_slot: info.parent,
+                status: info.status.as_str(),
+                bank_hash: info.bank_hash,
+                dead_error: info.dead_error,
+            },
+        };
+        append(&self.slots, row.slot, |rows| rows.append(&row))
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        self.accounts.is_some()
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        self.transactions.is_some() || self.instructions.is_some()
+    }
+
+    fn slot_status_notifications_enabled(&self) -> bool {
+        self.slots.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        arrow::{
+            array::{Array, AsArray},
+            datatypes::UInt64Type,
+            record_batch::RecordBatch,
+        },
+        parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder,
+        solana_geyser_plugin_interface::geyser_plugin_interface::{
+            ReplicaAccountInfoV3, ReplicaBlockInfoV3, ReplicaSlotStatus, ReplicaSlotStatusInfo,
+        },
+        solana_sdk::pubkey::Pubkey,
+        std::{fs, io::Write},
+        tempfile::TempDir,
+    };
+
+    /// Reads the only file written to the first partition of a table
+    fn read_table(tables_dir: &Path, table: &str) -> RecordBatch {
+        let file = fs::File::open(
+            tables_dir
+                .join(table)
+                .join("slots_000000000000_000000009999")
+                .join("part-00000.parquet"),
+        )
+        .unwrap();
+        let mut batches: Vec<_> = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap())
+            .collect();
+        assert_eq!(batches.len(), 1);
+        batches.pop().unwrap()
+    }
+
+    #[test]
+    fn test_parquet_plugin() {
+        let output_dir = TempDir::new().unwrap();
+        let config_file = output_dir.path().join("config.json");
+        fs::File::create(&config_file)
+            .unwrap()
+            .write_all(br#"{"parquet": {"outputDir": "tables", "transactions": false}}"#)
+            .unwrap();
+
+        let mut plugin = GeyserParquetPlugin::default();
+        plugin
+            .on_load(config_file.to_str().unwrap(), false)
+            .unwrap();
+        assert!(plugin.account_data_notifications_enabled());
+        assert!(!plugin.transaction_notifications_enabled());
+        assert!(plugin.slot_status_notifications_enabled());
+
+        let (pubkey, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let account = ReplicaAccountInfoV3 {
+            pubkey: pubkey.as_ref(),
+            lamports: 1,
+            owner: owner.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data: &[1, 2, 3],
+            write_version: 1,
+            txn: None,
+        };
+        // Startup accounts are skipped by default
+        plugin
+            .update_account(ReplicaAccountInfoVersions::V0_0_3(&account), 0, true)
+            .unwrap();
+        plugin
+            .update_account(ReplicaAccountInfoVersions::V0_0_3(&account), 42, false)
+            .unwrap();
+        plugin
+            .notify_block_metadata(ReplicaBlockInfoVersions::V0_0_3(&ReplicaBlockInfoV3 {
+                parent_slot: 41,
+                parent_blockhash: "parent",
+                slot: 42,
+                blockhash: "blockhash",
+                rewards: &[],
+                block_time: None,
+                block_height: Some(40),
+                executed_transaction_count: 0,
+                entry_count: 1,
+            }))
+            .unwrap();
+        let bank_hash = [7; 32];
+        for (slot, status, bank_hash, dead_error) in [
+            (42, ReplicaSlotStatus::Frozen, Some(&bank_hash[..]), None),
+            (43, ReplicaSlotStatus::Dead, None, Some("invalid block")),
+            (42, ReplicaSlotStatus::Rooted, None, None),
+        ] {
+            plugin
+                .notify_slot_status(ReplicaSlotStatusInfoVersions::V0_0_1(
+                    &ReplicaSlotStatusInfo {
+                        slot,
+                        parent: Some(41),
+                        status,
+                        bank_hash,
+                        dead_error,
+                    },
+                ))
+                .unwrap();
+        }
+        plugin.on_unload();
+
+        let tables_dir = output_dir.path().join("tables");
+        let mut tables: Vec<_> = fs::read_dir(&tables_dir)
+            .unwrap()
+            .map(|table| table.unwrap().file_name().into_string().unwrap())
+            .collect();
+        tables.sort();
+        assert_eq!(tables, vec!["accounts", "blocks", "slots"]);
+
+        let accounts = read_table(&tables_dir, "accounts");
+        assert_eq!(accounts.num_rows(), 1);
+        let column = |name| accounts.column_by_name(name).unwrap();
+        assert_eq!(column("slot").as_primitive::<UInt64Type>().value(0), 42);
+        assert_eq!(
+            column("pubkey").as_string::<i32>().value(0),
+            pubkey.to_string()
+        );
+        assert_eq!(
+            column("owner").as_string::<i32>().value(0),
+            owner.to_string()
+        );
+        assert_eq!(column("lamports").as_primitive::<UInt64Type>().value(0), 1);
+        assert_eq!(column("data").as_binary::<i32>().value(0), &[1, 2, 3]);
+        assert!(column("txn_signature").is_null(0));
+        assert!(!column("is_startup").as_boolean().value(0));
+
+        let blocks = read_table(&tables_dir, "blocks");
+        assert_eq!(blocks.num_rows(), 1);
+        let column = |name| blocks.column_by_name(name).unwrap();
+        assert_eq!(column("slot").as_primitive::<UInt64Type>().value(0), 42);
+        assert_eq!(
+            column("parent_slot").as_primitive::<UInt64Type>().value(0),
+            41
+        );
+        assert_eq!(column("blockhash").as_string::<i32>().value(0), "blockhash");
+        assert_eq!(
+            column("block_height").as_primitive::<UInt64Type>().value(0),
+            40
+        );
+        assert!(column("block_time").is_null(0));
+
+        let slots = read_table(&tables_dir, "slots");
+        let column = |name| slots.column_by_name(name).unwrap();
+        assert_eq!(
+            column("slot")
+                .as_primitive::<UInt64Type>()
+                .values()
+                .to_vec(),
+            vec![42, 43, 42]
+        );
+        let status = column("status").as_string::<i32>();
+        assert_eq!(
+            (0..3).map(|row| status.value(row)).collect::<Vec<_>>(),
+            vec!["frozen", "dead", "rooted"]
+        );
+        assert_eq!(
+            column("bank_hash").as_string::<i32>().value(0),
+            bs58::encode(bank_hash).into_string()
+        );
+        assert!(column("bank_hash").is_null(1));
+        assert_eq!(
+            column("dead_error").as_string::<i32>().value(1),
+            "invalid block"
+        );
+    }
+}
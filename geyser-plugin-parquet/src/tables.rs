@@ -0,0 +1,777 @@
+//! The tables written by the Parquet plugin. Each table buffers its rows in Arrow builders until
+//! they are drained into a record batch by the writer.
+
+use {
+    crate::config::{InstructionColumnConfig, InstructionColumnType, InstructionsConfig},
+    arrow::{
+        array::{
+            ArrayRef, BinaryBuilder, BooleanBuilder, Int64Builder, ListBuilder, StringBuilder,
+            UInt32Builder, UInt64Builder,
+        },
+        datatypes::{DataType, Field, Schema, SchemaRef},
+        error::ArrowError,
+        record_batch::RecordBatch,
+    },
+    serde_json::Value,
+    solana_sdk::{
+        clock::{Slot, UnixTimestamp},
+        instruction::CompiledInstruction,
+        message::AccountKeys,
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::SanitizedTransaction,
+    },
+    solana_transaction_status::{parse_instruction::parse, TransactionStatusMeta},
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// The columns of the instructions table that are always written, before the configured ones
+pub const INSTRUCTION_COLUMNS: &[&str] = &[
+    "slot",
+    "signature",
+    "transaction_index",
+    "instruction_index",
+    "inner_index",
+    "stack_height",
+    "program_id",
+    "program",
+    "type",
+    "info",
+];
+
+/// The rows of a table buffered in memory
+pub trait TableRows: Send {
+    /// The name of the table, used as the name of its directory
+    fn name(&self) -> &'static str;
+
+    fn schema(&self) -> SchemaRef;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drains the buffered rows into a record batch
+    fn finish(&mut self) -> Result<RecordBatch, ArrowError>;
+}
+
+fn string_list_type() -> DataType {
+    DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)))
+}
+
+fn encode_base58(bytes: &[u8]) -> String {
+    bs58::encode(bytes).into_string()
+}
+
+/// An account update
+pub struct AccountRow<'a> {
+    pub slot: Slot,
+    pub pubkey: &'a [u8],
+    pub lamports: u64,
+    pub owner: &'a [u8],
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub data: &'a [u8],
+    pub write_version: u64,
+    pub txn_signature: Option<&'a Signature>,
+    pub is_startup: bool,
+}
+
+pub struct AccountRows {
+    schema: SchemaRef,
+    rows: usize,
+    slot: UInt64Builder,
+    pubkey: StringBuilder,
+    lamports: UInt64Builder,
+    owner: StringBuilder,
+    executable: BooleanBuilder,
+    rent_epoch: UInt64Builder,
+    data: BinaryBuilder,
+    write_version: UInt64Builder,
+    txn_signature: StringBuilder,
+    is_startup: BooleanBuilder,
+}
+
+impl Default for AccountRows {
+    fn default() -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("slot", DataType::UInt64, false),
+                Field::new("pubkey", DataType::Utf8, false),
+                Field::new("lamports", DataType::UInt64, false),
+                Field::new("owner", DataType::Utf8, false),
+                Field::new("executable", DataType::Boolean, false),
+                Field::new("rent_epoch", DataType::UInt64, false),
+                Field::new("data", DataType::Binary, false),
+                Field::new("write_version", DataType::UInt64, false),
+                Field::new("txn_signature", DataType::Utf8, true),
+                Field::new("is_startup", DataType::Boolean, false),
+            ])),
+            rows: 0,
+            slot: UInt64Builder::new(),
+            pubkey: StringBuilder::new(),
+            lamports: UInt64Builder::new(),
+            owner: StringBuilder::new(),
+            executable: BooleanBuilder::new(),
+            rent_epoch: UInt64Builder::new(),
+            data: BinaryBuilder::new(),
+            write_version: UInt64Builder::new(),
+            txn_signature: StringBuilder::new(),
+            is_startup: BooleanBuilder::new(),
+        }
+    }
+}
+
+impl AccountRows {
+    pub fn append(&mut self, row: &AccountRow) {
+        self.slot.append_value(row.slot);
+        self.pubkey.append_value(encode_base58(row.pubkey));
+        self.lamports.append_value(row.lamports);
+        self.owner.append_value(encode_base58(row.owner));
+        self.executable.append_value(row.executable);
+        self.rent_epoch.append_value(row.rent_epoch);
+        self.data.append_value(row.data);
+        self.write_version.append_value(row.write_version);
+        self.txn_signature
+            .append_option(row.txn_signature.map(ToString::to_string));
+        self.is_startup.append_value(row.is_startup);
+        self.rows += 1;
+    }
+}
+
+impl TableRows for AccountRows {
+    fn name(&self) -> &'static str {
+        "accounts"
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn len(&self) -> usize {
+        self.rows
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch, ArrowError> {
+        self.rows = 0;
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(self.slot.finish()),
+                Arc::new(self.pubkey.finish()),
+                Arc::new(self.lamports.finish()),
+                Arc::new(self.owner.finish()),
+                Arc::new(self.executable.finish()),
+                Arc::new(self.rent_epoch.finish()),
+                Arc::new(self.data.finish()),
+                Arc::new(self.write_version.finish()),
+                Arc::new(self.txn_signature.finish()),
+                Arc::new(self.is_startup.finish()),
+            ],
+        )
+    }
+}
+
+/// An executed transaction
+pub struct TransactionRow<'a> {
+    pub slot: Slot,
+    /// The index of the transaction in the block, unknown for the oldest notification version
+    pub index: Option<usize>,
+    pub signature: &'a Signature,
+    pub is_vote: bool,
+    pub transaction: &'a SanitizedTransaction,
+    pub transaction_status_meta: &'a TransactionStatusMeta,
+}
+
+pub struct TransactionRows {
+    schema: SchemaRef,
+    rows: usize,
+    slot: UInt64Builder,
+    index: UInt64Builder,
+    signature: StringBuilder,
+    is_vote: BooleanBuilder,
+    err: StringBuilder,
+    fee: UInt64Builder,
+    compute_units_consumed: UInt64Builder,
+    account_keys: ListBuilder<StringBuilder>,
+    log_messages: ListBuilder<StringBuilder>,
+}
+
+impl Default for TransactionRows {
+    fn default() -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("slot", DataType::UInt64, false),
+                Field::new("index", DataType::UInt64, true),
+                Field::new("signature", DataType::Utf8, false),
+                Field::new("is_vote", DataType::Boolean, false),
+                Field::new("err", DataType::Utf8, true),
+                Field::new("fee", DataType::UInt64, false),
+                Field::new("compute_units_consumed", DataType::UInt64, true),
+                Field::new("account_keys", string_list_type(), false),
+                Field::new("log_messages", string_list_type(), true),
+            ])),
+            rows: 0,
+            slot: UInt64Builder::new(),
+            index: UInt64Builder::new(),
+            signature: StringBuilder::new(),
+            is_vote: BooleanBuilder::new(),
+            err: StringBuilder::new(),
+            fee: UInt64Builder::new(),
+            compute_units_consumed: UInt64Builder::new(),
+            account_keys: ListBuilder::new(StringBuilder::new()),
+            log_messages: ListBuilder::new(StringBuilder::new()),
+        }
+    }
+}
+
+impl TransactionRows {
+    pub fn append(&mut self, row: &TransactionRow) {
+        let meta = row.transaction_status_meta;
+        self.slot.append_value(row.slot);
+        self.index
+            .append_option(row.index.map(|index| index as u64));
+        self.signature.append_value(row.signature.to_string());
+        self.is_vote.append_value(row.is_vote);
+        self.err
+            .append_option(meta.status.as_ref().err().map(ToString::to_string));
+        self.fee.append_value(meta.fee);
+        self.compute_units_consumed
+            .append_option(meta.compute_units_consumed);
+        for pubkey in row.transaction.message().account_keys().iter() {
+            self.account_keys.values().append_value(pubkey.to_string());
+        }
+        self.account_keys.append(true);
+        if let Some(log_messages) = &meta.log_messages {
+            for log_message in log_messages {
+                self.log_messages.values().append_value(log_message);
+            }
+        }
+        self.log_messages.append(meta.log_messages.is_some());
+        self.rows += 1;
+    }
+}
+
+impl TableRows for TransactionRows {
+    fn name(&self) -> &'static str {
+        "transactions"
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn len(&self) -> usize {
+        self.rows
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch, ArrowError> {
+        self.rows = 0;
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(self.slot.finish()),
+                Arc::new(self.index.finish()),
+                Arc::new(self.signature.finish()),
+                Arc::new(self.is_vote.finish()),
+                Arc::new(self.err.finish()),
+                Arc::new(self.fee.finish()),
+                Arc::new(self.compute_units_consumed.finish()),
+                Arc::new(self.account_keys.finish()),
+                Arc::new(self.log_messages.finish()),
+            ],
+        )
+    }
+}
+
+/// The builder of an instruction column extracted from the parsed `info` object
+enum InstructionColumnBuilder {
+    String(StringBuilder),
+    U64(UInt64Builder),
+    Bool(BooleanBuilder),
+}
+
+impl InstructionColumnBuilder {
+    fn new(column_type: InstructionColumnType) -> Self {
+        match column_type {
+            InstructionColumnType::String => Self::String(StringBuilder::new()),
+            InstructionColumnType::U64 => Self::U64(UInt64Builder::new()),
+            InstructionColumnType::Bool => Self::Bool(BooleanBuilder::new()),
+        }
+    }
+
+    fn data_type(column_type: InstructionColumnType) -> DataType {
+        match column_type {
+            InstructionColumnType::String => DataType::Utf8,
+            InstructionColumnType::U64 => DataType::UInt64,
+            InstructionColumnType::Bool => DataType::Boolean,
+        }
+    }
+
+    fn append(&mut self, value: Option<&Value>) {
+        match self {
+            Self::String(builder) => match value {
+                None | Some(Value::Null) => builder.append_null(),
+                Some(Value::String(value)) => builder.append_value(value),
+                Some(value) => builder.append_value(value.to_string()),
+            },
+            Self::U64(builder) => builder.append_option(value.and_then(|value| {
+                value
+                    .as_u64()
+                    .or_else(|| value.as_str().and_then(|value| value.parse().ok()))
+            })),
+            Self::Bool(builder) => builder.append_option(value.and_then(Value::as_bool)),
+        }
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            Self::String(builder) => Arc::new(builder.finish()),
+            Self::U64(builder) => Arc::new(builder.finish()),
+            Self::Bool(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+/// The instructions of transactions, parsed by `solana-transaction-status`. Instructions of
+/// programs without a parser are not written.
+pub struct InstructionRows {
+    schema: SchemaRef,
+    programs: Arc<HashSet<Pubkey>>,
+    inner_instructions: bool,
+    rows: usize,
+    slot: UInt64Builder,
+    signature: StringBuilder,
+    transaction_index: UInt64Builder,
+    instruction_index: UInt32Builder,
+    inner_index: UInt32Builder,
+    stack_height: UInt32Builder,
+    program_id: StringBuilder,
+    program: StringBuilder,
+    instruction_type: StringBuilder,
+    info: StringBuilder,
+    columns: Vec<(InstructionColumnConfig, InstructionColumnBuilder)>,
+}
+
+impl InstructionRows {
+    pub fn new(config: &InstructionsConfig, programs: Arc<HashSet<Pubkey>>) -> Self {
+        let mut fields = vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("signature", DataType::Utf8, false),
+            Field::new("transaction_index", DataType::UInt64, true),
+            Field::new("instruction_index", DataType::UInt32, false),
+            Field::new("inner_index", DataType::UInt32, true),
+            Field::new("stack_height", DataType::UInt32, true),
+            Field::new("program_id", DataType::Utf8, false),
+            Field::new("program", DataType::Utf8, false),
+            Field::new("type", DataType::Utf8, true),
+            Field::new("info", DataType::Utf8, false),
+        ];
+        fields.extend(config.columns.iter().map(|column| {
+            Field::new(
+                &column.name,
+                InstructionColumnBuilder::data_type(column.column_type),
+                true,
+            )
+        }));
+        Self {
+            schema: Arc::new(Schema::new(fields)),
+            programs,
+            inner_instructions: config.inner_instructions,
+            rows: 0,
+            slot: UInt64Builder::new(),
+            signature: StringBuilder::new(),
+            transaction_index: UInt64Builder::new(),
+            instruction_index: UInt32Builder::new(),
+            inner_index: UInt32Builder::new(),
+            stack_height: UInt32Builder::new(),
+            program_id: StringBuilder::new(),
+            program: StringBuilder::new(),
+            instruction_type: StringBuilder::new(),
+            info: StringBuilder::new(),
+            columns: config
+                .columns
+                .iter()
+                .map(|column| {
+                    (
+                        column.clone(),
+                        InstructionColumnBuilder::new(column.column_type),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Appends the parsable instructions of a transaction, including its inner instructions
+    /// when configured
+    pub fn append_transaction(&mut self, row: &TransactionRow) {
+        let account_keys = row.transaction.message().account_keys();
+        let inner_instructions = row
+            .transaction_status_meta
+            .inner_instructions
+            .as_deref()
+            .filter(|_| self.inner_instructions)
+            .unwrap_or_default();
+        for (instruction_index, instruction) in
+            row.transaction.message().instructions().iter().enumerate()
+        {
+            let instruction_index = instruction_index as u32;
+            self.append_instruction(
+                row,
+                &account_keys,
+                instruction,
+                instruction_index,
+                None,
+                None,
+            );
+            for inner_instructions in inner_instructions.iter().filter(|inner_instructions| {
+                u32::from(inner_instructions.index) == instruction_index
+            }) {
+                for (inner_index, inner_instruction) in
+                    inner_instructions.instructions.iter().enumerate()
+                {
+                    self.append_instruction(
+                        row,
+                        &account_keys,
+                        &inner_instruction.instruction,
+                        instruction_index,
+                        Some(inner_index as u32),
+                        inner_instruction.stack_height,
+                    );
+                }
+            }
+        }
+    }
+
+    fn append_instruction(
+        &mut self,
+        row: &TransactionRow,
+        account_keys: &AccountKeys,
+        instruction: &CompiledInstruction,
+        instruction_index: u32,
+        inner_index: Option<u32>,
+        stack_height: Option<u32>,
+    ) {
+        let Some(program_id) = account_keys.get(usize::from(instruction.program_id_index)) else {
+            return;
+        };
+        if !self.programs.is_empty() && !self.programs.contains(program_id) {
+            return;
+        }
+        let Ok(parsed_instruction) = parse(program_id, instruction, account_keys, stack_height)
+        else {
+            return;
+        };
+        // Most parsers return a `{"type": ..., "info": ...}` object, the memo parser a string
+        let instruction_type = parsed_instruction
+            .parsed
+            .get("type")
+            .and_then(Value::as_str);
+        let info = match instruction_type {
+            Some(_) => parsed_instruction
+                .parsed
+                .get("info")
+                .unwrap_or(&Value::Null),
+            None => &parsed_instruction.parsed,
+        };
+
+        self.slot.append_value(row.slot);
+        self.signature.append_value(row.signature.to_string());
+        self.transaction_index
+            .append_option(row.index.map(|index| index as u64));
+        self.instruction_index.append_value(instruction_index);
+        self.inner_index.append_option(inner_index);
+        self.stack_height.append_option(stack_height);
+        self.program_id.append_value(&parsed_instruction.program_id);
+        self.program.append_value(&parsed_instruction.program);
+        self.instruction_type.append_option(instruction_type);
+        self.info.append_value(info.to_string());
+        for (column, builder) in &mut self.columns {
+            builder.append(info.pointer(&column.path));
+        }
+        self.rows += 1;
+    }
+}
+
+impl TableRows for InstructionRows {
+    fn name(&self) -> &'static str {
+        "instructions"
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn len(&self) -> usize {
+        self.rows
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch, ArrowError> {
+        self.rows = 0;
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(self.slot.finish()),
+            Arc::new(self.signature.finish()),
+            Arc::new(self.transaction_index.finish()),
+            Arc::new(self.instruction_index.finish()),
+            Arc::new(self.inner_index.finish()),
+            Arc::new(self.stack_height.finish()),
+            Arc::new(self.program_id.finish()),
+            Arc::new(self.program.finish()),
+            Arc::new(self.instruction_type.finish()),
+            Arc::new(self.info.finish()),
+        ];
+        columns.extend(self.columns.iter_mut().map(|(_, builder)| builder.finish()));
+        RecordBatch::try_new(self.schema.clone(), columns)
+    }
+}
+
+/// The metadata of a block
+pub struct BlockRow<'a> {
+    pub slot: Slot,
+    pub parent_slot: Option<Slot>,
+    pub blockhash: &'a str,
+    pub parent_blockhash: Option<&'a str>,
+    pub block_time: Option<UnixTimestamp>,
+    pub block_height: Option<u64>,
+    pub executed_transaction_count: Option<u64>,
+    pub entry_count: Option<u64>,
+}
+
+pub struct BlockRows {
+    schema: SchemaRef,
+    rows: usize,
+    slot: UInt64Builder,
+    parent_slot: UInt64Builder,
+    blockhash: StringBuilder,
+    parent_blockhash: StringBuilder,
+    block_time: Int64Builder,
+    block_height: UInt64Builder,
+    executed_transaction_count: UInt64Builder,
+    entry_count: UInt64Builder,
+}
+
+impl Default for BlockRows {
+    fn default() -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("slot", DataType::UInt64, false),
+                Field::new("parent_slot", DataType::UInt64, true),
+                Field::new("blockhash", DataType::Utf8, false),
+                Field::new("parent_blockhash", DataType::Utf8, true),
+                Field::new("block_time", DataType::Int64, true),
+                Field::new("block_height", DataType::UInt64, true),
+                Field::new("executed_transaction_count", DataType::UInt64, true),
+                Field::new("entry_count", DataType::UInt64, true),
+            ])),
+            rows: 0,
+            slot: UInt64Builder::new(),
+            parent_slot: UInt64Builder::new(),
+            blockhash: StringBuilder::new(),
+            parent_blockhash: StringBuilder::new(),
+            block_time: Int64Builder::new(),
+            block_height: UInt64Builder::new(),
+            executed_transaction_count: UInt64Builder::new(),
+            entry_count: UInt64Builder::new(),
+        }
+    }
+}
+
+impl BlockRows {
+    pub fn append(&mut self, row: &BlockRow) {
+        self.slot.append_value(row.slot);
+        self.parent_slot.append_option(row.parent_slot);
+        self.blockhash.append_value(row.blockhash);
+        self.parent_blockhash.append_option(row.parent_blockhash);
+        self.block_time.append_option(row.block_time);
+        self.block_height.append_option(row.block_height);
+        self.executed_transaction_count
+            .append_option(row.executed_transaction_count);
+        self.entry_count.append_option(row.entry_count);
+        self.rows += 1;
+    }
+}
+
+impl TableRows for BlockRows {
+    fn name(&self) -> &'static str {
+        "blocks"
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn len(&self) -> usize {
+        self.rows
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch, ArrowError> {
+        self.rows = 0;
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(self.slot.finish()),
+                Arc::new(self.parent_slot.finish()),
+                Arc::new(self.blockhash.finish()),
+                Arc::new(self.parent_blockhash.finish()),
+                Arc::new(self.block_time.finish()),
+                Arc::new(self.block_height.finish()),
+                Arc::new(self.executed_transaction_count.finish()),
+                Arc::new(self.entry_count.finish()),
+            ],
+        )
+    }
+}
+
+/// A slot status update
+pub struct SlotRow<'a> {
+    pub slot: Slot,
+    pub parent_slot: Option<Slot>,
+    pub status: &'static str,
+    pub bank_hash: Option<&'a [u8]>,
+    pub dead_error: Option<&'a str>,
+}
+
+pub struct SlotRows {
+    schema: SchemaRef,
+    rows: usize,
+    slot: UInt64Builder,
+    parent_slot: UInt64Builder,
+    status: StringBuilder,
+    bank_hash: StringBuilder,
+    dead_error: StringBuilder,
+}
+
+impl Default for SlotRows {
+    fn default() -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("slot", DataType::UInt64, false),
+                Field::new("parent_slot", DataType::UInt64, true),
+                Field::new("status", DataType::Utf8, false),
+                Field::new("bank_hash", DataType::Utf8, true),
+                Field::new("dead_error", DataType::Utf8, true),
+            ])),
+            rows: 0,
+            slot: UInt64Builder::new(),
+            parent_slot: UInt64Builder::new(),
+            status: StringBuilder::new(),
+            bank_hash: StringBuilder::new(),
+            dead_error: StringBuilder::new(),
+        }
+    }
+}
+
+impl SlotRows {
+    pub fn append(&mut self, row: &SlotRow) {
+        self.slot.append_value(row.slot);
+        self.parent_slot.append_option(row.parent_slot);
+        self.status.append_value(row.status);
+        self.bank_hash
+            .append_option(row.bank_hash.map(encode_base58));
+        self.dead_error.append_option(row.dead_error);
+        self.rows += 1;
+    }
+}
+
+impl TableRows for SlotRows {
+    fn name(&self) -> &'static str {
+        "slots"
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn len(&self) -> usize {
+        self.rows
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch, ArrowError> {
+        self.rows = 0;
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(self.slot.finish()),
+                Arc::new(self.parent_slot.finish()),
+                Arc::new(self.status.finish()),
+                Arc::new(self.bank_hash.finish()),
+                Arc::new(self.dead_error.finish()),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        arrow::{
+            array::{Array, AsArray},
+            datatypes::UInt64Type,
+        },
+        solana_sdk::{hash::Hash, signature::Keypair, system_transaction},
+    };
+
+    #[test]
+    fn test_instruction_rows() {
+        let from = Keypair::new();
+        let to = Pubkey::new_unique();
+        let transaction = SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(&from, &to, 42, Hash::default()),
+        );
+        let transaction_status_meta = TransactionStatusMeta::default();
+        let config = InstructionsConfig {
+            programs: vec![],
+            inner_instructions: true,
+            columns: vec![
+                InstructionColumnConfig {
+                    name: "lamports".to_string(),
+                    path: "/lamports".to_string(),
+                    column_type: InstructionColumnType::U64,
+                },
+                InstructionColumnConfig {
+                    name: "destination".to_string(),
+                    path: "/destination".to_string(),
+                    column_type: InstructionColumnType::String,
+                },
+            ],
+        };
+        let mut rows = InstructionRows::new(&config, Arc::default());
+        rows.append_transaction(&TransactionRow {
+            slot: 7,
+            index: Some(0),
+            signature: transaction.signature(),
+            is_vote: false,
+            transaction: &transaction,
+            transaction_status_meta: &transaction_status_meta,
+        });
+        assert_eq!(rows.len(), 1);
+
+        let batch = rows.finish().unwrap();
+        assert!(rows.is_empty());
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(batch.num_columns(), INSTRUCTION_COLUMNS.len() + 2);
+        let column = |name| batch.column_by_name(name).unwrap();
+        assert_eq!(column("program").as_string::<i32>().value(0), "system");
+        assert_eq!(column("type").as_string::<i32>().value(0), "transfer");
+        assert_eq!(column("lamports").as_primitive::<UInt64Type>().value(0), 42);
+        assert_eq!(
+            column("destination").as_string::<i32>().value(0),
+            to.to_string()
+        );
+        assert!(column("inner_index").is_null(0));
+
+        // Instructions of the programs that are not selected are skipped
+        let mut rows = InstructionRows::new(&config, Arc::new(HashSet::from([to])));
+        rows.append_transaction(&TransactionRow {
+            slot: 7,
+            index: Some(0),
+            signature: transaction.signature(),
+            is_vote: false,
+            transaction: &transaction,
+            transaction_status_meta: &transaction_status_meta,
+        });
+        assert!(rows.is_empty());
+    }
+}
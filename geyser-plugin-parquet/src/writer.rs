@@ -0,0 +1,334 @@
+//! Writes the rows of a table to rolling Parquet files, partitioned by slot range:
+//! `<output_dir>/<table>/slots_<first slot>_<last slot>/part-<n>.parquet`. Files are written
+//! under a `.tmp` name and renamed once closed, so readers never see a partial file.
+
+use {
+    crate::{config::ParquetPluginConfig, tables::TableRows},
+    arrow::error::ArrowError,
+    log::*,
+    parquet::{
+        arrow::ArrowWriter, basic::Compression, errors::ParquetError,
+        file::properties::WriterProperties,
+    },
+    solana_sdk::clock::Slot,
+    std::{
+        collections::BTreeMap,
+        fs::{self, File},
+        io,
+        path::{Path, PathBuf},
+    },
+    thiserror::Error,
+};
+
+/// Number of partitions with open files per table. Keeping the previous partition open lets the
+/// slots replayed out of order around a partition boundary go to the same files.
+const MAX_OPEN_PARTITIONS: usize = 2;
+
+const PARTITION_FILE_PREFIX: &str = "part-";
+const PARTITION_FILE_EXTENSION: &str = "parquet";
+
+#[derive(Error, Debug)]
+pub enum WriterError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] ArrowError),
+
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] ParquetError),
+}
+
+pub type Result<T> = std::result::Result<T, WriterError>;
+
+struct PartitionFile {
+    path: PathBuf,
+    tmp_path: PathBuf,
+    writer: ArrowWriter<File>,
+    rows: usize,
+}
+
+struct Partition<R> {
+    rows: R,
+    file: Option<PartitionFile>,
+}
+
+pub struct PartitionedWriter<R: TableRows> {
+    table_dir: PathBuf,
+    slots_per_partition: u64,
+    row_group_size: usize,
+    max_rows_per_file: usize,
+    new_rows: Box<dyn Fn() -> R + Send>,
+    /// The open partitions, by first slot
+    partitions: BTreeMap<Slot, Partition<R>>,
+}
+
+impl<R: TableRows> PartitionedWriter<R> {
+    pub fn new(config: &ParquetPluginConfig, new_rows: impl Fn() -> R + Send + 'static) -> Self {
+        let table_dir = config.output_dir.join(new_rows().name());
+        Self {
+            table_dir,
+            slots_per_partition: config.slots_per_partition,
+            row_group_size: config.row_group_size,
+            max_rows_per_file: config.max_rows_per_file,
+            new_rows: Box::new(new_rows),
+            partitions: BTreeMap::new(),
+        }
+    }
+
+    pub fn table_dir(&self) -> &Path {
+        &self.table_dir
+    }
+
+    /// Appends rows for `slot` to the buffer of its partition, writing them as a row group once
+    /// there are `row_group_size` of them
+    pub fn append(&mut self, slot: Slot, append: impl FnOnce(&mut R)) -> Result<()> {
+        let first_slot = slot - slot % self.slots_per_partition;
+        let partition = self
+            .partitions
+            .entry(first_slot)
+            .or_insert_with(|| Partition {
+                rows: (self.new_rows)(),
+                file: None,
+            });
+        append(&mut partition.rows);
+        if partition.rows.len() >= self.row_group_size {
+            self.flush_partition(first_slot)?;
+        }
+
+        while self.partitions.len() > MAX_OPEN_PARTITIONS {
+            let first_slot = *self.partitions.keys().next().unwrap();
+            self.close_partition(first_slot)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered rows of every partition, leaving the files open
+    pub fn flush(&mut self) -> Result<()> {
+        let first_slots: Vec<_> = self.partitions.keys().copied().collect();
+        for first_slot in first_slots {
+            self.flush_partition(first_slot)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered rows of every partition and closes their files
+    pub fn close(&mut self) -> Result<()> {
+        while let Some(first_slot) = self.partitions.keys().next().copied() {
+            self.close_partition(first_slot)?;
+        }
+        Ok(())
+    }
+
+    fn flush_partition(&mut self, first_slot: Slot) -> Result<()> {
+        let Some(partition) = self.partitions.get_mut(&first_slot) else {
+            return Ok(());
+        };
+        if partition.rows.is_empty() {
+            return Ok(());
+        }
+        let batch = partition.rows.finish()?;
+        if partition.file.is_none() {
+            partition.file = Some(Self::create_file(
+                &self.table_dir,
+                first_slot,
+                self.slots_per_partition,
+                self.row_group_size,
+                &partition.rows,
+            )?);
+        }
+        let file = partition.file.as_mut().unwrap();
+        file.writer.write(&batch)?;
+        file.rows += batch.num_rows();
+        if file.rows >= self.max_rows_per_file {
+            Self::close_file(partition.file.take().unwrap())?;
+        }
+        Ok(())
+    }
+
+    fn close_partition(&mut self, first_slot: Slot) -> Result<()> {
+        self.flush_partition(first_slot)?;
+        if let Some(partition) = self.partitions.remove(&first_slot) {
+            if let Some(file) = partition.file {
+                Self::close_file(file)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn create_file(
+        table_dir: &Path,
+        first_slot: Slot,
+        slots_per_partition: u64,
+        row_group_size: usize,
+        rows: &R,
+    ) -> Result<PartitionFile> {
+        let partition_dir = table_dir.join(format!(
+            "slots_{first_slot:012}_{:012}",
+            first_slot + slots_per_partition - 1
+        ));
+        fs::create_dir_all(&partition_dir)?;
+        let path = partition_dir.join(format!(
+            "{PARTITION_FILE_PREFIX}{:05}.{PARTITION_FILE_EXTENSION}",
+            Self::next_file_index(&partition_dir)?
+        ));
+        let tmp_path = path.with_extension(format!("{PARTITION_FILE_EXTENSION}.tmp"));
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .set_max_row_group_size(row_group_size)
+            .build();
+        let writer =
+            ArrowWriter::try_new(File::create(&tmp_path)?, rows.schema(), Some(properties))?;
+        debug!("Opened {}", tmp_path.display());
+        Ok(PartitionFile {
+            path,
+            tmp_path,
+            writer,
+            rows: 0,
+        })
+    }
+
+    /// The index following the highest index of the files of a partition; a partition gets new
+    /// files when it is reopened, for example after a restart
+    fn next_file_index(partition_dir: &Path) -> Result<u64> {
+        let mut next_index = 0;
+        for entry in fs::read_dir(partition_dir)? {
+            let file_name = entry?.file_name();
+            let index = file_name
+                .to_str()
+                .and_then(|file_name| file_name.strip_prefix(PARTITION_FILE_PREFIX))
+                .and_then(|file_name| file_name.split('.').next())
+                .and_then(|index| index.parse::<u64>().ok());
+            if let Some(index) = index {
+                next_index = next_index.max(index + 1);
+            }
+        }
+        Ok(next_index)
+    }
+
+    fn close_file(file: PartitionFile) -> Result<()> {
+        file.writer.close()?;
+        fs::rename(&file.tmp_path, &file.path)?;
+        debug!("Closed {} with {} rows", file.path.display(), file.rows);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::tables::{BlockRow, BlockRows},
+        parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder,
+        tempfile::TempDir,
+    };
+
+    fn append_block(writer: &mut PartitionedWriter<BlockRows>, slot: Slot) {
+        writer
+            .append(slot, |rows| {
+                rows.append(&BlockRow {
+                    slot,
+                    parent_slot: slot.checked_sub(1),
+                    blockhash: "blockhash",
+                    parent_blockhash: None,
+                    block_time: None,
+                    block_height: Some(slot),
+                    executed_transaction_count: Some(0),
+                    entry_count: Some(1),
+                })
+            })
+            .unwrap();
+    }
+
+    /// The files of a partition directory with their number of rows
+    fn read_partition(partition_dir: &Path) -> Vec<(String, usize)> {
+        let mut files: Vec<_> = fs::read_dir(partition_dir)
+            .unwrap()
+            .map(|file| {
+                let file = file.unwrap();
+                let rows =
+                    ParquetRecordBatchReaderBuilder::try_new(File::open(file.path()).unwrap())
+                        .unwrap()
+                        .build()
+                        .unwrap()
+                        .map(|batch| batch.unwrap().num_rows())
+                        .sum();
+                (file.file_name().into_string().unwrap(), rows)
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// The files of each partition directory of a table
+    fn read_table(table_dir: &Path) -> BTreeMap<String, Vec<(String, usize)>> {
+        fs::read_dir(table_dir)
+            .unwrap()
+            .map(|partition| {
+                let partition = partition.unwrap();
+                (
+                    partition.file_name().into_string().unwrap(),
+                    read_partition(&partition.path()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_partitioned_writer() {
+        let output_dir = TempDir::new().unwrap();
+        let config = ParquetPluginConfig {
+            output_dir: output_dir.path().to_path_buf(),
+            slots_per_partition: 10,
+            row_group_size: 2,
+            max_rows_per_file: 4,
+            accounts: false,
+            startup_accounts: false,
+            transactions: false,
+            blocks: true,
+            slots: false,
+            instructions: None,
+        };
+        let mut writer = PartitionedWriter::new(&config, BlockRows::default);
+
+        // Slots of the first partition replayed after the second one started go to its files
+        for slot in [0, 1, 2, 3, 4, 10, 5, 11] {
+            append_block(&mut writer, slot);
+        }
+        // Opening a third partition closes the first one
+        append_block(&mut writer, 20);
+        assert_eq!(
+            read_partition(&writer.table_dir().join("slots_000000000000_000000000009")),
+            vec![
+                ("part-00000.parquet".to_string(), 4),
+                ("part-00001.parquet".to_string(), 2),
+            ]
+        );
+        writer.close().unwrap();
+
+        // A partition reopened after being closed gets a new file
+        append_block(&mut writer, 6);
+        writer.close().unwrap();
+        assert_eq!(
+            read_table(writer.table_dir()),
+            BTreeMap::from([
+                (
+                    "slots_000000000000_000000000009".to_string(),
+                    vec![
+                        ("part-00000.parquet".to_string(), 4),
+                        ("part-00001.parquet".to_string(), 2),
+                        ("part-00002.parquet".to_string(), 1),
+                    ]
+                ),
+                (
+                    "slots_000000000010_000000000019".to_string(),
+                    vec![("part-00000.parquet".to_string(), 2)]
+                ),
+                (
+                    "slots_000000000020_000000000029".to_string(),
+                    vec![("part-00000.parquet".to_string(), 1)]
+                ),
+            ])
+        );
+    }
+}
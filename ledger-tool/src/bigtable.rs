@@ -2,7 +2,9 @@
 use {
     crate::{
         ledger_path::canonicalize_ledger_path,
-        output::{CliBlockWithEntries, CliEntries, EncodedConfirmedBlockWithEntries},
+        output::{
+            CliBlockWithEntries, CliEntries, CliSlotMetadata, EncodedConfirmedBlockWithEntries,
+        },
     },
     clap::{
         value_t, value_t_or_exit, values_t_or_exit, App, AppSettings, Arg, ArgMatches, SubCommand,
@@ -24,7 +26,7 @@ use {
         blockstore_options::AccessType,
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
-    solana_storage_bigtable::CredentialType,
+    solana_storage_bigtable::{BigTableEmulator, CredentialType, LongTermLedgerStorage},
     solana_transaction_status::{
         BlockEncodingOptions, ConfirmedBlock, EncodeError, EncodedConfirmedBlock,
         TransactionDetails, UiTransactionEncoding, VersionedConfirmedBlock,
@@ -32,6 +34,7 @@ use {
     std::{
         cmp::min,
         collections::HashSet,
+        net::SocketAddr,
         path::Path,
         process::exit,
         result::Result,
@@ -45,14 +48,18 @@ async fn upload(
     starting_slot: Option<Slot>,
     ending_slot: Option<Slot>,
     force_reupload: bool,
+    slot_metadata_only: bool,
     config: solana_storage_bigtable::LedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
-        .await
-        .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
+    let bigtable: Arc<dyn LongTermLedgerStorage> = Arc::new(
+        solana_storage_bigtable::LedgerStorage::new_with_config(config)
+            .await
+            .map_err(|err| format!("Failed to connect to storage: {err:?}"))?,
+    );
 
     let config = ConfirmedBlockUploadConfig {
         force_reupload,
+        slot_metadata_only,
         ..ConfirmedBlockUploadConfig::default()
     };
     let blockstore = Arc::new(blockstore);
@@ -76,6 +83,7 @@ async fn upload(
         let last_slot_checked = solana_ledger::bigtable_upload::upload_confirmed_blocks(
             blockstore.clone(),
             bigtable.clone(),
+            None,
             starting_slot,
             current_ending_slot,
             config.clone(),
@@ -178,6 +186,21 @@ async fn entries(
     Ok(())
 }
 
+async fn slot_metadata(
+    slot: Slot,
+    output_format: OutputFormat,
+    config: solana_storage_bigtable::LedgerStorageConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+        .await
+        .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
+
+    let slot_metadata = bigtable.get_slot_metadata(slot).await?;
+    let cli_slot_metadata = CliSlotMetadata::new(slot, slot_metadata);
+    println!("{}", output_format.formatted_string(&cli_slot_metadata));
+    Ok(())
+}
+
 async fn blocks(
     starting_slot: Slot,
     limit: usize,
@@ -203,7 +226,44 @@ async fn compare_blocks(
     let reference_bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(ref_config)
         .await
         .map_err(|err| format!("failed to connect to reference bigtable: {err:?}"))?;
+    let owned_bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+        .await
+        .map_err(|err| format!("failed to connect to owned bigtable: {err:?}"))?;
 
+    let Some(MissingBlocksData {
+        last_block_checked,
+        missing_blocks,
+        superfluous_blocks,
+        num_reference_blocks,
+        num_owned_blocks,
+    }) = find_missing_blocks(&reference_bigtable, &owned_bigtable, starting_slot, limit).await?
+    else {
+        println!("Reference bigtable is empty after {starting_slot}. Aborting.");
+        return Ok(());
+    };
+
+    println!(
+        "{}",
+        json!({
+            "num_reference_slots": json!(num_reference_blocks),
+            "num_owned_slots": json!(num_owned_blocks),
+            "reference_last_block": json!(last_block_checked),
+            "missing_blocks":  json!(missing_blocks),
+            "superfluous_blocks":  json!(superfluous_blocks),
+        })
+    );
+
+    Ok(())
+}
+
+/// Compare the blocks of `owned_bigtable` to those of `reference_bigtable`, from `starting_slot`
+/// to at most `limit` blocks. Returns None if the reference has no blocks in that range.
+async fn find_missing_blocks(
+    reference_bigtable: &solana_storage_bigtable::LedgerStorage,
+    owned_bigtable: &solana_storage_bigtable::LedgerStorage,
+    starting_slot: Slot,
+    limit: usize,
+) -> solana_storage_bigtable::Result<Option<MissingBlocksData>> {
     let reference_bigtable_slots = reference_bigtable
         .get_confirmed_blocks(starting_slot, limit)
         .await?;
@@ -213,13 +273,9 @@ async fn compare_blocks(
     );
 
     if reference_bigtable_slots.is_empty() {
-        println!("Reference bigtable is empty after {starting_slot}. Aborting.");
-        return Ok(());
+        return Ok(None);
     }
 
-    let owned_bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
-        .await
-        .map_err(|err| format!("failed to connect to owned bigtable: {err:?}"))?;
     let owned_bigtable_slots = owned_bigtable
         .get_confirmed_blocks(starting_slot, limit)
         .await?;
@@ -228,25 +284,19 @@ async fn compare_blocks(
         owned_bigtable_slots.len()
     );
 
-    let MissingBlocksData {
-        last_block_checked,
-        missing_blocks,
-        superfluous_blocks,
-        num_reference_blocks,
-        num_owned_blocks,
-    } = missing_blocks(&reference_bigtable_slots, &owned_bigtable_slots);
+    Ok(Some(missing_blocks(
+        &reference_bigtable_slots,
+        &owned_bigtable_slots,
+    )))
+}
 
+async fn emulator(bind_address: SocketAddr) -> Result<(), Box<dyn std::error::Error>> {
+    let emulator = BigTableEmulator::start(bind_address).await?;
     println!(
-        "{}",
-        json!({
-            "num_reference_slots": json!(num_reference_blocks),
-            "num_owned_slots": json!(num_owned_blocks),
-            "reference_last_block": json!(last_block_checked),
-            "missing_blocks":  json!(missing_blocks),
-            "superfluous_blocks":  json!(superfluous_blocks),
-        })
+        "BigTable emulator listening on {0}, connect with BIGTABLE_EMULATOR_HOST={0}",
+        emulator.endpoint()
     );
-
+    emulator.join().await?;
     Ok(())
 }
 
@@ -654,6 +704,7 @@ async fn get_bigtable(
                 instance_name: args.instance_name,
                 app_profile_id: args.app_profile_id,
                 max_message_size: solana_storage_bigtable::DEFAULT_MAX_MESSAGE_SIZE,
+                emulator_endpoint: None,
             },
         )
         .await
@@ -689,6 +740,17 @@ impl BigTableSubCommand for App<'_, '_> {
                         .default_value(solana_storage_bigtable::DEFAULT_APP_PROFILE_ID)
                         .help("Bigtable application profile id to use in requests"),
                 )
+                .arg(
+                    Arg::with_name("emulator")
+                        .global(true)
+                        .long("emulator")
+                        .takes_value(false)
+                        .help(
+                            "Run the command against an in-memory BigTable emulator served by \
+                             this process, instead of Google Cloud. Its data is discarded when \
+                             the command exits",
+                        ),
+                )
                 .subcommand(
                     SubCommand::with_name("upload")
                         .about("Upload the ledger to BigTable")
@@ -721,6 +783,33 @@ impl BigTableSubCommand for App<'_, '_> {
                                      instance. Note: reupload will *not* delete any data from the \
                                      tx-by-addr table; Use with care.",
                                 ),
+                        )
+                        .arg(
+                            Arg::with_name("slot_metadata_only")
+                                .long("slot-metadata-only")
+                                .takes_value(false)
+                                .conflicts_with("force_reupload")
+                                .help(
+                                    "Only upload the slot metadata of the blocks already present \
+                                     in BigTable instance, to backfill the slot-metadata table \
+                                     for blocks uploaded before it existed",
+                                ),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("emulator")
+                        .about(
+                            "Serve an in-memory BigTable emulator until interrupted, for other \
+                             processes to use through the BIGTABLE_EMULATOR_HOST environment \
+                             variable",
+                        )
+                        .arg(
+                            Arg::with_name("bind_address")
+                                .long("bind-address")
+                                .value_name("HOST:PORT")
+                                .takes_value(true)
+                                .default_value("127.0.0.1:8086")
+                                .help("Address to serve the emulator on"),
                         ),
                 )
                 .subcommand(
@@ -862,6 +951,22 @@ impl BigTableSubCommand for App<'_, '_> {
                                 .required(true),
                         ),
                 )
+                .subcommand(
+                    SubCommand::with_name("slot-metadata")
+                        .about(
+                            "Get the metadata of a slot: its bank hash, leader, tick count and \
+                             rewards",
+                        )
+                        .arg(
+                            Arg::with_name("slot")
+                                .long("slot")
+                                .validator(is_slot)
+                                .value_name("SLOT")
+                                .takes_value(true)
+                                .index(1)
+                                .required(true),
+                        ),
+                )
                 .subcommand(
                     SubCommand::with_name("confirm")
                         .about("Confirm transaction by signature")
@@ -1092,11 +1197,29 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
         solana_storage_bigtable::DEFAULT_APP_PROFILE_ID,
     );
 
+    // The emulator is served by the runtime until the command completes, and the connections of
+    // the command are directed to it
+    let local_emulator = (matches.is_present("emulator")
+        || sub_matches.is_some_and(|sub_matches| sub_matches.is_present("emulator")))
+    .then(|| {
+        runtime
+            .block_on(BigTableEmulator::start(SocketAddr::from((
+                [127, 0, 0, 1],
+                0,
+            ))))
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to start the BigTable emulator: {err:?}");
+                exit(1);
+            })
+    });
+    let emulator_endpoint = local_emulator.as_ref().map(BigTableEmulator::endpoint);
+
     let future = match (subcommand, sub_matches) {
         ("upload", Some(arg_matches)) => {
             let starting_slot = value_t!(arg_matches, "starting_slot", Slot).ok();
             let ending_slot = value_t!(arg_matches, "ending_slot", Slot).ok();
             let force_reupload = arg_matches.is_present("force_reupload");
+            let slot_metadata_only = arg_matches.is_present("slot_metadata_only");
             let blockstore = crate::open_blockstore(
                 &canonicalize_ledger_path(ledger_path),
                 arg_matches,
@@ -1106,6 +1229,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(upload(
@@ -1113,15 +1237,21 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 starting_slot,
                 ending_slot,
                 force_reupload,
+                slot_metadata_only,
                 config,
             ))
         }
+        ("emulator", Some(arg_matches)) => {
+            let bind_address = value_t_or_exit!(arg_matches, "bind_address", SocketAddr);
+            runtime.block_on(emulator(bind_address))
+        }
         ("delete-slots", Some(arg_matches)) => {
             let slots = values_t_or_exit!(arg_matches, "slots", Slot);
             let config = solana_storage_bigtable::LedgerStorageConfig {
                 read_only: !arg_matches.is_present("force"),
                 instance_name,
                 app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(delete_slots(slots, config))
@@ -1131,6 +1261,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(first_available_block(config))
@@ -1142,6 +1273,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(block(slot, output_format, show_entries, config))
@@ -1152,10 +1284,22 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(entries(slot, output_format, config))
         }
+        ("slot-metadata", Some(arg_matches)) => {
+            let slot = value_t_or_exit!(arg_matches, "slot", Slot);
+            let config = solana_storage_bigtable::LedgerStorageConfig {
+                read_only: true,
+                instance_name,
+                app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
+                ..solana_storage_bigtable::LedgerStorageConfig::default()
+            };
+            runtime.block_on(slot_metadata(slot, output_format, config))
+        }
         ("blocks", Some(arg_matches)) => {
             let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
             let limit = value_t_or_exit!(arg_matches, "limit", usize);
@@ -1163,6 +1307,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1175,6 +1320,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1193,6 +1339,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 credential_type: CredentialType::Filepath(credential_path),
                 instance_name: ref_instance_name,
                 app_profile_id: ref_app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1208,6 +1355,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1228,6 +1376,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                emulator_endpoint: emulator_endpoint.clone(),
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1242,7 +1391,15 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 config,
             ))
         }
-        ("copy", Some(arg_matches)) => runtime.block_on(copy(CopyArgs::process(arg_matches))),
+        ("copy", Some(arg_matches)) => {
+            let mut args = CopyArgs::process(arg_matches);
+            if let Some(endpoint) = &emulator_endpoint {
+                args.emulated_source.get_or_insert_with(|| endpoint.clone());
+                args.emulated_destination
+                    .get_or_insert_with(|| endpoint.clone());
+            }
+            runtime.block_on(copy(args))
+        }
         _ => unreachable!(),
     };
 
@@ -1330,7 +1487,7 @@ fn missing_blocks(reference: &[Slot], owned: &[Slot]) -> MissingBlocksData {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {super::*, solana_sdk::hash::Hash};
 
     #[test]
     fn test_missing_blocks() {
@@ -1401,4 +1558,70 @@ mod tests {
             }
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_find_missing_blocks() {
+        let reference_emulator = BigTableEmulator::start(SocketAddr::from(([127, 0, 0, 1], 0)))
+            .await
+            .unwrap();
+        let owned_emulator = BigTableEmulator::start(SocketAddr::from(([127, 0, 0, 1], 0)))
+            .await
+            .unwrap();
+        let connect = |emulator: &BigTableEmulator| {
+            solana_storage_bigtable::LedgerStorage::new_for_emulator(
+                solana_storage_bigtable::DEFAULT_INSTANCE_NAME,
+                solana_storage_bigtable::DEFAULT_APP_PROFILE_ID,
+                &emulator.endpoint(),
+                None,
+            )
+            .unwrap()
+        };
+        let reference_bigtable = connect(&reference_emulator);
+        let owned_bigtable = connect(&owned_emulator);
+
+        assert_eq!(
+            find_missing_blocks(&reference_bigtable, &owned_bigtable, 0, 10)
+                .await
+                .unwrap(),
+            None
+        );
+
+        let block = |parent_slot| VersionedConfirmedBlock {
+            previous_blockhash: Hash::new_unique().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot,
+            transactions: vec![],
+            rewards: vec![],
+            block_time: None,
+            block_height: None,
+        };
+        for slot in [1, 2, 3, 5] {
+            reference_bigtable
+                .upload_confirmed_block(slot, block(slot - 1))
+                .await
+                .unwrap();
+        }
+        for slot in [1, 3, 4] {
+            owned_bigtable
+                .upload_confirmed_block(slot, block(slot - 1))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            find_missing_blocks(&reference_bigtable, &owned_bigtable, 0, 10)
+                .await
+                .unwrap(),
+            Some(MissingBlocksData {
+                last_block_checked: 4,
+                missing_blocks: vec![2],
+                superfluous_blocks: vec![4],
+                num_reference_blocks: 3,
+                num_owned_blocks: 3,
+            })
+        );
+
+        reference_emulator.shutdown().await.unwrap();
+        owned_emulator.shutdown().await.unwrap();
+    }
 }
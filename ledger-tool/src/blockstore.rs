@@ -5,7 +5,11 @@ use {
         error::{LedgerToolError, Result},
         ledger_path::canonicalize_ledger_path,
         ledger_utils::{get_program_ids, get_shred_storage_type},
-        output::{output_ledger, output_slot, SlotBounds, SlotInfo},
+        output::{
+            output_ledger, output_slot, CliQueriedTransaction, CliStorageStats, RecordOutputFormat,
+            RecordOutputWriter, SlotBounds, SlotInfo,
+        },
+        transaction_filter::TransactionFilter,
     },
     chrono::{DateTime, Utc},
     clap::{
@@ -19,7 +23,7 @@ use {
     solana_cli_output::OutputFormat,
     solana_ledger::{
         ancestor_iterator::AncestorIterator,
-        blockstore::{Blockstore, PurgeType},
+        blockstore::{blockstore_archive::verify_blockstore_archive, Blockstore, PurgeType},
         blockstore_db::{self, Column, ColumnName, Database},
         blockstore_options::{AccessType, BLOCKSTORE_DIRECTORY_ROCKS_FIFO},
         shred::Shred,
@@ -31,7 +35,7 @@ use {
     std::{
         collections::{BTreeMap, BTreeSet, HashMap},
         fs::File,
-        io::{stdout, BufRead, BufReader, Write},
+        io::{stdout, BufRead, BufReader, BufWriter, Write},
         path::{Path, PathBuf},
         sync::atomic::AtomicBool,
         time::{Duration, UNIX_EPOCH},
@@ -127,7 +131,9 @@ fn analyze_storage(database: &Database) -> Result<()> {
     analyze_column::<ShredCode>(database, "ShredCode")?;
     analyze_column::<TransactionStatus>(database, "TransactionStatus")?;
     analyze_column::<AddressSignatures>(database, "AddressSignatures")?;
+    analyze_column::<ProgramSignatures>(database, "ProgramSignatures")?;
     analyze_column::<TransactionMemos>(database, "TransactionMemos")?;
+    analyze_column::<TransactionLogs>(database, "TransactionLogs")?;
     analyze_column::<TransactionStatusIndex>(database, "TransactionStatusIndex")?;
     analyze_column::<Rewards>(database, "Rewards")?;
     analyze_column::<Blocktime>(database, "Blocktime")?;
@@ -156,7 +162,13 @@ fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
         cf::AddressSignatures::NAME => Some(cf::AddressSignatures::slot(
             cf::AddressSignatures::index(key),
         )),
+        cf::ProgramSignatures::NAME => Some(cf::ProgramSignatures::slot(
+            cf::ProgramSignatures::index(key),
+        )),
         cf::TransactionMemos::NAME => None, // does not implement slot()
+        cf::TransactionLogs::NAME => {
+            Some(cf::TransactionLogs::slot(cf::TransactionLogs::index(key)))
+        }
         cf::TransactionStatusIndex::NAME => None, // does not implement slot()
         cf::Rewards::NAME => Some(cf::Rewards::slot(cf::Rewards::index(key))),
         cf::Blocktime::NAME => Some(cf::Blocktime::slot(cf::Blocktime::index(key))),
@@ -347,6 +359,59 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
             .about("Print all the duplicate slots in the ledger")
             .settings(&hidden)
             .arg(&starting_slot_arg),
+        SubCommand::with_name("export")
+            .about(
+                "Export a range of slots to a compressed, checksummed archive of their shreds, \
+                 slot metadata, roots, transaction statuses, rewards and block times",
+            )
+            .settings(&hidden)
+            .arg(
+                Arg::with_name("start_slot")
+                    .long("start-slot")
+                    .value_name("SLOT")
+                    .validator(is_slot)
+                    .takes_value(true)
+                    .required(true)
+                    .help("First slot to export (inclusive)"),
+            )
+            .arg(
+                Arg::with_name("end_slot")
+                    .long("end-slot")
+                    .value_name("SLOT")
+                    .validator(is_slot)
+                    .takes_value(true)
+                    .required(true)
+                    .help("Last slot to export (inclusive)"),
+            )
+            .arg(
+                Arg::with_name("archive")
+                    .long("output")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Path of the archive to write"),
+            ),
+        SubCommand::with_name("import")
+            .about(
+                "Verify an archive written by the export command and merge its slots into the \
+                 ledger. The shreds of the archive are not checked against the leader schedule, \
+                 so only import archives from a trusted source",
+            )
+            .settings(&hidden)
+            .arg(
+                Arg::with_name("archive")
+                    .index(1)
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Path of the archive to import"),
+            )
+            .arg(
+                Arg::with_name("verify_only")
+                    .long("verify-only")
+                    .takes_value(false)
+                    .help("Only verify the archive, without opening the ledger"),
+            ),
         SubCommand::with_name("json")
             .about("Print the ledger in JSON format")
             .settings(&hidden)
@@ -519,6 +584,40 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
                     .takes_value(false)
                     .help("Limit purging to dead slots only"),
             ),
+        SubCommand::with_name("query")
+            .about("Print the transactions in a range of slots that match a filter expression")
+            .settings(&hidden)
+            .arg(&starting_slot_arg)
+            .arg(&ending_slot_arg)
+            .arg(
+                Arg::with_name("filter")
+                    .index(1)
+                    .value_name("EXPRESSION")
+                    .takes_value(true)
+                    .help(
+                        "Filter expression, e.g. 'failed and program = <PUBKEY> and cu > 200000'. \
+                         Predicates are `success`, `failed`, `program = <PUBKEY>`, `account = \
+                         <PUBKEY>`, `error = <TYPE>`, `error ~ <TEXT>`, `log ~ <TEXT>` and \
+                         `fee`, `cu` or `slot` compared with =, !=, <, <=, > or >= to a number. \
+                         Predicates are combined with `and`, `or`, `not` and parentheses. If \
+                         omitted, every transaction is printed.",
+                    ),
+            )
+            .arg(
+                Arg::with_name("only_rooted")
+                    .long("only-rooted")
+                    .takes_value(false)
+                    .help("Only query root slots"),
+            )
+            .arg(
+                Arg::with_name("query_output_format")
+                    .long("format")
+                    .value_name("FORMAT")
+                    .takes_value(true)
+                    .possible_values(&["json-lines", "csv"])
+                    .default_value("json-lines")
+                    .help("Print the matching transactions as JSON lines or CSV rows"),
+            ),
         SubCommand::with_name("remove-dead-slot")
             .about("Remove the dead flag for a slot")
             .settings(&hidden)
@@ -593,9 +692,67 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
                     .required(true)
                     .help("Slots to print"),
             ),
+        SubCommand::with_name("stats")
+            .about(
+                "Print the disk usage, SST file count, estimated tombstones and compression \
+                 ratio of every column family in the ledger rocksdb",
+            )
+            .settings(&hidden),
     ]
 }
 
+/// Writes every transaction between `starting_slot` and `ending_slot` that
+/// matches `filter` and returns the number of matches
+fn query_transactions<W: Write>(
+    blockstore: &Blockstore,
+    starting_slot: Slot,
+    ending_slot: Slot,
+    only_rooted: bool,
+    filter: &TransactionFilter,
+    writer: &mut RecordOutputWriter<W>,
+) -> Result<usize> {
+    let slot_range = filter.slot_range();
+    let starting_slot = starting_slot.max(*slot_range.start());
+    let ending_slot = ending_slot.min(*slot_range.end());
+    if starting_slot > ending_slot {
+        return Ok(0);
+    }
+    let mut num_matches = 0;
+    for (slot, _slot_meta) in blockstore.slot_meta_iterator(starting_slot)? {
+        if slot > ending_slot {
+            break;
+        }
+        if (only_rooted && !blockstore.is_root(slot)) || blockstore.is_dead(slot) {
+            continue;
+        }
+
+        let transactions = blockstore.get_slot_entries(slot, 0).and_then(|entries| {
+            blockstore.map_transactions_to_statuses(
+                slot,
+                entries.into_iter().flat_map(|entry| entry.transactions),
+            )
+        });
+        let transactions = match transactions {
+            Ok(transactions) => transactions,
+            Err(err) => {
+                warn!("Skipping slot {slot}, failed to load its transactions: {err}");
+                continue;
+            }
+        };
+        for (transaction_index, transaction) in transactions.iter().enumerate() {
+            if filter.matches(slot, transaction) {
+                writer.write(&CliQueriedTransaction::new(
+                    slot,
+                    transaction_index,
+                    transaction,
+                ))?;
+                num_matches += 1;
+            }
+        }
+    }
+    Ok(num_matches)
+}
+
 pub fn blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
     do_blockstore_process_command(ledger_path, matches).unwrap_or_else(|err| {
         eprintln!("Failed to complete command: {err:?}");
@@ -717,6 +874,59 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
                 println!("{slot}");
             }
         }
+        ("export", Some(arg_matches)) => {
+            let start_slot = value_t_or_exit!(arg_matches, "start_slot", Slot);
+            let end_slot = value_t_or_exit!(arg_matches, "end_slot", Slot);
+            let archive_path = PathBuf::from(value_t_or_exit!(arg_matches, "archive", String));
+            if start_slot > end_slot {
+                return Err(LedgerToolError::BadArgument(format!(
+                    "starting slot {start_slot} should be less than or equal to ending slot \
+                     {end_slot}"
+                )));
+            }
+
+            let blockstore =
+                crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let archive = BufWriter::new(File::create(&archive_path)?);
+            let stats = blockstore.export_slot_range(start_slot, end_slot, archive)?;
+            println!(
+                "Exported {} slots ({} roots, {} shreds, {} transaction statuses) to {}",
+                stats.num_slots,
+                stats.num_roots,
+                stats.num_shreds,
+                stats.num_transaction_statuses,
+                archive_path.display()
+            );
+        }
+        ("import", Some(arg_matches)) => {
+            let archive_path = PathBuf::from(value_t_or_exit!(arg_matches, "archive", String));
+            let archive = BufReader::new(File::open(&archive_path)?);
+            if arg_matches.is_present("verify_only") {
+                let header = verify_blockstore_archive(archive)?;
+                println!(
+                    "{} is a valid archive of slots {} to {}",
+                    archive_path.display(),
+                    header.start_slot,
+                    header.end_slot
+                );
+                return Ok(());
+            }
+
+            let blockstore = crate::open_blockstore(&ledger_path, arg_matches, AccessType::Primary);
+            // The ledger alone has no leader schedule to check the shreds against, so the
+            // archive is trusted
+            let (header, stats) = blockstore.import_archive(archive, None)?;
+            println!(
+                "Imported {} slots ({} roots, {} shreds, {} transaction statuses) of slots {} \
+                 to {}",
+                stats.num_slots,
+                stats.num_roots,
+                stats.num_shreds,
+                stats.num_transaction_statuses,
+                header.start_slot,
+                header.end_slot
+            );
+        }
         ("json", Some(arg_matches)) => {
             let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
             let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
@@ -954,6 +1164,37 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
                 }
             }
         }
+        ("query", Some(arg_matches)) => {
+            let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+            let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
+            let only_rooted = arg_matches.is_present("only_rooted");
+            let filter = arg_matches
+                .value_of("filter")
+                .unwrap_or_default()
+                .parse::<TransactionFilter>()
+                .map_err(LedgerToolError::BadArgument)?;
+            let output_format = match arg_matches.value_of("query_output_format") {
+                Some("csv") => RecordOutputFormat::Csv,
+                _ => RecordOutputFormat::JsonLines,
+            };
+
+            let blockstore =
+                crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let mut writer = RecordOutputWriter::new::<CliQueriedTransaction>(
+                BufWriter::new(stdout()),
+                output_format,
+            )?;
+            let num_matches = query_transactions(
+                &blockstore,
+                starting_slot,
+                ending_slot,
+                only_rooted,
+                &filter,
+                &mut writer,
+            )?;
+            writer.flush()?;
+            eprintln!("{num_matches} matching transactions");
+        }
         ("remove-dead-slot", Some(arg_matches)) => {
             let slots = values_t_or_exit!(arg_matches, "slots", Slot);
             let blockstore = crate::open_blockstore(&ledger_path, arg_matches, AccessType::Primary);
@@ -1056,6 +1297,15 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
                 }
             }
         }
+        ("stats", Some(arg_matches)) => {
+            let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+            let blockstore =
+                crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let storage_stats = CliStorageStats {
+                columns: blockstore.storage_stats()?,
+            };
+            println!("{}", output_format.formatted_string(&storage_stats));
+        }
         _ => unreachable!(),
     }
     Ok(())
@@ -1065,7 +1315,18 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
 pub mod tests {
     use {
         super::*,
-        solana_ledger::{blockstore::make_many_slot_entries, get_tmp_ledger_path_auto_delete},
+        solana_entry::entry::Entry,
+        solana_ledger::{
+            blockstore::{entries_to_test_shreds, make_many_slot_entries},
+            get_tmp_ledger_path_auto_delete,
+        },
+        solana_sdk::{
+            instruction::InstructionError,
+            signature::{Keypair, Signer},
+            system_transaction,
+            transaction::TransactionError,
+        },
+        solana_transaction_status::TransactionStatusMeta,
     };
 
     #[test]
@@ -1099,4 +1360,88 @@ pub mod tests {
         let expected: Vec<_> = (start_slot..num_slots).rev().collect();
         assert_eq!(optimistic_slots, expected);
     }
+
+    #[test]
+    fn test_query_transactions() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let slot = 1;
+        let payer = Keypair::new();
+        let transactions: Vec<_> = (0..3)
+            .map(|lamports| {
+                system_transaction::transfer(&payer, &payer.pubkey(), lamports, Hash::default())
+            })
+            .collect();
+        let entries = vec![Entry::new(&Hash::default(), 1, transactions.clone())];
+        let shreds = entries_to_test_shreds(&entries, slot, 0, true, 0, true);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        for (transaction_index, transaction) in transactions.iter().enumerate() {
+            let status = if transaction_index == 1 {
+                Err(TransactionError::InstructionError(
+                    0,
+                    InstructionError::Custom(1),
+                ))
+            } else {
+                Ok(())
+            };
+            let meta = TransactionStatusMeta {
+                status,
+                fee: 5000,
+                compute_units_consumed: Some(150),
+                ..TransactionStatusMeta::default()
+            };
+            blockstore
+                .write_transaction_status(
+                    slot,
+                    transaction.signatures[0],
+                    vec![&payer.pubkey()],
+                    vec![],
+                    meta,
+                    transaction_index,
+                )
+                .unwrap();
+        }
+
+        let query = |filter: &str, format: RecordOutputFormat| {
+            let filter = filter.parse::<TransactionFilter>().unwrap();
+            let mut writer =
+                RecordOutputWriter::new::<CliQueriedTransaction>(vec![], format).unwrap();
+            let num_matches =
+                query_transactions(&blockstore, 0, Slot::MAX, false, &filter, &mut writer).unwrap();
+            writer.flush().unwrap();
+            let output = match writer {
+                RecordOutputWriter::JsonLines(output) => output,
+                RecordOutputWriter::Csv(writer) => writer.into_inner().unwrap(),
+            };
+            (num_matches, String::from_utf8(output).unwrap())
+        };
+
+        let (num_matches, output) = query("", RecordOutputFormat::JsonLines);
+        assert_eq!(num_matches, 3);
+        assert_eq!(output.lines().count(), 3);
+
+        let (num_matches, output) = query(
+            "failed and error = InstructionError and cu > 100",
+            RecordOutputFormat::JsonLines,
+        );
+        assert_eq!(num_matches, 1);
+        let row: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(row["slot"], slot);
+        assert_eq!(row["transactionIndex"], 1);
+        assert_eq!(row["signature"], transactions[1].signatures[0].to_string());
+        assert_eq!(row["success"], false);
+
+        let (num_matches, output) = query("success and slot = 1", RecordOutputFormat::Csv);
+        assert_eq!(num_matches, 2);
+        let mut lines = output.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "slot,transactionIndex,signature,success,err,fee,computeUnitsConsumed,programIds"
+        );
+        assert_eq!(lines.count(), 2);
+
+        let (num_matches, _) = query("slot > 1", RecordOutputFormat::Csv);
+        assert_eq!(num_matches, 0);
+    }
 }
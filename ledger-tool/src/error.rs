@@ -15,4 +15,7 @@ pub enum LedgerToolError {
 
     #[error("{0}")]
     BadArgument(String),
+
+    #[error("{0}")]
+    Generic(String),
 }
@@ -0,0 +1,489 @@
+//! Notifies the Geyser plugins loaded by ledger-tool of the replayed ledger
+
+use {
+    crossbeam_channel::{unbounded, Sender},
+    solana_accounts_db::{
+        account_storage::meta::StoredAccountMeta,
+        accounts_update_notifier_interface::{
+            AccountUpdate, AccountsUpdateNotifier, AccountsUpdateNotifierInterface,
+        },
+    },
+    solana_entry::entry::EntrySummary,
+    solana_geyser_plugin_manager::{
+        block_metadata_notifier_interface::BlockMetadataNotifierArc,
+        geyser_plugin_service::{GeyserPluginService, GeyserPluginServiceError},
+    },
+    solana_ledger::{
+        blockstore::Blockstore,
+        blockstore_processor::ProcessSlotCallback,
+        entry_notifier_interface::{EntryNotifier, EntryNotifierArc},
+        entry_notifier_service::{EntryNotifierSender, EntryNotifierService},
+        transaction_account_states::{AccountStatesPrograms, TransactionAccountState},
+    },
+    solana_rpc::{
+        optimistically_confirmed_bank_tracker::SlotNotification,
+        slot_status_notifier_interface::SlotStatusNotifier,
+        transaction_notifier_interface::{TransactionNotifier, TransactionNotifierArc},
+    },
+    solana_runtime::bank::Bank,
+    solana_sdk::{
+        account::AccountSharedData, clock::Slot, pubkey::Pubkey, signature::Signature,
+        transaction::SanitizedTransaction,
+    },
+    solana_transaction_status::TransactionStatusMeta,
+    std::{path::PathBuf, sync::Arc, thread},
+};
+
+/// The Geyser plugins of a ledger replay and the notifiers feeding them. Nothing is sent for the
+/// slots before the start slot, so that a plugin can be backfilled from the first slot it misses.
+pub struct GeyserReplay {
+    geyser_service: GeyserPluginService,
+    /// Kept open until `join`, as the slot status observer of the service stops once it is closed
+    slot_notification_sender: Sender<SlotNotification>,
+    entry_notifier_service: Option<EntryNotifierService>,
+    start_slot: Slot,
+}
+
+impl GeyserReplay {
+    pub fn new(
+        geyser_plugin_config_files: &[PathBuf],
+        start_slot: Slot,
+    ) -> Result<Self, GeyserPluginServiceError> {
+        let (slot_notification_sender, slot_notification_receiver) = unbounded();
+        let geyser_service =
+            GeyserPluginService::new(slot_notification_receiver, geyser_plugin_config_files)?;
+        Ok(Self::new_with_service(
+            geyser_service,
+            slot_notification_sender,
+            start_slot,
+        ))
+    }
+
+    fn new_with_service(
+        geyser_service: GeyserPluginService,
+        slot_notification_sender: Sender<SlotNotification>,
+        start_slot: Slot,
+    ) -> Self {
+        // The exit flag is never set: the service exits in `join`, once it has notified every
+        // entry it was sent
+        let entry_notifier_service = geyser_service.get_entry_notifier().map(|entry_notifier| {
+            EntryNotifierService::new(
+                Arc::new(FromSlot::new(entry_notifier, start_slot)),
+                Arc::default(),
+            )
+        });
+        Self {
+            geyser_service,
+            slot_notification_sender,
+            entry_notifier_service,
+            start_slot,
+        }
+    }
+
+    pub fn accounts_update_notifier(&self) -> Option<AccountsUpdateNotifier> {
+        self.geyser_service.get_accounts_update_notifier().map(
+            |notifier| -> AccountsUpdateNotifier {
+                Arc::new(FromSlot::new(notifier, self.start_slot))
+            },
+        )
+    }
+
+    pub fn transaction_notifier(&self) -> Option<TransactionNotifierArc> {
+        self.geyser_service
+            .get_transaction_notifier()
+            .map(|notifier| -> TransactionNotifierArc {
+                Arc::new(FromSlot::new(notifier, self.start_slot))
+            })
+    }
+
+    pub fn entry_notification_sender(&self) -> Option<&EntryNotifierSender> {
+        self.entry_notifier_service
+            .as_ref()
+            .map(EntryNotifierService::sender)
+    }
+
+    /// Wraps `slot_callback` to notify the plugins of the status and block metadata of every
+    /// replayed slot. A validator sends the created bank and completed statuses of a slot before
+    /// replaying it, while they are only sent here once the slot is replayed, together with the
+    /// frozen, processed and rooted statuses.
+    pub fn slot_callback(
+        &self,
+        slot_callback: Option<ProcessSlotCallback>,
+        blockstore: Arc<Blockstore>,
+    ) -> ProcessSlotCallback {
+        let start_slot = self.start_slot;
+        let slot_status_notifier = self.geyser_service.get_slot_status_notifier();
+        let block_metadata_notifier = self.geyser_service.get_block_metadata_notifier();
+        Arc::new(move |bank: &Bank| {
+            if let Some(slot_callback) = &slot_callback {
+                slot_callback(bank);
+            }
+            if bank.slot() >= start_slot {
+                notify_slot(
+                    bank,
+                    &blockstore,
+                    slot_status_notifier.as_ref(),
+                    block_metadata_notifier.as_ref(),
+                );
+            }
+        })
+    }
+
+    /// Waits for the plugins to be sent the queued notifications, then unloads them
+    pub fn join(self) -> thread::Result<()> {
+        if let Some(entry_notifier_service) = self.entry_notifier_service {
+            entry_notifier_service.join()?;
+        }
+        drop(self.slot_notification_sender);
+        self.geyser_service.join()
+    }
+}
+
+fn notify_slot(
+    bank: &Bank,
+    blockstore: &Blockstore,
+    slot_status_notifier: Option<&SlotStatusNotifier>,
+    block_metadata_notifier: Option<&BlockMetadataNotifierArc>,
+) {
+    let (slot, parent_slot) = (bank.slot(), bank.parent_slot());
+    if let Some(slot_status_notifier) = slot_status_notifier {
+        let slot_status_notifier = slot_status_notifier.read().unwrap();
+        slot_status_notifier.notify_created_bank(slot, parent_slot);
+        slot_status_notifier.notify_completed(slot, Some(parent_slot));
+        slot_status_notifier.notify_frozen(slot, parent_slot, &bank.hash());
+        slot_status_notifier.notify_slot_processed(slot, Some(parent_slot));
+        if blockstore.is_root(slot) {
+            slot_status_notifier.notify_slot_rooted(slot, Some(parent_slot));
+        }
+    }
+
+    if let Some(block_metadata_notifier) = block_metadata_notifier {
+        let parent_blockhash = bank
+            .parent()
+            .map(|bank| bank.last_blockhash())
+            .unwrap_or_default();
+        let entry_count = blockstore
+            .get_slot_entries(slot, 0)
+            .map(|entries| entries.len())
+            .unwrap_or_default();
+        block_metadata_notifier.notify_block_metadata(
+            parent_slot,
+            &parent_blockhash.to_string(),
+            slot,
+            &bank.last_blockhash().to_string(),
+            &bank.rewards,
+            Some(bank.clock().unix_timestamp),
+            Some(bank.block_height()),
+            bank.executed_transaction_count(),
+            entry_count as u64,
+        );
+    }
+}
+
+/// Forwards the notifications of the slots from `start_slot` on to `notifier`
+#[derive(Debug)]
+struct FromSlot<T> {
+    notifier: T,
+    start_slot: Slot,
+}
+
+impl<T> FromSlot<T> {
+    fn new(notifier: T, start_slot: Slot) -> Self {
+        Self {
+            notifier,
+            start_slot,
+        }
+    }
+}
+
+impl AccountsUpdateNotifierInterface for FromSlot<AccountsUpdateNotifier> {
+    fn notify_account_update(
+        &self,
+        slot: Slot,
+        account: &AccountSharedData,
+        txn: &Option<&SanitizedTransaction>,
+        pubkey: &Pubkey,
+        write_version: u64,
+    ) {
+        if slot >= self.start_slot {
+            self.notifier
+                .notify_account_update(slot, account, txn, pubkey, write_version);
+        }
+    }
+
+    fn notify_account_restore_from_snapshot(&self, slot: Slot, account: &StoredAccountMeta) {
+        if slot >= self.start_slot {
+            self.notifier
+                .notify_account_restore_from_snapshot(slot, account);
+        }
+    }
+
+    fn notify_end_of_restore_from_snapshot(&self) {
+        self.notifier.notify_end_of_restore_from_snapshot();
+    }
+
+    fn notify_account_updates(&self, slot: Slot, updates: &[AccountUpdate]) {
+        if slot >= self.start_slot {
+            self.notifier.notify_account_updates(slot, updates);
+        }
+    }
+
+    fn notify_accounts_restore_from_snapshot(&self, slot: Slot, accounts: &[StoredAccountMeta]) {
+        if slot >= self.start_slot {
+            self.notifier
+                .notify_accounts_restore_from_snapshot(slot, accounts);
+        }
+    }
+}
+
+impl TransactionNotifier for FromSlot<TransactionNotifierArc> {
+    fn notify_transaction(
+        &self,
+        slot: Slot,
+        transaction_slot_index: usize,
+        signature: &Signature,
+        transaction_status_meta: &TransactionStatusMeta,
+        transaction: &SanitizedTransaction,
+        account_states: &[TransactionAccountState],
+    ) {
+        if slot >= self.start_slot {
+            self.notifier.notify_transaction(
+                slot,
+                transaction_slot_index,
+                signature,
+                transaction_status_meta,
+                transaction,
+                account_states,
+            );
+        }
+    }
+
+    fn account_states_programs(&self) -> Option<AccountStatesPrograms> {
+        self.notifier.account_states_programs()
+    }
+}
+
+impl EntryNotifier for FromSlot<EntryNotifierArc> {
+    fn notify_entry(
+        &self,
+        slot: Slot,
+        index: usize,
+        entry: &EntrySummary,
+        starting_transaction_index: usize,
+    ) {
+        if slot >= self.start_slot {
+            self.notifier
+                .notify_entry(slot, index, entry, starting_transaction_index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_entry::entry::{create_ticks, Entry},
+        solana_geyser_plugin_interface::geyser_plugin_interface::{
+            GeyserPlugin, ReplicaAccountInfoVersions, ReplicaBlockInfoVersions,
+            ReplicaEntryInfoVersions, ReplicaSlotStatusInfoVersions,
+            ReplicaTransactionInfoVersions, Result as PluginResult,
+        },
+        solana_geyser_plugin_manager::geyser_plugin_manager::LoadedGeyserPlugin,
+        solana_ledger::{
+            bank_forks_utils,
+            blockstore::entries_to_test_shreds,
+            blockstore_processor::{self, ProcessOptions, TransactionStatusSender},
+            create_new_tmp_ledger_auto_delete,
+            genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        },
+        solana_rpc::transaction_status_service::TransactionStatusService,
+        solana_runtime::accounts_background_service::AbsRequestSender,
+        solana_sdk::system_transaction,
+        std::sync::Mutex,
+    };
+
+    /// Records the kind and slot of every notification
+    #[derive(Debug)]
+    struct RecordingPlugin {
+        notifications: Arc<Mutex<Vec<(String, Slot)>>>,
+    }
+
+    impl RecordingPlugin {
+        fn record(&self, kind: &str, slot: Slot) -> PluginResult<()> {
+            self.notifications
+                .lock()
+                .unwrap()
+                .push((kind.to_string(), slot));
+            Ok(())
+        }
+    }
+
+    impl GeyserPlugin for RecordingPlugin {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn update_account(
+            &self,
+            _account: ReplicaAccountInfoVersions,
+            slot: Slot,
+            _is_startup: bool,
+        ) -> PluginResult<()> {
+            self.record("account", slot)
+        }
+
+        fn notify_slot_status(
+            &self,
+            slot_status: ReplicaSlotStatusInfoVersions,
+        ) -> PluginResult<()> {
+            let ReplicaSlotStatusInfoVersions::V0_0_1(info) = slot_status;
+            self.record(info.status.as_str(), info.slot)
+        }
+
+        fn notify_transaction(
+            &self,
+            _transaction: ReplicaTransactionInfoVersions,
+            slot: Slot,
+        ) -> PluginResult<()> {
+            self.record("transaction", slot)
+        }
+
+        fn notify_entry(&self, entry: ReplicaEntryInfoVersions) -> PluginResult<()> {
+            let slot = match entry {
+                ReplicaEntryInfoVersions::V0_0_1(info) => info.slot,
+                ReplicaEntryInfoVersions::V0_0_2(info) => info.slot,
+            };
+            self.record("entry", slot)
+        }
+
+        fn notify_block_metadata(&self, blockinfo: ReplicaBlockInfoVersions) -> PluginResult<()> {
+            let slot = match blockinfo {
+                ReplicaBlockInfoVersions::V0_0_1(info) => info.slot,
+                ReplicaBlockInfoVersions::V0_0_2(info) => info.slot,
+                ReplicaBlockInfoVersions::V0_0_3(info) => info.slot,
+            };
+            self.record("block", slot)
+        }
+
+        fn transaction_notifications_enabled(&self) -> bool {
+            true
+        }
+
+        fn entry_notifications_enabled(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_geyser_replay() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(1_000_000_000);
+        let (ledger_path, blockhash) = create_new_tmp_ledger_auto_delete!(&genesis_config);
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let mut last_hash = blockhash;
+        for slot in 1..=2 {
+            let transaction =
+                system_transaction::transfer(&mint_keypair, &Pubkey::new_unique(), slot, blockhash);
+            let mut entries = vec![Entry::new(&last_hash, 1, vec![transaction])];
+            entries.extend(create_ticks(
+                genesis_config.ticks_per_slot,
+                1,
+                entries[0].hash,
+            ));
+            last_hash = entries.last().unwrap().hash;
+            let shreds = entries_to_test_shreds(&entries, slot, slot - 1, true, 0, true);
+            blockstore.insert_shreds(shreds, None, false).unwrap();
+        }
+
+        // Replay slots 1 and 2, backfilling the plugin from slot 2
+        let notifications = Arc::<Mutex<Vec<(String, Slot)>>>::default();
+        let mut plugin = LoadedGeyserPlugin::new(
+            Box::new(RecordingPlugin {
+                notifications: notifications.clone(),
+            }),
+            None,
+        );
+        plugin.load("", false).unwrap();
+        let (slot_notification_sender, slot_notification_receiver) = unbounded();
+        let geyser_replay = GeyserReplay::new_with_service(
+            GeyserPluginService::new_with_plugins(slot_notification_receiver, vec![plugin]),
+            slot_notification_sender,
+            2,
+        );
+        let process_options = ProcessOptions {
+            slot_callback: Some(geyser_replay.slot_callback(None, blockstore.clone())),
+            ..ProcessOptions::default()
+        };
+        let (bank_forks, leader_schedule_cache, ..) = bank_forks_utils::load_bank_forks(
+            &genesis_config,
+            &blockstore,
+            Vec::new(),
+            None,
+            &process_options,
+            None,
+            geyser_replay.entry_notification_sender(),
+            geyser_replay.accounts_update_notifier(),
+            Arc::default(),
+        )
+        .unwrap();
+        let (transaction_status_sender, transaction_status_receiver) = unbounded();
+        let transaction_status_service = TransactionStatusService::new(
+            transaction_status_receiver,
+            Arc::default(),
+            false,
+            geyser_replay.transaction_notifier(),
+            blockstore.clone(),
+            false,
+            None,
+            false,
+            Arc::default(),
+        );
+        blockstore_processor::process_blockstore_from_root(
+            &blockstore,
+            &bank_forks,
+            &leader_schedule_cache,
+            &process_options,
+            Some(&TransactionStatusSender {
+                sender: transaction_status_sender,
+                account_states_programs: None,
+            }),
+            None,
+            geyser_replay.entry_notification_sender(),
+            &AbsRequestSender::default(),
+        )
+        .unwrap();
+        assert_eq!(bank_forks.read().unwrap().working_bank().slot(), 2);
+        transaction_status_service.join().unwrap();
+        geyser_replay.join().unwrap();
+
+        let notifications = notifications.lock().unwrap();
+        assert!(
+            notifications.iter().all(|(_, slot)| *slot == 2),
+            "{notifications:?}"
+        );
+        let count = |kind: &str| {
+            notifications
+                .iter()
+                .filter(|(notification, _)| notification == kind)
+                .count()
+        };
+        assert!(count("account") > 0);
+        assert_eq!(count("transaction"), 1);
+        assert_eq!(count("entry"), 1 + genesis_config.ticks_per_slot as usize);
+        assert_eq!(count("block"), 1);
+        let slot_statuses: Vec<_> = notifications
+            .iter()
+            .map(|(notification, _)| notification.as_str())
+            .filter(|notification| {
+                !matches!(*notification, "account" | "transaction" | "entry" | "block")
+            })
+            .collect();
+        assert_eq!(
+            slot_statuses,
+            ["createdbank", "completed", "frozen", "processed"]
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use {
-    crate::LEDGER_TOOL_DIRECTORY,
+    crate::{geyser_replay::GeyserReplay, LEDGER_TOOL_DIRECTORY},
     clap::{value_t, value_t_or_exit, values_t_or_exit, ArgMatches},
     crossbeam_channel::unbounded,
     log::*,
@@ -10,9 +10,7 @@ use {
     solana_core::{
         accounts_hash_verifier::AccountsHashVerifier, validator::BlockVerificationMethod,
     },
-    solana_geyser_plugin_manager::geyser_plugin_service::{
-        GeyserPluginService, GeyserPluginServiceError,
-    },
+    solana_geyser_plugin_manager::geyser_plugin_service::GeyserPluginServiceError,
     solana_ledger::{
         bank_forks_utils::{self, BankForksUtilsError},
         blockstore::{Blockstore, BlockstoreError},
@@ -119,7 +117,7 @@ pub fn load_and_process_ledger(
     arg_matches: &ArgMatches,
     genesis_config: &GenesisConfig,
     blockstore: Arc<Blockstore>,
-    process_options: ProcessOptions,
+    mut process_options: ProcessOptions,
     snapshot_archive_path: Option<PathBuf>,
     incremental_snapshot_archive_path: Option<PathBuf>,
 ) -> Result<(Arc<RwLock<BankForks>>, Option<StartingSnapshotHashes>), LoadAndProcessLedgerError> {
@@ -247,27 +245,27 @@ pub fn load_and_process_ledger(
     clean_orphaned_account_snapshot_dirs(&bank_snapshots_dir, &account_snapshot_paths)
         .map_err(LoadAndProcessLedgerError::CleanOrphanedAccountSnapshotDirectories)?;
 
-    let geyser_plugin_active = arg_matches.is_present("geyser_plugin_config");
-    let (accounts_update_notifier, transaction_notifier) = if geyser_plugin_active {
+    let exit = Arc::new(AtomicBool::new(false));
+    let geyser_replay = if arg_matches.is_present("geyser_plugin_config") {
         let geyser_config_files = values_t_or_exit!(arg_matches, "geyser_plugin_config", String)
             .into_iter()
             .map(PathBuf::from)
             .collect::<Vec<_>>();
+        let start_slot = value_t!(arg_matches, "start_slot", Slot).unwrap_or_default();
 
-        let (confirmed_bank_sender, confirmed_bank_receiver) = unbounded();
-        drop(confirmed_bank_sender);
-        let geyser_service =
-            GeyserPluginService::new(confirmed_bank_receiver, &geyser_config_files)
-                .map_err(LoadAndProcessLedgerError::GeyserServiceSetup)?;
-        (
-            geyser_service.get_accounts_update_notifier(),
-            geyser_service.get_transaction_notifier(),
-        )
+        let geyser_replay = GeyserReplay::new(&geyser_config_files, start_slot)
+            .map_err(LoadAndProcessLedgerError::GeyserServiceSetup)?;
+        process_options.slot_callback = Some(
+            geyser_replay.slot_callback(process_options.slot_callback.take(), blockstore.clone()),
+        );
+        Some(geyser_replay)
     } else {
-        (None, None)
+        None
     };
+    let entry_notification_sender = geyser_replay
+        .as_ref()
+        .and_then(GeyserReplay::entry_notification_sender);
 
-    let exit = Arc::new(AtomicBool::new(false));
     let (bank_forks, leader_schedule_cache, starting_snapshot_hashes, ..) =
         bank_forks_utils::load_bank_forks(
             genesis_config,
@@ -276,8 +274,10 @@ pub fn load_and_process_ledger(
             snapshot_config.as_ref(),
             &process_options,
             None,
-            None, // Maybe support this later, though
-            accounts_update_notifier,
+            entry_notification_sender,
+            geyser_replay
+                .as_ref()
+                .and_then(GeyserReplay::accounts_update_notifier),
             exit.clone(),
         )
         .map_err(LoadAndProcessLedgerError::LoadBankForks)?;
@@ -355,7 +355,10 @@ pub fn load_and_process_ledger(
 
     let enable_rpc_transaction_history = arg_matches.is_present("enable_rpc_transaction_history");
 
-    let (transaction_status_sender, transaction_status_service) = if geyser_plugin_active
+    let transaction_notifier = geyser_replay
+        .as_ref()
+        .and_then(GeyserReplay::transaction_notifier);
+    let (transaction_status_sender, transaction_status_service) = if transaction_notifier.is_some()
         || enable_rpc_transaction_history
     {
         // Need Primary (R/W) access to insert transaction data;
@@ -371,6 +374,9 @@ pub fn load_and_process_ledger(
         };
 
         let (transaction_status_sender, transaction_status_receiver) = unbounded();
+        let account_states_programs = transaction_notifier
+            .as_ref()
+            .and_then(|transaction_notifier| transaction_notifier.account_states_programs());
         let transaction_status_service = TransactionStatusService::new(
             transaction_status_receiver,
             Arc::default(),
@@ -378,11 +384,16 @@ pub fn load_and_process_ledger(
             transaction_notifier,
             tss_blockstore,
             false,
-            exit.clone(),
+            None,
+            false,
+            // Not set on exit: the service exits once it has written every batch and the sender
+            // is dropped
+            Arc::default(),
         );
         (
             Some(TransactionStatusSender {
                 sender: transaction_status_sender,
+                account_states_programs,
             }),
             Some(transaction_status_service),
         )
@@ -397,7 +408,7 @@ pub fn load_and_process_ledger(
         &process_options,
         transaction_status_sender.as_ref(),
         None,
-        None, // Maybe support this later, though
+        entry_notification_sender,
         &accounts_background_request_sender,
     )
     .map(|_| (bank_forks, starting_snapshot_hashes))
@@ -406,9 +417,13 @@ pub fn load_and_process_ledger(
     exit.store(true, Ordering::Relaxed);
     accounts_background_service.join().unwrap();
     accounts_hash_verifier.join().unwrap();
+    drop(transaction_status_sender);
     if let Some(service) = transaction_status_service {
         service.join().unwrap();
     }
+    if let Some(geyser_replay) = geyser_replay {
+        geyser_replay.join().unwrap();
+    }
 
     result
 }
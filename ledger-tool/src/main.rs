@@ -4,12 +4,15 @@ use {
         args::*,
         bigtable::*,
         blockstore::*,
+        error::LedgerToolError,
         ledger_path::*,
         ledger_utils::*,
         output::{
             output_account, AccountsOutputConfig, AccountsOutputMode, AccountsOutputStreamer,
+            ReplayProfileOutputFormat, ReplayProfileOutputWriter,
         },
         program::*,
+        replay_profile::replay_profile,
     },
     clap::{
         crate_description, crate_name, value_t, value_t_or_exit, values_t_or_exit, App,
@@ -98,10 +101,13 @@ mod args;
 mod bigtable;
 mod blockstore;
 mod error;
+mod geyser_replay;
 mod ledger_path;
 mod ledger_utils;
 mod output;
 mod program;
+mod replay_profile;
+mod transaction_filter;
 
 fn parse_encoding_format(matches: &ArgMatches<'_>) -> UiAccountEncoding {
     match matches.value_of("encoding") {
@@ -1085,6 +1091,107 @@ fn main() {
                         .help("In the slot recording, include bank details or not"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("geyser-replay")
+                .about(
+                    "Replay the ledger into Geyser plugins, sending account, transaction, \
+                     entry, block metadata and slot status notifications. Slots are replayed \
+                     from the latest snapshot, or genesis with --no-snapshot, to \
+                     --halt-at-slot or the highest slot in the blockstore; the statuses of a \
+                     slot are all sent once it is replayed, unlike a running validator which \
+                     sends the created bank and completed statuses before replaying it.",
+                )
+                .arg(&no_snapshot_arg)
+                .arg(&account_paths_arg)
+                .arg(&accounts_hash_cache_path_arg)
+                .arg(&accounts_index_path_arg)
+                .arg(&halt_at_slot_arg)
+                .arg(&limit_load_slot_count_from_snapshot_arg)
+                .arg(&accounts_index_bins)
+                .arg(&accounts_index_limit)
+                .arg(&disable_disk_index)
+                .arg(&accountsdb_skip_shrink)
+                .arg(&hard_forks_arg)
+                .arg(&allow_dead_slots_arg)
+                .arg(&max_genesis_archive_unpacked_size_arg)
+                .arg(&use_snapshot_archives_at_startup)
+                .arg(geyser_plugin_args.clone().required(true))
+                .arg(
+                    Arg::with_name("start_slot")
+                        .long("start-slot")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .help(
+                            "First slot to notify the Geyser plugins of; earlier slots are \
+                             replayed without notifications, including the accounts of an \
+                             older snapshot [default: the starting slot of the replay]",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("skip_verification")
+                        .long("skip-verification")
+                        .takes_value(false)
+                        .help("Skip ledger PoH and transaction verification."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("replay-profile")
+                .about(
+                    "Replay a range of slots and print the account lock, load, execute and \
+                     commit times, compute units, number of accounts loaded and program cache \
+                     hits and misses of every transaction. The bank of the \
+                     parent of --starting-slot is loaded from the latest snapshot at or before \
+                     it, or genesis with --no-snapshot.",
+                )
+                .arg(&no_snapshot_arg)
+                .arg(&account_paths_arg)
+                .arg(&accounts_hash_cache_path_arg)
+                .arg(&accounts_index_path_arg)
+                .arg(&accounts_index_bins)
+                .arg(&accounts_index_limit)
+                .arg(&disable_disk_index)
+                .arg(&accountsdb_skip_shrink)
+                .arg(&hard_forks_arg)
+                .arg(&max_genesis_archive_unpacked_size_arg)
+                .arg(&use_snapshot_archives_at_startup)
+                .arg(
+                    Arg::with_name("skip_verification")
+                        .long("skip-verification")
+                        .takes_value(false)
+                        .help("Skip ledger PoH and transaction verification."),
+                )
+                .arg(
+                    Arg::with_name("starting_slot")
+                        .long("starting-slot")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .required(true)
+                        .help("First slot to replay"),
+                )
+                .arg(
+                    Arg::with_name("ending_slot")
+                        .long("ending-slot")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .help("Last slot to replay [default: --starting-slot]"),
+                )
+                .arg(
+                    Arg::with_name("profile_output_format")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .possible_values(&["csv", "json-lines", "folded"])
+                        .default_value("csv")
+                        .help(
+                            "Print one CSV row or JSON line per transaction, or the total \
+                             microseconds per program and phase as folded stacks for \
+                             flamegraph tools",
+                        ),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("graph")
                 .about("Create a Graphviz rendering of the ledger")
@@ -1802,6 +1909,101 @@ fn main() {
                     exit_signal.store(true, Ordering::Relaxed);
                     system_monitor_service.join().unwrap();
                 }
+                ("geyser-replay", Some(arg_matches)) => {
+                    let process_options = parse_process_options(&ledger_path, arg_matches);
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    let blockstore = open_blockstore(
+                        &ledger_path,
+                        arg_matches,
+                        get_access_type(&process_options),
+                    );
+                    let (bank_forks, starting_snapshot_hashes) = load_and_process_ledger_or_exit(
+                        arg_matches,
+                        &genesis_config,
+                        Arc::new(blockstore),
+                        process_options,
+                        snapshot_archive_path,
+                        incremental_snapshot_archive_path,
+                    );
+                    let starting_slot = starting_snapshot_hashes
+                        .map(|hashes| {
+                            hashes
+                                .incremental
+                                .map(|incremental| incremental.0 .0)
+                                .unwrap_or(hashes.full.0 .0)
+                        })
+                        .unwrap_or_default()
+                        .max(value_t!(arg_matches, "start_slot", Slot).unwrap_or_default());
+                    let ending_slot = bank_forks.read().unwrap().working_bank().slot();
+                    println!(
+                        "Replayed slots {starting_slot} to {ending_slot} into the Geyser plugins"
+                    );
+                }
+                ("replay-profile", Some(arg_matches)) => {
+                    let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+                    let ending_slot =
+                        value_t!(arg_matches, "ending_slot", Slot).unwrap_or(starting_slot);
+                    let output_format = match arg_matches.value_of("profile_output_format") {
+                        Some("json-lines") => ReplayProfileOutputFormat::JsonLines,
+                        Some("folded") => ReplayProfileOutputFormat::Folded,
+                        _ => ReplayProfileOutputFormat::Csv,
+                    };
+
+                    let mut process_options = parse_process_options(&ledger_path, arg_matches);
+                    let blockstore = open_blockstore(
+                        &ledger_path,
+                        arg_matches,
+                        get_access_type(&process_options),
+                    );
+                    let parent_slot = blockstore
+                        .meta(starting_slot)
+                        .ok()
+                        .flatten()
+                        .and_then(|slot_meta| slot_meta.parent_slot)
+                        .unwrap_or_else(|| {
+                            eprintln!("Parent of slot {starting_slot} not found in the blockstore");
+                            exit(1);
+                        });
+                    process_options.halt_at_slot = Some(parent_slot);
+
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    let blockstore = Arc::new(blockstore);
+                    let (bank_forks, _) = load_and_process_ledger_or_exit(
+                        arg_matches,
+                        &genesis_config,
+                        blockstore.clone(),
+                        process_options,
+                        snapshot_archive_path,
+                        incremental_snapshot_archive_path,
+                    );
+
+                    let result = ReplayProfileOutputWriter::new(
+                        io::BufWriter::new(io::stdout()),
+                        output_format,
+                    )
+                    .map_err(LedgerToolError::from)
+                    .and_then(|mut writer| {
+                        let num_slots = replay_profile(
+                            &bank_forks,
+                            &blockstore,
+                            parent_slot,
+                            starting_slot,
+                            ending_slot,
+                            &mut writer,
+                        )?;
+                        writer.finish()?;
+                        Ok(num_slots)
+                    });
+                    match result {
+                        Ok(num_slots) => {
+                            info!("Profiled the transactions of {num_slots} slots")
+                        }
+                        Err(err) => {
+                            eprintln!("Failed to profile replay: {err}");
+                            exit(1);
+                        }
+                    }
+                }
                 ("graph", Some(arg_matches)) => {
                     let output_file = value_t_or_exit!(arg_matches, "graph_filename", String);
                     let graph_config = GraphConfig {
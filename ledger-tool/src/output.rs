@@ -12,23 +12,26 @@ use {
         VerboseDisplay,
     },
     solana_entry::entry::Entry,
-    solana_ledger::blockstore::Blockstore,
+    solana_ledger::{blockstore::Blockstore, blockstore_db::ColumnStorageStats},
     solana_runtime::bank::{Bank, TotalAccountsStats},
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount},
         clock::{Slot, UnixTimestamp},
+        compute_budget,
         hash::Hash,
         native_token::lamports_to_sol,
         pubkey::Pubkey,
     },
+    solana_storage_bigtable::SlotMetadata,
     solana_transaction_status::{
         EncodedConfirmedBlock, EncodedTransactionWithStatusMeta, EntrySummary, Rewards,
+        VersionedTransactionWithStatusMeta,
     },
     std::{
         cell::RefCell,
-        collections::HashMap,
+        collections::{BTreeMap, HashMap},
         fmt::{self, Display, Formatter},
-        io::{stdout, Write},
+        io::{self, stdout, Write},
         rc::Rc,
         result::Result,
         sync::Arc,
@@ -99,6 +102,60 @@ impl Display for SlotBounds<'_> {
     }
 }
 
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliStorageStats {
+    pub columns: Vec<ColumnStorageStats>,
+}
+
+impl VerboseDisplay for CliStorageStats {}
+impl QuietDisplay for CliStorageStats {}
+
+impl Display for CliStorageStats {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<28} {:>16} {:>16} {:>9} {:>14} {:>14} {:>11}",
+            "Column",
+            "Live Bytes",
+            "SST Bytes",
+            "SST Files",
+            "Entries",
+            "Tombstones",
+            "Compression",
+        )?;
+        for stats in &self.columns {
+            let compression_ratio = stats
+                .compression_ratio
+                .map(|ratio| format!("{ratio:.2}"))
+                .unwrap_or_else(|| "-".to_string());
+            writeln!(
+                f,
+                "{:<28} {:>16} {:>16} {:>9} {:>14} {:>14} {:>11}",
+                stats.column,
+                stats.live_data_bytes,
+                stats.sst_files_bytes,
+                stats.num_sst_files,
+                stats.num_entries,
+                stats.estimated_tombstones,
+                compression_ratio,
+            )?;
+        }
+        let total =
+            |field: fn(&ColumnStorageStats) -> u64| -> u64 { self.columns.iter().map(field).sum() };
+        writeln!(
+            f,
+            "{:<28} {:>16} {:>16} {:>9} {:>14} {:>14}",
+            "Total",
+            total(|stats| stats.live_data_bytes),
+            total(|stats| stats.sst_files_bytes),
+            total(|stats| stats.num_sst_files),
+            total(|stats| stats.num_entries),
+            total(|stats| stats.estimated_tombstones),
+        )
+    }
+}
+
 fn writeln_entry(f: &mut dyn fmt::Write, i: usize, entry: &CliEntry, prefix: &str) -> fmt::Result {
     writeln!(
         f,
@@ -128,6 +185,91 @@ impl fmt::Display for CliEntries {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSlotMetadata {
+    pub slot: Slot,
+    pub parent_slot: Slot,
+    pub blockhash: String,
+    pub bank_hash: Option<String>,
+    pub leader: Option<String>,
+    pub tick_count: u64,
+    pub num_hashes: u64,
+    pub num_entries: u64,
+    pub num_transactions: u64,
+    pub rewards: Rewards,
+    pub block_time: Option<UnixTimestamp>,
+    pub block_height: Option<u64>,
+}
+
+impl CliSlotMetadata {
+    pub fn new(slot: Slot, slot_metadata: SlotMetadata) -> Self {
+        Self {
+            slot,
+            parent_slot: slot_metadata.parent_slot,
+            blockhash: slot_metadata.blockhash,
+            bank_hash: slot_metadata.bank_hash.map(|hash| hash.to_string()),
+            leader: slot_metadata.leader.map(|leader| leader.to_string()),
+            tick_count: slot_metadata.tick_count,
+            num_hashes: slot_metadata.num_hashes,
+            num_entries: slot_metadata.num_entries,
+            num_transactions: slot_metadata.num_transactions,
+            rewards: slot_metadata.rewards,
+            block_time: slot_metadata.block_time,
+            block_height: slot_metadata.block_height,
+        }
+    }
+}
+
+impl QuietDisplay for CliSlotMetadata {}
+impl VerboseDisplay for CliSlotMetadata {}
+
+impl fmt::Display for CliSlotMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Slot: {}", self.slot)?;
+        writeln!(f, "Parent Slot: {}", self.parent_slot)?;
+        writeln!(f, "Blockhash: {}", self.blockhash)?;
+        writeln!(
+            f,
+            "Bank Hash: {}",
+            self.bank_hash.as_deref().unwrap_or("unknown")
+        )?;
+        writeln!(f, "Leader: {}", self.leader.as_deref().unwrap_or("unknown"))?;
+        writeln!(f, "Ticks: {}", self.tick_count)?;
+        writeln!(f, "Hashes: {}", self.num_hashes)?;
+        writeln!(f, "Entries: {}", self.num_entries)?;
+        writeln!(f, "Transactions: {}", self.num_transactions)?;
+        if let Some(block_time) = self.block_time {
+            writeln!(
+                f,
+                "Block Time: {:?}",
+                Local.timestamp_opt(block_time, 0).unwrap()
+            )?;
+        }
+        if let Some(block_height) = self.block_height {
+            writeln!(f, "Block Height: {block_height}")?;
+        }
+        if !self.rewards.is_empty() {
+            writeln!(f, "Rewards:")?;
+            for reward in &self.rewards {
+                let sign = if reward.lamports < 0 { "-" } else { "" };
+                writeln!(
+                    f,
+                    "  {:<44}  {:^15}  {}◎{:<14.9}",
+                    reward.pubkey,
+                    reward
+                        .reward_type
+                        .map(|reward_type| reward_type.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    sign,
+                    lamports_to_sol(reward.lamports.unsigned_abs()),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CliEntry {
@@ -562,6 +704,256 @@ pub fn output_sorted_program_ids(program_ids: HashMap<Pubkey, u64>) {
     }
 }
 
+/// A transaction selected by the `blockstore query` subcommand
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliQueriedTransaction {
+    pub slot: Slot,
+    pub transaction_index: usize,
+    pub signature: String,
+    pub success: bool,
+    pub err: Option<String>,
+    pub fee: u64,
+    pub compute_units_consumed: Option<u64>,
+    pub program_ids: Vec<String>,
+}
+
+impl CliQueriedTransaction {
+    pub fn new(
+        slot: Slot,
+        transaction_index: usize,
+        transaction: &VersionedTransactionWithStatusMeta,
+    ) -> Self {
+        let meta = &transaction.meta;
+        Self {
+            slot,
+            transaction_index,
+            signature: transaction.transaction.signatures[0].to_string(),
+            success: meta.status.is_ok(),
+            err: meta.status.as_ref().err().map(|err| format!("{err:?}")),
+            fee: meta.fee,
+            compute_units_consumed: meta.compute_units_consumed,
+            program_ids: transaction
+                .invoked_program_ids()
+                .iter()
+                .map(|program_id| program_id.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl OutputRecord for CliQueriedTransaction {
+    const CSV_HEADER: &'static [&'static str] = &[
+        "slot",
+        "transactionIndex",
+        "signature",
+        "success",
+        "err",
+        "fee",
+        "computeUnitsConsumed",
+        "programIds",
+    ];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.slot.to_string(),
+            self.transaction_index.to_string(),
+            self.signature.clone(),
+            self.success.to_string(),
+            self.err.clone().unwrap_or_default(),
+            self.fee.to_string(),
+            self.compute_units_consumed
+                .map(|compute_units| compute_units.to_string())
+                .unwrap_or_default(),
+            self.program_ids.join(" "),
+        ]
+    }
+}
+
+/// A record streamed out by `RecordOutputWriter`
+pub trait OutputRecord: Serialize {
+    /// The CSV column names, in the order of the values of `csv_row()`
+    const CSV_HEADER: &'static [&'static str];
+
+    fn csv_row(&self) -> Vec<String>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordOutputFormat {
+    JsonLines,
+    Csv,
+}
+
+/// Streams records out as one JSON object per line or as CSV rows
+pub enum RecordOutputWriter<W: Write> {
+    JsonLines(W),
+    Csv(csv::Writer<W>),
+}
+
+impl<W: Write> RecordOutputWriter<W> {
+    /// Starts an output of records of type `T`, whose CSV header is written right away
+    pub fn new<T: OutputRecord>(writer: W, format: RecordOutputFormat) -> io::Result<Self> {
+        match format {
+            RecordOutputFormat::JsonLines => Ok(Self::JsonLines(writer)),
+            RecordOutputFormat::Csv => {
+                let mut writer = csv::WriterBuilder::new().from_writer(writer);
+                writer.write_record(T::CSV_HEADER)?;
+                Ok(Self::Csv(writer))
+            }
+        }
+    }
+
+    pub fn write<T: OutputRecord>(&mut self, record: &T) -> io::Result<()> {
+        match self {
+            Self::JsonLines(writer) => {
+                serde_json::to_writer(&mut *writer, record)?;
+                writer.write_all(b"\n")
+            }
+            Self::Csv(writer) => {
+                writer.write_record(record.csv_row())?;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::JsonLines(writer) => writer.flush(),
+            Self::Csv(writer) => writer.flush(),
+        }
+    }
+}
+
+/// The timings and resource usage of a transaction replayed by the `replay-profile` subcommand
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTransactionProfile {
+    pub slot: Slot,
+    pub transaction_index: usize,
+    pub signature: String,
+    pub program_ids: Vec<String>,
+    pub err: Option<String>,
+    /// The time to lock the accounts of the transaction's entry, which are locked as one
+    /// batch, split evenly among the transactions of the entry
+    pub lock_us: u64,
+    pub load_us: u64,
+    pub execute_us: u64,
+    pub commit_us: u64,
+    pub compute_units_consumed: u64,
+    /// The accounts loaded for the transaction: those of the message, including the ones from
+    /// address lookup tables, and the loaders of its programs
+    pub num_accounts_loaded: usize,
+    pub program_cache_hits: u64,
+    pub program_cache_misses: u64,
+}
+
+impl CliTransactionProfile {
+    /// The program a transaction is attributed to in flamegraphs: its first
+    /// top-level program other than the compute budget program
+    fn flamegraph_program(&self) -> &str {
+        let compute_budget_program_id = compute_budget::id().to_string();
+        self.program_ids
+            .iter()
+            .find(|program_id| **program_id != compute_budget_program_id)
+            .or_else(|| self.program_ids.first())
+            .map(String::as_str)
+            .unwrap_or("none")
+    }
+}
+
+impl OutputRecord for CliTransactionProfile {
+    const CSV_HEADER: &'static [&'static str] = &[
+        "slot",
+        "transactionIndex",
+        "signature",
+        "programIds",
+        "err",
+        "lockUs",
+        "loadUs",
+        "executeUs",
+        "commitUs",
+        "computeUnitsConsumed",
+        "numAccountsLoaded",
+        "programCacheHits",
+        "programCacheMisses",
+    ];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.slot.to_string(),
+            self.transaction_index.to_string(),
+            self.signature.clone(),
+            self.program_ids.join(" "),
+            self.err.clone().unwrap_or_default(),
+            self.lock_us.to_string(),
+            self.load_us.to_string(),
+            self.execute_us.to_string(),
+            self.commit_us.to_string(),
+            self.compute_units_consumed.to_string(),
+            self.num_accounts_loaded.to_string(),
+            self.program_cache_hits.to_string(),
+            self.program_cache_misses.to_string(),
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayProfileOutputFormat {
+    Csv,
+    JsonLines,
+    Folded,
+}
+
+/// Streams transaction profiles out as CSV rows or JSON lines, or accumulates
+/// them into folded stacks of microseconds per program and replay phase, the
+/// input format of flamegraph tools
+pub enum ReplayProfileOutputWriter<W: Write> {
+    Records(RecordOutputWriter<W>),
+    Folded(W, BTreeMap<String, u64>),
+}
+
+impl<W: Write> ReplayProfileOutputWriter<W> {
+    pub fn new(writer: W, format: ReplayProfileOutputFormat) -> io::Result<Self> {
+        let record_format = match format {
+            ReplayProfileOutputFormat::Csv => RecordOutputFormat::Csv,
+            ReplayProfileOutputFormat::JsonLines => RecordOutputFormat::JsonLines,
+            ReplayProfileOutputFormat::Folded => return Ok(Self::Folded(writer, BTreeMap::new())),
+        };
+        RecordOutputWriter::new::<CliTransactionProfile>(writer, record_format).map(Self::Records)
+    }
+
+    pub fn write(&mut self, profile: &CliTransactionProfile) -> io::Result<()> {
+        match self {
+            Self::Records(writer) => writer.write(profile),
+            Self::Folded(_, stacks) => {
+                let program = profile.flamegraph_program();
+                for (phase, us) in [
+                    ("lock", profile.lock_us),
+                    ("load", profile.load_us),
+                    ("execute", profile.execute_us),
+                    ("commit", profile.commit_us),
+                ] {
+                    *stacks.entry(format!("{program};{phase}")).or_default() += us;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Flushes the output, writing the folded stacks out if they were being accumulated
+    pub fn finish(&mut self) -> io::Result<()> {
+        match self {
+            Self::Records(writer) => writer.flush(),
+            Self::Folded(writer, stacks) => {
+                for (stack, us) in stacks.iter() {
+                    writeln!(writer, "{stack} {us}")?;
+                }
+                writer.flush()
+            }
+        }
+    }
+}
+
 /// A type to facilitate streaming account information to an output destination
 ///
 /// This type scans every account, so streaming is preferred over the simpler
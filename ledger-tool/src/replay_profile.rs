@@ -0,0 +1,337 @@
+//! The `replay-profile` subcommand
+//!
+//! Slots are replayed on top of the bank of their parent. The accounts of the
+//! transactions of an entry are locked as one batch, like a regular replay
+//! does, and the lock time is split evenly among them. The transactions are
+//! then executed one at a time, so the program cache lookups and execute
+//! timings that are recorded for a transaction are not shared with the other
+//! transactions of its entry. A transaction whose accounts could not be locked
+//! is reported with the lock error and not executed.
+
+use {
+    crate::{
+        error::{LedgerToolError, Result},
+        output::{CliTransactionProfile, ReplayProfileOutputWriter},
+    },
+    log::*,
+    solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
+    solana_measure::measure_us,
+    solana_program_runtime::timings::{ExecuteTimingType, ExecuteTimings},
+    solana_runtime::{
+        bank::{Bank, CommitTransactionCounts, LoadAndExecuteTransactionsOutput},
+        bank_forks::BankForks,
+        transaction_batch::TransactionBatch,
+    },
+    solana_sdk::{
+        clock::{Slot, MAX_PROCESSING_AGE},
+        transaction::{SanitizedTransaction, TransactionVerificationMode},
+    },
+    solana_svm::transaction_processor::ExecutionRecordingConfig,
+    std::{
+        borrow::Cow,
+        io::Write,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// Replays `starting_slot`, a child of `parent_slot`, and its descendants up to
+/// `ending_slot`, writing the profile of every transaction. Where the ledger
+/// forks, the rooted child is followed, otherwise the lowest one.
+///
+/// Returns the number of slots that were replayed.
+pub fn replay_profile<W: Write>(
+    bank_forks: &RwLock<BankForks>,
+    blockstore: &Blockstore,
+    parent_slot: Slot,
+    starting_slot: Slot,
+    ending_slot: Slot,
+    writer: &mut ReplayProfileOutputWriter<W>,
+) -> Result<usize> {
+    let mut parent_bank = bank_forks.read().unwrap().get(parent_slot).ok_or_else(|| {
+        LedgerToolError::Generic(format!("Bank for parent slot {parent_slot} was not loaded"))
+    })?;
+    let leader_schedule_cache = LeaderScheduleCache::new_from_bank(&parent_bank);
+
+    let mut next_slot = Some(starting_slot);
+    let mut num_slots = 0;
+    while let Some(slot) = next_slot {
+        parent_bank = replay_slot_profile(
+            bank_forks,
+            blockstore,
+            &leader_schedule_cache,
+            parent_bank,
+            slot,
+            writer,
+        )?;
+        num_slots += 1;
+        next_slot = next_slot_to_replay(blockstore, slot, ending_slot)?;
+    }
+    Ok(num_slots)
+}
+
+fn next_slot_to_replay(
+    blockstore: &Blockstore,
+    slot: Slot,
+    ending_slot: Slot,
+) -> Result<Option<Slot>> {
+    let Some(slot_meta) = blockstore.meta(slot)? else {
+        return Ok(None);
+    };
+    let mut next_slots: Vec<_> = slot_meta
+        .next_slots
+        .into_iter()
+        .filter(|next_slot| *next_slot <= ending_slot && !blockstore.is_dead(*next_slot))
+        .collect();
+    next_slots.sort_unstable();
+    Ok(next_slots
+        .iter()
+        .find(|next_slot| blockstore.is_root(**next_slot))
+        .or_else(|| next_slots.first())
+        .copied())
+}
+
+fn replay_slot_profile<W: Write>(
+    bank_forks: &RwLock<BankForks>,
+    blockstore: &Blockstore,
+    leader_schedule_cache: &LeaderScheduleCache,
+    parent_bank: Arc<Bank>,
+    slot: Slot,
+    writer: &mut ReplayProfileOutputWriter<W>,
+) -> Result<Arc<Bank>> {
+    let leader = leader_schedule_cache
+        .slot_leader_at(slot, Some(&parent_bank))
+        .ok_or_else(|| LedgerToolError::Generic(format!("No leader found for slot {slot}")))?;
+    let bank =
+        bank_forks
+            .write()
+            .unwrap()
+            .insert(Bank::new_from_parent(parent_bank, &leader, slot));
+
+    let mut transaction_index = 0;
+    for entry in blockstore.get_slot_entries(slot, 0)? {
+        if entry.is_tick() {
+            bank.register_tick(&entry.hash);
+            continue;
+        }
+        let transactions = entry
+            .transactions
+            .into_iter()
+            .enumerate()
+            .map(|(index, transaction)| {
+                bank.verify_transaction(
+                    transaction,
+                    TransactionVerificationMode::HashAndVerifyPrecompiles,
+                )
+                .map_err(|err| {
+                    LedgerToolError::Generic(format!(
+                        "Failed to verify transaction {} of slot {slot}: {err}",
+                        transaction_index + index,
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let (batch, lock_us) = measure_us!(bank.prepare_sanitized_batch(&transactions));
+        let lock_us = lock_us / transactions.len() as u64;
+        for (transaction, lock_result) in transactions.iter().zip(batch.lock_results()) {
+            let profile = match lock_result {
+                Ok(()) => profile_transaction(&bank, slot, transaction_index, transaction, lock_us),
+                Err(err) => CliTransactionProfile {
+                    err: Some(format!("{err:?}")),
+                    lock_us,
+                    ..new_profile(slot, transaction_index, transaction)
+                },
+            };
+            writer.write(&profile)?;
+            transaction_index += 1;
+        }
+    }
+
+    bank.freeze();
+    match blockstore.get_bank_hash(slot) {
+        Some(bank_hash) if bank_hash != bank.hash() => warn!(
+            "Replayed bank hash {} of slot {slot} does not match {bank_hash} in the blockstore",
+            bank.hash(),
+        ),
+        _ => info!("Replayed slot {slot}, bank hash {}", bank.hash()),
+    }
+    Ok(bank.clone_without_scheduler())
+}
+
+/// A profile of the transaction without timings or resource usage
+fn new_profile(
+    slot: Slot,
+    transaction_index: usize,
+    transaction: &SanitizedTransaction,
+) -> CliTransactionProfile {
+    CliTransactionProfile {
+        slot,
+        transaction_index,
+        signature: transaction.signature().to_string(),
+        program_ids: transaction
+            .message()
+            .program_instructions_iter()
+            .map(|(program_id, _)| program_id.to_string())
+            .collect(),
+        ..CliTransactionProfile::default()
+    }
+}
+
+/// Executes and commits a transaction whose accounts are locked by the batch of its entry
+fn profile_transaction(
+    bank: &Bank,
+    slot: Slot,
+    transaction_index: usize,
+    transaction: &SanitizedTransaction,
+    lock_us: u64,
+) -> CliTransactionProfile {
+    let (program_cache_hits, program_cache_misses) = bank.get_program_cache_hits_and_misses();
+
+    let mut batch = TransactionBatch::new(
+        vec![Ok(())],
+        bank,
+        Cow::Borrowed(std::slice::from_ref(transaction)),
+    );
+    batch.set_needs_unlock(false);
+    let mut timings = ExecuteTimings::default();
+    let LoadAndExecuteTransactionsOutput {
+        mut loaded_transactions,
+        execution_results,
+        executed_transactions_count,
+        executed_non_vote_transactions_count,
+        executed_with_successful_result_count,
+        signature_count,
+        ..
+    } = bank.load_and_execute_transactions(
+        &batch,
+        MAX_PROCESSING_AGE,
+        ExecutionRecordingConfig::new_single_setting(false),
+        &mut timings,
+        None,
+        None,
+        false,
+    );
+    let num_accounts_loaded = loaded_transactions[0]
+        .0
+        .as_ref()
+        .map_or(0, |loaded_transaction| loaded_transaction.accounts.len());
+    let err = execution_results[0]
+        .flattened_result()
+        .err()
+        .map(|err| format!("{err:?}"));
+    let compute_units_consumed = execution_results[0]
+        .details()
+        .map(|details| details.executed_units)
+        .unwrap_or_default();
+
+    let (last_blockhash, lamports_per_signature) = bank.last_blockhash_and_lamports_per_signature();
+    bank.commit_transactions(
+        batch.sanitized_transactions(),
+        &mut loaded_transactions,
+        execution_results,
+        last_blockhash,
+        lamports_per_signature,
+        CommitTransactionCounts {
+            committed_transactions_count: executed_transactions_count as u64,
+            committed_non_vote_transactions_count: executed_non_vote_transactions_count as u64,
+            committed_with_failure_result_count: executed_transactions_count
+                .saturating_sub(executed_with_successful_result_count)
+                as u64,
+            signature_count,
+        },
+        &mut timings,
+    );
+    let (post_program_cache_hits, post_program_cache_misses) =
+        bank.get_program_cache_hits_and_misses();
+
+    CliTransactionProfile {
+        err,
+        lock_us,
+        load_us: timings.metrics[ExecuteTimingType::LoadUs],
+        execute_us: timings.metrics[ExecuteTimingType::ExecuteUs],
+        commit_us: timings.metrics[ExecuteTimingType::StoreUs],
+        compute_units_consumed,
+        num_accounts_loaded,
+        program_cache_hits: post_program_cache_hits.saturating_sub(program_cache_hits),
+        program_cache_misses: post_program_cache_misses.saturating_sub(program_cache_misses),
+        ..new_profile(slot, transaction_index, transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::output::{RecordOutputWriter, ReplayProfileOutputFormat},
+        solana_entry::entry::{create_ticks, Entry},
+        solana_ledger::{
+            blockstore::entries_to_test_shreds,
+            genesis_utils::{create_genesis_config, GenesisConfigInfo},
+            get_tmp_ledger_path_auto_delete,
+        },
+        solana_sdk::{pubkey::Pubkey, system_program, system_transaction},
+    };
+
+    #[test]
+    fn test_replay_profile() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(1_000_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let blockhash = bank.last_blockhash();
+        let bank_forks = BankForks::new_rw_arc(bank);
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let recipient = Pubkey::new_unique();
+        let transaction = system_transaction::transfer(&mint_keypair, &recipient, 42, blockhash);
+        // Writes the fee payer locked by the first transaction of the entry
+        let conflicting_transaction =
+            system_transaction::transfer(&mint_keypair, &Pubkey::new_unique(), 1, blockhash);
+        let mut entries = vec![Entry::new(
+            &blockhash,
+            1,
+            vec![transaction.clone(), conflicting_transaction],
+        )];
+        entries.extend(create_ticks(genesis_config.ticks_per_slot, 1, blockhash));
+        let shreds = entries_to_test_shreds(&entries, 1, 0, true, 0, true);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+
+        let mut writer =
+            ReplayProfileOutputWriter::new(vec![], ReplayProfileOutputFormat::JsonLines).unwrap();
+        let num_slots = replay_profile(&bank_forks, &blockstore, 0, 1, 1, &mut writer).unwrap();
+        assert_eq!(num_slots, 1);
+        let ReplayProfileOutputWriter::Records(RecordOutputWriter::JsonLines(output)) = writer
+        else {
+            panic!("unexpected writer");
+        };
+        let profiles: Vec<serde_json::Value> = serde_json::Deserializer::from_slice(&output)
+            .into_iter()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(profiles.len(), 2);
+        let profile = &profiles[0];
+        assert_eq!(profile["slot"], 1);
+        assert_eq!(profile["transactionIndex"], 0);
+        assert_eq!(profile["signature"], transaction.signatures[0].to_string());
+        assert_eq!(profile["programIds"][0], system_program::id().to_string());
+        assert!(profile["err"].is_null());
+        // The fee payer, the recipient and the system program
+        assert_eq!(profile["numAccountsLoaded"], 3);
+        assert!(profile["computeUnitsConsumed"].as_u64().unwrap() > 0);
+        assert!(profile["lockUs"].is_u64());
+
+        // The lock conflict is reported and the transaction isn't executed
+        let profile = &profiles[1];
+        assert_eq!(profile["transactionIndex"], 1);
+        assert_eq!(profile["err"], "AccountInUse");
+        assert_eq!(profile["numAccountsLoaded"], 0);
+        assert_eq!(profile["computeUnitsConsumed"], 0);
+
+        let bank = bank_forks.read().unwrap().get(1).unwrap();
+        assert!(bank.is_frozen());
+        assert_eq!(bank.get_balance(&recipient), 42);
+    }
+}
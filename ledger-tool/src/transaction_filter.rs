@@ -0,0 +1,586 @@
+//! Filter expressions used by the `blockstore query` subcommand
+//!
+//! An expression is made up of predicates that are combined with `and`, `or`,
+//! `not` and parentheses, for example:
+//!
+//! ```text
+//! failed and program = <PUBKEY> and cu > 200000 and slot >= 1000
+//! ```
+//!
+//! The supported predicates are:
+//!
+//! - `success`, `failed`
+//! - `program = <PUBKEY>`, `program != <PUBKEY>`: top-level and inner instructions
+//! - `account = <PUBKEY>`, `account != <PUBKEY>`: static and loaded account keys
+//! - `error = <TYPE>`, `error != <TYPE>`, `error ~ <TEXT>`: transaction error
+//! - `log ~ <TEXT>`: any log message contains the text
+//! - `fee`, `cu` and `slot` compared with `=`, `!=`, `<`, `<=`, `>` or `>=` to a number
+//!
+//! Values containing whitespace or operator characters must be double quoted.
+
+use {
+    solana_sdk::{clock::Slot, pubkey::Pubkey, transaction::TransactionError},
+    solana_transaction_status::VersionedTransactionWithStatusMeta,
+    std::{ops::RangeInclusive, str::FromStr},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    fn negate(self) -> Self {
+        match self {
+            Self::Equal => Self::NotEqual,
+            Self::NotEqual => Self::Equal,
+            Self::Less => Self::GreaterOrEqual,
+            Self::LessOrEqual => Self::Greater,
+            Self::Greater => Self::LessOrEqual,
+            Self::GreaterOrEqual => Self::Less,
+        }
+    }
+
+    /// The values `v` for which `v <self> rhs` holds
+    fn range(self, rhs: u64) -> RangeInclusive<u64> {
+        match self {
+            Self::Equal => rhs..=rhs,
+            Self::NotEqual => 0..=u64::MAX,
+            Self::Less => match rhs.checked_sub(1) {
+                Some(end) => 0..=end,
+                None => empty_range(),
+            },
+            Self::LessOrEqual => 0..=rhs,
+            Self::Greater => match rhs.checked_add(1) {
+                Some(start) => start..=u64::MAX,
+                None => empty_range(),
+            },
+            Self::GreaterOrEqual => rhs..=u64::MAX,
+        }
+    }
+
+    fn compare(self, lhs: u64, rhs: u64) -> bool {
+        match self {
+            Self::Equal => lhs == rhs,
+            Self::NotEqual => lhs != rhs,
+            Self::Less => lhs < rhs,
+            Self::LessOrEqual => lhs <= rhs,
+            Self::Greater => lhs > rhs,
+            Self::GreaterOrEqual => lhs >= rhs,
+        }
+    }
+}
+
+fn empty_range() -> RangeInclusive<u64> {
+    1..=0
+}
+
+fn intersect_ranges(lhs: RangeInclusive<u64>, rhs: RangeInclusive<u64>) -> RangeInclusive<u64> {
+    (*lhs.start()).max(*rhs.start())..=(*lhs.end()).min(*rhs.end())
+}
+
+/// The smallest range containing both ranges
+fn join_ranges(lhs: RangeInclusive<u64>, rhs: RangeInclusive<u64>) -> RangeInclusive<u64> {
+    if lhs.is_empty() {
+        rhs
+    } else if rhs.is_empty() {
+        lhs
+    } else {
+        (*lhs.start()).min(*rhs.start())..=(*lhs.end()).max(*rhs.end())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Comparison(Comparison),
+    Contains,
+    LeftParen,
+    RightParen,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Predicate {
+    Success,
+    Program(Pubkey),
+    Account(Pubkey),
+    ErrorType(String),
+    ErrorContains(String),
+    LogContains(String),
+    Fee(Comparison, u64),
+    ComputeUnits(Comparison, u64),
+    Slot(Comparison, u64),
+}
+
+impl Predicate {
+    fn matches(&self, slot: Slot, transaction: &VersionedTransactionWithStatusMeta) -> bool {
+        let meta = &transaction.meta;
+        match self {
+            Self::Success => meta.status.is_ok(),
+            Self::Program(program_id) => transaction.invoked_program_ids().contains(program_id),
+            Self::Account(pubkey) => transaction.account_keys().iter().any(|key| key == pubkey),
+            Self::ErrorType(error_type) => meta
+                .status
+                .as_ref()
+                .err()
+                .map(|err| transaction_error_type(err) == *error_type)
+                .unwrap_or(false),
+            Self::ErrorContains(text) => meta
+                .status
+                .as_ref()
+                .err()
+                .map(|err| format!("{err:?}").contains(text.as_str()))
+                .unwrap_or(false),
+            Self::LogContains(text) => meta
+                .log_messages
+                .iter()
+                .flatten()
+                .any(|log| log.contains(text.as_str())),
+            Self::Fee(comparison, fee) => comparison.compare(meta.fee, *fee),
+            Self::ComputeUnits(comparison, compute_units) => meta
+                .compute_units_consumed
+                .map(|consumed| comparison.compare(consumed, *compute_units))
+                .unwrap_or(false),
+            Self::Slot(comparison, other_slot) => comparison.compare(slot, *other_slot),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Expression {
+    Predicate(Predicate),
+    Not(Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// A range containing every slot for which the expression, or its negation if `negated` is
+    /// set, can hold
+    fn slot_range(&self, negated: bool) -> RangeInclusive<Slot> {
+        match self {
+            Self::Predicate(Predicate::Slot(comparison, slot)) if negated => {
+                comparison.negate().range(*slot)
+            }
+            Self::Predicate(Predicate::Slot(comparison, slot)) => comparison.range(*slot),
+            Self::Predicate(_) => 0..=Slot::MAX,
+            Self::Not(expression) => expression.slot_range(!negated),
+            // not (a and b) is (not a) or (not b)
+            Self::And(lhs, rhs) if negated => {
+                join_ranges(lhs.slot_range(negated), rhs.slot_range(negated))
+            }
+            Self::And(lhs, rhs) => {
+                intersect_ranges(lhs.slot_range(negated), rhs.slot_range(negated))
+            }
+            Self::Or(lhs, rhs) if negated => {
+                intersect_ranges(lhs.slot_range(negated), rhs.slot_range(negated))
+            }
+            Self::Or(lhs, rhs) => join_ranges(lhs.slot_range(negated), rhs.slot_range(negated)),
+        }
+    }
+
+    fn matches(&self, slot: Slot, transaction: &VersionedTransactionWithStatusMeta) -> bool {
+        match self {
+            Self::Predicate(predicate) => predicate.matches(slot, transaction),
+            Self::Not(expression) => !expression.matches(slot, transaction),
+            Self::And(lhs, rhs) => lhs.matches(slot, transaction) && rhs.matches(slot, transaction),
+            Self::Or(lhs, rhs) => lhs.matches(slot, transaction) || rhs.matches(slot, transaction),
+        }
+    }
+}
+
+/// A parsed filter expression; the empty expression matches every transaction
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TransactionFilter {
+    expression: Option<Expression>,
+}
+
+impl TransactionFilter {
+    pub fn matches(&self, slot: Slot, transaction: &VersionedTransactionWithStatusMeta) -> bool {
+        self.expression
+            .as_ref()
+            .map(|expression| expression.matches(slot, transaction))
+            .unwrap_or(true)
+    }
+
+    /// The slots outside of this range never match, so they do not need to be scanned. The range
+    /// is empty if no slot can match.
+    pub fn slot_range(&self) -> RangeInclusive<Slot> {
+        self.expression
+            .as_ref()
+            .map(|expression| expression.slot_range(false))
+            .unwrap_or(0..=Slot::MAX)
+    }
+}
+
+impl FromStr for TransactionFilter {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Ok(Self::default());
+        }
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let expression = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {token:?} in filter expression"));
+        }
+        Ok(Self {
+            expression: Some(expression),
+        })
+    }
+}
+
+/// Returns the name of the `TransactionError` variant, e.g. `InstructionError`
+pub fn transaction_error_type(err: &TransactionError) -> String {
+    format!("{err:?}")
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect()
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '(' | ')' | '"' | '=' | '!' | '<' | '>' | '~')
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '~' => Token::Contains,
+            '=' => Token::Comparison(Comparison::Equal),
+            '!' => {
+                if chars.next_if_eq(&'=').is_none() {
+                    return Err("Expected `!=` in filter expression".to_string());
+                }
+                Token::Comparison(Comparison::NotEqual)
+            }
+            '<' => Token::Comparison(if chars.next_if_eq(&'=').is_some() {
+                Comparison::LessOrEqual
+            } else {
+                Comparison::Less
+            }),
+            '>' => Token::Comparison(if chars.next_if_eq(&'=').is_some() {
+                Comparison::GreaterOrEqual
+            } else {
+                Comparison::Greater
+            }),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => value.push(c),
+                        None => return Err("Unterminated string in filter expression".to_string()),
+                    }
+                }
+                Token::Quoted(value)
+            }
+            c => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|c| is_word_char(*c)) {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) if word == keyword => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_and()?;
+        while self.next_if_keyword("or") {
+            expression = Expression::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_unary()?;
+        while self.next_if_keyword("and") {
+            expression = Expression::And(Box::new(expression), Box::new(self.parse_unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        if self.next_if_keyword("not") {
+            return Ok(Expression::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.peek() == Some(&Token::LeftParen) {
+            self.position += 1;
+            let expression = self.parse_or()?;
+            return match self.next_token() {
+                Some(Token::RightParen) => Ok(expression),
+                _ => Err("Expected `)` in filter expression".to_string()),
+            };
+        }
+        self.parse_predicate()
+    }
+
+    fn parse_value(&mut self, field: &str) -> Result<String, String> {
+        match self.next_token() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => Ok(value),
+            _ => Err(format!("Expected a value for `{field}`")),
+        }
+    }
+
+    fn parse_pubkey(&mut self, field: &str) -> Result<Pubkey, String> {
+        let value = self.parse_value(field)?;
+        Pubkey::from_str(&value).map_err(|err| format!("Invalid pubkey for `{field}`: {err}"))
+    }
+
+    fn parse_number(&mut self, field: &str) -> Result<u64, String> {
+        let value = self.parse_value(field)?;
+        value
+            .parse()
+            .map_err(|err| format!("Invalid number for `{field}`: {err}"))
+    }
+
+    fn parse_predicate(&mut self) -> Result<Expression, String> {
+        let field = match self.next_token() {
+            Some(Token::Word(field)) => field,
+            Some(token) => return Err(format!("Unexpected {token:?} in filter expression")),
+            None => return Err("Unexpected end of filter expression".to_string()),
+        };
+        match field.as_str() {
+            "success" => return Ok(Expression::Predicate(Predicate::Success)),
+            "failed" => {
+                return Ok(Expression::Not(Box::new(Expression::Predicate(
+                    Predicate::Success,
+                ))))
+            }
+            _ => {}
+        }
+
+        let operator = self.next_token();
+        let (predicate, negate) = match (field.as_str(), operator) {
+            (
+                "program",
+                Some(Token::Comparison(comparison @ (Comparison::Equal | Comparison::NotEqual))),
+            ) => (
+                Predicate::Program(self.parse_pubkey(&field)?),
+                comparison == Comparison::NotEqual,
+            ),
+            (
+                "account",
+                Some(Token::Comparison(comparison @ (Comparison::Equal | Comparison::NotEqual))),
+            ) => (
+                Predicate::Account(self.parse_pubkey(&field)?),
+                comparison == Comparison::NotEqual,
+            ),
+            (
+                "error",
+                Some(Token::Comparison(comparison @ (Comparison::Equal | Comparison::NotEqual))),
+            ) => (
+                Predicate::ErrorType(self.parse_value(&field)?),
+                comparison == Comparison::NotEqual,
+            ),
+            ("error", Some(Token::Contains)) => {
+                (Predicate::ErrorContains(self.parse_value(&field)?), false)
+            }
+            ("log", Some(Token::Contains)) => {
+                (Predicate::LogContains(self.parse_value(&field)?), false)
+            }
+            ("fee", Some(Token::Comparison(comparison))) => (
+                Predicate::Fee(comparison, self.parse_number(&field)?),
+                false,
+            ),
+            ("cu", Some(Token::Comparison(comparison))) => (
+                Predicate::ComputeUnits(comparison, self.parse_number(&field)?),
+                false,
+            ),
+            ("slot", Some(Token::Comparison(comparison))) => (
+                Predicate::Slot(comparison, self.parse_number(&field)?),
+                false,
+            ),
+            ("program" | "account" | "error" | "log" | "fee" | "cu" | "slot", _) => {
+                return Err(format!("Unsupported operator for `{field}`"));
+            }
+            _ => return Err(format!("Unknown filter field `{field}`")),
+        };
+
+        let expression = Expression::Predicate(predicate);
+        Ok(if negate {
+            Expression::Not(Box::new(expression))
+        } else {
+            expression
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, instruction::InstructionError, signature::Keypair, system_program,
+            system_transaction, transaction::VersionedTransaction,
+        },
+        solana_transaction_status::TransactionStatusMeta,
+    };
+
+    fn parse(input: &str) -> Expression {
+        TransactionFilter::from_str(input)
+            .unwrap()
+            .expression
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let program_id = Pubkey::new_unique();
+        assert_eq!(
+            parse(&format!("failed and program = {program_id} or cu >= 10")),
+            Expression::Or(
+                Box::new(Expression::And(
+                    Box::new(Expression::Not(Box::new(Expression::Predicate(
+                        Predicate::Success
+                    )))),
+                    Box::new(Expression::Predicate(Predicate::Program(program_id))),
+                )),
+                Box::new(Expression::Predicate(Predicate::ComputeUnits(
+                    Comparison::GreaterOrEqual,
+                    10
+                ))),
+            )
+        );
+        assert_eq!(
+            parse("not (slot<5 or fee!=5000) and log ~ \"failed: out of\""),
+            Expression::And(
+                Box::new(Expression::Not(Box::new(Expression::Or(
+                    Box::new(Expression::Predicate(Predicate::Slot(Comparison::Less, 5))),
+                    Box::new(Expression::Predicate(Predicate::Fee(
+                        Comparison::NotEqual,
+                        5000
+                    ))),
+                )))),
+                Box::new(Expression::Predicate(Predicate::LogContains(
+                    "failed: out of".to_string()
+                ))),
+            )
+        );
+        assert_eq!(
+            TransactionFilter::from_str("  ").unwrap(),
+            TransactionFilter::default()
+        );
+
+        assert!(TransactionFilter::from_str("program = not-a-pubkey").is_err());
+        assert!(TransactionFilter::from_str("program > 5").is_err());
+        assert!(TransactionFilter::from_str("cu = many").is_err());
+        assert!(TransactionFilter::from_str("log ~ \"unterminated").is_err());
+        assert!(TransactionFilter::from_str("(success").is_err());
+        assert!(TransactionFilter::from_str("success failed").is_err());
+        assert!(TransactionFilter::from_str("signer = 1").is_err());
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let from = Keypair::new();
+        let to = Pubkey::new_unique();
+        let transaction = VersionedTransactionWithStatusMeta {
+            transaction: VersionedTransaction::from(system_transaction::transfer(
+                &from,
+                &to,
+                1,
+                Hash::default(),
+            )),
+            meta: TransactionStatusMeta {
+                status: Err(TransactionError::InstructionError(
+                    0,
+                    InstructionError::Custom(1),
+                )),
+                fee: 5000,
+                log_messages: Some(vec![
+                    "Program 11111111111111111111111111111111 invoke [1]".to_string(),
+                    "Transfer: insufficient lamports 0, need 1".to_string(),
+                ]),
+                compute_units_consumed: Some(150),
+                ..TransactionStatusMeta::default()
+            },
+        };
+        let slot = 42;
+        let matches = |input: &str| {
+            TransactionFilter::from_str(input)
+                .unwrap()
+                .matches(slot, &transaction)
+        };
+
+        assert!(matches(""));
+        assert!(matches("failed"));
+        assert!(!matches("success"));
+        assert!(matches(&format!("program = {}", system_program::id())));
+        assert!(!matches(&format!("program = {to}")));
+        assert!(matches(&format!("account = {to}")));
+        assert!(matches(&format!("account != {}", Pubkey::new_unique())));
+        assert!(matches("error = InstructionError"));
+        assert!(!matches("error = InsufficientFundsForFee"));
+        assert!(matches("error ~ \"Custom(1)\""));
+        assert!(matches("log ~ \"insufficient lamports\""));
+        assert!(!matches("log ~ exceeded"));
+        assert!(matches("fee = 5000 and cu > 100 and cu <= 150"));
+        assert!(!matches("fee < 5000 or cu > 150"));
+        assert!(matches("slot >= 42 and slot < 43"));
+        assert!(matches("not (success or slot != 42)"));
+    }
+
+    #[test]
+    fn test_filter_slot_range() {
+        let slot_range = |input: &str| TransactionFilter::from_str(input).unwrap().slot_range();
+
+        assert_eq!(slot_range(""), 0..=Slot::MAX);
+        assert_eq!(slot_range("failed and cu > 100"), 0..=Slot::MAX);
+        assert_eq!(slot_range("slot >= 10 and slot < 20"), 10..=19);
+        assert_eq!(slot_range("failed and slot = 7"), 7..=7);
+        assert_eq!(slot_range("slot = 7 or slot > 20"), 7..=Slot::MAX);
+        assert_eq!(slot_range("slot <= 7 or slot = 20"), 0..=20);
+        assert_eq!(slot_range("slot <= 7 or failed"), 0..=Slot::MAX);
+        assert_eq!(slot_range("not (slot < 10 or slot > 20)"), 10..=20);
+        assert_eq!(slot_range("not (slot >= 10 and failed)"), 0..=Slot::MAX);
+        assert_eq!(slot_range("not slot != 5"), 5..=5);
+        assert!(slot_range("slot < 0").is_empty());
+        assert!(slot_range("slot > 20 and slot < 10").is_empty());
+    }
+}
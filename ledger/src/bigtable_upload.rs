@@ -1,9 +1,10 @@
 use {
-    crate::blockstore::Blockstore,
+    crate::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
     crossbeam_channel::{bounded, unbounded},
     log::*,
     solana_measure::measure::Measure,
     solana_sdk::clock::Slot,
+    solana_storage_bigtable::{LongTermLedgerStorage, SlotMetadata},
     std::{
         cmp::{max, min},
         collections::HashSet,
@@ -19,6 +20,9 @@ use {
 #[derive(Clone)]
 pub struct ConfirmedBlockUploadConfig {
     pub force_reupload: bool,
+    /// Only upload the slot metadata of the blocks already in long-term storage, to backfill
+    /// the metadata of blocks uploaded before the slot metadata table existed
+    pub slot_metadata_only: bool,
     pub max_num_slots_to_check: usize,
     pub num_blocks_to_upload_in_parallel: usize,
     pub block_read_ahead_depth: usize, // should always be >= `num_blocks_to_upload_in_parallel`
@@ -29,6 +33,7 @@ impl Default for ConfirmedBlockUploadConfig {
         let num_blocks_to_upload_in_parallel = num_cpus::get() / 2;
         ConfirmedBlockUploadConfig {
             force_reupload: false,
+            slot_metadata_only: false,
             max_num_slots_to_check: num_blocks_to_upload_in_parallel * 4,
             num_blocks_to_upload_in_parallel,
             block_read_ahead_depth: num_blocks_to_upload_in_parallel * 2,
@@ -41,12 +46,19 @@ struct BlockstoreLoadStats {
     pub elapsed: Duration,
 }
 
-/// Uploads a range of blocks from a Blockstore to bigtable LedgerStorage
+/// Uploads a range of blocks from a Blockstore to long-term LedgerStorage
 /// Returns the Slot of the last block checked. If no blocks in the range `[staring_slot,
 /// ending_slot]` are found in Blockstore, this value is equal to `ending_slot`.
+///
+/// The slot metadata of every block is uploaded on a best-effort basis before the block, so a
+/// failure to upload it, e.g. while the slot metadata table has yet to be created, does not keep
+/// the block from being uploaded. Its leader is only known when a `leader_schedule_cache`
+/// covering the slot is provided. With `config.slot_metadata_only`, only the metadata of the
+/// blocks already in long-term storage is uploaded.
 pub async fn upload_confirmed_blocks(
     blockstore: Arc<Blockstore>,
-    bigtable: solana_storage_bigtable::LedgerStorage,
+    bigtable: Arc<dyn LongTermLedgerStorage>,
+    leader_schedule_cache: Option<Arc<LeaderScheduleCache>>,
     starting_slot: Slot,
     ending_slot: Slot,
     config: ConfirmedBlockUploadConfig,
@@ -81,7 +93,7 @@ pub async fn upload_confirmed_blocks(
     );
 
     // Gather the blocks that are already present in bigtable, by slot
-    let bigtable_slots = if !config.force_reupload {
+    let bigtable_slots = if !config.force_reupload || config.slot_metadata_only {
         let mut bigtable_slots = vec![];
         info!(
             "Loading list of bigtable blocks between slots {} and {}...",
@@ -119,15 +131,22 @@ pub async fn upload_confirmed_blocks(
     };
 
     // The blocks that still need to be uploaded is the difference between what's already in the
-    // bigtable and what's in blockstore...
+    // bigtable and what's in blockstore, or their intersection when only backfilling metadata...
     let blocks_to_upload = {
         let blockstore_slots = blockstore_slots.into_iter().collect::<HashSet<_>>();
         let bigtable_slots = bigtable_slots.into_iter().collect::<HashSet<_>>();
 
-        let mut blocks_to_upload = blockstore_slots
-            .difference(&bigtable_slots)
-            .cloned()
-            .collect::<Vec<_>>();
+        let mut blocks_to_upload = if config.slot_metadata_only {
+            blockstore_slots
+                .intersection(&bigtable_slots)
+                .cloned()
+                .collect::<Vec<_>>()
+        } else {
+            blockstore_slots
+                .difference(&bigtable_slots)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
         blocks_to_upload.sort_unstable();
         blocks_to_upload.truncate(config.max_num_slots_to_check);
         blocks_to_upload
@@ -164,6 +183,7 @@ pub async fn upload_confirmed_blocks(
             (0..config.num_blocks_to_upload_in_parallel)
                 .map(|i| {
                     let blockstore = blockstore.clone();
+                    let leader_schedule_cache = leader_schedule_cache.clone();
                     let sender = sender.clone();
                     let slot_receiver = slot_receiver.clone();
                     let exit = exit.clone();
@@ -181,7 +201,19 @@ pub async fn upload_confirmed_blocks(
                                 let _ = match blockstore.get_rooted_block_with_entries(slot, true) {
                                     Ok(confirmed_block_with_entries) => {
                                         num_blocks_read += 1;
-                                        sender.send((slot, Some(confirmed_block_with_entries)))
+                                        let slot_metadata = SlotMetadata::new(
+                                            &confirmed_block_with_entries,
+                                            blockstore.get_bank_hash(slot),
+                                            leader_schedule_cache.as_ref().and_then(
+                                                |leader_schedule_cache| {
+                                                    leader_schedule_cache.slot_leader_at(slot, None)
+                                                },
+                                            ),
+                                        );
+                                        sender.send((
+                                            slot,
+                                            Some((confirmed_block_with_entries, slot_metadata)),
+                                        ))
                                     }
                                     Err(err) => {
                                         warn!(
@@ -224,9 +256,20 @@ pub async fn upload_confirmed_blocks(
                 num_blocks -= 1;
                 None
             }
-            Some(confirmed_block) => {
+            Some((confirmed_block, slot_metadata)) => {
                 let bt = bigtable.clone();
+                let slot_metadata_only = config.slot_metadata_only;
                 Some(tokio::spawn(async move {
+                    if slot_metadata_only {
+                        return bt.upload_slot_metadata(slot, slot_metadata).await;
+                    }
+                    if let Err(err) = bt.upload_slot_metadata(slot, slot_metadata).await {
+                        warn!(
+                            "Failed to upload the metadata of slot {}, uploading its block \
+                             anyway: {:?}",
+                            slot, err
+                        );
+                    }
                     bt.upload_confirmed_block_with_entries(slot, confirmed_block)
                         .await
                 }))
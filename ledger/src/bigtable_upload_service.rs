@@ -2,8 +2,10 @@ use {
     crate::{
         bigtable_upload::{self, ConfirmedBlockUploadConfig},
         blockstore::Blockstore,
+        leader_schedule_cache::LeaderScheduleCache,
     },
     solana_runtime::commitment::BlockCommitmentCache,
+    solana_storage_bigtable::LongTermLedgerStorage,
     std::{
         cmp::min,
         sync::{
@@ -22,8 +24,9 @@ pub struct BigTableUploadService {
 impl BigTableUploadService {
     pub fn new(
         runtime: Arc<Runtime>,
-        bigtable_ledger_storage: solana_storage_bigtable::LedgerStorage,
+        bigtable_ledger_storage: Arc<dyn LongTermLedgerStorage>,
         blockstore: Arc<Blockstore>,
+        leader_schedule_cache: Arc<LeaderScheduleCache>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
@@ -33,6 +36,7 @@ impl BigTableUploadService {
             runtime,
             bigtable_ledger_storage,
             blockstore,
+            leader_schedule_cache,
             block_commitment_cache,
             max_complete_transaction_status_slot,
             max_complete_rewards_slot,
@@ -43,8 +47,9 @@ impl BigTableUploadService {
 
     pub fn new_with_config(
         runtime: Arc<Runtime>,
-        bigtable_ledger_storage: solana_storage_bigtable::LedgerStorage,
+        bigtable_ledger_storage: Arc<dyn LongTermLedgerStorage>,
         blockstore: Arc<Blockstore>,
+        leader_schedule_cache: Arc<LeaderScheduleCache>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
@@ -59,6 +64,7 @@ impl BigTableUploadService {
                     runtime,
                     bigtable_ledger_storage,
                     blockstore,
+                    leader_schedule_cache,
                     block_commitment_cache,
                     max_complete_transaction_status_slot,
                     max_complete_rewards_slot,
@@ -73,8 +79,9 @@ impl BigTableUploadService {
 
     fn run(
         runtime: Arc<Runtime>,
-        bigtable_ledger_storage: solana_storage_bigtable::LedgerStorage,
+        bigtable_ledger_storage: Arc<dyn LongTermLedgerStorage>,
         blockstore: Arc<Blockstore>,
+        leader_schedule_cache: Arc<LeaderScheduleCache>,
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
@@ -110,6 +117,7 @@ impl BigTableUploadService {
             let result = runtime.block_on(bigtable_upload::upload_confirmed_blocks(
                 blockstore.clone(),
                 bigtable_ledger_storage.clone(),
+                Some(leader_schedule_cache.clone()),
                 start_slot,
                 end_slot,
                 config.clone(),
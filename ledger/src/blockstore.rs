@@ -6,8 +6,8 @@ use {
     crate::{
         ancestor_iterator::AncestorIterator,
         blockstore_db::{
-            columns as cf, Column, ColumnIndexDeprecation, Database, IteratorDirection,
-            IteratorMode, LedgerColumn, Result, WriteBatch,
+            columns as cf, Column, ColumnIndexDeprecation, ColumnStorageStats, Database,
+            IteratorDirection, IteratorMode, LedgerColumn, Result, WriteBatch,
         },
         blockstore_meta::*,
         blockstore_metrics::BlockstoreRpcApiMetrics,
@@ -73,13 +73,14 @@ use {
         rc::Rc,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
-            Arc, Mutex, RwLock,
+            Arc, Mutex, RwLock, RwLockReadGuard,
         },
     },
     tempfile::{Builder, TempDir},
     thiserror::Error,
     trees::{Tree, TreeWalk},
 };
+pub mod blockstore_archive;
 pub mod blockstore_purge;
 pub use {
     crate::{
@@ -87,7 +88,7 @@ pub use {
         blockstore_meta::{OptimisticSlotMetaVersioned, SlotMeta},
         blockstore_metrics::BlockstoreInsertionMetrics,
     },
-    blockstore_purge::PurgeType,
+    blockstore_purge::{PurgeColumns, PurgeType},
     rocksdb::properties as RocksProperties,
 };
 
@@ -99,6 +100,11 @@ pub const MAX_COMPLETED_SLOTS_IN_CHANNEL: usize = 100_000;
 // (32K shreds per slot * 4 TX per shred * 2.5 slots per sec)
 pub const MAX_DATA_SHREDS_PER_SLOT: usize = 32_768;
 
+// The most program signatures index entries that are read by one
+// `get_confirmed_signatures_for_program` query, so that queries whose status
+// filter or commitment skips most of the entries are bounded
+pub const MAX_PROGRAM_SIGNATURES_SCANNED: usize = 100_000;
+
 pub type CompletedSlotsSender = Sender<Vec<Slot>>;
 pub type CompletedSlotsReceiver = Receiver<Vec<Slot>>;
 type CompletedRanges = Vec<(u32, u32)>;
@@ -109,6 +115,24 @@ pub struct SignatureInfosForAddress {
     pub found_before: bool,
 }
 
+/// Restricts the signatures returned by
+/// `Blockstore::get_confirmed_signatures_for_program()` to the transactions
+/// that either succeeded or failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionStatusFilter {
+    Success,
+    Failed,
+}
+
+impl TransactionStatusFilter {
+    fn matches(&self, success: bool) -> bool {
+        match self {
+            Self::Success => success,
+            Self::Failed => !success,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum InsertDataShredError {
     Exists,
@@ -210,7 +234,9 @@ pub struct Blockstore {
     code_shred_cf: LedgerColumn<cf::ShredCode>,
     transaction_status_cf: LedgerColumn<cf::TransactionStatus>,
     address_signatures_cf: LedgerColumn<cf::AddressSignatures>,
+    program_signatures_cf: LedgerColumn<cf::ProgramSignatures>,
     transaction_memos_cf: LedgerColumn<cf::TransactionMemos>,
+    transaction_logs_cf: LedgerColumn<cf::TransactionLogs>,
     transaction_status_index_cf: LedgerColumn<cf::TransactionStatusIndex>,
     highest_primary_index_slot: RwLock<Option<Slot>>,
     rewards_cf: LedgerColumn<cf::Rewards>,
@@ -227,10 +253,28 @@ pub struct Blockstore {
     completed_slots_senders: Mutex<Vec<CompletedSlotsSender>>,
     pub shred_timing_point_sender: Option<PohTimingSender>,
     pub lowest_cleanup_slot: RwLock<Slot>,
+    /// The highest slot purged from the transaction history columns once they
+    /// are retained separately from the shreds, see `PurgeColumns`. While this
+    /// is `None`, `lowest_cleanup_slot` bounds those columns as well.
+    pub lowest_transaction_history_cleanup_slot: RwLock<Option<Slot>>,
     pub slots_stats: SlotsStats,
     rpc_api_metrics: BlockstoreRpcApiMetrics,
 }
 
+/// The read locks held while reading the transaction history columns, so that
+/// the cleanup service cannot purge the slots being read at the same time
+struct TransactionHistoryReadLock<'a> {
+    lowest_transaction_history_cleanup_slot: RwLockReadGuard<'a, Option<Slot>>,
+    _lowest_cleanup_slot: Option<RwLockReadGuard<'a, Slot>>,
+    lowest_available_slot: Slot,
+}
+
+impl TransactionHistoryReadLock<'_> {
+    fn is_retained_separately(&self) -> bool {
+        self.lowest_transaction_history_cleanup_slot.is_some()
+    }
+}
+
 pub struct IndexMetaWorkingSetEntry {
     index: Index,
     // true only if at least one shred for this Index was inserted since the time this
@@ -314,7 +358,9 @@ impl Blockstore {
         let code_shred_cf = db.column();
         let transaction_status_cf = db.column();
         let address_signatures_cf = db.column();
+        let program_signatures_cf = db.column();
         let transaction_memos_cf = db.column();
+        let transaction_logs_cf = db.column();
         let transaction_status_index_cf = db.column();
         let rewards_cf = db.column();
         let blocktime_cf = db.column();
@@ -351,7 +397,9 @@ impl Blockstore {
             code_shred_cf,
             transaction_status_cf,
             address_signatures_cf,
+            program_signatures_cf,
             transaction_memos_cf,
+            transaction_logs_cf,
             transaction_status_index_cf,
             highest_primary_index_slot: RwLock::<Option<Slot>>::default(),
             rewards_cf,
@@ -368,6 +416,7 @@ impl Blockstore {
             insert_shreds_lock: Mutex::<()>::default(),
             max_root,
             lowest_cleanup_slot: RwLock::<Slot>::default(),
+            lowest_transaction_history_cleanup_slot: RwLock::<Option<Slot>>::default(),
             slots_stats: SlotsStats::default(),
             rpc_api_metrics: BlockstoreRpcApiMetrics::default(),
         };
@@ -726,7 +775,9 @@ impl Blockstore {
         self.code_shred_cf.submit_rocksdb_cf_metrics();
         self.transaction_status_cf.submit_rocksdb_cf_metrics();
         self.address_signatures_cf.submit_rocksdb_cf_metrics();
+        self.program_signatures_cf.submit_rocksdb_cf_metrics();
         self.transaction_memos_cf.submit_rocksdb_cf_metrics();
+        self.transaction_logs_cf.submit_rocksdb_cf_metrics();
         self.transaction_status_index_cf.submit_rocksdb_cf_metrics();
         self.rewards_cf.submit_rocksdb_cf_metrics();
         self.blocktime_cf.submit_rocksdb_cf_metrics();
@@ -2114,7 +2165,7 @@ impl Blockstore {
     }
 
     fn get_block_time(&self, slot: Slot) -> Result<Option<UnixTimestamp>> {
-        let _lock = self.check_lowest_cleanup_slot(slot)?;
+        let _lock = self.check_lowest_transaction_history_cleanup_slot(slot)?;
         self.blocktime_cf.get(slot)
     }
 
@@ -2122,7 +2173,7 @@ impl Blockstore {
         self.rpc_api_metrics
             .num_get_rooted_block_time
             .fetch_add(1, Ordering::Relaxed);
-        let _lock = self.check_lowest_cleanup_slot(slot)?;
+        let _lock = self.check_lowest_transaction_history_cleanup_slot(slot)?;
 
         if self.is_root(slot) {
             return self
@@ -2141,7 +2192,7 @@ impl Blockstore {
         self.rpc_api_metrics
             .num_get_block_height
             .fetch_add(1, Ordering::Relaxed);
-        let _lock = self.check_lowest_cleanup_slot(slot)?;
+        let _lock = self.check_lowest_transaction_history_cleanup_slot(slot)?;
 
         self.block_height_cf.get(slot)
     }
@@ -2308,12 +2359,11 @@ impl Blockstore {
         iterator
             .map(|transaction| {
                 let signature = transaction.signatures[0];
-                Ok(VersionedTransactionWithStatusMeta {
-                    transaction,
-                    meta: self
-                        .read_transaction_status((signature, slot))?
-                        .ok_or(BlockstoreError::MissingTransactionMetadata)?,
-                })
+                let mut meta = self
+                    .read_transaction_status((signature, slot))?
+                    .ok_or(BlockstoreError::MissingTransactionMetadata)?;
+                self.fill_transaction_logs(slot, signature, &mut meta)?;
+                Ok(VersionedTransactionWithStatusMeta { transaction, meta })
             })
             .collect()
     }
@@ -2462,6 +2512,27 @@ impl Blockstore {
         Ok(())
     }
 
+    /// Indexes a transaction under each of the programs it invoked, so that it
+    /// is found by `get_confirmed_signatures_for_program()`
+    pub fn write_program_signatures(
+        &self,
+        slot: Slot,
+        signature: Signature,
+        program_ids: &[Pubkey],
+        success: bool,
+        transaction_index: usize,
+    ) -> Result<()> {
+        let transaction_index = u32::try_from(transaction_index)
+            .map_err(|_| BlockstoreError::TransactionIndexOverflow)?;
+        for program_id in program_ids {
+            self.program_signatures_cf.put(
+                (*program_id, slot, transaction_index, signature),
+                &ProgramSignatureMeta { success },
+            )?;
+        }
+        Ok(())
+    }
+
     pub fn read_transaction_memos(
         &self,
         signature: Signature,
@@ -2489,6 +2560,43 @@ impl Blockstore {
         self.transaction_memos_cf.put((*signature, slot), &memos)
     }
 
+    /// Returns the log messages of a transaction that were written to the
+    /// separate transaction logs column, see `write_transaction_logs()`
+    pub fn read_transaction_logs(
+        &self,
+        slot: Slot,
+        signature: Signature,
+    ) -> Result<Option<Vec<String>>> {
+        self.transaction_logs_cf.get((slot, signature))
+    }
+
+    /// Stores the log messages of a transaction apart from its status meta, so
+    /// that they can be retained and compressed independently of the rest of
+    /// the transaction history
+    pub fn write_transaction_logs(
+        &self,
+        slot: Slot,
+        signature: Signature,
+        logs: &[String],
+    ) -> Result<()> {
+        self.transaction_logs_cf
+            .put((slot, signature), &logs.to_vec())
+    }
+
+    /// Fills in the log messages of `meta` from the transaction logs column
+    /// when they were not stored inline with the status
+    fn fill_transaction_logs(
+        &self,
+        slot: Slot,
+        signature: Signature,
+        meta: &mut TransactionStatusMeta,
+    ) -> Result<()> {
+        if meta.log_messages.is_none() {
+            meta.log_messages = self.read_transaction_logs(slot, signature)?;
+        }
+        Ok(())
+    }
+
     /// Acquires the `lowest_cleanup_slot` lock and returns a tuple of the held lock
     /// and lowest available slot.
     ///
@@ -2505,22 +2613,62 @@ impl Blockstore {
         Ok(lowest_cleanup_slot)
     }
 
-    /// Acquires the lock of `lowest_cleanup_slot` and returns the tuple of
-    /// the held lock and the lowest available slot.
+    /// Acquires the locks bounding the transaction history columns and
+    /// returns them along with the lowest available slot of those columns.
     ///
-    /// This function ensures a consistent result by using lowest_cleanup_slot
-    /// as the lower bound for reading columns that do not employ strong read
-    /// consistency with slot-based delete_range.
-    fn ensure_lowest_cleanup_slot(&self) -> (std::sync::RwLockReadGuard<Slot>, Slot) {
-        let lowest_cleanup_slot = self.lowest_cleanup_slot.read().unwrap();
-        let lowest_available_slot = (*lowest_cleanup_slot)
+    /// This function ensures a consistent result by using the cleanup slot as
+    /// the lower bound for reading columns that do not employ strong read
+    /// consistency with slot-based delete_range. That is
+    /// `lowest_transaction_history_cleanup_slot` once the transaction history
+    /// is retained separately from the shreds, `lowest_cleanup_slot` otherwise.
+    fn ensure_lowest_transaction_history_cleanup_slot(&self) -> TransactionHistoryReadLock {
+        let lowest_transaction_history_cleanup_slot =
+            self.lowest_transaction_history_cleanup_slot.read().unwrap();
+        let (lowest_cleanup_slot, cleanup_slot) = match *lowest_transaction_history_cleanup_slot {
+            Some(cleanup_slot) => (None, cleanup_slot),
+            None => {
+                let lowest_cleanup_slot = self.lowest_cleanup_slot.read().unwrap();
+                let cleanup_slot = *lowest_cleanup_slot;
+                (Some(lowest_cleanup_slot), cleanup_slot)
+            }
+        };
+        let lowest_available_slot = cleanup_slot
             .checked_add(1)
             .expect("overflow from trusted value");
 
         // Make caller hold this lock properly; otherwise LedgerCleanupService can purge/compact
         // needed slots here at any given moment.
         // Blockstore callers, like rpc, can process concurrent read queries
-        (lowest_cleanup_slot, lowest_available_slot)
+        TransactionHistoryReadLock {
+            lowest_transaction_history_cleanup_slot,
+            _lowest_cleanup_slot: lowest_cleanup_slot,
+            lowest_available_slot,
+        }
+    }
+
+    /// Same as `check_lowest_cleanup_slot()`, for the slot-keyed columns of the
+    /// transaction history
+    fn check_lowest_transaction_history_cleanup_slot(
+        &self,
+        slot: Slot,
+    ) -> Result<TransactionHistoryReadLock> {
+        let lock = self.ensure_lowest_transaction_history_cleanup_slot();
+        if lock.lowest_available_slot > 1 && lock.lowest_available_slot > slot {
+            return Err(BlockstoreError::SlotCleanedUp);
+        }
+        Ok(lock)
+    }
+
+    /// The lowest slot from which the transaction history is available, which
+    /// is below `get_first_available_block()` when the history is retained for
+    /// longer than the shreds
+    fn get_first_available_transaction_history_slot(&self) -> Result<Slot> {
+        let lock = self.ensure_lowest_transaction_history_cleanup_slot();
+        if lock.is_retained_separately() {
+            return Ok(lock.lowest_available_slot);
+        }
+        drop(lock);
+        self.get_first_available_block()
     }
 
     // Returns a transaction status, as well as a loop counter for unit testing
@@ -2530,8 +2678,12 @@ impl Blockstore {
         confirmed_unrooted_slots: &HashSet<Slot>,
     ) -> Result<(Option<(Slot, TransactionStatusMeta)>, u64)> {
         let mut counter = 0;
-        let (lock, _) = self.ensure_lowest_cleanup_slot();
-        let first_available_block = self.get_first_available_block()?;
+        let lock = self.ensure_lowest_transaction_history_cleanup_slot();
+        let first_available_block = if lock.is_retained_separately() {
+            lock.lowest_available_slot
+        } else {
+            self.get_first_available_block()?
+        };
 
         let iterator =
             self.transaction_status_cf
@@ -2654,12 +2806,18 @@ impl Blockstore {
         signature: Signature,
         confirmed_unrooted_slots: &HashSet<Slot>,
     ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
-        if let Some((slot, meta)) =
+        if let Some((slot, mut meta)) =
             self.get_transaction_status(signature, confirmed_unrooted_slots)?
         {
-            let transaction = self
-                .find_transaction_in_slot(slot, signature)?
-                .ok_or(BlockstoreError::TransactionStatusSlotMismatch)?; // Should not happen
+            let Some(transaction) = self.find_transaction_in_slot(slot, signature)? else {
+                // The transaction history can be retained for longer than the
+                // shreds the transactions are read from
+                if self.is_slot_cleaned_up(slot) {
+                    return Ok(None);
+                }
+                return Err(BlockstoreError::TransactionStatusSlotMismatch); // Should not happen
+            };
+            self.fill_transaction_logs(slot, signature, &mut meta)?;
 
             let block_time = self.get_block_time(slot)?;
             Ok(Some(ConfirmedTransactionWithStatusMeta {
@@ -2679,7 +2837,10 @@ impl Blockstore {
         slot: Slot,
         signature: Signature,
     ) -> Result<Option<VersionedTransaction>> {
-        let slot_entries = self.get_slot_entries(slot, 0)?;
+        let slot_entries = match self.get_slot_entries(slot, 0) {
+            Err(BlockstoreError::SlotCleanedUp) => return Ok(None),
+            slot_entries => slot_entries?,
+        };
         Ok(slot_entries
             .iter()
             .cloned()
@@ -2716,7 +2877,8 @@ impl Blockstore {
         pubkey: Pubkey,
         slot: Slot,
     ) -> Result<Vec<(Slot, Signature)>> {
-        let (lock, lowest_available_slot) = self.ensure_lowest_cleanup_slot();
+        let lock = self.ensure_lowest_transaction_history_cleanup_slot();
+        let lowest_available_slot = lock.lowest_available_slot;
         let mut signatures: Vec<(Slot, Signature)> = vec![];
         if slot < lowest_available_slot {
             return Ok(signatures);
@@ -2825,7 +2987,7 @@ impl Blockstore {
         };
         get_before_slot_timer.stop();
 
-        let first_available_block = self.get_first_available_block()?;
+        let first_available_block = self.get_first_available_transaction_history_slot()?;
         // Generate a HashSet of signatures that should be excluded from the results based on
         // `until` signature
         let mut get_until_slot_timer = Measure::start("get_until_slot_timer");
@@ -2905,21 +3067,7 @@ impl Blockstore {
 
         // Fill in the status information for each found transaction
         let mut get_status_info_timer = Measure::start("get_status_info_timer");
-        let mut infos = vec![];
-        for (slot, signature) in address_signatures.into_iter() {
-            let transaction_status =
-                self.get_transaction_status(signature, &confirmed_unrooted_slots)?;
-            let err = transaction_status.and_then(|(_slot, status)| status.status.err());
-            let memo = self.read_transaction_memos(signature, slot)?;
-            let block_time = self.get_block_time(slot)?;
-            infos.push(ConfirmedTransactionStatusWithSignature {
-                signature,
-                slot,
-                err,
-                memo,
-                block_time,
-            });
-        }
+        let infos = self.get_signature_infos(address_signatures, &confirmed_unrooted_slots)?;
         get_status_info_timer.stop();
 
         datapoint_info!(
@@ -2957,6 +3105,185 @@ impl Blockstore {
         })
     }
 
+    /// Returns the signatures of the transactions that invoked `program_id`,
+    /// either directly or through a cross-program invocation, in descending
+    /// ledger order. Only the transactions written while the program
+    /// signatures index was enabled are found. At most
+    /// `MAX_PROGRAM_SIGNATURES_SCANNED` index entries are read, so fewer than
+    /// `limit` signatures can be returned when `status_filter` skips most of
+    /// them.
+    pub fn get_confirmed_signatures_for_program(
+        &self,
+        program_id: Pubkey,
+        highest_slot: Slot, // highest_super_majority_root or highest_confirmed_slot
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: usize,
+        status_filter: Option<TransactionStatusFilter>,
+    ) -> Result<SignatureInfosForAddress> {
+        self.scan_program_signatures(
+            program_id,
+            highest_slot,
+            before,
+            until,
+            limit,
+            status_filter,
+            MAX_PROGRAM_SIGNATURES_SCANNED,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn scan_program_signatures(
+        &self,
+        program_id: Pubkey,
+        highest_slot: Slot,
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: usize,
+        status_filter: Option<TransactionStatusFilter>,
+        max_scanned: usize,
+    ) -> Result<SignatureInfosForAddress> {
+        self.rpc_api_metrics
+            .num_get_confirmed_signatures_for_program
+            .fetch_add(1, Ordering::Relaxed);
+
+        let max_root = self.max_root();
+        let confirmed_unrooted_slots: HashSet<_> =
+            AncestorIterator::new_inclusive(highest_slot, self)
+                .take_while(|&slot| slot > max_root)
+                .collect();
+
+        // Signatures are listed from the ledger position of the `before` signature, exclusive,
+        // down to the one of the `until` signature, exclusive
+        let (before_slot, before_transaction_index) = match before {
+            None => (highest_slot.saturating_add(1), 0),
+            Some(before) => {
+                match self.find_program_transaction_position(
+                    program_id,
+                    before,
+                    &confirmed_unrooted_slots,
+                )? {
+                    None => return Ok(SignatureInfosForAddress::default()),
+                    Some(position) => position,
+                }
+            }
+        };
+        let until_position = match until {
+            None => None,
+            Some(until) => self.find_program_transaction_position(
+                program_id,
+                until,
+                &confirmed_unrooted_slots,
+            )?,
+        };
+        let first_available_block = self.get_first_available_transaction_history_slot()?;
+
+        let iterator = self.program_signatures_cf.iter(IteratorMode::From(
+            (
+                program_id,
+                before_slot,
+                before_transaction_index,
+                Signature::default(),
+            ),
+            IteratorDirection::Reverse,
+        ))?;
+        let mut program_signatures = vec![];
+        for ((key_program_id, slot, transaction_index, signature), value) in
+            iterator.take(max_scanned)
+        {
+            if program_signatures.len() >= limit
+                || key_program_id != program_id
+                || slot < first_available_block
+                || until_position.is_some_and(|position| (slot, transaction_index) <= position)
+            {
+                break;
+            }
+            if !(self.is_root(slot) || confirmed_unrooted_slots.contains(&slot)) {
+                continue;
+            }
+            let meta: ProgramSignatureMeta = deserialize(&value)?;
+            if status_filter.is_some_and(|status_filter| !status_filter.matches(meta.success)) {
+                continue;
+            }
+            program_signatures.push((slot, signature));
+        }
+
+        Ok(SignatureInfosForAddress {
+            infos: self.get_signature_infos(program_signatures, &confirmed_unrooted_slots)?,
+            found_before: true, // if `before` signature was not found, this method returned early
+        })
+    }
+
+    /// Returns the slot and transaction index of a confirmed transaction that
+    /// is queried relative to the transactions of `program_id`. The program
+    /// signatures index is looked up first, so that the shreds of the slot
+    /// are only read when the transaction did not invoke the program.
+    fn find_program_transaction_position(
+        &self,
+        program_id: Pubkey,
+        signature: Signature,
+        confirmed_unrooted_slots: &HashSet<Slot>,
+    ) -> Result<Option<(Slot, u32)>> {
+        let Some((slot, _)) = self.get_transaction_status(signature, confirmed_unrooted_slots)?
+        else {
+            return Ok(None);
+        };
+        let indexed_transaction_index = self
+            .program_signatures_cf
+            .iter(IteratorMode::From(
+                (program_id, slot, 0, Signature::default()),
+                IteratorDirection::Forward,
+            ))?
+            .take_while(|((key_program_id, key_slot, _, _), _)| {
+                *key_program_id == program_id && *key_slot == slot
+            })
+            .find(|((_, _, _, key_signature), _)| *key_signature == signature)
+            .map(|((_, _, transaction_index, _), _)| transaction_index);
+        if let Some(transaction_index) = indexed_transaction_index {
+            return Ok(Some((slot, transaction_index)));
+        }
+        // The signatures of a slot can outlive its shreds, in which case the
+        // position of the transaction is no longer known
+        let slot_entries = match self.get_slot_entries(slot, 0) {
+            Err(BlockstoreError::SlotCleanedUp) => return Ok(None),
+            slot_entries => slot_entries?,
+        };
+        slot_entries
+            .into_iter()
+            .flat_map(|entry| entry.transactions)
+            .position(|transaction| transaction.signatures.first() == Some(&signature))
+            .map(|transaction_index| {
+                u32::try_from(transaction_index)
+                    .map(|transaction_index| (slot, transaction_index))
+                    .map_err(|_| BlockstoreError::TransactionIndexOverflow)
+            })
+            .transpose()
+    }
+
+    /// Fills in the status, memo and block time of each of `signatures`
+    fn get_signature_infos(
+        &self,
+        signatures: Vec<(Slot, Signature)>,
+        confirmed_unrooted_slots: &HashSet<Slot>,
+    ) -> Result<Vec<ConfirmedTransactionStatusWithSignature>> {
+        let mut infos = vec![];
+        for (slot, signature) in signatures.into_iter() {
+            let transaction_status =
+                self.get_transaction_status(signature, confirmed_unrooted_slots)?;
+            let err = transaction_status.and_then(|(_slot, status)| status.status.err());
+            let memo = self.read_transaction_memos(signature, slot)?;
+            let block_time = self.get_block_time(slot)?;
+            infos.push(ConfirmedTransactionStatusWithSignature {
+                signature,
+                slot,
+                err,
+                memo,
+                block_time,
+            });
+        }
+        Ok(infos)
+    }
+
     pub fn read_rewards(&self, index: Slot) -> Result<Option<Rewards>> {
         self.rewards_cf
             .get_protobuf_or_bincode::<Rewards>(index)
@@ -3547,10 +3874,36 @@ impl Blockstore {
         *self.lowest_cleanup_slot.read().unwrap()
     }
 
+    /// Whether the shreds of `slot` have been purged by the cleanup service
+    fn is_slot_cleaned_up(&self, slot: Slot) -> bool {
+        let lowest_cleanup_slot = self.lowest_cleanup_slot();
+        lowest_cleanup_slot > 0 && lowest_cleanup_slot >= slot
+    }
+
     pub fn storage_size(&self) -> Result<u64> {
         self.db.storage_size()
     }
 
+    /// Returns the disk usage and the estimated number of keys and tombstones
+    /// of every column
+    pub fn storage_stats(&self) -> Result<Vec<ColumnStorageStats>> {
+        self.db.storage_stats()
+    }
+
+    /// Checks that `compact_column()` accepts the column named `name` and the
+    /// optional slot range, without compacting anything
+    pub fn check_compact_column(&self, name: &str, slot_range: Option<(Slot, Slot)>) -> Result<()> {
+        self.db.check_compact_column(name, slot_range).map(|_| ())
+    }
+
+    /// Manually compacts the column named `name`, optionally restricted to the
+    /// entries of the \[`from`, `to`\] slot range, to reclaim the space of
+    /// deleted entries without waiting for automatic compactions. This blocks
+    /// until the compaction completes, which may take a while for large columns.
+    pub fn compact_column(&self, name: &str, slot_range: Option<(Slot, Slot)>) -> Result<()> {
+        self.db.compact_column(name, slot_range)
+    }
+
     /// Returns the total physical storage size contributed by all data shreds.
     ///
     /// Note that the reported size does not include those recently inserted
@@ -8675,6 +9028,81 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_get_rooted_transaction_with_separate_logs() {
+        let slot = 2;
+        let entries = make_slot_entries_with_transactions(5);
+        let shreds = entries_to_test_shreds(
+            &entries,
+            slot,
+            slot - 1, // parent_slot
+            true,     // is_full_slot
+            0,        // version
+            true,     // merkle_variant
+        );
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        blockstore.set_roots([slot - 1, slot].iter()).unwrap();
+
+        let signatures: Vec<_> = entries
+            .iter()
+            .flat_map(|entry| &entry.transactions)
+            .map(|transaction| transaction.signatures[0])
+            .collect();
+        for (i, signature) in signatures.iter().enumerate() {
+            let status = TransactionStatusMeta {
+                status: Ok(()),
+                fee: 42,
+                log_messages: None,
+                ..TransactionStatusMeta::default()
+            }
+            .into();
+            blockstore
+                .transaction_status_cf
+                .put_protobuf((*signature, slot), &status)
+                .unwrap();
+            blockstore
+                .write_transaction_logs(slot, *signature, &[format!("Program log: {i}")])
+                .unwrap();
+        }
+
+        // Logs are loaded from their own column for both transactions and blocks
+        for (i, signature) in signatures.iter().enumerate() {
+            let transaction = blockstore.get_rooted_transaction(*signature).unwrap();
+            let TransactionWithStatusMeta::Complete(tx_with_meta) =
+                transaction.unwrap().tx_with_meta
+            else {
+                panic!("expected a complete transaction");
+            };
+            assert_eq!(
+                tx_with_meta.meta.log_messages,
+                Some(vec![format!("Program log: {i}")])
+            );
+        }
+        let block = blockstore.get_rooted_block(slot, false).unwrap();
+        assert!(block
+            .transactions
+            .iter()
+            .all(|tx_with_meta| tx_with_meta.meta.log_messages.is_some()));
+
+        // Purging the logs leaves the rest of the transaction history in place
+        blockstore.purge_transaction_logs(0, slot).unwrap();
+        for signature in &signatures {
+            assert_eq!(
+                blockstore.read_transaction_logs(slot, *signature).unwrap(),
+                None
+            );
+            let transaction = blockstore.get_rooted_transaction(*signature).unwrap();
+            let TransactionWithStatusMeta::Complete(tx_with_meta) =
+                transaction.unwrap().tx_with_meta
+            else {
+                panic!("expected a complete transaction");
+            };
+            assert_eq!(tx_with_meta.meta.log_messages, None);
+        }
+    }
+
     #[test]
     fn test_get_complete_transaction() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
@@ -9380,6 +9808,208 @@ pub mod tests {
         assert!(sig_infos.infos.is_empty());
     }
 
+    #[test]
+    fn test_get_confirmed_signatures_for_program() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let program0 = Pubkey::new_unique();
+        let program1 = Pubkey::new_unique();
+
+        // Transactions alternate between the two programs, the odd ones fail,
+        // and the last one of each slot also invokes program0 through a CPI
+        for slot in 1..=5 {
+            for transaction_index in 0..4 {
+                let signature = Signature::new_unique();
+                let success = transaction_index % 2 == 0;
+                let status = if success {
+                    Ok(())
+                } else {
+                    Err(TransactionError::AccountNotFound)
+                };
+                blockstore
+                    .write_transaction_status(
+                        slot,
+                        signature,
+                        vec![],
+                        vec![],
+                        TransactionStatusMeta {
+                            status,
+                            ..TransactionStatusMeta::default()
+                        },
+                        transaction_index,
+                    )
+                    .unwrap();
+                let program_ids = match transaction_index {
+                    0 | 2 => vec![program0],
+                    1 => vec![program1],
+                    _ => vec![program1, program0],
+                };
+                blockstore
+                    .write_program_signatures(
+                        slot,
+                        signature,
+                        &program_ids,
+                        success,
+                        transaction_index,
+                    )
+                    .unwrap();
+            }
+        }
+        // Leave the last slot unrooted to test only returns confirmed signatures
+        blockstore.set_roots([0, 1, 2, 3, 4].iter()).unwrap();
+        let highest_super_majority_root = 4;
+
+        let all0 = blockstore
+            .get_confirmed_signatures_for_program(
+                program0,
+                highest_super_majority_root,
+                None,
+                None,
+                usize::MAX,
+                None,
+            )
+            .unwrap()
+            .infos;
+        assert_eq!(all0.len(), 12);
+        assert!(all0.windows(2).all(|infos| infos[0].slot >= infos[1].slot));
+        assert_eq!(all0[0].slot, 4);
+        assert!(all0[0].err.is_some());
+        assert!(all0[1].err.is_none());
+
+        let all1 = blockstore
+            .get_confirmed_signatures_for_program(
+                program1,
+                highest_super_majority_root,
+                None,
+                None,
+                usize::MAX,
+                None,
+            )
+            .unwrap()
+            .infos;
+        assert_eq!(all1.len(), 8);
+
+        // Filter on the status of the transactions
+        let failed0 = blockstore
+            .get_confirmed_signatures_for_program(
+                program0,
+                highest_super_majority_root,
+                None,
+                None,
+                usize::MAX,
+                Some(TransactionStatusFilter::Failed),
+            )
+            .unwrap()
+            .infos;
+        assert_eq!(failed0.len(), 4);
+        assert!(failed0.iter().all(|info| info.err.is_some()));
+        let succeeded0 = blockstore
+            .get_confirmed_signatures_for_program(
+                program0,
+                highest_super_majority_root,
+                None,
+                None,
+                usize::MAX,
+                Some(TransactionStatusFilter::Success),
+            )
+            .unwrap()
+            .infos;
+        assert_eq!(succeeded0.len(), 8);
+        assert!(succeeded0.iter().all(|info| info.err.is_none()));
+
+        // The scan stops after reading `max_scanned` index entries, whether they match or not
+        let scan = |max_scanned| {
+            blockstore
+                .scan_program_signatures(
+                    program0,
+                    highest_super_majority_root,
+                    None,
+                    None,
+                    usize::MAX,
+                    Some(TransactionStatusFilter::Failed),
+                    max_scanned,
+                )
+                .unwrap()
+                .infos
+        };
+        assert_eq!(scan(2), failed0[..1]);
+        assert_eq!(scan(3), failed0[..1]);
+        assert_eq!(scan(4), failed0[..2]);
+        assert_eq!(scan(usize::MAX), failed0);
+
+        // Page through the signatures with `before` and `limit`
+        for i in 0..all0.len() {
+            let results = blockstore
+                .get_confirmed_signatures_for_program(
+                    program0,
+                    highest_super_majority_root,
+                    if i == 0 {
+                        None
+                    } else {
+                        Some(all0[i - 1].signature)
+                    },
+                    None,
+                    1,
+                    None,
+                )
+                .unwrap()
+                .infos;
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0], all0[i], "Unexpected result for {i}");
+        }
+
+        // Stop at the `until` signature
+        let results = blockstore
+            .get_confirmed_signatures_for_program(
+                program0,
+                highest_super_majority_root,
+                Some(all0[1].signature),
+                Some(all0[7].signature),
+                usize::MAX,
+                None,
+            )
+            .unwrap()
+            .infos;
+        assert_eq!(results, all0[2..7]);
+
+        // A `before` signature that is not confirmed yields no signatures
+        let sig_infos = blockstore
+            .get_confirmed_signatures_for_program(
+                program0,
+                highest_super_majority_root,
+                Some(Signature::new_unique()),
+                None,
+                usize::MAX,
+                None,
+            )
+            .unwrap();
+        assert!(!sig_infos.found_before);
+        assert!(sig_infos.infos.is_empty());
+
+        // Neither does a `before` signature that did not invoke the program once
+        // the shreds of its slot are purged, while its status is retained
+        let program1_only = &all1[5];
+        assert_eq!(program1_only.slot, 2);
+        assert!(!all0.contains(program1_only));
+        *blockstore
+            .lowest_transaction_history_cleanup_slot
+            .write()
+            .unwrap() = Some(0);
+        *blockstore.lowest_cleanup_slot.write().unwrap() = 2;
+        let sig_infos = blockstore
+            .get_confirmed_signatures_for_program(
+                program0,
+                highest_super_majority_root,
+                Some(program1_only.signature),
+                None,
+                usize::MAX,
+                None,
+            )
+            .unwrap();
+        assert!(sig_infos.infos.is_empty());
+    }
+
     #[test]
     fn test_get_last_hash() {
         let entries: Vec<Entry> = vec![];
@@ -0,0 +1,942 @@
+//! Export and import of slot ranges of the blockstore in a portable archive.
+//!
+//! An archive starts with [`BLOCKSTORE_ARCHIVE_MAGIC`] and the little-endian format version,
+//! followed by a zstd stream of length-prefixed, bincode-serialized records: a header with the
+//! exported slot range, one record per slot, and a trailer with the number of slots and the
+//! SHA-256 checksum of all the records before it.
+use {
+    super::*,
+    prost::Message,
+    serde::{Deserialize, Serialize},
+    solana_sdk::{hash::Hasher, message::AccountKeys},
+    solana_storage_proto::convert::generated,
+    std::io::{BufReader, Read, Seek, SeekFrom, Write},
+};
+
+/// The first bytes of every blockstore archive
+pub const BLOCKSTORE_ARCHIVE_MAGIC: [u8; 8] = *b"SOLBSARC";
+
+/// The archive format version written by `Blockstore::export_slot_range()`. Version 2 added the
+/// transaction logs and version 3 the program signatures; archives of every earlier version are
+/// still read.
+pub const BLOCKSTORE_ARCHIVE_VERSION: u32 = 3;
+
+/// Upper bound of the size of one record, so that a corrupt length is rejected before allocating
+const MAX_ARCHIVE_RECORD_SIZE: u64 = 1 << 30;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockstoreArchiveHeader {
+    /// The first slot of the exported range
+    pub start_slot: Slot,
+    /// The last slot of the exported range
+    pub end_slot: Slot,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BlockstoreArchiveStats {
+    pub num_slots: usize,
+    pub num_roots: usize,
+    pub num_shreds: usize,
+    pub num_transaction_statuses: usize,
+}
+
+/// A record of the archive, generic over the transaction status of its format version
+#[derive(Serialize, Deserialize)]
+enum ArchiveRecord<S = ArchivedTransactionStatus> {
+    Header(BlockstoreArchiveHeader),
+    Slot(Box<ArchivedSlot<S>>),
+    Trailer { num_slots: u64, checksum: Hash },
+}
+
+impl<S: Into<ArchivedTransactionStatus>> ArchiveRecord<S> {
+    fn into_current(self) -> ArchiveRecord {
+        match self {
+            Self::Header(header) => ArchiveRecord::Header(header),
+            Self::Slot(archived_slot) => {
+                ArchiveRecord::Slot(Box::new(archived_slot.map_transaction_statuses(Into::into)))
+            }
+            Self::Trailer {
+                num_slots,
+                checksum,
+            } => ArchiveRecord::Trailer {
+                num_slots,
+                checksum,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ArchivedSlot<S = ArchivedTransactionStatus> {
+    slot: Slot,
+    meta: SlotMeta,
+    is_root: bool,
+    data_shreds: Vec<Vec<u8>>,
+    coding_shreds: Vec<Vec<u8>>,
+    transaction_statuses: Vec<S>,
+    /// Protobuf-encoded `generated::Rewards`
+    rewards: Option<Vec<u8>>,
+    block_time: Option<UnixTimestamp>,
+    block_height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ArchivedTransactionStatus {
+    signature: Signature,
+    transaction_index: u32,
+    /// Protobuf-encoded `generated::TransactionStatusMeta`
+    status: Vec<u8>,
+    /// The addresses indexing the transaction in `AddressSignatures`, and whether each is
+    /// writable
+    addresses: Vec<(Pubkey, bool)>,
+    /// The programs indexing the transaction in `ProgramSignatures`, and whether the transaction
+    /// succeeded
+    programs: Vec<(Pubkey, bool)>,
+    memos: Option<String>,
+    /// Log messages stored in the separate `TransactionLogs` column, if any
+    logs: Option<Vec<String>>,
+}
+
+/// `ArchivedTransactionStatus` of version 1 archives, which predate the transaction logs
+#[derive(Serialize, Deserialize)]
+struct ArchivedTransactionStatusV1 {
+    signature: Signature,
+    transaction_index: u32,
+    status: Vec<u8>,
+    addresses: Vec<(Pubkey, bool)>,
+    memos: Option<String>,
+}
+
+impl From<ArchivedTransactionStatusV1> for ArchivedTransactionStatus {
+    fn from(transaction_status: ArchivedTransactionStatusV1) -> Self {
+        let ArchivedTransactionStatusV1 {
+            signature,
+            transaction_index,
+            status,
+            addresses,
+            memos,
+        } = transaction_status;
+        Self {
+            signature,
+            transaction_index,
+            status,
+            addresses,
+            programs: vec![],
+            memos,
+            logs: None,
+        }
+    }
+}
+
+/// `ArchivedTransactionStatus` of version 2 archives, which predate the program signatures
+#[derive(Serialize, Deserialize)]
+struct ArchivedTransactionStatusV2 {
+    signature: Signature,
+    transaction_index: u32,
+    status: Vec<u8>,
+    addresses: Vec<(Pubkey, bool)>,
+    memos: Option<String>,
+    logs: Option<Vec<String>>,
+}
+
+impl From<ArchivedTransactionStatusV2> for ArchivedTransactionStatus {
+    fn from(transaction_status: ArchivedTransactionStatusV2) -> Self {
+        let ArchivedTransactionStatusV2 {
+            signature,
+            transaction_index,
+            status,
+            addresses,
+            memos,
+            logs,
+        } = transaction_status;
+        Self {
+            signature,
+            transaction_index,
+            status,
+            addresses,
+            programs: vec![],
+            memos,
+            logs,
+        }
+    }
+}
+
+impl<S> ArchivedSlot<S> {
+    fn map_transaction_statuses<T>(self, f: impl FnMut(S) -> T) -> ArchivedSlot<T> {
+        let ArchivedSlot {
+            slot,
+            meta,
+            is_root,
+            data_shreds,
+            coding_shreds,
+            transaction_statuses,
+            rewards,
+            block_time,
+            block_height,
+        } = self;
+        ArchivedSlot {
+            slot,
+            meta,
+            is_root,
+            data_shreds,
+            coding_shreds,
+            transaction_statuses: transaction_statuses.into_iter().map(f).collect(),
+            rewards,
+            block_time,
+            block_height,
+        }
+    }
+}
+
+impl ArchivedSlot {
+    fn shreds(&self) -> Result<Vec<Shred>> {
+        self.data_shreds
+            .iter()
+            .chain(&self.coding_shreds)
+            .map(|payload| {
+                let shred = Shred::new_from_serialized_shred(payload.clone()).map_err(|err| {
+                    invalid_archive(format!("invalid shred in slot {}: {err}", self.slot))
+                })?;
+                if shred.slot() != self.slot {
+                    return Err(invalid_archive(format!(
+                        "shred of slot {} archived in slot {}",
+                        shred.slot(),
+                        self.slot
+                    )));
+                }
+                Ok(shred)
+            })
+            .collect()
+    }
+
+    /// Check that every shred of the slot is signed by its leader in `leader_schedule_cache`
+    fn verify_leader_signatures(&self, leader_schedule_cache: &LeaderScheduleCache) -> Result<()> {
+        let leader = leader_schedule_cache
+            .slot_leader_at(self.slot, None)
+            .ok_or_else(|| invalid_archive(format!("unknown leader of slot {}", self.slot)))?;
+        for shred in self.shreds()? {
+            if !shred.verify(&leader) {
+                return Err(invalid_archive(format!(
+                    "shred {} of slot {} is not signed by the slot leader {leader}",
+                    shred.index(),
+                    self.slot
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that every archived value decodes, without writing anything
+    fn validate(&self) -> Result<()> {
+        self.shreds()?;
+        for transaction_status in &self.transaction_statuses {
+            generated::TransactionStatusMeta::decode(&transaction_status.status[..])?;
+        }
+        if let Some(rewards) = &self.rewards {
+            generated::Rewards::decode(&rewards[..])?;
+        }
+        Ok(())
+    }
+}
+
+fn invalid_archive(reason: impl Into<String>) -> BlockstoreError {
+    BlockstoreError::InvalidArchive(reason.into())
+}
+
+struct ArchiveWriter<W: Write> {
+    encoder: zstd::Encoder<'static, W>,
+    hasher: Hasher,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Start an archive of format `version`, whose records must be of the same version
+    fn new(mut writer: W, version: u32) -> Result<Self> {
+        writer.write_all(&BLOCKSTORE_ARCHIVE_MAGIC)?;
+        writer.write_all(&version.to_le_bytes())?;
+        Ok(Self {
+            encoder: zstd::Encoder::new(writer, zstd::DEFAULT_COMPRESSION_LEVEL)?,
+            hasher: Hasher::default(),
+        })
+    }
+
+    fn write_record<S: Serialize>(&mut self, record: &ArchiveRecord<S>) -> Result<()> {
+        let bytes = serialize(record)?;
+        let len = (bytes.len() as u64).to_le_bytes();
+        self.encoder.write_all(&len)?;
+        self.encoder.write_all(&bytes)?;
+        self.hasher.hash(&len);
+        self.hasher.hash(&bytes);
+        Ok(())
+    }
+
+    fn finish(mut self, num_slots: u64) -> Result<()> {
+        let checksum = self.hasher.clone().result();
+        self.write_record(&ArchiveRecord::<ArchivedTransactionStatus>::Trailer {
+            num_slots,
+            checksum,
+        })?;
+        self.encoder.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+struct ArchiveReader<R: Read> {
+    decoder: zstd::Decoder<'static, BufReader<R>>,
+    hasher: Hasher,
+    version: u32,
+}
+
+impl<R: Read> ArchiveReader<R> {
+    fn new(mut reader: R) -> Result<Self> {
+        let mut magic = [0u8; BLOCKSTORE_ARCHIVE_MAGIC.len()];
+        read_exact(&mut reader, &mut magic)?;
+        if magic != BLOCKSTORE_ARCHIVE_MAGIC {
+            return Err(invalid_archive("not a blockstore archive"));
+        }
+        let mut version = [0u8; 4];
+        read_exact(&mut reader, &mut version)?;
+        let version = u32::from_le_bytes(version);
+        if !(1..=BLOCKSTORE_ARCHIVE_VERSION).contains(&version) {
+            return Err(invalid_archive(format!(
+                "unsupported archive version {version}, this build reads versions 1 to \
+                 {BLOCKSTORE_ARCHIVE_VERSION}"
+            )));
+        }
+        Ok(Self {
+            decoder: zstd::Decoder::new(reader)?,
+            hasher: Hasher::default(),
+            version,
+        })
+    }
+
+    fn read_record(&mut self) -> Result<ArchiveRecord> {
+        let mut len = [0u8; 8];
+        read_exact(&mut self.decoder, &mut len)?;
+        let record_size = u64::from_le_bytes(len);
+        if record_size > MAX_ARCHIVE_RECORD_SIZE {
+            return Err(invalid_archive(format!(
+                "record of {record_size} bytes exceeds the limit"
+            )));
+        }
+        let mut bytes = vec![0; record_size as usize];
+        read_exact(&mut self.decoder, &mut bytes)?;
+        let record = match self.version {
+            1 => deserialize::<ArchiveRecord<ArchivedTransactionStatusV1>>(&bytes)?.into_current(),
+            2 => deserialize::<ArchiveRecord<ArchivedTransactionStatusV2>>(&bytes)?.into_current(),
+            _ => deserialize(&bytes)?,
+        };
+        if !matches!(record, ArchiveRecord::Trailer { .. }) {
+            self.hasher.hash(&len);
+            self.hasher.hash(&bytes);
+        }
+        Ok(record)
+    }
+
+    /// Read the whole archive, passing each slot to `f` in order, and return its header once the
+    /// trailer has been checked
+    fn read_slots(
+        mut self,
+        mut f: impl FnMut(ArchivedSlot) -> Result<()>,
+    ) -> Result<BlockstoreArchiveHeader> {
+        let ArchiveRecord::Header(header) = self.read_record()? else {
+            return Err(invalid_archive("missing header"));
+        };
+        let mut num_slots = 0u64;
+        let mut last_slot = None;
+        loop {
+            match self.read_record()? {
+                ArchiveRecord::Header(_) => return Err(invalid_archive("duplicate header")),
+                ArchiveRecord::Slot(archived_slot) => {
+                    let slot = archived_slot.slot;
+                    if slot < header.start_slot || slot > header.end_slot {
+                        return Err(invalid_archive(format!(
+                            "slot {slot} is outside of the archived range {}..={}",
+                            header.start_slot, header.end_slot
+                        )));
+                    }
+                    if last_slot.is_some_and(|last_slot| slot <= last_slot) {
+                        return Err(invalid_archive(format!("slot {slot} is out of order")));
+                    }
+                    last_slot = Some(slot);
+                    num_slots += 1;
+                    f(*archived_slot)?;
+                }
+                ArchiveRecord::Trailer {
+                    num_slots: expected_num_slots,
+                    checksum,
+                } => {
+                    if num_slots != expected_num_slots {
+                        return Err(invalid_archive(format!(
+                            "found {num_slots} slots, expected {expected_num_slots}"
+                        )));
+                    }
+                    if self.hasher.result() != checksum {
+                        return Err(invalid_archive("checksum mismatch"));
+                    }
+                    if self.decoder.read(&mut [0u8; 1])? != 0 {
+                        return Err(invalid_archive("unexpected data after the trailer"));
+                    }
+                    return Ok(header);
+                }
+            }
+        }
+    }
+}
+
+fn read_exact(reader: &mut impl Read, buf: &mut [u8]) -> Result<()> {
+    reader.read_exact(buf).map_err(|err| match err.kind() {
+        ErrorKind::UnexpectedEof => invalid_archive("archive is truncated"),
+        _ => err.into(),
+    })
+}
+
+/// Read the whole archive, checking its structure, checksum and the encoding of every value,
+/// and return its header
+pub fn verify_blockstore_archive<R: Read>(reader: R) -> Result<BlockstoreArchiveHeader> {
+    ArchiveReader::new(reader)?.read_slots(|archived_slot| archived_slot.validate())
+}
+
+impl Blockstore {
+    /// Write the slots of [`start_slot`, `end_slot`] to `writer` as a blockstore archive: their
+    /// shreds, `SlotMeta`, root flag, transaction statuses with their address and memo indexes,
+    /// rewards, block time and block height. Dead slots are left out.
+    pub fn export_slot_range<W: Write>(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        writer: W,
+    ) -> Result<BlockstoreArchiveStats> {
+        let mut stats = BlockstoreArchiveStats::default();
+        let mut writer = ArchiveWriter::new(writer, BLOCKSTORE_ARCHIVE_VERSION)?;
+        writer.write_record(&ArchiveRecord::<ArchivedTransactionStatus>::Header(
+            BlockstoreArchiveHeader {
+                start_slot,
+                end_slot,
+            },
+        ))?;
+        for (slot, meta) in self.slot_meta_iterator(start_slot)? {
+            if slot > end_slot {
+                break;
+            }
+            if self.is_dead(slot) {
+                debug!("Skipping dead slot {slot}");
+                continue;
+            }
+            let archived_slot = self.archive_slot(slot, meta)?;
+            stats.num_slots += 1;
+            stats.num_roots += usize::from(archived_slot.is_root);
+            stats.num_shreds += archived_slot.data_shreds.len() + archived_slot.coding_shreds.len();
+            stats.num_transaction_statuses += archived_slot.transaction_statuses.len();
+            writer.write_record(&ArchiveRecord::Slot(Box::new(archived_slot)))?;
+        }
+        writer.finish(stats.num_slots as u64)?;
+        Ok(stats)
+    }
+
+    fn archive_slot(&self, slot: Slot, meta: SlotMeta) -> Result<ArchivedSlot> {
+        let data_shreds = self
+            .slot_data_iterator(slot, 0)?
+            .map(|(_, payload)| payload.into_vec())
+            .collect();
+        let coding_shreds = self
+            .slot_coding_iterator(slot, 0)?
+            .map(|(_, payload)| payload.into_vec())
+            .collect();
+        let transaction_statuses = if meta.is_full() {
+            self.archive_transaction_statuses(slot)?
+        } else {
+            vec![]
+        };
+        let rewards = self
+            .rewards_cf
+            .get_protobuf_or_bincode::<StoredExtendedRewards>(slot)?
+            .map(|rewards| rewards.encode_to_vec());
+        Ok(ArchivedSlot {
+            slot,
+            meta,
+            is_root: self.is_root(slot),
+            data_shreds,
+            coding_shreds,
+            transaction_statuses,
+            rewards,
+            block_time: self.blocktime_cf.get(slot)?,
+            block_height: self.block_height_cf.get(slot)?,
+        })
+    }
+
+    fn archive_transaction_statuses(&self, slot: Slot) -> Result<Vec<ArchivedTransactionStatus>> {
+        let transactions = self
+            .get_slot_entries(slot, 0)?
+            .into_iter()
+            .flat_map(|entry| entry.transactions);
+        let mut transaction_statuses = vec![];
+        for (i, transaction) in transactions.enumerate() {
+            let Some(&signature) = transaction.signatures.first() else {
+                continue;
+            };
+            let Some(status) = self.read_transaction_status((signature, slot))? else {
+                continue;
+            };
+            let transaction_index =
+                u32::try_from(i).map_err(|_| BlockstoreError::TransactionIndexOverflow)?;
+            let account_keys = AccountKeys::new(
+                transaction.message.static_account_keys(),
+                Some(&status.loaded_addresses),
+            );
+            let mut addresses = vec![];
+            let mut programs = vec![];
+            for pubkey in account_keys.iter() {
+                if let Some(meta) =
+                    self.address_signatures_cf
+                        .get((*pubkey, slot, transaction_index, signature))?
+                {
+                    addresses.push((*pubkey, meta.writeable));
+                }
+                if let Some(meta) =
+                    self.program_signatures_cf
+                        .get((*pubkey, slot, transaction_index, signature))?
+                {
+                    programs.push((*pubkey, meta.success));
+                }
+            }
+            let memos = self.read_transaction_memos(signature, slot)?;
+            let logs = self.read_transaction_logs(slot, signature)?;
+            transaction_statuses.push(ArchivedTransactionStatus {
+                signature,
+                transaction_index,
+                status: generated::TransactionStatusMeta::from(status).encode_to_vec(),
+                addresses,
+                programs,
+                memos,
+                logs,
+            });
+        }
+        Ok(transaction_statuses)
+    }
+
+    /// Merge a blockstore archive written by `export_slot_range()` into this blockstore. The
+    /// whole archive is verified before anything is written; the shreds are then inserted like
+    /// repaired shreds, so the `SlotMeta` of the imported slots are chained with the existing
+    /// ones.
+    ///
+    /// The archive checksum only detects corruption. With a `leader_schedule_cache`, the shreds
+    /// of every slot must also be signed by the slot leader; without one, the archive is trusted
+    /// and its shreds are inserted unchecked, so only import archives from a trusted source.
+    pub fn import_archive<R: Read + Seek>(
+        &self,
+        mut reader: R,
+        leader_schedule_cache: Option<&LeaderScheduleCache>,
+    ) -> Result<(BlockstoreArchiveHeader, BlockstoreArchiveStats)> {
+        ArchiveReader::new(&mut reader)?.read_slots(|archived_slot| {
+            archived_slot.validate()?;
+            match leader_schedule_cache {
+                Some(leader_schedule_cache) => {
+                    archived_slot.verify_leader_signatures(leader_schedule_cache)
+                }
+                None => Ok(()),
+            }
+        })?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut stats = BlockstoreArchiveStats::default();
+        let mut roots = vec![];
+        let header = ArchiveReader::new(reader)?.read_slots(|archived_slot| {
+            if archived_slot.is_root {
+                roots.push(archived_slot.slot);
+            }
+            self.import_archived_slot(archived_slot, leader_schedule_cache, &mut stats)
+        })?;
+        stats.num_roots = roots.len();
+        self.set_roots(roots.iter())?;
+        Ok((header, stats))
+    }
+
+    fn import_archived_slot(
+        &self,
+        archived_slot: ArchivedSlot,
+        leader_schedule_cache: Option<&LeaderScheduleCache>,
+        stats: &mut BlockstoreArchiveStats,
+    ) -> Result<()> {
+        let shreds = archived_slot.shreds()?;
+        let ArchivedSlot {
+            slot,
+            meta,
+            transaction_statuses,
+            rewards,
+            block_time,
+            block_height,
+            ..
+        } = archived_slot;
+
+        stats.num_slots += 1;
+        stats.num_shreds += shreds.len();
+        self.insert_shreds(
+            shreds,
+            leader_schedule_cache,
+            leader_schedule_cache.is_none(), // is_trusted
+        )?;
+        if meta.is_full() && !self.meta(slot)?.is_some_and(|meta| meta.is_full()) {
+            warn!("Slot {slot} is full in the archive but not after its import");
+        }
+
+        let mut write_batch = self.db.batch()?;
+        for transaction_status in transaction_statuses {
+            let ArchivedTransactionStatus {
+                signature,
+                transaction_index,
+                status,
+                addresses,
+                programs,
+                memos,
+                logs,
+            } = transaction_status;
+            write_batch.put_bytes::<cf::TransactionStatus>((signature, slot), &status)?;
+            for (address, writeable) in addresses {
+                write_batch.put::<cf::AddressSignatures>(
+                    (address, slot, transaction_index, signature),
+                    &AddressSignatureMeta { writeable },
+                )?;
+            }
+            for (program_id, success) in programs {
+                write_batch.put::<cf::ProgramSignatures>(
+                    (program_id, slot, transaction_index, signature),
+                    &ProgramSignatureMeta { success },
+                )?;
+            }
+            if let Some(memos) = memos {
+                write_batch.put::<cf::TransactionMemos>((signature, slot), &memos)?;
+            }
+            if let Some(logs) = logs {
+                write_batch.put::<cf::TransactionLogs>((slot, signature), &logs)?;
+            }
+            stats.num_transaction_statuses += 1;
+        }
+        if let Some(rewards) = rewards {
+            write_batch.put_bytes::<cf::Rewards>(slot, &rewards)?;
+        }
+        if let Some(block_time) = block_time {
+            write_batch.put::<cf::Blocktime>(slot, &block_time)?;
+        }
+        if let Some(block_height) = block_height {
+            write_batch.put::<cf::BlockHeight>(slot, &block_height)?;
+        }
+        self.db.write(write_batch)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use {
+        super::*,
+        crate::{
+            blockstore::tests::make_slot_entries_with_transactions,
+            get_tmp_ledger_path_auto_delete,
+            leader_schedule::{FixedSchedule, LeaderSchedule},
+        },
+        assert_matches::assert_matches,
+        solana_transaction_status::{Reward, RewardType},
+        std::io::Cursor,
+    };
+
+    fn populate_blockstore_for_archive(blockstore: &Blockstore) {
+        let entries = make_slot_entries_with_transactions(5);
+        let shreds = entries_to_test_shreds(&entries, 1, 0, true, 0, /*merkle_variant:*/ true);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        for (i, transaction) in entries
+            .iter()
+            .flat_map(|entry| &entry.transactions)
+            .enumerate()
+        {
+            let account_keys = transaction.message.static_account_keys();
+            blockstore
+                .write_transaction_status(
+                    1,
+                    transaction.signatures[0],
+                    vec![&account_keys[0]],
+                    vec![&account_keys[1]],
+                    TransactionStatusMeta::default(),
+                    i,
+                )
+                .unwrap();
+        }
+        blockstore
+            .write_rewards(
+                1,
+                vec![Reward {
+                    pubkey: Pubkey::new_unique().to_string(),
+                    lamports: 42,
+                    post_balance: 42,
+                    reward_type: Some(RewardType::Fee),
+                    commission: None,
+                }],
+            )
+            .unwrap();
+        blockstore.cache_block_time(1, 1_700_000_000).unwrap();
+        blockstore.cache_block_height(1, 1).unwrap();
+
+        let (shreds, _) = make_slot_entries(2, 1, 10, /*merkle_variant:*/ true);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        let (shreds, _) = make_slot_entries(3, 2, 10, /*merkle_variant:*/ true);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        blockstore.set_roots([1, 2].iter()).unwrap();
+    }
+
+    #[test]
+    fn test_export_import_slot_range() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        populate_blockstore_for_archive(&blockstore);
+
+        let mut archive = vec![];
+        let export_stats = blockstore.export_slot_range(1, 2, &mut archive).unwrap();
+        assert_eq!(export_stats.num_slots, 2);
+        assert_eq!(export_stats.num_roots, 2);
+        assert_eq!(export_stats.num_transaction_statuses, 5);
+
+        let header = verify_blockstore_archive(&archive[..]).unwrap();
+        assert_eq!(
+            header,
+            BlockstoreArchiveHeader {
+                start_slot: 1,
+                end_slot: 2
+            }
+        );
+
+        let target_path = get_tmp_ledger_path_auto_delete!();
+        let target = Blockstore::open(target_path.path()).unwrap();
+        let (_, import_stats) = target.import_archive(Cursor::new(&archive), None).unwrap();
+        assert_eq!(import_stats, export_stats);
+
+        for slot in [1, 2] {
+            assert!(target.meta(slot).unwrap().unwrap().is_full());
+            assert!(target.is_root(slot));
+            assert_eq!(
+                target.get_slot_entries(slot, 0).unwrap(),
+                blockstore.get_slot_entries(slot, 0).unwrap()
+            );
+        }
+        assert!(target.meta(3).unwrap().is_none());
+        assert_eq!(
+            target.get_rooted_block(1, false).unwrap(),
+            blockstore.get_rooted_block(1, false).unwrap()
+        );
+        assert_eq!(target.get_block_height(1).unwrap(), Some(1));
+        let transaction = &blockstore.get_slot_entries(1, 0).unwrap()[0].transactions[0];
+        assert_eq!(
+            target
+                .get_confirmed_signatures_for_address2(
+                    transaction.message.static_account_keys()[0],
+                    1,
+                    None,
+                    None,
+                    10,
+                )
+                .unwrap()
+                .infos
+                .into_iter()
+                .map(|info| info.signature)
+                .collect::<Vec<_>>(),
+            vec![transaction.signatures[0]]
+        );
+    }
+
+    /// Rewrite a current archive in the format of an earlier `version`, converting each
+    /// transaction status with `f`
+    fn rewrite_archive<S: Serialize>(
+        archive: &[u8],
+        version: u32,
+        mut f: impl FnMut(ArchivedTransactionStatus) -> S,
+    ) -> Vec<u8> {
+        let mut archived_slots = vec![];
+        let header = ArchiveReader::new(archive)
+            .unwrap()
+            .read_slots(|archived_slot| {
+                archived_slots.push(archived_slot.map_transaction_statuses(&mut f));
+                Ok(())
+            })
+            .unwrap();
+
+        let mut rewritten = vec![];
+        let mut writer = ArchiveWriter::new(&mut rewritten, version).unwrap();
+        writer
+            .write_record(&ArchiveRecord::<S>::Header(header))
+            .unwrap();
+        let num_slots = archived_slots.len() as u64;
+        for archived_slot in archived_slots {
+            writer
+                .write_record(&ArchiveRecord::Slot(Box::new(archived_slot)))
+                .unwrap();
+        }
+        writer.finish(num_slots).unwrap();
+        rewritten
+    }
+
+    #[test]
+    fn test_import_archive_v1() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        populate_blockstore_for_archive(&blockstore);
+        let mut archive = vec![];
+        let export_stats = blockstore.export_slot_range(1, 2, &mut archive).unwrap();
+        let archive_v1 = rewrite_archive(&archive, 1, |transaction_status| {
+            ArchivedTransactionStatusV1 {
+                signature: transaction_status.signature,
+                transaction_index: transaction_status.transaction_index,
+                status: transaction_status.status,
+                addresses: transaction_status.addresses,
+                memos: transaction_status.memos,
+            }
+        });
+        assert_eq!(archive_v1[8..12], 1u32.to_le_bytes());
+
+        let target_path = get_tmp_ledger_path_auto_delete!();
+        let target = Blockstore::open(target_path.path()).unwrap();
+        let (_, import_stats) = target
+            .import_archive(Cursor::new(&archive_v1), None)
+            .unwrap();
+        assert_eq!(import_stats, export_stats);
+        assert_eq!(
+            target.get_rooted_block(1, false).unwrap(),
+            blockstore.get_rooted_block(1, false).unwrap()
+        );
+
+        // Archives of a later format version are rejected
+        let mut archive_next = archive;
+        archive_next[8..12].copy_from_slice(&(BLOCKSTORE_ARCHIVE_VERSION + 1).to_le_bytes());
+        assert_matches!(
+            verify_blockstore_archive(&archive_next[..]),
+            Err(BlockstoreError::InvalidArchive(reason)) if reason.contains("unsupported archive version")
+        );
+    }
+
+    #[test]
+    fn test_import_archive_v2() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        populate_blockstore_for_archive(&blockstore);
+        let transaction = &blockstore.get_slot_entries(1, 0).unwrap()[0].transactions[0];
+        let signature = transaction.signatures[0];
+        let program_id = transaction.message.static_account_keys()[1];
+        let logs = vec!["Program log: archived".to_string()];
+        blockstore
+            .write_transaction_logs(1, signature, &logs)
+            .unwrap();
+        blockstore
+            .write_program_signatures(1, signature, &[program_id], true, 0)
+            .unwrap();
+        let mut archive = vec![];
+        let export_stats = blockstore.export_slot_range(1, 2, &mut archive).unwrap();
+        let archive_v2 = rewrite_archive(&archive, 2, |transaction_status| {
+            ArchivedTransactionStatusV2 {
+                signature: transaction_status.signature,
+                transaction_index: transaction_status.transaction_index,
+                status: transaction_status.status,
+                addresses: transaction_status.addresses,
+                memos: transaction_status.memos,
+                logs: transaction_status.logs,
+            }
+        });
+        assert_eq!(archive_v2[8..12], 2u32.to_le_bytes());
+
+        let target_path = get_tmp_ledger_path_auto_delete!();
+        let target = Blockstore::open(target_path.path()).unwrap();
+        let (_, import_stats) = target
+            .import_archive(Cursor::new(&archive_v2), None)
+            .unwrap();
+        assert_eq!(import_stats, export_stats);
+        assert_eq!(
+            target.read_transaction_logs(1, signature).unwrap(),
+            Some(logs)
+        );
+
+        // Version 2 archives do not carry the program signatures index
+        let program_signatures = |blockstore: &Blockstore| {
+            blockstore
+                .get_confirmed_signatures_for_program(program_id, 2, None, None, 10, None)
+                .unwrap()
+                .infos
+                .len()
+        };
+        assert_eq!(program_signatures(&blockstore), 1);
+        assert_eq!(program_signatures(&target), 0);
+    }
+
+    #[test]
+    fn test_import_invalid_archive() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        populate_blockstore_for_archive(&blockstore);
+        let mut archive = vec![];
+        blockstore.export_slot_range(0, 3, &mut archive).unwrap();
+
+        let target_path = get_tmp_ledger_path_auto_delete!();
+        let target = Blockstore::open(target_path.path()).unwrap();
+
+        let mut bad_magic = archive.clone();
+        bad_magic[0] ^= 0xff;
+        assert_matches!(
+            target.import_archive(Cursor::new(&bad_magic), None),
+            Err(BlockstoreError::InvalidArchive(_))
+        );
+
+        let truncated = &archive[..archive.len() / 2];
+        assert!(target.import_archive(Cursor::new(truncated), None).is_err());
+
+        // Nothing is written unless the whole archive is valid
+        assert!(target.slot_meta_iterator(0).unwrap().next().is_none());
+
+        target.import_archive(Cursor::new(&archive), None).unwrap();
+        assert!(target.meta(3).unwrap().unwrap().is_full());
+    }
+
+    #[test]
+    fn test_import_archive_verifies_leader_signatures() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let leader = Keypair::new();
+        let entries = make_slot_entries_with_transactions(5);
+        let (data_shreds, coding_shreds) = Shredder::new(1, 0, 0, 0).unwrap().entries_to_shreds(
+            &leader,
+            &entries,
+            true,                     // is_last_in_slot
+            Some(Hash::new_unique()), // chained_merkle_root
+            0,                        // next_shred_index
+            0,                        // next_code_index
+            true,                     // merkle_variant
+            &ReedSolomonCache::default(),
+            &mut ProcessShredsStats::default(),
+        );
+        blockstore
+            .insert_shreds([data_shreds, coding_shreds].concat(), None, false)
+            .unwrap();
+        let mut archive = vec![];
+        blockstore.export_slot_range(1, 1, &mut archive).unwrap();
+
+        let leader_schedule_cache = |leader: Pubkey| {
+            let mut leader_schedule_cache = LeaderScheduleCache::default();
+            leader_schedule_cache.set_fixed_leader_schedule(Some(FixedSchedule {
+                leader_schedule: Arc::new(LeaderSchedule::new_from_schedule(vec![leader])),
+            }));
+            leader_schedule_cache
+        };
+        let target_path = get_tmp_ledger_path_auto_delete!();
+        let target = Blockstore::open(target_path.path()).unwrap();
+
+        assert_matches!(
+            target.import_archive(
+                Cursor::new(&archive),
+                Some(&leader_schedule_cache(Pubkey::new_unique()))
+            ),
+            Err(BlockstoreError::InvalidArchive(_))
+        );
+        assert!(target.meta(1).unwrap().is_none());
+
+        target
+            .import_archive(
+                Cursor::new(&archive),
+                Some(&leader_schedule_cache(leader.pubkey())),
+            )
+            .unwrap();
+        assert!(target.meta(1).unwrap().unwrap().is_full());
+    }
+}
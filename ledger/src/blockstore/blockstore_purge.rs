@@ -23,6 +23,31 @@ pub enum PurgeType {
     CompactionFilter,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Controls which columns `blockstore::purge_columns` purges, so that the
+/// transaction history can be retained for longer than the shreds.
+pub enum PurgeColumns {
+    /// Every column.
+    All,
+    /// The shreds and the per-slot metadata used to assemble, repair and
+    /// replay them.
+    Shreds,
+    /// The transaction statuses, address and program signatures, memos and
+    /// logs, along with the roots, rewards, block times and block heights of
+    /// their slots.
+    TransactionHistory,
+}
+
+impl PurgeColumns {
+    fn includes_shreds(&self) -> bool {
+        matches!(self, Self::All | Self::Shreds)
+    }
+
+    fn includes_transaction_history(&self) -> bool {
+        matches!(self, Self::All | Self::TransactionHistory)
+    }
+}
+
 impl Blockstore {
     /// Performs cleanup based on the specified deletion range.  After this
     /// function call, entries within \[`from_slot`, `to_slot`\] will become
@@ -40,14 +65,28 @@ impl Blockstore {
     /// `AddressSignature`, and `cf::TransactionStatusIndex`, are cleaned-up
     /// based on the `purge_type` setting.
     pub fn purge_slots(&self, from_slot: Slot, to_slot: Slot, purge_type: PurgeType) {
+        self.purge_columns(from_slot, to_slot, PurgeColumns::All, purge_type)
+    }
+
+    /// Same as `purge_slots()`, but only purges the given class of columns,
+    /// leaving the data of the other class in \[`from_slot`, `to_slot`\]
+    /// readable.
+    pub fn purge_columns(
+        &self,
+        from_slot: Slot,
+        to_slot: Slot,
+        columns: PurgeColumns,
+        purge_type: PurgeType,
+    ) {
         let mut purge_stats = PurgeStats::default();
         let purge_result =
-            self.run_purge_with_stats(from_slot, to_slot, purge_type, &mut purge_stats);
+            self.run_purge_with_stats(from_slot, to_slot, columns, purge_type, &mut purge_stats);
 
         datapoint_info!(
             "blockstore-purge",
             ("from_slot", from_slot as i64, i64),
             ("to_slot", to_slot as i64, i64),
+            ("columns", format!("{columns:?}"), String),
             ("delete_range_us", purge_stats.delete_range as i64, i64),
             ("write_batch_us", purge_stats.write_batch as i64, i64),
             (
@@ -81,6 +120,22 @@ impl Blockstore {
         }
     }
 
+    /// Purges the transaction logs column for slots \[`from_slot`, `to_slot`\],
+    /// leaving the rest of the ledger untouched. The logs column is keyed by
+    /// slot first, so this is a range delete that lets the logs be retained
+    /// for fewer slots than the transaction statuses they belong to.
+    pub fn purge_transaction_logs(&self, from_slot: Slot, to_slot: Slot) -> Result<()> {
+        let mut write_batch = self.db.batch()?;
+        self.db
+            .delete_range_cf::<cf::TransactionLogs>(&mut write_batch, from_slot, to_slot)?;
+        self.db.write(write_batch)?;
+        if from_slot == 0 {
+            self.db
+                .delete_file_in_range_cf::<cf::TransactionLogs>(from_slot, to_slot)?;
+        }
+        Ok(())
+    }
+
     pub fn purge_and_compact_slots(&self, from_slot: Slot, to_slot: Slot) {
         self.purge_slots(from_slot, to_slot, PurgeType::Exact);
     }
@@ -142,7 +197,13 @@ impl Blockstore {
         to_slot: Slot,
         purge_type: PurgeType,
     ) -> Result<bool> {
-        self.run_purge_with_stats(from_slot, to_slot, purge_type, &mut PurgeStats::default())
+        self.run_purge_with_stats(
+            from_slot,
+            to_slot,
+            PurgeColumns::All,
+            purge_type,
+            &mut PurgeStats::default(),
+        )
     }
 
     /// Purges all columns relating to `slot`.
@@ -157,7 +218,13 @@ impl Blockstore {
         };
         let mut write_batch = self.db.batch()?;
 
-        let columns_purged = self.purge_range(&mut write_batch, slot, slot, PurgeType::Exact)?;
+        let columns_purged = self.purge_range(
+            &mut write_batch,
+            slot,
+            slot,
+            PurgeColumns::All,
+            PurgeType::Exact,
+        )?;
 
         if let Some(parent_slot) = slot_meta.parent_slot {
             let parent_slot_meta = self.meta(parent_slot)?;
@@ -203,13 +270,15 @@ impl Blockstore {
         &self,
         from_slot: Slot,
         to_slot: Slot,
+        columns: PurgeColumns,
         purge_type: PurgeType,
         purge_stats: &mut PurgeStats,
     ) -> Result<bool> {
         let mut write_batch = self.db.batch()?;
 
         let mut delete_range_timer = Measure::start("delete_range");
-        let columns_purged = self.purge_range(&mut write_batch, from_slot, to_slot, purge_type)?;
+        let columns_purged =
+            self.purge_range(&mut write_batch, from_slot, to_slot, columns, purge_type)?;
         delete_range_timer.stop();
 
         let mut write_timer = Measure::start("write_batch");
@@ -234,7 +303,7 @@ impl Blockstore {
         // because all the sst files that have key range below to_slot
         // can be deleted immediately.
         if columns_purged && from_slot == 0 {
-            self.purge_files_in_range(from_slot, to_slot);
+            self.purge_files_in_range(from_slot, to_slot, columns);
         }
         purge_files_in_range_timer.stop();
 
@@ -250,20 +319,47 @@ impl Blockstore {
         write_batch: &mut WriteBatch,
         from_slot: Slot,
         to_slot: Slot,
+        columns: PurgeColumns,
         purge_type: PurgeType,
     ) -> Result<bool> {
-        let columns_purged = self
-            .db
+        let mut columns_purged = true;
+        if columns.includes_shreds() {
+            columns_purged &= self.purge_shred_columns_range(write_batch, from_slot, to_slot);
+        }
+        if !columns.includes_transaction_history() {
+            return Ok(columns_purged);
+        }
+        columns_purged &=
+            self.purge_transaction_history_columns_range(write_batch, from_slot, to_slot);
+
+        match purge_type {
+            PurgeType::Exact => {
+                self.purge_special_columns_exact(write_batch, from_slot, to_slot)?;
+            }
+            PurgeType::CompactionFilter => {
+                // No explicit action is required here because this purge type completely and
+                // indefinitely relies on the proper working of compaction filter for those
+                // special column families, never toggling the primary index from the current
+                // one. Overall, this enables well uniformly distributed writes, resulting
+                // in no spiky periodic huge delete_range for them.
+            }
+        }
+        Ok(columns_purged)
+    }
+
+    fn purge_shred_columns_range(
+        &self,
+        write_batch: &mut WriteBatch,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> bool {
+        self.db
             .delete_range_cf::<cf::SlotMeta>(write_batch, from_slot, to_slot)
             .is_ok()
             & self
                 .db
                 .delete_range_cf::<cf::BankHash>(write_batch, from_slot, to_slot)
                 .is_ok()
-            & self
-                .db
-                .delete_range_cf::<cf::Root>(write_batch, from_slot, to_slot)
-                .is_ok()
             & self
                 .db
                 .delete_range_cf::<cf::ShredData>(write_batch, from_slot, to_slot)
@@ -294,45 +390,60 @@ impl Blockstore {
                 .is_ok()
             & self
                 .db
-                .delete_range_cf::<cf::Rewards>(write_batch, from_slot, to_slot)
+                .delete_range_cf::<cf::PerfSamples>(write_batch, from_slot, to_slot)
                 .is_ok()
             & self
                 .db
-                .delete_range_cf::<cf::Blocktime>(write_batch, from_slot, to_slot)
+                .delete_range_cf::<cf::OptimisticSlots>(write_batch, from_slot, to_slot)
                 .is_ok()
             & self
                 .db
-                .delete_range_cf::<cf::PerfSamples>(write_batch, from_slot, to_slot)
+                .delete_range_cf::<cf::MerkleRootMeta>(write_batch, from_slot, to_slot)
                 .is_ok()
+    }
+
+    /// Range deletes the slot-keyed columns of the transaction history. The
+    /// special columns that are not keyed by slot are handled by the caller.
+    fn purge_transaction_history_columns_range(
+        &self,
+        write_batch: &mut WriteBatch,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> bool {
+        self.db
+            .delete_range_cf::<cf::Root>(write_batch, from_slot, to_slot)
+            .is_ok()
             & self
                 .db
-                .delete_range_cf::<cf::BlockHeight>(write_batch, from_slot, to_slot)
+                .delete_range_cf::<cf::Rewards>(write_batch, from_slot, to_slot)
                 .is_ok()
             & self
                 .db
-                .delete_range_cf::<cf::OptimisticSlots>(write_batch, from_slot, to_slot)
+                .delete_range_cf::<cf::Blocktime>(write_batch, from_slot, to_slot)
                 .is_ok()
             & self
                 .db
-                .delete_range_cf::<cf::MerkleRootMeta>(write_batch, from_slot, to_slot)
-                .is_ok();
+                .delete_range_cf::<cf::BlockHeight>(write_batch, from_slot, to_slot)
+                .is_ok()
+            & self
+                .db
+                .delete_range_cf::<cf::TransactionLogs>(write_batch, from_slot, to_slot)
+                .is_ok()
+    }
 
-        match purge_type {
-            PurgeType::Exact => {
-                self.purge_special_columns_exact(write_batch, from_slot, to_slot)?;
-            }
-            PurgeType::CompactionFilter => {
-                // No explicit action is required here because this purge type completely and
-                // indefinitely relies on the proper working of compaction filter for those
-                // special column families, never toggling the primary index from the current
-                // one. Overall, this enables well uniformly distributed writes, resulting
-                // in no spiky periodic huge delete_range for them.
-            }
+    fn purge_files_in_range(&self, from_slot: Slot, to_slot: Slot, columns: PurgeColumns) -> bool {
+        let mut columns_purged = true;
+        if columns.includes_shreds() {
+            columns_purged &= self.purge_shred_column_files_in_range(from_slot, to_slot);
         }
-        Ok(columns_purged)
+        if columns.includes_transaction_history() {
+            columns_purged &=
+                self.purge_transaction_history_column_files_in_range(from_slot, to_slot);
+        }
+        columns_purged
     }
 
-    fn purge_files_in_range(&self, from_slot: Slot, to_slot: Slot) -> bool {
+    fn purge_shred_column_files_in_range(&self, from_slot: Slot, to_slot: Slot) -> bool {
         self.db
             .delete_file_in_range_cf::<cf::SlotMeta>(from_slot, to_slot)
             .is_ok()
@@ -340,10 +451,6 @@ impl Blockstore {
                 .db
                 .delete_file_in_range_cf::<cf::BankHash>(from_slot, to_slot)
                 .is_ok()
-            & self
-                .db
-                .delete_file_in_range_cf::<cf::Root>(from_slot, to_slot)
-                .is_ok()
             & self
                 .db
                 .delete_file_in_range_cf::<cf::ShredData>(from_slot, to_slot)
@@ -374,27 +481,41 @@ impl Blockstore {
                 .is_ok()
             & self
                 .db
-                .delete_file_in_range_cf::<cf::Rewards>(from_slot, to_slot)
+                .delete_file_in_range_cf::<cf::PerfSamples>(from_slot, to_slot)
                 .is_ok()
             & self
                 .db
-                .delete_file_in_range_cf::<cf::Blocktime>(from_slot, to_slot)
+                .delete_file_in_range_cf::<cf::OptimisticSlots>(from_slot, to_slot)
                 .is_ok()
             & self
                 .db
-                .delete_file_in_range_cf::<cf::PerfSamples>(from_slot, to_slot)
+                .delete_file_in_range_cf::<cf::MerkleRootMeta>(from_slot, to_slot)
                 .is_ok()
+    }
+
+    fn purge_transaction_history_column_files_in_range(
+        &self,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> bool {
+        self.db
+            .delete_file_in_range_cf::<cf::Root>(from_slot, to_slot)
+            .is_ok()
             & self
                 .db
-                .delete_file_in_range_cf::<cf::BlockHeight>(from_slot, to_slot)
+                .delete_file_in_range_cf::<cf::Rewards>(from_slot, to_slot)
                 .is_ok()
             & self
                 .db
-                .delete_file_in_range_cf::<cf::OptimisticSlots>(from_slot, to_slot)
+                .delete_file_in_range_cf::<cf::Blocktime>(from_slot, to_slot)
                 .is_ok()
             & self
                 .db
-                .delete_file_in_range_cf::<cf::MerkleRootMeta>(from_slot, to_slot)
+                .delete_file_in_range_cf::<cf::BlockHeight>(from_slot, to_slot)
+                .is_ok()
+            & self
+                .db
+                .delete_file_in_range_cf::<cf::TransactionLogs>(from_slot, to_slot)
                 .is_ok()
     }
 
@@ -495,6 +616,13 @@ impl Blockstore {
                             transaction_index,
                             signature,
                         ))?;
+                        // Every invoked program is one of the account keys
+                        batch.delete::<cf::ProgramSignatures>((
+                            *pubkey,
+                            slot,
+                            transaction_index,
+                            signature,
+                        ))?;
                         for primary_index in &primary_indexes {
                             batch.delete_raw::<cf::AddressSignatures>(
                                 &cf::AddressSignatures::deprecated_key((
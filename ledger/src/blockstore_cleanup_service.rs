@@ -3,10 +3,13 @@
 //! can be done quickly and should have a fairly stable correlation to actual bytes.
 //! Once the shred count (and thus roughly the byte count) reaches a threshold,
 //! the services begins removing data in FIFO order.
+//! Optionally, the transaction history can be retained for a number of slots
+//! behind the latest root instead, independently of (but never shorter than)
+//! the shreds.
 
 use {
     crate::{
-        blockstore::{Blockstore, PurgeType},
+        blockstore::{Blockstore, PurgeColumns, PurgeType},
         blockstore_db::{Result as BlockstoreResult, DATA_SHRED_CF},
     },
     solana_measure::measure::Measure,
@@ -52,7 +55,12 @@ pub struct BlockstoreCleanupService {
 }
 
 impl BlockstoreCleanupService {
-    pub fn new(blockstore: Arc<Blockstore>, max_ledger_shreds: u64, exit: Arc<AtomicBool>) -> Self {
+    pub fn new(
+        blockstore: Arc<Blockstore>,
+        max_ledger_shreds: u64,
+        transaction_history_slots: Option<u64>,
+        exit: Arc<AtomicBool>,
+    ) -> Self {
         let mut last_purge_slot = 0;
         let mut last_check_time = Instant::now();
 
@@ -61,7 +69,8 @@ impl BlockstoreCleanupService {
             .spawn(move || {
                 info!(
                     "BlockstoreCleanupService has started with max \
-                    ledger shreds={max_ledger_shreds}",
+                    ledger shreds={max_ledger_shreds}, transaction \
+                    history slots={transaction_history_slots:?}",
                 );
                 loop {
                     if exit.load(Ordering::Relaxed) {
@@ -71,6 +80,7 @@ impl BlockstoreCleanupService {
                         Self::cleanup_ledger(
                             &blockstore,
                             max_ledger_shreds,
+                            transaction_history_slots,
                             &mut last_purge_slot,
                             DEFAULT_CLEANUP_SLOT_INTERVAL,
                         );
@@ -183,6 +193,9 @@ impl BlockstoreCleanupService {
     /// # Arguments
     ///
     /// - `max_ledger_shreds`: the number of shreds to keep since the new root.
+    /// - `transaction_history_slots`: if set, the number of slots behind the
+    ///   new root for which the transaction history is kept, independently of
+    ///   the shreds. The history is never purged ahead of the shreds, though.
     /// - `last_purge_slot`: an both an input and output parameter indicating
     ///   the id of the last purged slot.  As an input parameter, it works
     ///   together with `purge_interval` on whether it is too early to perform
@@ -198,6 +211,7 @@ impl BlockstoreCleanupService {
     pub fn cleanup_ledger(
         blockstore: &Arc<Blockstore>,
         max_ledger_shreds: u64,
+        transaction_history_slots: Option<u64>,
         last_purge_slot: &mut u64,
         purge_interval: u64,
     ) {
@@ -212,7 +226,14 @@ impl BlockstoreCleanupService {
         let (slots_to_clean, lowest_cleanup_slot, total_shreds) =
             Self::find_slots_to_clean(blockstore, root, max_ledger_shreds);
 
-        if slots_to_clean {
+        if let Some(transaction_history_slots) = transaction_history_slots {
+            Self::cleanup_ledger_with_transaction_history_retention(
+                blockstore,
+                root,
+                transaction_history_slots,
+                slots_to_clean.then_some(lowest_cleanup_slot),
+            );
+        } else if slots_to_clean {
             *blockstore.lowest_cleanup_slot.write().unwrap() = lowest_cleanup_slot;
 
             let mut purge_time = Measure::start("purge_slots()");
@@ -238,6 +259,67 @@ impl BlockstoreCleanupService {
         Self::report_disk_metrics(disk_utilization_pre, disk_utilization_post, total_shreds);
     }
 
+    /// Purges the shreds below `lowest_cleanup_slot`, if any, and separately the
+    /// transaction history older than `transaction_history_slots` behind `root`
+    fn cleanup_ledger_with_transaction_history_retention(
+        blockstore: &Blockstore,
+        root: Slot,
+        transaction_history_slots: u64,
+        lowest_cleanup_slot: Option<Slot>,
+    ) {
+        // From now on, the transaction history is bounded by its own cleanup
+        // slot rather than by lowest_cleanup_slot, so that it stays readable
+        // once the shreds of its slots are purged.
+        let transaction_history_cleanup_slot = *blockstore
+            .lowest_transaction_history_cleanup_slot
+            .write()
+            .unwrap()
+            .get_or_insert(0);
+
+        if let Some(lowest_cleanup_slot) = lowest_cleanup_slot {
+            *blockstore.lowest_cleanup_slot.write().unwrap() = lowest_cleanup_slot;
+
+            let mut purge_time = Measure::start("purge_slots()");
+            blockstore.purge_columns(
+                0,
+                lowest_cleanup_slot,
+                PurgeColumns::Shreds,
+                PurgeType::CompactionFilter,
+            );
+            purge_time.stop();
+            info!(
+                "Cleaned up Blockstore shreds older than slot {lowest_cleanup_slot}. {purge_time}"
+            );
+        }
+
+        let lowest_transaction_history_cleanup_slot = root
+            .saturating_sub(transaction_history_slots)
+            .min(blockstore.lowest_cleanup_slot());
+        if lowest_transaction_history_cleanup_slot <= transaction_history_cleanup_slot {
+            return;
+        }
+        *blockstore
+            .lowest_transaction_history_cleanup_slot
+            .write()
+            .unwrap() = Some(lowest_transaction_history_cleanup_slot);
+
+        let mut purge_time = Measure::start("purge_slots()");
+        blockstore.purge_columns(
+            0,
+            lowest_transaction_history_cleanup_slot,
+            PurgeColumns::TransactionHistory,
+            PurgeType::CompactionFilter,
+        );
+        // Same as in cleanup_ledger(), the compaction filter of the special
+        // columns must only be advanced after the purge
+        blockstore.set_max_expired_slot(lowest_transaction_history_cleanup_slot);
+        purge_time.stop();
+        info!(
+            "Cleaned up Blockstore transaction history older than slot \
+            {lowest_transaction_history_cleanup_slot}. {purge_time}"
+        );
+    }
+
     fn report_disk_metrics(
         pre: BlockstoreResult<u64>,
         post: BlockstoreResult<u64>,
@@ -262,7 +344,17 @@ impl BlockstoreCleanupService {
 mod tests {
     use {
         super::*,
-        crate::{blockstore::make_many_slot_entries, get_tmp_ledger_path_auto_delete},
+        crate::{
+            blockstore::{
+                entries_to_test_shreds, make_many_slot_entries,
+                tests::make_slot_entries_with_transactions,
+            },
+            blockstore_db::BlockstoreError,
+            get_tmp_ledger_path_auto_delete,
+        },
+        assert_matches::assert_matches,
+        solana_sdk::pubkey::Pubkey,
+        solana_transaction_status::TransactionStatusMeta,
     };
 
     fn flush_blockstore_contents_to_disk(blockstore: Blockstore) -> Blockstore {
@@ -366,7 +458,7 @@ mod tests {
         // Mark 50 as a root to kill all but 5 shreds, which will be in the newest slots
         let mut last_purge_slot = 0;
         blockstore.set_roots([50].iter()).unwrap();
-        BlockstoreCleanupService::cleanup_ledger(&blockstore, 5, &mut last_purge_slot, 10);
+        BlockstoreCleanupService::cleanup_ledger(&blockstore, 5, None, &mut last_purge_slot, 10);
         assert_eq!(last_purge_slot, 50);
 
         //check that 0-40 don't exist
@@ -376,6 +468,135 @@ mod tests {
             .for_each(|(slot, _)| assert!(slot > 40));
     }
 
+    #[test]
+    fn test_cleanup_with_transaction_history_retention() {
+        solana_logger::setup();
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let (shreds, _) = make_many_slot_entries(0, 50, 5);
+        blockstore.insert_shreds(shreds, None, false).unwrap();
+        for slot in 0..=50 {
+            blockstore.cache_block_height(slot, slot).unwrap();
+        }
+
+        // Initiate a flush so inserted shreds found by find_slots_to_clean()
+        let blockstore = Arc::new(flush_blockstore_contents_to_disk(blockstore));
+
+        // Mark 50 as a root to kill all but 5 shreds, while keeping the
+        // transaction history of the last 20 slots
+        let mut last_purge_slot = 0;
+        blockstore.set_roots([50].iter()).unwrap();
+        BlockstoreCleanupService::cleanup_ledger(
+            &blockstore,
+            5,
+            Some(20),
+            &mut last_purge_slot,
+            10,
+        );
+        assert_eq!(last_purge_slot, 50);
+
+        // The shreds of 0-40 are gone, the history is retained from 31 onwards
+        assert!(blockstore.lowest_cleanup_slot() >= 40);
+        blockstore
+            .slot_meta_iterator(0)
+            .unwrap()
+            .for_each(|(slot, _)| assert!(slot > 40));
+        assert_eq!(
+            *blockstore
+                .lowest_transaction_history_cleanup_slot
+                .read()
+                .unwrap(),
+            Some(30)
+        );
+        assert_matches!(
+            blockstore.get_block_height(30),
+            Err(BlockstoreError::SlotCleanedUp)
+        );
+        for slot in 31..=50 {
+            assert_eq!(blockstore.get_block_height(slot).unwrap(), Some(slot));
+        }
+    }
+
+    #[test]
+    fn test_read_transaction_history_retained_past_shreds() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let address = Pubkey::new_unique();
+        let mut signatures = vec![];
+        for slot in 1..=50 {
+            let entries = make_slot_entries_with_transactions(1);
+            let shreds = entries_to_test_shreds(
+                &entries,
+                slot,
+                slot - 1,
+                true, // is_full_slot
+                0,    // version
+                true, // merkle_variant
+            );
+            blockstore.insert_shreds(shreds, None, false).unwrap();
+            let signature = entries[0].transactions[0].signatures[0];
+            blockstore
+                .write_transaction_status(
+                    slot,
+                    signature,
+                    vec![&address],
+                    vec![],
+                    TransactionStatusMeta::default(),
+                    0,
+                )
+                .unwrap();
+            signatures.push((slot, signature));
+        }
+        blockstore
+            .set_roots((1..=50).collect::<Vec<_>>().iter())
+            .unwrap();
+
+        // Purge the shreds of slots up to 40 and the transaction history of
+        // slots up to 30
+        BlockstoreCleanupService::cleanup_ledger_with_transaction_history_retention(
+            &blockstore,
+            50,
+            20,
+            Some(40),
+        );
+
+        let infos = blockstore
+            .get_confirmed_signatures_for_address2(address, 50, None, None, 100)
+            .unwrap()
+            .infos;
+        assert_eq!(
+            infos
+                .iter()
+                .map(|info| (info.slot, info.signature))
+                .collect::<Vec<_>>(),
+            signatures[30..].iter().rev().copied().collect::<Vec<_>>()
+        );
+        for &(slot, signature) in &signatures[30..] {
+            assert_eq!(
+                blockstore
+                    .get_rooted_transaction_status(signature)
+                    .unwrap()
+                    .map(|(slot, _)| slot),
+                Some(slot)
+            );
+            // The transactions are read from the shreds, so only the statuses
+            // of slots 31-40 remain
+            assert_eq!(
+                blockstore
+                    .get_complete_transaction(signature, 50)
+                    .unwrap()
+                    .map(|transaction| transaction.slot),
+                (slot > 40).then_some(slot)
+            );
+        }
+        assert_eq!(
+            blockstore
+                .get_rooted_transaction_status(signatures[29].1)
+                .unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn test_cleanup_speed() {
         solana_logger::setup();
@@ -411,6 +632,7 @@ mod tests {
             BlockstoreCleanupService::cleanup_ledger(
                 &blockstore,
                 initial_slots,
+                None,
                 &mut last_purge_slot,
                 10,
             );
@@ -21,12 +21,12 @@ use {
         self,
         compaction_filter::CompactionFilter,
         compaction_filter_factory::{CompactionFilterContext, CompactionFilterFactory},
-        properties as RocksProperties, ColumnFamily, ColumnFamilyDescriptor, CompactionDecision,
-        DBCompactionStyle, DBCompressionType, DBIterator, DBPinnableSlice, DBRawIterator,
-        FifoCompactOptions, IteratorMode as RocksIteratorMode, LiveFile, Options,
+        properties as RocksProperties, ColumnFamily, ColumnFamilyDescriptor, CompactOptions,
+        CompactionDecision, DBCompactionStyle, DBCompressionType, DBIterator, DBPinnableSlice,
+        DBRawIterator, FifoCompactOptions, IteratorMode as RocksIteratorMode, LiveFile, Options,
         WriteBatch as RWriteBatch, DB,
     },
-    serde::{de::DeserializeOwned, Serialize},
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
     solana_accounts_db::hardened_unpack::UnpackError,
     solana_sdk::{
         clock::{Slot, UnixTimestamp},
@@ -86,8 +86,12 @@ const CODE_SHRED_CF: &str = "code_shred";
 const TRANSACTION_STATUS_CF: &str = "transaction_status";
 /// Column family for Address Signatures
 const ADDRESS_SIGNATURES_CF: &str = "address_signatures";
+/// Column family for Program Signatures
+const PROGRAM_SIGNATURES_CF: &str = "program_signatures";
 /// Column family for TransactionMemos
 const TRANSACTION_MEMOS_CF: &str = "transaction_memos";
+/// Column family for TransactionLogs
+const TRANSACTION_LOGS_CF: &str = "transaction_logs";
 /// Column family for the Transaction Status Index.
 /// This column family is used for tracking the active primary index for columns that for
 /// query performance reasons should not be indexed by Slot.
@@ -151,6 +155,12 @@ pub enum BlockstoreError {
     MissingTransactionMetadata,
     #[error("transaction-index overflow")]
     TransactionIndexOverflow,
+    #[error("invalid blockstore archive: {0}")]
+    InvalidArchive(String),
+    #[error("unknown column: {0}")]
+    UnknownColumn(String),
+    #[error("column {0} is not keyed by slot")]
+    ColumnNotKeyedBySlot(&'static str),
 }
 pub type Result<T> = std::result::Result<T, BlockstoreError>;
 
@@ -286,6 +296,18 @@ pub mod columns {
     /// * value type: [`blockstore_meta::AddressSignatureMeta`]
     pub struct AddressSignatures;
 
+    #[derive(Debug)]
+    /// The program signatures column
+    ///
+    /// Indexes transactions by the programs they invoked, either directly or
+    /// through cross-program invocations, unlike [`AddressSignatures`] which
+    /// indexes every account key of a transaction.  Only populated when the
+    /// index is enabled.
+    ///
+    /// * index type: `(`[`Pubkey`]`, `[`Slot`]`, u32, `[`Signature`]`)`
+    /// * value type: [`blockstore_meta::ProgramSignatureMeta`]
+    pub struct ProgramSignatures;
+
     #[derive(Debug)]
     /// The transaction memos column
     ///
@@ -293,6 +315,18 @@ pub mod columns {
     /// * value type: [`String`]
     pub struct TransactionMemos;
 
+    #[derive(Debug)]
+    /// The transaction logs column
+    ///
+    /// Holds the program log messages of a transaction when they are stored
+    /// apart from its [`TransactionStatus`] entry.  Keyed by slot first so
+    /// that the column can be range deleted independently of the rest of the
+    /// transaction history.
+    ///
+    /// * index type: `(`[`Slot`]`, `[`Signature`]`)`
+    /// * value type: `Vec<`[`String`]`>`
+    pub struct TransactionLogs;
+
     #[derive(Debug)]
     /// The transaction status index column.
     ///
@@ -359,7 +393,8 @@ pub mod columns {
     // - Add struct below and implement `Column` and `ColumnName` traits
     // - Add descriptor in Rocks::cf_descriptors() and name in Rocks::columns()
     // - Account for column in both `run_purge_with_stats()` and
-    //   `compact_storage()` in ledger/src/blockstore/blockstore_purge.rs !!
+    //   `compact_storage()` in ledger/src/blockstore/blockstore_purge.rs,
+    //   under the `PurgeColumns` class the column belongs to !!
     // - Account for column in `analyze_storage()` in ledger-tool/src/main.rs
 }
 
@@ -485,7 +520,9 @@ impl Rocks {
             cf_descriptor_shred_code,
             new_cf_descriptor::<TransactionStatus>(options, oldest_slot),
             new_cf_descriptor::<AddressSignatures>(options, oldest_slot),
+            new_cf_descriptor::<ProgramSignatures>(options, oldest_slot),
             new_cf_descriptor::<TransactionMemos>(options, oldest_slot),
+            new_cf_descriptor::<TransactionLogs>(options, oldest_slot),
             new_cf_descriptor::<TransactionStatusIndex>(options, oldest_slot),
             new_cf_descriptor::<Rewards>(options, oldest_slot),
             new_cf_descriptor::<Blocktime>(options, oldest_slot),
@@ -558,7 +595,9 @@ impl Rocks {
             ShredCode::NAME,
             TransactionStatus::NAME,
             AddressSignatures::NAME,
+            ProgramSignatures::NAME,
             TransactionMemos::NAME,
+            TransactionLogs::NAME,
             TransactionStatusIndex::NAME,
             Rewards::NAME,
             Blocktime::NAME,
@@ -756,6 +795,14 @@ impl Rocks {
         }
     }
 
+    /// Retrieves the specified RocksDB string property of the current
+    /// column family.
+    fn get_property_cf(&self, cf: &ColumnFamily, name: &CStr) -> Result<Option<String>> {
+        self.db
+            .property_value_cf(cf, name)
+            .map_err(BlockstoreError::RocksDb)
+    }
+
     fn live_files_metadata(&self) -> Result<Vec<LiveFile>> {
         match self.db.live_files() {
             Ok(live_files) => Ok(live_files),
@@ -788,10 +835,18 @@ impl TypedColumn for columns::AddressSignatures {
     type Type = blockstore_meta::AddressSignatureMeta;
 }
 
+impl TypedColumn for columns::ProgramSignatures {
+    type Type = blockstore_meta::ProgramSignatureMeta;
+}
+
 impl TypedColumn for columns::TransactionMemos {
     type Type = String;
 }
 
+impl TypedColumn for columns::TransactionLogs {
+    type Type = Vec<String>;
+}
+
 impl TypedColumn for columns::TransactionStatusIndex {
     type Type = blockstore_meta::TransactionStatusIndexMeta;
 }
@@ -963,6 +1018,40 @@ impl ColumnName for columns::AddressSignatures {
     const NAME: &'static str = ADDRESS_SIGNATURES_CF;
 }
 
+impl Column for columns::ProgramSignatures {
+    type Index = (Pubkey, Slot, u32, Signature);
+
+    fn key((program_id, slot, transaction_index, signature): Self::Index) -> Vec<u8> {
+        let mut key = vec![0; 108];
+        key[0..32].copy_from_slice(&program_id.as_ref()[0..32]);
+        BigEndian::write_u64(&mut key[32..40], slot);
+        BigEndian::write_u32(&mut key[40..44], transaction_index);
+        key[44..108].copy_from_slice(&signature.as_ref()[0..64]);
+        key
+    }
+
+    fn index(key: &[u8]) -> Self::Index {
+        let program_id = Pubkey::try_from(&key[0..32]).unwrap();
+        let slot = BigEndian::read_u64(&key[32..40]);
+        let transaction_index = BigEndian::read_u32(&key[40..44]);
+        let signature = Signature::try_from(&key[44..108]).unwrap();
+        (program_id, slot, transaction_index, signature)
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.1
+    }
+
+    // The ProgramSignatures column is not keyed by slot so this method is meaningless
+    // See Column::as_index() declaration for more details
+    fn as_index(_index: u64) -> Self::Index {
+        (Pubkey::default(), 0, 0, Signature::default())
+    }
+}
+impl ColumnName for columns::ProgramSignatures {
+    const NAME: &'static str = PROGRAM_SIGNATURES_CF;
+}
+
 impl ColumnIndexDeprecation for columns::AddressSignatures {
     const DEPRECATED_INDEX_LEN: usize = 112;
     const CURRENT_INDEX_LEN: usize = 108;
@@ -1060,6 +1149,34 @@ impl ColumnIndexDeprecation for columns::TransactionMemos {
     }
 }
 
+impl Column for columns::TransactionLogs {
+    type Index = (Slot, Signature);
+
+    fn key((slot, signature): Self::Index) -> Vec<u8> {
+        let mut key = vec![0; 72];
+        BigEndian::write_u64(&mut key[0..8], slot);
+        key[8..72].copy_from_slice(&signature.as_ref()[0..64]);
+        key
+    }
+
+    fn index(key: &[u8]) -> Self::Index {
+        let slot = BigEndian::read_u64(&key[0..8]);
+        let signature = Signature::try_from(&key[8..72]).unwrap();
+        (slot, signature)
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.0
+    }
+
+    fn as_index(slot: Slot) -> Self::Index {
+        (slot, Signature::default())
+    }
+}
+impl ColumnName for columns::TransactionLogs {
+    const NAME: &'static str = TRANSACTION_LOGS_CF;
+}
+
 impl Column for columns::TransactionStatusIndex {
     type Index = u64;
 
@@ -1545,6 +1662,138 @@ impl Database {
         let cf = self.cf_handle::<C>();
         self.backend.db.compact_range_cf(cf, Some(from), Some(to));
     }
+
+    /// Checks that the column named `name` exists and, if a slot range is
+    /// given, that its keys start with the slot, see [`Self::compact_column`]
+    pub fn check_compact_column(
+        &self,
+        name: &str,
+        slot_range: Option<(Slot, Slot)>,
+    ) -> Result<&'static str> {
+        let name = Rocks::columns()
+            .into_iter()
+            .find(|column| *column == name)
+            .ok_or_else(|| BlockstoreError::UnknownColumn(name.to_string()))?;
+        if slot_range.is_some() && !is_slot_keyed_cf(name) {
+            return Err(BlockstoreError::ColumnNotKeyedBySlot(name));
+        }
+        Ok(name)
+    }
+
+    /// Compacts the column named `name`. If a \[`from`, `to`\] slot range is
+    /// given, only the entries of those slots are compacted, which is supported
+    /// by the columns whose keys start with the slot.
+    ///
+    /// The compaction is not exclusive, so the automatic compactions of the
+    /// column keep running alongside it.
+    pub fn compact_column(&self, name: &str, slot_range: Option<(Slot, Slot)>) -> Result<()> {
+        let name = self.check_compact_column(name, slot_range)?;
+        let cf = self.backend.cf_handle(name);
+        let mut compact_options = CompactOptions::default();
+        compact_options.set_exclusive_manual_compaction(false);
+        match slot_range {
+            None => self.backend.db.compact_range_cf_opt(
+                cf,
+                None::<&[u8]>,
+                None::<&[u8]>,
+                &compact_options,
+            ),
+            // Every key of slot `to` sorts before the first key of slot `to + 1`
+            Some((from, to)) => self.backend.db.compact_range_cf_opt(
+                cf,
+                Some(from.to_be_bytes()),
+                Some(to.saturating_add(1).to_be_bytes()),
+                &compact_options,
+            ),
+        }
+        Ok(())
+    }
+
+    /// Returns the disk usage and the estimated number of keys and
+    /// tombstones of every column, as reported by RocksDB.
+    pub fn storage_stats(&self) -> Result<Vec<ColumnStorageStats>> {
+        let live_files = self.live_files_metadata()?;
+        Rocks::columns()
+            .into_iter()
+            .map(|name| {
+                let cf = self.backend.cf_handle(name);
+                let mut stats = ColumnStorageStats {
+                    column: name.to_string(),
+                    live_data_bytes: self
+                        .backend
+                        .get_int_property_cf(cf, RocksProperties::ESTIMATE_LIVE_DATA_SIZE)?
+                        as u64,
+                    sst_files_bytes: self
+                        .backend
+                        .get_int_property_cf(cf, RocksProperties::TOTAL_SST_FILES_SIZE)?
+                        as u64,
+                    estimated_num_keys: self
+                        .backend
+                        .get_int_property_cf(cf, RocksProperties::ESTIMATE_NUM_KEYS)?
+                        as u64,
+                    ..ColumnStorageStats::default()
+                };
+
+                let mut level_sizes = HashMap::<i32, u64>::new();
+                for file in live_files
+                    .iter()
+                    .filter(|file| file.column_family_name == name)
+                {
+                    stats.num_sst_files += 1;
+                    stats.num_entries += file.num_entries;
+                    stats.estimated_tombstones += file.num_deletions;
+                    *level_sizes.entry(file.level).or_default() += file.size as u64;
+                }
+                stats.compression_ratio = self.compression_ratio_cf(cf, &level_sizes)?;
+                Ok(stats)
+            })
+            .collect()
+    }
+
+    /// Averages the compression ratio of the levels of a column, weighted by
+    /// the size of their SST files
+    fn compression_ratio_cf(
+        &self,
+        cf: &ColumnFamily,
+        level_sizes: &HashMap<i32, u64>,
+    ) -> Result<Option<f64>> {
+        let mut weighted_ratio = 0.0;
+        let mut total_size = 0;
+        for (level, size) in level_sizes {
+            let property = RocksProperties::compression_ratio_at_level(*level as usize);
+            let ratio = self
+                .backend
+                .get_property_cf(cf, property.as_c_str())?
+                .and_then(|ratio| ratio.parse::<f64>().ok())
+                .filter(|ratio| *ratio > 0.0);
+            if let Some(ratio) = ratio {
+                weighted_ratio += ratio * *size as f64;
+                total_size += size;
+            }
+        }
+        if total_size == 0 {
+            return Ok(None);
+        }
+        Ok(Some(weighted_ratio / total_size as f64))
+    }
+}
+
+/// The storage statistics of a column, see [`Database::storage_stats`]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnStorageStats {
+    pub column: String,
+    /// Estimated size of the live data, excluding overwritten and deleted entries
+    pub live_data_bytes: u64,
+    pub sst_files_bytes: u64,
+    pub num_sst_files: u64,
+    /// Number of entries in the SST files, including tombstones
+    pub num_entries: u64,
+    /// Number of deletion entries in the SST files that were not compacted away yet
+    pub estimated_tombstones: u64,
+    pub estimated_num_keys: u64,
+    /// Ratio of the uncompressed to the compressed size, if the column has SST files
+    pub compression_ratio: Option<f64>,
 }
 
 impl<C> LedgerColumn<C>
@@ -2058,6 +2307,16 @@ fn process_cf_options_advanced<C: 'static + Column + ColumnName>(
                 .to_rocksdb_compression_type(),
         );
     }
+
+    // Log messages are large and highly compressible, so they get their own
+    // setting rather than following the one for the transaction status column
+    if C::NAME == columns::TransactionLogs::NAME {
+        cf_options.set_compression_type(
+            column_options
+                .transaction_logs_compression_type
+                .to_rocksdb_compression_type(),
+        );
+    }
 }
 
 /// Creates and returns the column family descriptors for both data shreds and
@@ -2209,6 +2468,33 @@ fn should_enable_cf_compaction(cf_name: &str) -> bool {
         columns::TransactionStatus::NAME
             | columns::TransactionMemos::NAME
             | columns::AddressSignatures::NAME
+            | columns::ProgramSignatures::NAME
+    )
+}
+
+// Returns whether the keys of the column start with the big-endian slot, so
+// that a slot range maps to a contiguous key range. New columns must be added
+// here explicitly to support compacting a slot range of them.
+fn is_slot_keyed_cf(cf_name: &str) -> bool {
+    matches!(
+        cf_name,
+        columns::ErasureMeta::NAME
+            | columns::DeadSlots::NAME
+            | columns::DuplicateSlots::NAME
+            | columns::Index::NAME
+            | columns::Orphans::NAME
+            | columns::BankHash::NAME
+            | columns::Root::NAME
+            | columns::SlotMeta::NAME
+            | columns::ShredData::NAME
+            | columns::ShredCode::NAME
+            | columns::TransactionLogs::NAME
+            | columns::Rewards::NAME
+            | columns::Blocktime::NAME
+            | columns::PerfSamples::NAME
+            | columns::BlockHeight::NAME
+            | columns::OptimisticSlots::NAME
+            | columns::MerkleRootMeta::NAME
     )
 }
 
@@ -2220,7 +2506,8 @@ fn should_enable_compression<C: 'static + Column + ColumnName>() -> bool {
 #[cfg(test)]
 pub mod tests {
     use {
-        super::*, crate::blockstore_db::columns::ShredData, std::path::PathBuf, tempfile::tempdir,
+        super::*, crate::blockstore_db::columns::ShredData, assert_matches::assert_matches,
+        std::path::PathBuf, tempfile::tempdir,
     };
 
     #[test]
@@ -2300,6 +2587,7 @@ pub mod tests {
         let columns_to_compact = [
             columns::TransactionStatus::NAME,
             columns::AddressSignatures::NAME,
+            columns::ProgramSignatures::NAME,
         ];
         columns_to_compact.iter().for_each(|cf_name| {
             assert!(should_enable_cf_compaction(cf_name));
@@ -2350,6 +2638,58 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_compact_column_and_storage_stats() {
+        let temp_dir = tempdir().unwrap();
+        let options = BlockstoreOptions {
+            enforce_ulimit_nofile: false,
+            ..BlockstoreOptions::default()
+        };
+        let db = Database::open(temp_dir.path(), options).unwrap();
+        let roots = db.column::<columns::Root>();
+        for slot in 0..10 {
+            roots.put(slot, &true).unwrap();
+        }
+        roots.delete(9).unwrap();
+
+        // Compacting the whole column drops the tombstone of slot 9
+        db.compact_column(columns::Root::NAME, None).unwrap();
+        let stats = db.storage_stats().unwrap();
+        assert_eq!(stats.len(), Rocks::columns().len());
+        let root_stats = stats
+            .iter()
+            .find(|stats| stats.column == columns::Root::NAME)
+            .unwrap();
+        assert_eq!(root_stats.num_sst_files, 1);
+        assert_eq!(root_stats.num_entries, 9);
+        assert_eq!(root_stats.estimated_tombstones, 0);
+        assert!(root_stats.sst_files_bytes > 0);
+
+        db.compact_column(columns::Root::NAME, Some((2, 5)))
+            .unwrap();
+        assert_matches!(
+            db.compact_column("unknown", None),
+            Err(BlockstoreError::UnknownColumn(_))
+        );
+        assert_matches!(
+            db.compact_column(columns::AddressSignatures::NAME, Some((2, 5))),
+            Err(BlockstoreError::ColumnNotKeyedBySlot(
+                columns::AddressSignatures::NAME
+            ))
+        );
+        assert_matches!(
+            db.check_compact_column(columns::ProgramSignatures::NAME, Some((2, 5))),
+            Err(BlockstoreError::ColumnNotKeyedBySlot(
+                columns::ProgramSignatures::NAME
+            ))
+        );
+        assert_eq!(
+            db.check_compact_column(columns::TransactionLogs::NAME, Some((2, 5)))
+                .unwrap(),
+            columns::TransactionLogs::NAME
+        );
+    }
+
     impl<C> LedgerColumn<C>
     where
         C: ColumnIndexDeprecation + ProtobufColumn + ColumnName,
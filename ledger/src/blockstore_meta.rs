@@ -458,6 +458,13 @@ pub struct AddressSignatureMeta {
     pub writeable: bool,
 }
 
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ProgramSignatureMeta {
+    /// Whether the transaction that invoked the program succeeded, so that
+    /// lookups can filter on it without reading the transaction status
+    pub success: bool,
+}
+
 /// Performance information about validator execution during a time slice.
 ///
 /// Older versions should only arise as a result of deserialization of entries stored by a previous
@@ -150,6 +150,7 @@ pub(crate) struct BlockstoreRpcApiMetrics {
     pub num_get_complete_transaction: AtomicU64,
     pub num_get_confirmed_signatures_for_address: AtomicU64,
     pub num_get_confirmed_signatures_for_address2: AtomicU64,
+    pub num_get_confirmed_signatures_for_program: AtomicU64,
     pub num_get_rooted_block: AtomicU64,
     pub num_get_rooted_block_time: AtomicU64,
     pub num_get_rooted_transaction: AtomicU64,
@@ -169,6 +170,9 @@ impl BlockstoreRpcApiMetrics {
         let num_get_confirmed_signatures_for_address2 = self
             .num_get_confirmed_signatures_for_address2
             .swap(0, Ordering::Relaxed);
+        let num_get_confirmed_signatures_for_program = self
+            .num_get_confirmed_signatures_for_program
+            .swap(0, Ordering::Relaxed);
         let num_get_rooted_block = self.num_get_rooted_block.swap(0, Ordering::Relaxed);
         let num_get_rooted_block_time = self.num_get_rooted_block_time.swap(0, Ordering::Relaxed);
         let num_get_rooted_transaction = self.num_get_rooted_transaction.swap(0, Ordering::Relaxed);
@@ -184,6 +188,7 @@ impl BlockstoreRpcApiMetrics {
             .saturating_add(num_get_complete_transaction)
             .saturating_add(num_get_confirmed_signatures_for_address)
             .saturating_add(num_get_confirmed_signatures_for_address2)
+            .saturating_add(num_get_confirmed_signatures_for_program)
             .saturating_add(num_get_rooted_block)
             .saturating_add(num_get_rooted_block_time)
             .saturating_add(num_get_rooted_transaction)
@@ -210,6 +215,11 @@ impl BlockstoreRpcApiMetrics {
                     num_get_confirmed_signatures_for_address2 as i64,
                     i64
                 ),
+                (
+                    "num_get_confirmed_signatures_for_program",
+                    num_get_confirmed_signatures_for_program as i64,
+                    i64
+                ),
                 ("num_get_rooted_block", num_get_rooted_block as i64, i64),
                 (
                     "num_get_rooted_block_time",
@@ -89,6 +89,10 @@ pub struct LedgerColumnOptions {
     // compression.
     pub compression_type: BlockstoreCompressionType,
 
+    // Determine the way to compress the transaction logs column family, which
+    // is configured independently of the other eligible column families.
+    pub transaction_logs_compression_type: BlockstoreCompressionType,
+
     // Control how often RocksDB read/write performance samples are collected.
     // If the value is greater than 0, then RocksDB read/write perf sample
     // will be collected once for every `rocks_perf_sample_interval` ops.
@@ -100,6 +104,7 @@ impl Default for LedgerColumnOptions {
         Self {
             shred_storage_type: ShredStorageType::RocksLevel,
             compression_type: BlockstoreCompressionType::default(),
+            transaction_logs_compression_type: BlockstoreCompressionType::default(),
             rocks_perf_sample_interval: 0,
         }
     }
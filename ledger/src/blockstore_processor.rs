@@ -7,6 +7,10 @@ use {
         entry_notifier_service::{EntryNotification, EntryNotifierSender},
         leader_schedule_cache::LeaderScheduleCache,
         token_balances::collect_token_balances,
+        transaction_account_states::{
+            collect_writable_accounts, pair_account_states, AccountStatesPrograms,
+            TransactionAccountState,
+        },
         use_snapshot_archives_at_startup::UseSnapshotArchivesAtStartup,
     },
     chrono_humanize::{Accuracy, HumanTime, Tense},
@@ -160,6 +164,15 @@ pub fn execute_batch(
         vec![]
     };
 
+    let account_states_programs = transaction_status_sender
+        .and_then(|transaction_status_sender| {
+            transaction_status_sender.account_states_programs.as_ref()
+        })
+        .and_then(AccountStatesPrograms::get);
+    let pre_accounts = account_states_programs
+        .as_ref()
+        .map(|_| collect_writable_accounts(bank, batch));
+
     let (tx_results, balances) = batch.bank().load_execute_and_commit_transactions(
         batch,
         MAX_PROCESSING_AGE,
@@ -199,6 +212,14 @@ pub fn execute_batch(
         let token_balances =
             TransactionTokenBalancesSet::new(pre_token_balances, post_token_balances);
 
+        let account_states =
+            account_states_programs
+                .zip(pre_accounts)
+                .map(|(programs, pre_accounts)| {
+                    let post_accounts = collect_writable_accounts(bank, batch);
+                    pair_account_states(pre_accounts, post_accounts, &programs)
+                });
+
         transaction_status_sender.send_transaction_status_batch(
             bank.clone(),
             transactions,
@@ -207,6 +228,7 @@ pub fn execute_batch(
             token_balances,
             rent_debits,
             transaction_indexes.to_vec(),
+            account_states,
         );
     }
 
@@ -1862,11 +1884,18 @@ pub struct TransactionStatusBatch {
     pub token_balances: TransactionTokenBalancesSet,
     pub rent_debits: Vec<RentDebits>,
     pub transaction_indexes: Vec<usize>,
+    /// The states of the selected writable accounts of each transaction, before and after its
+    /// execution, when `TransactionStatusSender::account_states_programs` is set
+    pub account_states: Option<Vec<Vec<TransactionAccountState>>>,
 }
 
 #[derive(Clone, Debug)]
 pub struct TransactionStatusSender {
     pub sender: Sender<TransactionStatusMessage>,
+    /// Owner programs of the writable accounts whose states before and after each transaction are
+    /// sent with the transaction statuses, read for each batch so that they can change at
+    /// runtime. The account states are not collected when None.
+    pub account_states_programs: Option<AccountStatesPrograms>,
 }
 
 impl TransactionStatusSender {
@@ -1879,6 +1908,7 @@ impl TransactionStatusSender {
         token_balances: TransactionTokenBalancesSet,
         rent_debits: Vec<RentDebits>,
         transaction_indexes: Vec<usize>,
+        account_states: Option<Vec<Vec<TransactionAccountState>>>,
     ) {
         let slot = bank.slot();
 
@@ -1898,6 +1928,7 @@ impl TransactionStatusSender {
                 token_balances,
                 rent_debits,
                 transaction_indexes,
+                account_states,
             }))
         {
             trace!(
@@ -1984,7 +2015,7 @@ pub mod tests {
             },
         },
         solana_sdk::{
-            account::{AccountSharedData, WritableAccount},
+            account::{AccountSharedData, ReadableAccount, WritableAccount},
             epoch_schedule::EpochSchedule,
             hash::Hash,
             instruction::{Instruction, InstructionError},
@@ -4451,6 +4482,7 @@ pub mod tests {
             crossbeam_channel::unbounded();
         let transaction_status_sender = TransactionStatusSender {
             sender: transaction_status_sender,
+            account_states_programs: None,
         };
 
         let blockhash = bank.last_blockhash();
@@ -4540,6 +4572,86 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_confirm_slot_entries_account_states() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(100 * LAMPORTS_PER_SOL);
+        let genesis_hash = genesis_config.hash();
+        let bank = BankWithScheduler::new_without_scheduler(
+            Bank::new_with_bank_forks_for_tests(&genesis_config).0,
+        );
+        let replay_tx_thread_pool = create_thread_pool(1);
+        let mut timing = ConfirmationTiming::default();
+        let mut progress = ConfirmationProgress::new(genesis_hash);
+        let amount = genesis_config.rent.minimum_balance(0);
+        let keypair1 = Keypair::new();
+        let pubkey2 = Pubkey::new_unique();
+        bank.transfer(LAMPORTS_PER_SOL, &mint_keypair, &keypair1.pubkey())
+            .unwrap();
+
+        // An empty set of programs selects every writable account
+        let account_states_programs = AccountStatesPrograms::new(Some(HashSet::new()));
+        let (transaction_status_sender, transaction_status_receiver) =
+            crossbeam_channel::unbounded();
+        let transaction_status_sender = TransactionStatusSender {
+            sender: transaction_status_sender,
+            account_states_programs: Some(account_states_programs.clone()),
+        };
+        let mut confirm_transfer = |lamports, previous_hash| {
+            let tx =
+                system_transaction::transfer(&keypair1, &pubkey2, lamports, bank.last_blockhash());
+            let entry = next_entry(&previous_hash, 1, vec![tx]);
+            let hash = entry.hash;
+            confirm_slot_entries(
+                &bank,
+                &replay_tx_thread_pool,
+                (vec![entry], 0, false),
+                &mut timing,
+                &mut progress,
+                false,
+                Some(&transaction_status_sender),
+                None,
+                None,
+                &VerifyRecyclers::default(),
+                None,
+                &PrioritizationFeeCache::new(0u64),
+            )
+            .unwrap();
+            let TransactionStatusMessage::Batch(batch) =
+                transaction_status_receiver.recv().unwrap()
+            else {
+                panic!("batch should have been sent");
+            };
+            (hash, batch.account_states)
+        };
+
+        let (hash, account_states) = confirm_transfer(amount, bank.last_blockhash());
+        let account_states = account_states.unwrap();
+        assert_eq!(account_states.len(), 1);
+        let [payer, recipient] = &account_states[0][..] else {
+            panic!("expected the states of the payer and the recipient");
+        };
+        assert_eq!(payer.pubkey, keypair1.pubkey());
+        assert_eq!(payer.pre.lamports(), LAMPORTS_PER_SOL);
+        assert!(payer.post.lamports() < LAMPORTS_PER_SOL - amount);
+        assert_eq!(recipient.pubkey, pubkey2);
+        assert_eq!(recipient.pre, AccountSharedData::default());
+        assert_eq!(recipient.post.lamports(), amount);
+
+        // Only the accounts owned by the selected programs are kept
+        account_states_programs.set(Some(HashSet::from([Pubkey::new_unique()])));
+        let (hash, account_states) = confirm_transfer(amount + 1, hash);
+        assert_eq!(account_states, Some(vec![vec![]]));
+
+        // The account states are no longer collected once nobody wants them
+        account_states_programs.set(None);
+        let (_, account_states) = confirm_transfer(amount + 2, hash);
+        assert_eq!(account_states, None);
+    }
+
     #[test]
     fn test_rebatch_transactions() {
         let dummy_leader_pubkey = solana_sdk::pubkey::new_rand();
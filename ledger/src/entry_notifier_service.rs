@@ -73,7 +73,10 @@ impl EntryNotifierService {
         self.sender.clone()
     }
 
+    /// Drops the sender of the service and waits for it to exit: once `exit` is set, or once
+    /// every entry was notified and the other senders are dropped
     pub fn join(self) -> thread::Result<()> {
+        drop(self.sender);
         self.thread_hdl.join()
     }
 }
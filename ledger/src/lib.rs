@@ -25,11 +25,13 @@ pub mod leader_schedule_utils;
 pub mod next_slots_iterator;
 pub mod rooted_slot_iterator;
 pub mod shred;
+pub mod shred_notifier_interface;
 mod shredder;
 pub mod sigverify_shreds;
 pub mod slot_stats;
 mod staking_utils;
 pub mod token_balances;
+pub mod transaction_account_states;
 mod transaction_address_lookup_table_scanner;
 pub mod use_snapshot_archives_at_startup;
 
@@ -0,0 +1,17 @@
+use {
+    crate::shred::Shred,
+    solana_sdk::clock::Slot,
+    std::{sync::Arc, time::SystemTime},
+};
+
+pub trait ShredNotifier {
+    /// Notified of each shred received by the window service, before it is inserted into the
+    /// blockstore. `arrival_time` is when the packet carrying the shred was read off the socket.
+    fn notify_shred(&self, shred: &Shred, is_repair: bool, arrival_time: SystemTime);
+
+    /// Notified of a proof, detected by the window service, that the leader of `slot` produced
+    /// the two conflicting shreds.
+    fn notify_duplicate_shred(&self, slot: Slot, shred1: &[u8], shred2: &[u8]);
+}
+
+pub type ShredNotifierArc = Arc<dyn ShredNotifier + Sync + Send>;
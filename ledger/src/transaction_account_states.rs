@@ -0,0 +1,157 @@
+use {
+    solana_measure::measure::Measure,
+    solana_metrics::datapoint_debug,
+    solana_runtime::{bank::Bank, transaction_batch::TransactionBatch},
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        pubkey::Pubkey,
+    },
+    std::{
+        collections::HashSet,
+        sync::{Arc, PoisonError, RwLock},
+    },
+};
+
+/// The states of a writable account of a transaction, before and after its execution. An account
+/// that doesn't exist has zero lamports and no data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionAccountState {
+    pub pubkey: Pubkey,
+    pub pre: AccountSharedData,
+    pub post: AccountSharedData,
+}
+
+/// The owner programs of the writable accounts whose states are collected around the execution
+/// of each transaction; an empty set selects every writable account and None collects none. The
+/// programs are shared by the clones of a handle, so that an update is seen by every holder.
+#[derive(Clone, Debug, Default)]
+pub struct AccountStatesPrograms(Arc<RwLock<Option<Arc<HashSet<Pubkey>>>>>);
+
+impl AccountStatesPrograms {
+    pub fn new(programs: Option<HashSet<Pubkey>>) -> Self {
+        Self(Arc::new(RwLock::new(programs.map(Arc::new))))
+    }
+
+    /// The current programs, to be read once per batch of transactions
+    pub fn get(&self) -> Option<Arc<HashSet<Pubkey>>> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn set(&self, programs: Option<HashSet<Pubkey>>) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = programs.map(Arc::new);
+    }
+}
+
+/// The states of the writable accounts of each transaction of a batch, from the bank
+pub type TransactionWritableAccounts = Vec<Vec<(Pubkey, AccountSharedData)>>;
+
+pub fn collect_writable_accounts(
+    bank: &Bank,
+    batch: &TransactionBatch,
+) -> TransactionWritableAccounts {
+    let mut collect_time = Measure::start("collect_writable_accounts");
+    let accounts = batch
+        .sanitized_transactions()
+        .iter()
+        .map(|transaction| {
+            let message = transaction.message();
+            message
+                .account_keys()
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| message.is_writable(*index))
+                .map(|(_, pubkey)| (*pubkey, bank.get_account(pubkey).unwrap_or_default()))
+                .collect()
+        })
+        .collect();
+    collect_time.stop();
+    datapoint_debug!(
+        "collect_writable_accounts",
+        ("collect_time_us", collect_time.as_us(), i64),
+    );
+    accounts
+}
+
+/// Pairs the states of the writable accounts collected before and after the execution of a batch,
+/// keeping the accounts owned by one of `programs` before or after the execution. An empty set of
+/// programs keeps every writable account.
+pub fn pair_account_states(
+    pre_accounts: TransactionWritableAccounts,
+    post_accounts: TransactionWritableAccounts,
+    programs: &HashSet<Pubkey>,
+) -> Vec<Vec<TransactionAccountState>> {
+    let is_selected =
+        |account: &AccountSharedData| programs.is_empty() || programs.contains(account.owner());
+    pre_accounts
+        .into_iter()
+        .zip(post_accounts)
+        .map(|(pre_accounts, post_accounts)| {
+            pre_accounts
+                .into_iter()
+                .zip(post_accounts)
+                .filter(|((_, pre), (_, post))| is_selected(pre) || is_selected(post))
+                .map(|((pubkey, pre), (_, post))| TransactionAccountState { pubkey, pre, post })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, solana_sdk::system_program};
+
+    #[test]
+    fn test_pair_account_states() {
+        let program = Pubkey::new_unique();
+        let (created, updated, transfer) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let program_account = |lamports| AccountSharedData::new(lamports, 8, &program);
+        let system_account = |lamports| AccountSharedData::new(lamports, 0, &system_program::id());
+        let pre_accounts = vec![
+            vec![
+                (created, AccountSharedData::default()),
+                (transfer, system_account(5)),
+            ],
+            vec![(updated, program_account(1))],
+        ];
+        let post_accounts = vec![
+            vec![(created, program_account(2)), (transfer, system_account(3))],
+            vec![(updated, program_account(4))],
+        ];
+
+        assert_eq!(
+            pair_account_states(
+                pre_accounts.clone(),
+                post_accounts.clone(),
+                &HashSet::from([program])
+            ),
+            vec![
+                vec![TransactionAccountState {
+                    pubkey: created,
+                    pre: AccountSharedData::default(),
+                    post: program_account(2),
+                }],
+                vec![TransactionAccountState {
+                    pubkey: updated,
+                    pre: program_account(1),
+                    post: program_account(4),
+                }],
+            ]
+        );
+
+        let account_states = pair_account_states(pre_accounts, post_accounts, &HashSet::new());
+        assert_eq!(
+            account_states
+                .iter()
+                .map(|account_states| account_states.len())
+                .collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+    }
+}
@@ -19,6 +19,7 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         pubsub_config: config.pubsub_config.clone(),
         snapshot_config: config.snapshot_config.clone(),
         max_ledger_shreds: config.max_ledger_shreds,
+        transaction_history_retention_slots: config.transaction_history_retention_slots,
         broadcast_stage_type: config.broadcast_stage_type.clone(),
         turbine_disabled: config.turbine_disabled.clone(),
         enforce_ulimit_nofile: config.enforce_ulimit_nofile,
@@ -8,8 +8,9 @@ use {
     std::{
         io::Read,
         net::SocketAddr,
-        ops::{Index, IndexMut},
+        ops::{Index, IndexMut, Range},
         slice::{Iter, IterMut, SliceIndex},
+        time::{Duration, SystemTime, UNIX_EPOCH},
     },
 };
 
@@ -21,19 +22,30 @@ pub const NUM_RCVMMSGS: usize = 64;
 #[derive(Debug, Default, Clone, Serialize, Deserialize, AbiExample)]
 pub struct PacketBatch {
     packets: PinnedVec<Packet>,
+    /// When each packet was read off the socket, in microseconds since the UNIX epoch, or 0 if
+    /// unknown. Empty unless the receiver records receive times.
+    #[serde(skip)]
+    receive_times: Vec<u64>,
 }
 
 pub type PacketBatchRecycler = Recycler<PinnedVec<Packet>>;
 
 impl PacketBatch {
+    fn from_pinned_vec(packets: PinnedVec<Packet>) -> Self {
+        Self {
+            packets,
+            receive_times: Vec::new(),
+        }
+    }
+
     pub fn new(packets: Vec<Packet>) -> Self {
         let packets = PinnedVec::from_vec(packets);
-        Self { packets }
+        Self::from_pinned_vec(packets)
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
         let packets = PinnedVec::with_capacity(capacity);
-        Self { packets }
+        Self::from_pinned_vec(packets)
     }
 
     pub fn new_pinned_with_capacity(capacity: usize) -> Self {
@@ -49,7 +61,7 @@ impl PacketBatch {
     ) -> Self {
         let mut packets = recycler.allocate(name);
         packets.reserve(capacity);
-        Self { packets }
+        Self::from_pinned_vec(packets)
     }
 
     pub fn new_with_recycler(
@@ -59,7 +71,7 @@ impl PacketBatch {
     ) -> Self {
         let mut packets = recycler.allocate(name);
         packets.reserve_and_pin(capacity);
-        Self { packets }
+        Self::from_pinned_vec(packets)
     }
 
     pub fn new_with_recycler_data(
@@ -108,11 +120,38 @@ impl PacketBatch {
     }
 
     pub fn resize(&mut self, new_len: usize, value: Packet) {
-        self.packets.resize(new_len, value)
+        self.packets.resize(new_len, value);
+        if !self.receive_times.is_empty() {
+            self.receive_times.resize(new_len, 0);
+        }
     }
 
     pub fn truncate(&mut self, len: usize) {
         self.packets.truncate(len);
+        self.receive_times.truncate(len);
+    }
+
+    /// Records that the packets in `range` were read off the socket at `time`.
+    pub fn set_receive_time(&mut self, range: Range<usize>, time: SystemTime) {
+        let time = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        if self.receive_times.len() < self.len() {
+            self.receive_times.resize(self.len(), 0);
+        }
+        self.receive_times[range].fill(time);
+    }
+
+    /// When the packet at `index` was read off the socket, if the receiver recorded it.
+    pub fn receive_time(&self, index: usize) -> Option<SystemTime> {
+        if index >= self.len() {
+            return None;
+        }
+        self.receive_times
+            .get(index)
+            .filter(|time| **time != 0)
+            .map(|time| UNIX_EPOCH + Duration::from_micros(*time))
     }
 
     pub fn push(&mut self, packet: Packet) {
@@ -159,6 +198,7 @@ impl PacketBatch {
     ///   should specifically be initialized to known values.
     pub unsafe fn set_len(&mut self, new_len: usize) {
         self.packets.set_len(new_len);
+        self.receive_times.truncate(new_len);
     }
 }
 
@@ -274,6 +314,26 @@ mod tests {
         assert_eq!(rv[1].len(), 1);
     }
 
+    #[test]
+    fn test_receive_time() {
+        let mut batch = PacketBatch::new(vec![Packet::default(); 4]);
+        assert_eq!(batch.receive_time(0), None);
+        let first = UNIX_EPOCH + Duration::from_micros(1_000);
+        let second = UNIX_EPOCH + Duration::from_micros(2_000);
+        batch.set_receive_time(0..2, first);
+        batch.set_receive_time(2..3, second);
+        assert_eq!(batch.receive_time(0), Some(first));
+        assert_eq!(batch.receive_time(1), Some(first));
+        assert_eq!(batch.receive_time(2), Some(second));
+        assert_eq!(batch.receive_time(3), None);
+        assert_eq!(batch.receive_time(4), None);
+        batch.truncate(2);
+        assert_eq!(batch.receive_time(1), Some(first));
+        assert_eq!(batch.receive_time(2), None);
+        batch.resize(3, Packet::default());
+        assert_eq!(batch.receive_time(2), None);
+    }
+
     #[test]
     fn test_to_packets_pinning() {
         let recycler = PacketBatchRecycler::default();
@@ -0,0 +1,41 @@
+use {
+    solana_sdk::{instruction::InstructionError, transaction_context::TransactionReturnData},
+    std::{cell::RefCell, rc::Rc},
+};
+
+/// The outcome of a single program invocation, top-level or CPI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvocationRecord {
+    /// Index of the invocation in the transaction's instruction trace
+    pub index_in_trace: usize,
+    /// Compute units consumed by the invocation, including nested invocations
+    pub compute_units_consumed: u64,
+    /// Return data visible when the invocation completed
+    pub return_data: Option<TransactionReturnData>,
+    pub error: Option<InstructionError>,
+}
+
+/// Collects an `InvocationRecord` for every invocation processed by an `InvokeContext`
+#[derive(Debug, Default)]
+pub struct InvocationRecorder {
+    records: Vec<InvocationRecord>,
+}
+
+impl InvocationRecorder {
+    pub fn record(&mut self, record: InvocationRecord) {
+        self.records.push(record);
+    }
+
+    /// Records in the order the invocations completed
+    pub fn get_records(&self) -> &[InvocationRecord] {
+        self.records.as_slice()
+    }
+
+    pub fn new_ref() -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self::default()))
+    }
+
+    pub fn into_records(self) -> Vec<InvocationRecord> {
+        self.records
+    }
+}
@@ -2,6 +2,7 @@ use {
     crate::{
         compute_budget::ComputeBudget,
         ic_msg,
+        invocation_recorder::{InvocationRecord, InvocationRecorder},
         loaded_programs::{LoadedProgram, LoadedProgramType, LoadedProgramsForTxBatch},
         log_collector::LogCollector,
         stable_log,
@@ -28,6 +29,7 @@ use {
         stable_layout::stable_instruction::StableInstruction,
         transaction_context::{
             IndexOfAccount, InstructionAccount, TransactionAccount, TransactionContext,
+            TransactionReturnData,
         },
     },
     std::{
@@ -157,6 +159,7 @@ pub struct InvokeContext<'a> {
     pub transaction_context: &'a mut TransactionContext,
     sysvar_cache: &'a SysvarCache,
     log_collector: Option<Rc<RefCell<LogCollector>>>,
+    invocation_recorder: Option<Rc<RefCell<InvocationRecorder>>>,
     compute_budget: ComputeBudget,
     current_compute_budget: ComputeBudget,
     compute_meter: RefCell<u64>,
@@ -176,6 +179,7 @@ impl<'a> InvokeContext<'a> {
         transaction_context: &'a mut TransactionContext,
         sysvar_cache: &'a SysvarCache,
         log_collector: Option<Rc<RefCell<LogCollector>>>,
+        invocation_recorder: Option<Rc<RefCell<InvocationRecorder>>>,
         compute_budget: ComputeBudget,
         programs_loaded_for_tx_batch: &'a LoadedProgramsForTxBatch,
         programs_modified_by_tx: &'a mut LoadedProgramsForTxBatch,
@@ -187,6 +191,7 @@ impl<'a> InvokeContext<'a> {
             transaction_context,
             sysvar_cache,
             log_collector,
+            invocation_recorder,
             current_compute_budget: compute_budget,
             compute_budget,
             compute_meter: RefCell::new(compute_budget.compute_unit_limit),
@@ -427,11 +432,38 @@ impl<'a> InvokeContext<'a> {
         self.transaction_context
             .get_next_instruction_context()?
             .configure(program_indices, instruction_accounts, instruction_data);
+        let index_in_trace = self.transaction_context.get_instruction_trace_length();
         self.push()?;
-        self.process_executable_chain(compute_units_consumed, timings)
-            // MUST pop if and only if `push` succeeded, independent of `result`.
-            // Thus, the `.and()` instead of an `.and_then()`.
-            .and(self.pop())
+        let result = self.process_executable_chain(compute_units_consumed, timings);
+        self.record_invocation(index_in_trace, *compute_units_consumed, &result);
+        // MUST pop if and only if `push` succeeded, independent of `result`.
+        // Thus, the `.and()` instead of an `.and_then()`.
+        result.and(self.pop())
+    }
+
+    /// Hands the outcome of the current invocation to the InvocationRecorder, if any
+    fn record_invocation(
+        &self,
+        index_in_trace: usize,
+        compute_units_consumed: u64,
+        result: &Result<(), InstructionError>,
+    ) {
+        let Some(invocation_recorder) = self.invocation_recorder.as_ref() else {
+            return;
+        };
+        let (program_id, data) = self.transaction_context.get_return_data();
+        let return_data = (!data.is_empty()).then(|| TransactionReturnData {
+            program_id: *program_id,
+            data: data.to_vec(),
+        });
+        if let Ok(mut invocation_recorder) = invocation_recorder.try_borrow_mut() {
+            invocation_recorder.record(InvocationRecord {
+                index_in_trace,
+                compute_units_consumed,
+                return_data,
+                error: result.as_ref().err().cloned(),
+            });
+        }
     }
 
     /// Calls the instruction's program entrypoint method
@@ -665,6 +697,7 @@ macro_rules! with_mock_invoke_context {
             &mut $transaction_context,
             &sysvar_cache,
             Some(LogCollector::new_ref()),
+            None,
             compute_budget,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
@@ -1061,6 +1094,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_process_instruction_invocation_recorder() {
+        let callee_program_id = solana_sdk::pubkey::new_rand();
+        let owned_account = AccountSharedData::new(42, 1, &callee_program_id);
+        let not_owned_account = AccountSharedData::new(84, 1, &solana_sdk::pubkey::new_rand());
+        let readonly_account = AccountSharedData::new(168, 1, &solana_sdk::pubkey::new_rand());
+        let mut program_account = AccountSharedData::new(1, 1, &native_loader::id());
+        program_account.set_executable(true);
+        let transaction_accounts = vec![
+            (solana_sdk::pubkey::new_rand(), owned_account),
+            (solana_sdk::pubkey::new_rand(), not_owned_account),
+            (solana_sdk::pubkey::new_rand(), readonly_account),
+            (callee_program_id, program_account),
+        ];
+        let instruction_accounts = (0..4)
+            .map(|instruction_account_index| InstructionAccount {
+                index_in_transaction: instruction_account_index,
+                index_in_caller: instruction_account_index,
+                index_in_callee: instruction_account_index,
+                is_signer: false,
+                is_writable: instruction_account_index < 2,
+            })
+            .collect::<Vec<_>>();
+        with_mock_invoke_context!(invoke_context, transaction_context, transaction_accounts);
+        let mut programs_loaded_for_tx_batch = LoadedProgramsForTxBatch::default();
+        programs_loaded_for_tx_batch.replenish(
+            callee_program_id,
+            Arc::new(LoadedProgram::new_builtin(0, 1, MockBuiltin::vm)),
+        );
+        invoke_context.programs_loaded_for_tx_batch = &programs_loaded_for_tx_batch;
+        let invocation_recorder = InvocationRecorder::new_ref();
+        invoke_context.invocation_recorder = Some(invocation_recorder.clone());
+
+        let compute_units_to_consume = 10;
+        let expected_results = vec![Ok(()), Err(InstructionError::GenericError)];
+        for expected_result in expected_results.iter() {
+            let instruction_data = bincode::serialize(&MockInstruction::ConsumeComputeUnits {
+                compute_units_to_consume,
+                desired_result: expected_result.clone(),
+            })
+            .unwrap();
+            let result = invoke_context.process_instruction(
+                &instruction_data,
+                &instruction_accounts,
+                &[3],
+                &mut 0,
+                &mut ExecuteTimings::default(),
+            );
+            assert_eq!(&result, expected_result);
+        }
+
+        let records = invocation_recorder.borrow().get_records().to_vec();
+        assert_eq!(
+            records,
+            expected_results
+                .into_iter()
+                .enumerate()
+                .map(|(index_in_trace, result)| InvocationRecord {
+                    index_in_trace,
+                    compute_units_consumed: compute_units_to_consume
+                        .saturating_add(MOCK_BUILTIN_COMPUTE_UNIT_COST),
+                    return_data: None,
+                    error: result.err(),
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn test_invoke_context_compute_budget() {
         let transaction_accounts =
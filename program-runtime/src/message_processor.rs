@@ -1,6 +1,7 @@
 use {
     crate::{
         compute_budget::ComputeBudget,
+        invocation_recorder::InvocationRecorder,
         invoke_context::InvokeContext,
         loaded_programs::LoadedProgramsForTxBatch,
         log_collector::LogCollector,
@@ -47,6 +48,7 @@ impl MessageProcessor {
         program_indices: &[Vec<IndexOfAccount>],
         transaction_context: &mut TransactionContext,
         log_collector: Option<Rc<RefCell<LogCollector>>>,
+        invocation_recorder: Option<Rc<RefCell<InvocationRecorder>>>,
         programs_loaded_for_tx_batch: &LoadedProgramsForTxBatch,
         programs_modified_by_tx: &mut LoadedProgramsForTxBatch,
         feature_set: Arc<FeatureSet>,
@@ -61,6 +63,7 @@ impl MessageProcessor {
             transaction_context,
             sysvar_cache,
             log_collector,
+            invocation_recorder,
             compute_budget,
             programs_loaded_for_tx_batch,
             programs_modified_by_tx,
@@ -297,6 +300,7 @@ mod tests {
             &program_indices,
             &mut transaction_context,
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             Arc::new(FeatureSet::all_enabled()),
@@ -345,6 +349,7 @@ mod tests {
             &program_indices,
             &mut transaction_context,
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             Arc::new(FeatureSet::all_enabled()),
@@ -383,6 +388,7 @@ mod tests {
             &program_indices,
             &mut transaction_context,
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             Arc::new(FeatureSet::all_enabled()),
@@ -512,6 +518,7 @@ mod tests {
             &program_indices,
             &mut transaction_context,
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             Arc::new(FeatureSet::all_enabled()),
@@ -545,6 +552,7 @@ mod tests {
             &program_indices,
             &mut transaction_context,
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             Arc::new(FeatureSet::all_enabled()),
@@ -575,6 +583,7 @@ mod tests {
             &program_indices,
             &mut transaction_context,
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             Arc::new(FeatureSet::all_enabled()),
@@ -666,6 +675,7 @@ mod tests {
             &[vec![1], vec![2]],
             &mut transaction_context,
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             Arc::new(FeatureSet::all_enabled()),
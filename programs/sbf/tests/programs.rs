@@ -109,6 +109,7 @@ fn process_transaction_and_record_inner(
                 enable_cpi_recording: true,
                 enable_log_recording: true,
                 enable_return_data_recording: false,
+                enable_invocation_trace_recording: false,
             },
             &mut ExecuteTimings::default(),
             None,
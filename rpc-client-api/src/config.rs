@@ -2,10 +2,11 @@ use {
     crate::filter::RpcFilterType,
     solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig},
     solana_sdk::{
-        clock::{Epoch, Slot},
+        clock::{Epoch, Slot, UnixTimestamp},
         commitment_config::{CommitmentConfig, CommitmentLevel},
     },
     solana_transaction_status::{TransactionDetails, UiTransactionEncoding},
+    std::collections::HashMap,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -32,6 +33,32 @@ pub struct RpcSimulateTransactionAccountsConfig {
     pub addresses: Vec<String>,
 }
 
+/// Account fields to replace for a simulation. Fields that are not set keep the
+/// value of the existing account, or of an empty account if there is none.
+/// Program accounts, and accounts owned by a loader such as program data
+/// accounts, can not be overridden since programs run from the program cache.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountOverride {
+    pub lamports: Option<u64>,
+    pub owner: Option<String>, // base-58 encoded pubkey
+    pub data: Option<String>,  // base-64 encoded bytes
+    pub executable: Option<bool>,
+    pub rent_epoch: Option<Epoch>,
+}
+
+/// `Clock` sysvar fields to replace for a simulation. Fields that are not set
+/// keep the value of the simulation bank's clock.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcClockOverride {
+    pub slot: Option<Slot>,
+    pub epoch_start_timestamp: Option<UnixTimestamp>,
+    pub epoch: Option<Epoch>,
+    pub leader_schedule_epoch: Option<Epoch>,
+    pub unix_timestamp: Option<UnixTimestamp>,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcSimulateTransactionConfig {
@@ -46,6 +73,12 @@ pub struct RpcSimulateTransactionConfig {
     pub min_context_slot: Option<Slot>,
     #[serde(default)]
     pub inner_instructions: bool,
+    /// Return the tree of program invocations made by each simulated transaction. The trace stops
+    /// at invocation granularity; SBF instruction-level tracing is not supported
+    #[serde(default)]
+    pub trace: bool,
+    pub account_overrides: Option<HashMap<String, RpcAccountOverride>>,
+    pub clock_override: Option<RpcClockOverride>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -164,6 +197,10 @@ pub struct RpcProgramAccountsConfig {
     #[serde(flatten)]
     pub account_config: RpcAccountInfoConfig,
     pub with_context: Option<bool>,
+    /// Only return accounts whose latest version was written after this slot.
+    /// Accounts that were closed after this slot are not returned either, so
+    /// this can not be used on its own to detect deletions.
+    pub changed_since_slot: Option<Slot>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -225,6 +262,26 @@ pub struct RpcSignaturesForAddressConfig {
     pub min_context_slot: Option<Slot>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcTransactionStatusFilter {
+    Success,
+    Failed,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSignaturesForProgramConfig {
+    pub before: Option<String>, // Signature as base-58 string
+    pub until: Option<String>,  // Signature as base-58 string
+    pub limit: Option<usize>,
+    /// Only return the transactions that succeeded or failed
+    pub status: Option<RpcTransactionStatusFilter>,
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub min_context_slot: Option<Slot>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum RpcEncodingConfigWrapper<T> {
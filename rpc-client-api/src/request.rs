@@ -87,10 +87,12 @@ pub enum RpcRequest {
     )]
     GetSnapshotSlot,
     GetSignaturesForAddress,
+    GetSignaturesForProgram,
     GetSignatureStatuses,
     GetSlot,
     GetSlotLeader,
     GetSlotLeaders,
+    GetSlotMetadata,
     GetStorageTurn,
     GetStorageTurnRate,
     GetSlotsPerSegment,
@@ -113,6 +115,7 @@ pub enum RpcRequest {
     RequestAirdrop,
     SendTransaction,
     SimulateTransaction,
+    SimulateTransactions,
     SignVote,
 }
 
@@ -163,10 +166,12 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetHighestSnapshotSlot => "getHighestSnapshotSlot",
             RpcRequest::GetSnapshotSlot => "getSnapshotSlot",
             RpcRequest::GetSignaturesForAddress => "getSignaturesForAddress",
+            RpcRequest::GetSignaturesForProgram => "getSignaturesForProgram",
             RpcRequest::GetSignatureStatuses => "getSignatureStatuses",
             RpcRequest::GetSlot => "getSlot",
             RpcRequest::GetSlotLeader => "getSlotLeader",
             RpcRequest::GetSlotLeaders => "getSlotLeaders",
+            RpcRequest::GetSlotMetadata => "getSlotMetadata",
             RpcRequest::GetStakeActivation => "getStakeActivation",
             RpcRequest::GetStakeMinimumDelegation => "getStakeMinimumDelegation",
             RpcRequest::GetStorageTurn => "getStorageTurn",
@@ -189,6 +194,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::RequestAirdrop => "requestAirdrop",
             RpcRequest::SendTransaction => "sendTransaction",
             RpcRequest::SimulateTransaction => "simulateTransaction",
+            RpcRequest::SimulateTransactions => "simulateTransactions",
             RpcRequest::SignVote => "signVote",
         };
 
@@ -204,6 +210,8 @@ pub const MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT: usize = 1_000;
 pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
 pub const NUM_LARGEST_ACCOUNTS: usize = 20;
 pub const MAX_GET_PROGRAM_ACCOUNT_FILTERS: usize = 4;
+pub const MAX_SIMULATE_TRANSACTIONS: usize = 16;
+pub const MAX_SIMULATE_ACCOUNT_OVERRIDES: usize = 64;
 pub const MAX_GET_SLOT_LEADERS: usize = 5000;
 
 // Limit the length of the `epoch_credits` array for each validator in a `get_vote_accounts`
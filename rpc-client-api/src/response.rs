@@ -7,11 +7,12 @@ use {
         fee_calculator::{FeeCalculator, FeeRateGovernor},
         hash::Hash,
         inflation::Inflation,
+        instruction::InstructionError,
         transaction::{Result, TransactionError},
     },
     solana_transaction_status::{
-        ConfirmedTransactionStatusWithSignature, TransactionConfirmationStatus, UiConfirmedBlock,
-        UiInnerInstructions, UiTransactionReturnData,
+        ConfirmedTransactionStatusWithSignature, Rewards, TransactionConfirmationStatus,
+        UiConfirmedBlock, UiInnerInstructions, UiTransactionReturnData,
     },
     std::{collections::HashMap, fmt, net::SocketAddr, str::FromStr},
     thiserror::Error,
@@ -424,6 +425,22 @@ pub struct RpcSimulateTransactionResult {
     pub units_consumed: Option<u64>,
     pub return_data: Option<UiTransactionReturnData>,
     pub inner_instructions: Option<Vec<UiInnerInstructions>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Vec<RpcInvocationTraceNode>>,
+}
+
+/// A program invocation made by a simulated transaction, with the invocations it made
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInvocationTraceNode {
+    pub program_id: String,
+    pub accounts: Vec<String>,
+    pub stack_height: usize,
+    /// Includes the compute units consumed by inner invocations
+    pub compute_units_consumed: u64,
+    pub return_data: Option<UiTransactionReturnData>,
+    pub err: Option<InstructionError>,
+    pub inner_invocations: Vec<RpcInvocationTraceNode>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -555,6 +572,26 @@ pub struct RpcPrioritizationFee {
     pub prioritization_fee: u64,
 }
 
+/// Metadata of a rooted slot archived in long-term storage
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSlotMetadata {
+    pub slot: Slot,
+    pub parent_slot: Slot,
+    pub blockhash: String,
+    /// Unknown if the uploading node did not replay the slot
+    pub bank_hash: Option<String>,
+    /// Unknown if the uploading node did not have the leader schedule of the slot
+    pub leader: Option<String>,
+    pub tick_count: u64,
+    pub num_hashes: u64,
+    pub num_entries: u64,
+    pub num_transactions: u64,
+    pub rewards: Rewards,
+    pub block_time: Option<UnixTimestamp>,
+    pub block_height: Option<u64>,
+}
+
 #[cfg(test)]
 pub mod tests {
 
@@ -1412,6 +1412,48 @@ impl RpcClient {
         .await
     }
 
+    /// Simulates sending an ordered list of transactions.
+    ///
+    /// The transactions are simulated one after the other against the same
+    /// bank, and each one observes the account writes of those before it.
+    /// Simulation stops at the first transaction that fails, so the returned
+    /// list may be shorter than `transactions`.
+    ///
+    /// Programs are executed as they exist in the bank, so deployments and
+    /// upgrades are not visible to later transactions. The RPC node rejects
+    /// lists in which any transaction but the last invokes a program loader.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `simulateTransactions` RPC method.
+    pub async fn simulate_transactions_with_config(
+        &self,
+        transactions: &[impl SerializableTransaction],
+        config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<Vec<RpcSimulateTransactionResult>> {
+        let encoding = if let Some(encoding) = config.encoding {
+            encoding
+        } else {
+            self.default_cluster_transaction_encoding().await?
+        };
+        let commitment = config.commitment.unwrap_or_default();
+        let commitment = self.maybe_map_commitment(commitment).await?;
+        let config = RpcSimulateTransactionConfig {
+            encoding: Some(encoding),
+            commitment: Some(commitment),
+            ..config
+        };
+        let serialized_encoded = transactions
+            .iter()
+            .map(|transaction| serialize_and_encode(transaction, encoding))
+            .collect::<ClientResult<Vec<_>>>()?;
+        self.send(
+            RpcRequest::SimulateTransactions,
+            json!([serialized_encoded, config]),
+        )
+        .await
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
@@ -3058,6 +3100,29 @@ impl RpcClient {
         Ok(result)
     }
 
+    /// Get confirmed signatures for transactions that invoked a program,
+    /// directly or through a cross-program invocation, in descending ledger
+    /// order.
+    ///
+    /// The node must index transactions by program, see the
+    /// `--enable-program-signatures-index` validator flag.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getSignaturesForProgram` RPC
+    /// method.
+    pub async fn get_signatures_for_program_with_config(
+        &self,
+        program_id: &Pubkey,
+        config: RpcSignaturesForProgramConfig,
+    ) -> ClientResult<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        self.send(
+            RpcRequest::GetSignaturesForProgram,
+            json!([program_id.to_string(), config]),
+        )
+        .await
+    }
+
     #[deprecated(
         since = "1.7.0",
         note = "Please use RpcClient::get_signatures_for_address() instead"
@@ -4437,6 +4502,7 @@ impl RpcClient {
     ///         min_context_slot: Some(1234),
     ///     },
     ///     with_context: Some(false),
+    ///     changed_since_slot: None,
     /// };
     /// let accounts = rpc_client.get_program_accounts_with_config(
     ///     &alice.pubkey(),
@@ -4774,6 +4840,12 @@ impl RpcClient {
             .await
     }
 
+    /// Returns the metadata archived in long-term storage for a rooted slot,
+    /// including its bank hash and leader when they were known at upload.
+    pub async fn get_slot_metadata(&self, slot: Slot) -> ClientResult<Option<RpcSlotMetadata>> {
+        self.send(RpcRequest::GetSlotMetadata, json!([slot])).await
+    }
+
     pub async fn get_genesis_hash(&self) -> ClientResult<Hash> {
         let hash_str: String = self.send(RpcRequest::GetGenesisHash, Value::Null).await?;
         let hash = hash_str.parse().map_err(|_| {
@@ -1151,6 +1151,30 @@ impl RpcClient {
         )
     }
 
+    /// Simulates sending an ordered list of transactions.
+    ///
+    /// The transactions are simulated one after the other against the same
+    /// bank, and each one observes the account writes of those before it.
+    /// Simulation stops at the first transaction that fails, so the returned
+    /// list may be shorter than `transactions`.
+    ///
+    /// Programs are executed as they exist in the bank, so deployments and
+    /// upgrades are not visible to later transactions. The RPC node rejects
+    /// lists in which any transaction but the last invokes a program loader.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `simulateTransactions` RPC method.
+    pub fn simulate_transactions_with_config(
+        &self,
+        transactions: &[impl SerializableTransaction],
+        config: RpcSimulateTransactionConfig,
+    ) -> RpcResult<Vec<RpcSimulateTransactionResult>> {
+        self.invoke(
+            (self.rpc_client.as_ref()).simulate_transactions_with_config(transactions, config),
+        )
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
@@ -2517,6 +2541,27 @@ impl RpcClient {
         )
     }
 
+    /// Get confirmed signatures for transactions that invoked a program,
+    /// directly or through a cross-program invocation, in descending ledger
+    /// order.
+    ///
+    /// The node must index transactions by program, see the
+    /// `--enable-program-signatures-index` validator flag.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getSignaturesForProgram` RPC
+    /// method.
+    pub fn get_signatures_for_program_with_config(
+        &self,
+        program_id: &Pubkey,
+        config: RpcSignaturesForProgramConfig,
+    ) -> ClientResult<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        self.invoke(
+            (self.rpc_client.as_ref()).get_signatures_for_program_with_config(program_id, config),
+        )
+    }
+
     #[deprecated(
         since = "1.7.0",
         note = "Please use RpcClient::get_signatures_for_address() instead"
@@ -3616,6 +3661,7 @@ impl RpcClient {
     ///         min_context_slot: Some(1234),
     ///     },
     ///     with_context: Some(false),
+    ///     changed_since_slot: None,
     /// };
     /// let accounts = rpc_client.get_program_accounts_with_config(
     ///     &alice.pubkey(),
@@ -3780,6 +3826,12 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_first_available_block())
     }
 
+    /// Returns the metadata archived in long-term storage for a rooted slot,
+    /// including its bank hash and leader when they were known at upload.
+    pub fn get_slot_metadata(&self, slot: Slot) -> ClientResult<Option<RpcSlotMetadata>> {
+        self.invoke((self.rpc_client.as_ref()).get_slot_metadata(slot))
+    }
+
     pub fn get_genesis_hash(&self) -> ClientResult<Hash> {
         self.invoke((self.rpc_client.as_ref()).get_genesis_hash())
     }
@@ -4381,6 +4433,7 @@ mod tests {
                             min_context_slot: None,
                         },
                         with_context: None,
+                        changed_since_slot: None,
                     },
                 )
                 .unwrap();
@@ -4415,6 +4468,7 @@ mod tests {
                             min_context_slot: None,
                         },
                         with_context: Some(true),
+                        changed_since_slot: None,
                     },
                 )
                 .unwrap();
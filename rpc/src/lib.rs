@@ -4,6 +4,7 @@ pub mod max_slots;
 pub mod optimistically_confirmed_bank_tracker;
 pub mod parsed_token_accounts;
 pub mod rpc;
+mod rpc_batch;
 mod rpc_cache;
 pub mod rpc_completed_slots_service;
 pub mod rpc_health;
@@ -12,6 +13,7 @@ pub mod rpc_pubsub_service;
 pub mod rpc_service;
 pub mod rpc_subscription_tracker;
 pub mod rpc_subscriptions;
+pub mod slot_status_notifier_interface;
 pub mod transaction_notifier_interface;
 pub mod transaction_status_service;
 
@@ -3,6 +3,7 @@ use {
     crate::{
         max_slots::MaxSlots, optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
         parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_health::*,
+        transaction_status_service::TransactionLogsStorageConfig,
     },
     base64::{prelude::BASE64_STANDARD, Engine},
     bincode::{config::Options, serialize},
@@ -24,7 +25,7 @@ use {
     solana_faucet::faucet::request_airdrop_transaction,
     solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
     solana_ledger::{
-        blockstore::{Blockstore, SignatureInfosForAddress},
+        blockstore::{Blockstore, SignatureInfosForAddress, TransactionStatusFilter},
         blockstore_db::BlockstoreError,
         blockstore_meta::{PerfSample, PerfSampleV1, PerfSampleV2},
         get_tmp_ledger_path,
@@ -42,7 +43,8 @@ use {
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
             MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
             MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
+            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, MAX_SIMULATE_ACCOUNT_OVERRIDES,
+            MAX_SIMULATE_TRANSACTIONS, NUM_LARGEST_ACCOUNTS,
         },
         response::{Response as RpcResponse, *},
     },
@@ -57,7 +59,10 @@ use {
         snapshot_utils,
     },
     solana_sdk::{
-        account::{AccountSharedData, ReadableAccount},
+        account::{
+            from_account, to_account, AccountSharedData, ReadableAccount, WritableAccount,
+            PROGRAM_OWNERS,
+        },
         account_utils::StateMut,
         clock::{Slot, UnixTimestamp, MAX_RECENT_BLOCKHASHES},
         commitment_config::{CommitmentConfig, CommitmentLevel},
@@ -73,7 +78,7 @@ use {
         stake::state::{StakeActivationStatus, StakeStateV2},
         stake_history::StakeHistory,
         system_instruction,
-        sysvar::stake_history,
+        sysvar::{self, stake_history},
         transaction::{
             self, AddressLoader, MessageHash, SanitizedTransaction, TransactionError,
             VersionedTransaction, MAX_TX_ACCOUNT_LOCKS,
@@ -84,8 +89,9 @@ use {
         tpu_info::NullTpuInfo,
     },
     solana_stake_program,
-    solana_storage_bigtable::Error as StorageError,
+    solana_storage_bigtable::{Error as StorageError, LongTermLedgerStorage},
     solana_streamer::socket::SocketAddrSpace,
+    solana_svm::transaction_results::InvocationTraceNode,
     solana_transaction_status::{
         map_inner_instructions, BlockEncodingOptions, ConfirmedBlock,
         ConfirmedTransactionStatusWithSignature, ConfirmedTransactionWithStatusMeta,
@@ -104,6 +110,7 @@ use {
         collections::{BinaryHeap, HashMap, HashSet},
         convert::TryFrom,
         net::SocketAddr,
+        path::PathBuf,
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -141,6 +148,10 @@ fn is_finalized(
 pub struct JsonRpcConfig {
     pub enable_rpc_transaction_history: bool,
     pub enable_extended_tx_metadata_storage: bool,
+    /// Store transaction logs in their own blockstore column, inline with the status if `None`
+    pub transaction_logs_storage: Option<TransactionLogsStorageConfig>,
+    /// Index transactions by the programs they invoke, for `getSignaturesForProgram`
+    pub enable_program_signatures_index: bool,
     pub faucet_addr: Option<SocketAddr>,
     pub health_check_slot_distance: u64,
     pub rpc_bigtable_config: Option<RpcBigtableConfig>,
@@ -152,6 +163,8 @@ pub struct JsonRpcConfig {
     pub obsolete_v1_7_api: bool,
     pub rpc_scan_and_fix_roots: bool,
     pub max_request_body_size: Option<usize>,
+    /// Maximum number of calls in a batch request, unlimited if `None`
+    pub max_batch_size: Option<usize>,
     /// Disable the health check, used for tests and TestValidator
     pub disable_health_check: bool,
 }
@@ -173,6 +186,8 @@ pub struct RpcBigtableConfig {
     pub bigtable_app_profile_id: String,
     pub timeout: Option<Duration>,
     pub max_message_size: usize,
+    /// Serve and upload history from a local embedded store at this path instead of BigTable
+    pub local_storage_path: Option<PathBuf>,
 }
 
 impl Default for RpcBigtableConfig {
@@ -185,6 +200,7 @@ impl Default for RpcBigtableConfig {
             bigtable_app_profile_id,
             timeout: None,
             max_message_size: solana_storage_bigtable::DEFAULT_MAX_MESSAGE_SIZE,
+            local_storage_path: None,
         }
     }
 }
@@ -202,7 +218,7 @@ pub struct JsonRpcRequestProcessor {
     cluster_info: Arc<ClusterInfo>,
     genesis_hash: Hash,
     transaction_sender: Arc<Mutex<Sender<TransactionInfo>>>,
-    bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+    long_term_ledger_storage: Option<Arc<dyn LongTermLedgerStorage>>,
     optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
     largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
     max_slots: Arc<MaxSlots>,
@@ -216,7 +232,7 @@ impl Metadata for JsonRpcRequestProcessor {}
 impl JsonRpcRequestProcessor {
     pub fn clone_without_bigtable(&self) -> JsonRpcRequestProcessor {
         Self {
-            bigtable_ledger_storage: None, // Disable BigTable
+            long_term_ledger_storage: None, // Disable long-term ledger storage
             ..self.clone()
         }
     }
@@ -318,7 +334,7 @@ impl JsonRpcRequestProcessor {
         health: Arc<RpcHealth>,
         cluster_info: Arc<ClusterInfo>,
         genesis_hash: Hash,
-        bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+        long_term_ledger_storage: Option<Arc<dyn LongTermLedgerStorage>>,
         optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
         largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
         max_slots: Arc<MaxSlots>,
@@ -340,7 +356,7 @@ impl JsonRpcRequestProcessor {
                 cluster_info,
                 genesis_hash,
                 transaction_sender: Arc::new(Mutex::new(sender)),
-                bigtable_ledger_storage,
+                long_term_ledger_storage,
                 optimistically_confirmed_bank,
                 largest_accounts_cache,
                 max_slots,
@@ -414,7 +430,7 @@ impl JsonRpcRequestProcessor {
             cluster_info,
             genesis_hash,
             transaction_sender: Arc::new(Mutex::new(sender)),
-            bigtable_ledger_storage: None,
+            long_term_ledger_storage: None,
             optimistically_confirmed_bank,
             largest_accounts_cache: Arc::new(RwLock::new(LargestAccountsCache::new(30))),
             max_slots: Arc::new(MaxSlots::default()),
@@ -485,6 +501,7 @@ impl JsonRpcRequestProcessor {
         config: Option<RpcAccountInfoConfig>,
         mut filters: Vec<RpcFilterType>,
         with_context: bool,
+        changed_since_slot: Option<Slot>,
     ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>> {
         let RpcAccountInfoConfig {
             encoding,
@@ -498,13 +515,29 @@ impl JsonRpcRequestProcessor {
         })?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
         optimize_filters(&mut filters);
+        let scan_config = ScanConfig {
+            changed_since_slot,
+            ..ScanConfig::default()
+        };
         let keyed_accounts = {
             if let Some(owner) = get_spl_token_owner_filter(program_id, &filters) {
-                self.get_filtered_spl_token_accounts_by_owner(&bank, program_id, &owner, filters)?
+                self.get_filtered_spl_token_accounts_by_owner(
+                    &bank,
+                    program_id,
+                    &owner,
+                    filters,
+                    &scan_config,
+                )?
             } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
-                self.get_filtered_spl_token_accounts_by_mint(&bank, program_id, &mint, filters)?
+                self.get_filtered_spl_token_accounts_by_mint(
+                    &bank,
+                    program_id,
+                    &mint,
+                    filters,
+                    &scan_config,
+                )?
             } else {
-                self.get_filtered_program_accounts(&bank, program_id, filters)?
+                self.get_filtered_program_accounts(&bank, program_id, filters, &scan_config)?
             }
         };
         let accounts = if is_known_spl_token_id(program_id)
@@ -550,7 +583,7 @@ impl JsonRpcRequestProcessor {
         // Rewards for this epoch are found in the first confirmed block of the next epoch
         let first_slot_in_epoch = epoch_schedule.get_first_slot_in_epoch(epoch.saturating_add(1));
         if first_slot_in_epoch < first_available_block {
-            if self.bigtable_ledger_storage.is_some() {
+            if self.long_term_ledger_storage.is_some() {
                 return Err(RpcCustomError::LongTermStorageSlotSkipped {
                     slot: first_slot_in_epoch,
                 }
@@ -1125,9 +1158,9 @@ impl JsonRpcRequestProcessor {
                     Ok(encoded_block)
                 };
                 if result.is_err() {
-                    if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
+                    if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
                         let bigtable_result =
-                            bigtable_ledger_storage.get_confirmed_block(slot).await;
+                            long_term_ledger_storage.get_confirmed_block(slot).await;
                         self.check_bigtable_result(&bigtable_result)?;
                         return bigtable_result.ok().map(encode_block).transpose();
                     }
@@ -1225,8 +1258,8 @@ impl JsonRpcRequestProcessor {
             // If the starting slot is lower than what's available in blockstore assume the entire
             // [start_slot..end_slot] can be fetched from BigTable. This range should not ever run
             // into unfinalized confirmed blocks due to MAX_GET_CONFIRMED_BLOCKS_RANGE
-            if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                return bigtable_ledger_storage
+            if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+                return long_term_ledger_storage
                     .get_confirmed_blocks(start_slot, (end_slot - start_slot) as usize + 1) // increment limit by 1 to ensure returned range is inclusive of both start_slot and end_slot
                     .await
                     .map(|mut bigtable_blocks| {
@@ -1295,8 +1328,8 @@ impl JsonRpcRequestProcessor {
             // If the starting slot is lower than what's available in blockstore assume the entire
             // range can be fetched from BigTable. This range should not ever run into unfinalized
             // confirmed blocks due to MAX_GET_CONFIRMED_BLOCKS_RANGE
-            if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                return Ok(bigtable_ledger_storage
+            if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+                return Ok(long_term_ledger_storage
                     .get_confirmed_blocks(start_slot, limit)
                     .await
                     .unwrap_or_default());
@@ -1363,8 +1396,8 @@ impl JsonRpcRequestProcessor {
             let result = self.blockstore.get_rooted_block_time(slot);
             self.check_blockstore_root(&result, slot)?;
             if result.is_err() {
-                if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    let bigtable_result = bigtable_ledger_storage.get_confirmed_block(slot).await;
+                if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+                    let bigtable_result = long_term_ledger_storage.get_confirmed_block(slot).await;
                     self.check_bigtable_result(&bigtable_result)?;
                     return Ok(bigtable_result
                         .ok()
@@ -1456,8 +1489,8 @@ impl JsonRpcRequestProcessor {
                     })
                 {
                     Some(status)
-                } else if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                    bigtable_ledger_storage
+                } else if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+                    long_term_ledger_storage
                         .get_signature_status(&signature)
                         .await
                         .map(Some)
@@ -1565,8 +1598,8 @@ impl JsonRpcRequestProcessor {
                     }
                 }
                 None => {
-                    if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                        return bigtable_ledger_storage
+                    if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+                        return long_term_ledger_storage
                             .get_confirmed_transaction(&signature)
                             .await
                             .unwrap_or(None)
@@ -1588,7 +1621,7 @@ impl JsonRpcRequestProcessor {
         end_slot: Slot,
     ) -> Vec<Signature> {
         if self.config.enable_rpc_transaction_history {
-            // TODO: Add bigtable_ledger_storage support as a part of
+            // TODO: Add long_term_ledger_storage support as a part of
             // https://github.com/solana-labs/solana/pull/10928
             let end_slot = min(
                 end_slot,
@@ -1617,24 +1650,8 @@ impl JsonRpcRequestProcessor {
         check_is_at_least_confirmed(commitment)?;
 
         if self.config.enable_rpc_transaction_history {
-            let highest_super_majority_root = self
-                .block_commitment_cache
-                .read()
-                .unwrap()
-                .highest_super_majority_root();
-            let highest_slot = if commitment.is_confirmed() {
-                let confirmed_bank = self.get_bank_with_config(config)?;
-                confirmed_bank.slot()
-            } else {
-                let min_context_slot = config.min_context_slot.unwrap_or_default();
-                if highest_super_majority_root < min_context_slot {
-                    return Err(RpcCustomError::MinContextSlotNotReached {
-                        context_slot: highest_super_majority_root,
-                    }
-                    .into());
-                }
-                highest_super_majority_root
-            };
+            let (highest_super_majority_root, highest_slot) =
+                self.get_highest_signatures_slot(config)?;
 
             let SignatureInfosForAddress {
                 infos: mut results,
@@ -1645,30 +1662,11 @@ impl JsonRpcRequestProcessor {
                 .map_err(|err| Error::invalid_params(format!("{err}")))?;
 
             let map_results = |results: Vec<ConfirmedTransactionStatusWithSignature>| {
-                results
-                    .into_iter()
-                    .map(|x| {
-                        let mut item: RpcConfirmedTransactionStatusWithSignature = x.into();
-                        if item.slot <= highest_super_majority_root {
-                            item.confirmation_status =
-                                Some(TransactionConfirmationStatus::Finalized);
-                        } else {
-                            item.confirmation_status =
-                                Some(TransactionConfirmationStatus::Confirmed);
-                            if item.block_time.is_none() {
-                                let r_bank_forks = self.bank_forks.read().unwrap();
-                                item.block_time = r_bank_forks
-                                    .get(item.slot)
-                                    .map(|bank| bank.clock().unix_timestamp);
-                            }
-                        }
-                        item
-                    })
-                    .collect()
+                self.map_signature_infos(results, highest_super_majority_root)
             };
 
             if results.len() < limit {
-                if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
+                if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
                     let mut bigtable_before = before;
                     if !results.is_empty() {
                         limit -= results.len();
@@ -1679,7 +1677,7 @@ impl JsonRpcRequestProcessor {
                     // uploaded to long-term storage, modify the storage query to return all latest
                     // signatures to prevent erroring on RowNotFound. This can race with upload.
                     if found_before && bigtable_before.is_some() {
-                        match bigtable_ledger_storage
+                        match long_term_ledger_storage
                             .get_signature_status(&bigtable_before.unwrap())
                             .await
                         {
@@ -1694,7 +1692,7 @@ impl JsonRpcRequestProcessor {
                         }
                     }
 
-                    let bigtable_results = bigtable_ledger_storage
+                    let bigtable_results = long_term_ledger_storage
                         .get_confirmed_signatures_for_address(
                             &address,
                             bigtable_before.as_ref(),
@@ -1730,14 +1728,103 @@ impl JsonRpcRequestProcessor {
         }
     }
 
+    pub async fn get_signatures_for_program(
+        &self,
+        program_id: Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: usize,
+        status_filter: Option<TransactionStatusFilter>,
+        config: RpcContextConfig,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let commitment = config.commitment.unwrap_or_default();
+        check_is_at_least_confirmed(commitment)?;
+
+        if !(self.config.enable_rpc_transaction_history
+            && self.config.enable_program_signatures_index)
+        {
+            return Err(RpcCustomError::TransactionHistoryNotAvailable.into());
+        }
+        let (highest_super_majority_root, highest_slot) =
+            self.get_highest_signatures_slot(config)?;
+        // The index scan reads from rocksdb, so it runs off the event loop
+        let blockstore = self.blockstore.clone();
+        let SignatureInfosForAddress { infos, .. } = tokio::task::spawn_blocking(move || {
+            blockstore.get_confirmed_signatures_for_program(
+                program_id,
+                highest_slot,
+                before,
+                until,
+                limit,
+                status_filter,
+            )
+        })
+        .await
+        .map_err(|err| {
+            warn!("get_signatures_for_program failed: {err}");
+            Error::internal_error()
+        })?
+        .map_err(|err| Error::invalid_params(format!("{err}")))?;
+        Ok(self.map_signature_infos(infos, highest_super_majority_root))
+    }
+
+    /// Returns the highest super-majority root and the highest slot whose
+    /// transaction signatures are listed at the commitment of `config`
+    fn get_highest_signatures_slot(&self, config: RpcContextConfig) -> Result<(Slot, Slot)> {
+        let highest_super_majority_root = self
+            .block_commitment_cache
+            .read()
+            .unwrap()
+            .highest_super_majority_root();
+        let highest_slot = if config.commitment.unwrap_or_default().is_confirmed() {
+            let confirmed_bank = self.get_bank_with_config(config)?;
+            confirmed_bank.slot()
+        } else {
+            let min_context_slot = config.min_context_slot.unwrap_or_default();
+            if highest_super_majority_root < min_context_slot {
+                return Err(RpcCustomError::MinContextSlotNotReached {
+                    context_slot: highest_super_majority_root,
+                }
+                .into());
+            }
+            highest_super_majority_root
+        };
+        Ok((highest_super_majority_root, highest_slot))
+    }
+
+    fn map_signature_infos(
+        &self,
+        results: Vec<ConfirmedTransactionStatusWithSignature>,
+        highest_super_majority_root: Slot,
+    ) -> Vec<RpcConfirmedTransactionStatusWithSignature> {
+        results
+            .into_iter()
+            .map(|x| {
+                let mut item: RpcConfirmedTransactionStatusWithSignature = x.into();
+                if item.slot <= highest_super_majority_root {
+                    item.confirmation_status = Some(TransactionConfirmationStatus::Finalized);
+                } else {
+                    item.confirmation_status = Some(TransactionConfirmationStatus::Confirmed);
+                    if item.block_time.is_none() {
+                        let r_bank_forks = self.bank_forks.read().unwrap();
+                        item.block_time = r_bank_forks
+                            .get(item.slot)
+                            .map(|bank| bank.clock().unix_timestamp);
+                    }
+                }
+                item
+            })
+            .collect()
+    }
+
     pub async fn get_first_available_block(&self) -> Slot {
         let slot = self
             .blockstore
             .get_first_available_block()
             .unwrap_or_default();
 
-        if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-            let bigtable_slot = bigtable_ledger_storage
+        if let Some(long_term_ledger_storage) = &self.long_term_ledger_storage {
+            let bigtable_slot = long_term_ledger_storage
                 .get_first_available_block()
                 .await
                 .unwrap_or(None)
@@ -1750,6 +1837,33 @@ impl JsonRpcRequestProcessor {
         slot
     }
 
+    pub async fn get_slot_metadata(&self, slot: Slot) -> Result<Option<RpcSlotMetadata>> {
+        let Some(long_term_ledger_storage) = &self.long_term_ledger_storage else {
+            return Err(RpcCustomError::TransactionHistoryNotAvailable.into());
+        };
+        match long_term_ledger_storage.get_slot_metadata(slot).await {
+            Ok(slot_metadata) => Ok(Some(RpcSlotMetadata {
+                slot,
+                parent_slot: slot_metadata.parent_slot,
+                blockhash: slot_metadata.blockhash,
+                bank_hash: slot_metadata.bank_hash.map(|hash| hash.to_string()),
+                leader: slot_metadata.leader.map(|leader| leader.to_string()),
+                tick_count: slot_metadata.tick_count,
+                num_hashes: slot_metadata.num_hashes,
+                num_entries: slot_metadata.num_entries,
+                num_transactions: slot_metadata.num_transactions,
+                rewards: slot_metadata.rewards,
+                block_time: slot_metadata.block_time,
+                block_height: slot_metadata.block_height,
+            })),
+            Err(StorageError::SlotMetadataNotFound(_)) => Ok(None),
+            Err(err) => {
+                warn!("get_slot_metadata failed for slot {slot}: {err:?}");
+                Err(Error::internal_error())
+            }
+        }
+    }
+
     pub fn get_stake_activation(
         &self,
         pubkey: &Pubkey,
@@ -1892,9 +2006,13 @@ impl JsonRpcRequestProcessor {
 
         let mut token_balances =
             BinaryHeap::<Reverse<(u64, Pubkey)>>::with_capacity(NUM_LARGEST_ACCOUNTS);
-        for (address, account) in
-            self.get_filtered_spl_token_accounts_by_mint(&bank, &mint_owner, mint, vec![])?
-        {
+        for (address, account) in self.get_filtered_spl_token_accounts_by_mint(
+            &bank,
+            &mint_owner,
+            mint,
+            vec![],
+            &ScanConfig::default(),
+        )? {
             let amount = StateWithExtensions::<TokenAccount>::unpack(account.data())
                 .map(|account| account.base.amount)
                 .unwrap_or(0);
@@ -1957,6 +2075,7 @@ impl JsonRpcRequestProcessor {
             &token_program_id,
             owner,
             filters,
+            &ScanConfig::default(),
         )?;
         let accounts = if encoding == UiAccountEncoding::JsonParsed {
             get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
@@ -2004,11 +2123,22 @@ impl JsonRpcRequestProcessor {
         ];
         // Optional filter on Mint address, uses mint account index for scan
         let keyed_accounts = if let Some(mint) = mint {
-            self.get_filtered_spl_token_accounts_by_mint(&bank, &token_program_id, &mint, filters)?
+            self.get_filtered_spl_token_accounts_by_mint(
+                &bank,
+                &token_program_id,
+                &mint,
+                filters,
+                &ScanConfig::default(),
+            )?
         } else {
             // Filter on Token Account state
             filters.push(RpcFilterType::TokenAccountState);
-            self.get_filtered_program_accounts(&bank, &token_program_id, filters)?
+            self.get_filtered_program_accounts(
+                &bank,
+                &token_program_id,
+                filters,
+                &ScanConfig::default(),
+            )?
         };
         let accounts = if encoding == UiAccountEncoding::JsonParsed {
             get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
@@ -2032,6 +2162,7 @@ impl JsonRpcRequestProcessor {
         bank: &Bank,
         program_id: &Pubkey,
         mut filters: Vec<RpcFilterType>,
+        scan_config: &ScanConfig,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         optimize_filters(&mut filters);
         let filter_closure = |account: &AccountSharedData| {
@@ -2060,7 +2191,7 @@ impl JsonRpcRequestProcessor {
                         // accounts.
                         account.owner() == program_id && filter_closure(account)
                     },
-                    &ScanConfig::default(),
+                    scan_config,
                     bank.byte_limit_for_scans(),
                 )
                 .map_err(|e| RpcCustomError::ScanError {
@@ -2069,7 +2200,7 @@ impl JsonRpcRequestProcessor {
         } else {
             // this path does not need to provide a mb limit because we only want to support secondary indexes
             Ok(bank
-                .get_filtered_program_accounts(program_id, filter_closure, &ScanConfig::default())
+                .get_filtered_program_accounts(program_id, filter_closure, scan_config)
                 .map_err(|e| RpcCustomError::ScanError {
                     message: e.to_string(),
                 })?)
@@ -2083,6 +2214,7 @@ impl JsonRpcRequestProcessor {
         program_id: &Pubkey,
         owner_key: &Pubkey,
         mut filters: Vec<RpcFilterType>,
+        scan_config: &ScanConfig,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         // The by-owner accounts index checks for Token Account state and Owner address on
         // inclusion. However, due to the current AccountsDb implementation, an account may remain
@@ -2116,14 +2248,14 @@ impl JsonRpcRequestProcessor {
                                 .iter()
                                 .all(|filter_type| filter_type.allows(account))
                     },
-                    &ScanConfig::default(),
+                    scan_config,
                     bank.byte_limit_for_scans(),
                 )
                 .map_err(|e| RpcCustomError::ScanError {
                     message: e.to_string(),
                 })?)
         } else {
-            self.get_filtered_program_accounts(bank, program_id, filters)
+            self.get_filtered_program_accounts(bank, program_id, filters, scan_config)
         }
     }
 
@@ -2134,6 +2266,7 @@ impl JsonRpcRequestProcessor {
         program_id: &Pubkey,
         mint_key: &Pubkey,
         mut filters: Vec<RpcFilterType>,
+        scan_config: &ScanConfig,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         // The by-mint accounts index checks for Token Account state and Mint address on inclusion.
         // However, due to the current AccountsDb implementation, an account may remain in storage
@@ -2166,14 +2299,14 @@ impl JsonRpcRequestProcessor {
                                 .iter()
                                 .all(|filter_type| filter_type.allows(account))
                     },
-                    &ScanConfig::default(),
+                    scan_config,
                     bank.byte_limit_for_scans(),
                 )
                 .map_err(|e| RpcCustomError::ScanError {
                     message: e.to_string(),
                 })?)
         } else {
-            self.get_filtered_program_accounts(bank, program_id, filters)
+            self.get_filtered_program_accounts(bank, program_id, filters, scan_config)
         }
     }
 
@@ -3195,14 +3328,15 @@ pub mod rpc_accounts_scan {
                 program_id_str
             );
             let program_id = verify_pubkey(&program_id_str)?;
-            let (config, filters, with_context) = if let Some(config) = config {
+            let (config, filters, with_context, changed_since_slot) = if let Some(config) = config {
                 (
                     Some(config.account_config),
                     config.filters.unwrap_or_default(),
                     config.with_context.unwrap_or_default(),
+                    config.changed_since_slot,
                 )
             } else {
-                (None, vec![], false)
+                (None, vec![], false, None)
             };
             if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
                 return Err(Error::invalid_params(format!(
@@ -3212,7 +3346,13 @@ pub mod rpc_accounts_scan {
             for filter in &filters {
                 verify_filter(filter)?;
             }
-            meta.get_program_accounts(&program_id, config, filters, with_context)
+            meta.get_program_accounts(
+                &program_id,
+                config,
+                filters,
+                with_context,
+                changed_since_slot,
+            )
         }
 
         fn get_largest_accounts(
@@ -3350,6 +3490,14 @@ pub mod rpc_full {
             config: Option<RpcSimulateTransactionConfig>,
         ) -> Result<RpcResponse<RpcSimulateTransactionResult>>;
 
+        #[rpc(meta, name = "simulateTransactions")]
+        fn simulate_transactions(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateTransactionConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateTransactionResult>>>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -3402,9 +3550,24 @@ pub mod rpc_full {
             config: Option<RpcSignaturesForAddressConfig>,
         ) -> BoxFuture<Result<Vec<RpcConfirmedTransactionStatusWithSignature>>>;
 
+        #[rpc(meta, name = "getSignaturesForProgram")]
+        fn get_signatures_for_program(
+            &self,
+            meta: Self::Metadata,
+            program_id: String,
+            config: Option<RpcSignaturesForProgramConfig>,
+        ) -> BoxFuture<Result<Vec<RpcConfirmedTransactionStatusWithSignature>>>;
+
         #[rpc(meta, name = "getFirstAvailableBlock")]
         fn get_first_available_block(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>>;
 
+        #[rpc(meta, name = "getSlotMetadata")]
+        fn get_slot_metadata(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+        ) -> BoxFuture<Result<Option<RpcSlotMetadata>>>;
+
         #[rpc(meta, name = "getLatestBlockhash")]
         fn get_latest_blockhash(
             &self,
@@ -3699,6 +3862,7 @@ pub mod rpc_full {
                     units_consumed,
                     return_data,
                     inner_instructions: _, // Always `None` due to `enable_cpi_recording = false`
+                    invocation_trace: _,
                 } = preflight_bank.simulate_transaction(&transaction, false)
                 {
                     match err {
@@ -3718,6 +3882,7 @@ pub mod rpc_full {
                             units_consumed: Some(units_consumed),
                             return_data: return_data.map(|return_data| return_data.into()),
                             inner_instructions: None,
+                            trace: None,
                         },
                     }
                     .into());
@@ -3749,6 +3914,9 @@ pub mod rpc_full {
                 accounts: config_accounts,
                 min_context_slot,
                 inner_instructions: enable_cpi_recording,
+                trace: enable_invocation_tracing,
+                account_overrides,
+                clock_override,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -3779,81 +3947,114 @@ pub mod rpc_full {
                 verify_transaction(&transaction, &bank.feature_set)?;
             }
 
-            let TransactionSimulationResult {
-                result,
-                logs,
-                post_simulation_accounts,
-                units_consumed,
-                return_data,
-                inner_instructions,
-            } = bank.simulate_transaction(&transaction, enable_cpi_recording);
-
-            let account_keys = transaction.message().account_keys();
-            let number_of_accounts = account_keys.len();
-
-            let accounts = if let Some(config_accounts) = config_accounts {
-                let accounts_encoding = config_accounts
-                    .encoding
-                    .unwrap_or(UiAccountEncoding::Base64);
-
-                if accounts_encoding == UiAccountEncoding::Binary
-                    || accounts_encoding == UiAccountEncoding::Base58
-                {
-                    return Err(Error::invalid_params("base58 encoding not supported"));
-                }
+            let account_overrides =
+                get_simulation_account_overrides(bank, account_overrides, clock_override)?;
+            let simulation_result = bank
+                .simulate_transactions(
+                    std::slice::from_ref(&transaction),
+                    enable_cpi_recording,
+                    enable_invocation_tracing,
+                    account_overrides,
+                )
+                .pop()
+                .expect("simulation of a single transaction returns a single result");
+            let result = new_rpc_simulate_transaction_result(
+                bank,
+                &transaction,
+                simulation_result,
+                config_accounts.as_ref(),
+                &mut HashMap::new(),
+            )?;
+            Ok(new_response(bank, result))
+        }
 
-                if config_accounts.addresses.len() > number_of_accounts {
-                    return Err(Error::invalid_params(format!(
-                        "Too many accounts provided; max {number_of_accounts}"
-                    )));
-                }
+        fn simulate_transactions(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateTransactionConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateTransactionResult>>> {
+            debug!("simulate_transactions rpc request received");
+            let RpcSimulateTransactionConfig {
+                sig_verify,
+                replace_recent_blockhash,
+                commitment,
+                encoding,
+                accounts: config_accounts,
+                min_context_slot,
+                inner_instructions: enable_cpi_recording,
+                trace: enable_invocation_tracing,
+                account_overrides,
+                clock_override,
+            } = config.unwrap_or_default();
+            if data.is_empty() {
+                return Err(Error::invalid_params("No transactions provided"));
+            }
+            if data.len() > MAX_SIMULATE_TRANSACTIONS {
+                return Err(Error::invalid_params(format!(
+                    "Too many transactions provided; max {MAX_SIMULATE_TRANSACTIONS}"
+                )));
+            }
+            if replace_recent_blockhash && sig_verify {
+                return Err(Error::invalid_params(
+                    "sigVerify may not be used with replaceRecentBlockhash",
+                ));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                ))
+            })?;
 
-                if result.is_err() {
-                    Some(vec![None; config_accounts.addresses.len()])
-                } else {
-                    let mut post_simulation_accounts_map = HashMap::new();
-                    for (pubkey, data) in post_simulation_accounts {
-                        post_simulation_accounts_map.insert(pubkey, data);
+            let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?;
+            let transactions = data
+                .into_iter()
+                .map(|data| {
+                    let (_, mut unsanitized_tx) =
+                        decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+                    if replace_recent_blockhash {
+                        unsanitized_tx
+                            .message
+                            .set_recent_blockhash(bank.last_blockhash());
                     }
-
-                    Some(
-                        config_accounts
-                            .addresses
-                            .iter()
-                            .map(|address_str| {
-                                let pubkey = verify_pubkey(address_str)?;
-                                get_encoded_account(
-                                    bank,
-                                    &pubkey,
-                                    accounts_encoding,
-                                    None,
-                                    Some(&post_simulation_accounts_map),
-                                )
-                            })
-                            .collect::<Result<Vec<_>>>()?,
+                    let transaction = sanitize_transaction(unsanitized_tx, bank)?;
+                    if sig_verify {
+                        verify_transaction(&transaction, &bank.feature_set)?;
+                    }
+                    Ok(transaction)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            verify_simulation_chain(&transactions)?;
+
+            let account_overrides =
+                get_simulation_account_overrides(bank, account_overrides, clock_override)?;
+            // Accounts written by earlier transactions, so that requested post-simulation
+            // accounts reflect the whole chain rather than just the current transaction
+            let mut simulated_accounts = account_overrides.clone();
+            let results = bank
+                .simulate_transactions(
+                    &transactions,
+                    enable_cpi_recording,
+                    enable_invocation_tracing,
+                    account_overrides,
+                )
+                .into_iter()
+                .zip(transactions.iter())
+                .map(|(simulation_result, transaction)| {
+                    new_rpc_simulate_transaction_result(
+                        bank,
+                        transaction,
+                        simulation_result,
+                        config_accounts.as_ref(),
+                        &mut simulated_accounts,
                     )
-                }
-            } else {
-                None
-            };
-
-            let inner_instructions = inner_instructions.map(|info| {
-                map_inner_instructions(info)
-                    .map(|converted| UiInnerInstructions::parse(converted, &account_keys))
-                    .collect()
-            });
-
-            Ok(new_response(
-                bank,
-                RpcSimulateTransactionResult {
-                    err: result.err(),
-                    logs: Some(logs),
-                    accounts,
-                    units_consumed: Some(units_consumed),
-                    return_data: return_data.map(|return_data| return_data.into()),
-                    inner_instructions,
-                },
-            ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(new_response(bank, results))
         }
 
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot> {
@@ -3960,11 +4161,64 @@ pub mod rpc_full {
             }
         }
 
+        fn get_signatures_for_program(
+            &self,
+            meta: Self::Metadata,
+            program_id: String,
+            config: Option<RpcSignaturesForProgramConfig>,
+        ) -> BoxFuture<Result<Vec<RpcConfirmedTransactionStatusWithSignature>>> {
+            debug!(
+                "get_signatures_for_program rpc request received: {:?}",
+                program_id
+            );
+            let RpcSignaturesForProgramConfig {
+                before,
+                until,
+                limit,
+                status,
+                commitment,
+                min_context_slot,
+            } = config.unwrap_or_default();
+            let verification =
+                verify_and_parse_signatures_for_address_params(program_id, before, until, limit);
+            let status_filter = status.map(|status| match status {
+                RpcTransactionStatusFilter::Success => TransactionStatusFilter::Success,
+                RpcTransactionStatusFilter::Failed => TransactionStatusFilter::Failed,
+            });
+
+            match verification {
+                Err(err) => Box::pin(future::err(err)),
+                Ok((program_id, before, until, limit)) => Box::pin(async move {
+                    meta.get_signatures_for_program(
+                        program_id,
+                        before,
+                        until,
+                        limit,
+                        status_filter,
+                        RpcContextConfig {
+                            commitment,
+                            min_context_slot,
+                        },
+                    )
+                    .await
+                }),
+            }
+        }
+
         fn get_first_available_block(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>> {
             debug!("get_first_available_block rpc request received");
             Box::pin(async move { Ok(meta.get_first_available_block().await) })
         }
 
+        fn get_slot_metadata(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+        ) -> BoxFuture<Result<Option<RpcSlotMetadata>>> {
+            debug!("get_slot_metadata rpc request received: {:?}", slot);
+            Box::pin(async move { meta.get_slot_metadata(slot).await })
+        }
+
         fn get_inflation_reward(
             &self,
             meta: Self::Metadata,
@@ -4612,6 +4866,230 @@ fn sanitize_transaction(
         .map_err(|err| Error::invalid_params(format!("invalid transaction: {err}")))
 }
 
+/// Resolve the account and `Clock` overrides requested for a simulation against `bank`
+fn get_simulation_account_overrides(
+    bank: &Bank,
+    account_overrides: Option<HashMap<String, RpcAccountOverride>>,
+    clock_override: Option<RpcClockOverride>,
+) -> Result<HashMap<Pubkey, AccountSharedData>> {
+    let account_overrides = account_overrides.unwrap_or_default();
+    if account_overrides.len() > MAX_SIMULATE_ACCOUNT_OVERRIDES {
+        return Err(Error::invalid_params(format!(
+            "Too many account overrides provided; max {MAX_SIMULATE_ACCOUNT_OVERRIDES}"
+        )));
+    }
+
+    let mut overrides = account_overrides
+        .into_iter()
+        .map(|(address, account_override)| {
+            let pubkey = verify_pubkey(&address)?;
+            let RpcAccountOverride {
+                lamports,
+                owner,
+                data,
+                executable,
+                rent_epoch,
+            } = account_override;
+            let mut account = bank.get_account(&pubkey).unwrap_or_default();
+            if let Some(lamports) = lamports {
+                account.set_lamports(lamports);
+            }
+            if let Some(owner) = owner {
+                account.set_owner(verify_pubkey(&owner)?);
+            }
+            if let Some(data) = data {
+                let data = BASE64_STANDARD.decode(data).map_err(|err| {
+                    Error::invalid_params(format!(
+                        "invalid base64 data for account {address}: {err}"
+                    ))
+                })?;
+                account.set_data_from_slice(&data);
+            }
+            if let Some(executable) = executable {
+                account.set_executable(executable);
+            }
+            if let Some(rent_epoch) = rent_epoch {
+                account.set_rent_epoch(rent_epoch);
+            }
+            // Programs are executed from the program cache, which is not aware of overrides
+            let is_program_account = |account: &AccountSharedData| {
+                account.executable() || PROGRAM_OWNERS.contains(account.owner())
+            };
+            if bank
+                .get_account(&pubkey)
+                .is_some_and(|account| is_program_account(&account))
+                || is_program_account(&account)
+            {
+                return Err(Error::invalid_params(format!(
+                    "program account {address} can not be overridden"
+                )));
+            }
+            Ok((pubkey, account))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    if let Some(RpcClockOverride {
+        slot,
+        epoch_start_timestamp,
+        epoch,
+        leader_schedule_epoch,
+        unix_timestamp,
+    }) = clock_override
+    {
+        let mut clock_account = overrides
+            .remove(&sysvar::clock::id())
+            .or_else(|| bank.get_account(&sysvar::clock::id()))
+            .ok_or_else(Error::internal_error)?;
+        let invalid_clock_account =
+            || Error::invalid_params("invalid Clock sysvar account override");
+        let mut clock = from_account::<sysvar::clock::Clock, _>(&clock_account)
+            .ok_or_else(invalid_clock_account)?;
+        clock.slot = slot.unwrap_or(clock.slot);
+        clock.epoch_start_timestamp = epoch_start_timestamp.unwrap_or(clock.epoch_start_timestamp);
+        clock.epoch = epoch.unwrap_or(clock.epoch);
+        clock.leader_schedule_epoch = leader_schedule_epoch.unwrap_or(clock.leader_schedule_epoch);
+        clock.unix_timestamp = unix_timestamp.unwrap_or(clock.unix_timestamp);
+        to_account(&clock, &mut clock_account).ok_or_else(invalid_clock_account)?;
+        overrides.insert(sysvar::clock::id(), clock_account);
+    }
+
+    Ok(overrides)
+}
+
+/// Programs are executed from the program cache, which is not aware of the account writes of
+/// earlier transactions in a chained simulation, so only the last transaction may invoke a loader
+fn verify_simulation_chain(transactions: &[SanitizedTransaction]) -> Result<()> {
+    let Some((_last, transactions)) = transactions.split_last() else {
+        return Ok(());
+    };
+    for (index, transaction) in transactions.iter().enumerate() {
+        if transaction
+            .message()
+            .account_keys()
+            .iter()
+            .any(|pubkey| PROGRAM_OWNERS.contains(pubkey))
+        {
+            return Err(Error::invalid_params(format!(
+                "transaction {index} invokes a program loader; only the last transaction \
+                 may deploy, upgrade or close programs"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Convert a bank simulation result into its RPC representation. Successfully simulated accounts
+/// are added to `simulated_accounts`, which is then used to resolve the requested post-simulation
+/// accounts.
+fn new_rpc_simulate_transaction_result(
+    bank: &Bank,
+    transaction: &SanitizedTransaction,
+    simulation_result: TransactionSimulationResult,
+    config_accounts: Option<&RpcSimulateTransactionAccountsConfig>,
+    simulated_accounts: &mut HashMap<Pubkey, AccountSharedData>,
+) -> Result<RpcSimulateTransactionResult> {
+    let TransactionSimulationResult {
+        result,
+        logs,
+        post_simulation_accounts,
+        units_consumed,
+        return_data,
+        inner_instructions,
+        invocation_trace,
+    } = simulation_result;
+
+    let account_keys = transaction.message().account_keys();
+    let number_of_accounts = account_keys.len();
+
+    let accounts = if let Some(config_accounts) = config_accounts {
+        let accounts_encoding = config_accounts
+            .encoding
+            .unwrap_or(UiAccountEncoding::Base64);
+
+        if accounts_encoding == UiAccountEncoding::Binary
+            || accounts_encoding == UiAccountEncoding::Base58
+        {
+            return Err(Error::invalid_params("base58 encoding not supported"));
+        }
+
+        if config_accounts.addresses.len() > number_of_accounts {
+            return Err(Error::invalid_params(format!(
+                "Too many accounts provided; max {number_of_accounts}"
+            )));
+        }
+
+        if result.is_err() {
+            Some(vec![None; config_accounts.addresses.len()])
+        } else {
+            simulated_accounts.extend(post_simulation_accounts);
+            let simulated_accounts = &*simulated_accounts;
+
+            Some(
+                config_accounts
+                    .addresses
+                    .iter()
+                    .map(|address_str| {
+                        let pubkey = verify_pubkey(address_str)?;
+                        get_encoded_account(
+                            bank,
+                            &pubkey,
+                            accounts_encoding,
+                            None,
+                            Some(simulated_accounts),
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )
+        }
+    } else {
+        if result.is_ok() {
+            simulated_accounts.extend(post_simulation_accounts);
+        }
+        None
+    };
+
+    let inner_instructions = inner_instructions.map(|info| {
+        map_inner_instructions(info)
+            .map(|converted| UiInnerInstructions::parse(converted, &account_keys))
+            .collect()
+    });
+
+    Ok(RpcSimulateTransactionResult {
+        err: result.err(),
+        logs: Some(logs),
+        accounts,
+        units_consumed: Some(units_consumed),
+        return_data: return_data.map(|return_data| return_data.into()),
+        inner_instructions,
+        trace: invocation_trace.map(|invocation_trace| {
+            invocation_trace
+                .into_iter()
+                .map(new_rpc_invocation_trace_node)
+                .collect()
+        }),
+    })
+}
+
+fn new_rpc_invocation_trace_node(node: InvocationTraceNode) -> RpcInvocationTraceNode {
+    RpcInvocationTraceNode {
+        program_id: node.program_id.to_string(),
+        accounts: node
+            .accounts
+            .iter()
+            .map(|pubkey| pubkey.to_string())
+            .collect(),
+        stack_height: node.stack_height,
+        compute_units_consumed: node.compute_units_consumed,
+        return_data: node.return_data.map(|return_data| return_data.into()),
+        err: node.error,
+        inner_invocations: node
+            .inner_invocations
+            .into_iter()
+            .map(new_rpc_invocation_trace_node)
+            .collect(),
+    }
+}
+
 pub fn create_validator_exit(exit: Arc<AtomicBool>) -> Arc<RwLock<Exit>> {
     let mut validator_exit = Exit::default();
     validator_exit.register_exit(Box::new(move || exit.store(true, Ordering::Relaxed)));
@@ -4681,6 +5159,8 @@ pub fn populate_blockstore_for_tests(
             None,
             blockstore,
             false,
+            None,
+            true,
             Arc::new(AtomicBool::new(false)),
         );
 
@@ -4693,6 +5173,7 @@ pub fn populate_blockstore_for_tests(
             Some(
                 &solana_ledger::blockstore_processor::TransactionStatusSender {
                     sender: transaction_status_sender,
+                    account_states_programs: None,
                 },
             ),
             Some(&replay_vote_sender),
@@ -4746,6 +5227,7 @@ pub mod tests {
                 self,
                 state::{AddressLookupTable, LookupTableMeta},
             },
+            bpf_loader_upgradeable,
             clock::MAX_RECENT_BLOCKHASHES,
             compute_budget::ComputeBudgetInstruction,
             fee_calculator::{FeeRateGovernor, DEFAULT_BURN_PERCENT},
@@ -5936,6 +6418,49 @@ pub mod tests {
         assert_eq!(result.len(), 0);
     }
 
+    #[test]
+    fn test_rpc_get_program_accounts_changed_since_slot() {
+        let rpc = RpcHandler::start();
+        let program_id = Pubkey::new_unique();
+        let old_account_key = Pubkey::new_unique();
+        let new_account_key = Pubkey::new_unique();
+        let account = AccountSharedData::new(42, 0, &program_id);
+        rpc.working_bank().store_account(&old_account_key, &account);
+
+        let bank = rpc.advance_bank_to_confirmed_slot(1);
+        bank.store_account(&new_account_key, &account);
+
+        let get_program_account_keys = |config: Value| {
+            let request = create_test_request(
+                "getProgramAccounts",
+                Some(json!([program_id.to_string(), config])),
+            );
+            let result: Vec<RpcKeyedAccount> =
+                parse_success_result(rpc.handle_request_sync(request));
+            let mut keys = result
+                .into_iter()
+                .map(|keyed_account| keyed_account.pubkey)
+                .collect::<Vec<_>>();
+            keys.sort();
+            keys
+        };
+
+        let mut all_keys = vec![old_account_key.to_string(), new_account_key.to_string()];
+        all_keys.sort();
+        assert_eq!(
+            get_program_account_keys(json!({"commitment": "processed"})),
+            all_keys
+        );
+        assert_eq!(
+            get_program_account_keys(json!({"commitment": "processed", "changedSinceSlot": 0})),
+            vec![new_account_key.to_string()]
+        );
+        assert!(get_program_account_keys(
+            json!({"commitment": "processed", "changedSinceSlot": 1})
+        )
+        .is_empty());
+    }
+
     #[test]
     fn test_rpc_simulate_transaction() {
         let rpc = RpcHandler::start();
@@ -6592,6 +7117,238 @@ pub mod tests {
         let _ = io.handle_request_sync(&req, meta);
     }
 
+    #[test]
+    fn test_rpc_simulate_transaction_with_overrides() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        bank.freeze();
+
+        let alice = Keypair::new();
+        let bob_pubkey = Pubkey::new_unique();
+        let tx =
+            system_transaction::transfer(&alice, &bob_pubkey, rent_exempt_amount, recent_blockhash);
+        let tx_serialized_encoded = BASE64_STANDARD.encode(serialize(&tx).unwrap());
+
+        // alice does not exist in the bank
+        let request = create_test_request(
+            "simulateTransaction",
+            Some(json!([tx_serialized_encoded, {"encoding": "base64"}])),
+        );
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.err, Some(TransactionError::AccountNotFound));
+
+        let request = create_test_request(
+            "simulateTransaction",
+            Some(json!([
+                tx_serialized_encoded,
+                {
+                    "encoding": "base64",
+                    "accountOverrides": {
+                        alice.pubkey().to_string(): {"lamports": 10 * rent_exempt_amount},
+                    },
+                    "clockOverride": {"unixTimestamp": 1_234_567},
+                    "accounts": {"addresses": [alice.pubkey().to_string()]},
+                },
+            ])),
+        );
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.err, None);
+        let alice_account = result.value.accounts.unwrap()[0].clone().unwrap();
+        assert!(alice_account.lamports < 9 * rent_exempt_amount);
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+
+        let request = create_test_request(
+            "simulateTransaction",
+            Some(json!([
+                tx_serialized_encoded,
+                {
+                    "encoding": "base64",
+                    "accountOverrides": {alice.pubkey().to_string(): {"data": "not base64!"}},
+                },
+            ])),
+        );
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert!(message.starts_with(&format!(
+            "invalid base64 data for account {}",
+            alice.pubkey()
+        )));
+
+        // Programs run from the program cache, so their accounts can not be overridden
+        for account_override in [
+            json!({system_program::id().to_string(): {"lamports": 1}}),
+            json!({alice.pubkey().to_string(): {"executable": true}}),
+            json!({alice.pubkey().to_string(): {"owner": bpf_loader_upgradeable::id().to_string()}}),
+        ] {
+            let request = create_test_request(
+                "simulateTransaction",
+                Some(json!([
+                    tx_serialized_encoded,
+                    {"encoding": "base64", "accountOverrides": account_override},
+                ])),
+            );
+            let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(code, ErrorCode::InvalidParams.code());
+            assert!(message.ends_with("can not be overridden"), "{message}");
+        }
+    }
+
+    #[test]
+    fn test_rpc_simulate_transactions() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        bank.freeze();
+
+        let alice = Keypair::new();
+        let bob_pubkey = Pubkey::new_unique();
+        let encode = |tx: &Transaction| BASE64_STANDARD.encode(serialize(tx).unwrap());
+        let fund_alice = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &alice.pubkey(),
+            10 * rent_exempt_amount,
+            recent_blockhash,
+        );
+        let pay_bob =
+            system_transaction::transfer(&alice, &bob_pubkey, rent_exempt_amount, recent_blockhash);
+
+        let request = create_test_request(
+            "simulateTransactions",
+            Some(json!([
+                [encode(&fund_alice), encode(&pay_bob)],
+                {
+                    "sigVerify": true,
+                    "encoding": "base64",
+                    "accounts": {"addresses": [bob_pubkey.to_string()]},
+                },
+            ])),
+        );
+        let result: RpcResponse<Vec<RpcSimulateTransactionResult>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.len(), 2);
+        assert!(result.value.iter().all(|result| result.err.is_none()));
+        assert!(result.value[0].accounts.as_ref().unwrap()[0].is_none());
+        let bob_account = result.value[1].accounts.as_ref().unwrap()[0]
+            .as_ref()
+            .unwrap();
+        assert_eq!(bob_account.lamports, rent_exempt_amount);
+
+        // Without the funding transaction, alice cannot pay
+        let request = create_test_request(
+            "simulateTransactions",
+            Some(json!([[encode(&pay_bob)], {"encoding": "base64"}])),
+        );
+        let result: RpcResponse<Vec<RpcSimulateTransactionResult>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.len(), 1);
+        assert_eq!(result.value[0].err, Some(TransactionError::AccountNotFound));
+
+        let request = create_test_request(
+            "simulateTransactions",
+            Some(json!([
+                vec![encode(&pay_bob); MAX_SIMULATE_TRANSACTIONS + 1],
+                {"encoding": "base64"},
+            ])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            format!("Too many transactions provided; max {MAX_SIMULATE_TRANSACTIONS}"),
+        );
+        assert_eq!(response, expected);
+
+        // Program changes are not visible to later transactions, so only the last one may
+        // invoke a loader
+        let close_buffer = Transaction::new_signed_with_payer(
+            &[bpf_loader_upgradeable::close(
+                &Pubkey::new_unique(),
+                &alice.pubkey(),
+                &alice.pubkey(),
+            )],
+            Some(&alice.pubkey()),
+            &[&alice],
+            recent_blockhash,
+        );
+        let request = create_test_request(
+            "simulateTransactions",
+            Some(json!([
+                [encode(&fund_alice), encode(&close_buffer)],
+                {"encoding": "base64"},
+            ])),
+        );
+        let result: RpcResponse<Vec<RpcSimulateTransactionResult>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.len(), 2);
+        let request = create_test_request(
+            "simulateTransactions",
+            Some(json!([
+                [encode(&close_buffer), encode(&pay_bob)],
+                {"encoding": "base64"},
+            ])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            "transaction 0 invokes a program loader; only the last transaction may deploy, \
+             upgrade or close programs"
+                .to_string(),
+        );
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_trace() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        bank.freeze();
+
+        let bob_pubkey = Pubkey::new_unique();
+        let tx = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &bob_pubkey,
+            rent_exempt_amount,
+            recent_blockhash,
+        );
+        let tx_serialized_encoded = BASE64_STANDARD.encode(serialize(&tx).unwrap());
+
+        let request = create_test_request(
+            "simulateTransaction",
+            Some(json!([tx_serialized_encoded, {"encoding": "base64"}])),
+        );
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.trace, None);
+
+        let request = create_test_request(
+            "simulateTransaction",
+            Some(json!([tx_serialized_encoded, {"encoding": "base64", "trace": true}])),
+        );
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result.value.trace,
+            Some(vec![RpcInvocationTraceNode {
+                program_id: system_program::id().to_string(),
+                accounts: vec![
+                    rpc.mint_keypair.pubkey().to_string(),
+                    bob_pubkey.to_string()
+                ],
+                stack_height: 1,
+                compute_units_consumed: result.value.units_consumed.unwrap(),
+                return_data: None,
+                err: None,
+                inner_invocations: vec![],
+            }])
+        );
+    }
+
     #[test]
     fn test_rpc_get_signature_statuses() {
         let rpc = RpcHandler::start();
@@ -7625,6 +8382,76 @@ pub mod tests {
         assert_eq!(response, expected);
     }
 
+    #[test]
+    fn test_get_slot_metadata_without_long_term_storage() {
+        let rpc = RpcHandler::start();
+        let request = create_test_request("getSlotMetadata", Some(json!([1u64])));
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
+            String::from("Transaction history is not available from this node"),
+        );
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_get_signatures_for_program() {
+        // The blockstore is read on the blocking threads of a runtime
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _guard = runtime.enter();
+        let mut rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            enable_rpc_transaction_history: true,
+            enable_program_signatures_index: true,
+            ..JsonRpcConfig::default()
+        });
+        let signatures = rpc.create_test_transactions_and_populate_blockstore();
+
+        let request = create_test_request(
+            "getSignaturesForProgram",
+            Some(json!([system_program::id().to_string()])),
+        );
+        let result: Vec<RpcConfirmedTransactionStatusWithSignature> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let result_signatures: Vec<_> = result.iter().map(|info| &info.signature).collect();
+        assert_eq!(
+            result_signatures,
+            vec![&signatures[1].to_string(), &signatures[0].to_string()]
+        );
+
+        let request = create_test_request(
+            "getSignaturesForProgram",
+            Some(json!([system_program::id().to_string(), {"status": "failed"}])),
+        );
+        let result: Vec<RpcConfirmedTransactionStatusWithSignature> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].signature, signatures[1].to_string());
+        assert!(result[0].err.is_some());
+
+        let request = create_test_request(
+            "getSignaturesForProgram",
+            Some(json!([system_program::id().to_string(), {"status": "success"}])),
+        );
+        let result: Vec<RpcConfirmedTransactionStatusWithSignature> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].signature, signatures[0].to_string());
+        assert!(result[0].err.is_none());
+
+        // disable the program signatures index
+        rpc.meta.config.enable_program_signatures_index = false;
+        let request = create_test_request(
+            "getSignaturesForProgram",
+            Some(json!([system_program::id().to_string()])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
+            String::from("Transaction history is not available from this node"),
+        );
+        assert_eq!(response, expected);
+    }
+
     #[test]
     fn test_get_vote_accounts() {
         let rpc = RpcHandler::start();
@@ -0,0 +1,234 @@
+//! The `rpc_batch` module executes the calls of a JSON RPC batch request in parallel.
+
+use {
+    jsonrpc_core::{
+        futures::future::{self, Either, FutureExt},
+        middleware, BoxFuture, Call, Error, ErrorCode, MetaIoHandler, Metadata, Middleware, Output,
+        Request, Response, Version,
+    },
+    std::{future::Future, sync::Arc},
+    tokio::{runtime::Handle, sync::Semaphore},
+};
+
+/// A `Middleware` that dispatches every request to `handler`, running each call of a batch
+/// request on the blocking thread pool of `runtime` so that a batch takes about as long as its
+/// slowest call rather than the sum of them all. At most `max_parallel_calls` batch calls run at
+/// a time across all batches, and batches larger than `max_batch_size`, if set, are rejected as
+/// a whole.
+///
+/// Since the batch calls have to outlive the request, `handler` holds the rpc methods; the
+/// `MetaIoHandler` this middleware is installed in should not register any.
+pub struct RpcBatchMiddleware<T: Metadata> {
+    handler: Arc<MetaIoHandler<T>>,
+    runtime: Handle,
+    max_batch_size: Option<usize>,
+    parallel_calls: Arc<Semaphore>,
+}
+
+impl<T: Metadata> RpcBatchMiddleware<T> {
+    pub fn new(
+        handler: MetaIoHandler<T>,
+        runtime: Handle,
+        max_batch_size: Option<usize>,
+        max_parallel_calls: usize,
+    ) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            runtime,
+            max_batch_size,
+            parallel_calls: Arc::new(Semaphore::new(max_parallel_calls.max(1))),
+        }
+    }
+
+    fn handle_batch(&self, calls: Vec<Call>, meta: T) -> BoxFuture<Option<Response>> {
+        if let Some(max_batch_size) = self.max_batch_size {
+            if calls.len() > max_batch_size {
+                let error = Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: format!("Batch request too large; max {max_batch_size} calls"),
+                    data: None,
+                };
+                return future::ready(Some(Response::from(error, Some(Version::V2)))).boxed();
+            }
+        }
+
+        let outputs = calls.into_iter().map(|call| {
+            let id = match &call {
+                Call::MethodCall(method_call) => Some(method_call.id.clone()),
+                Call::Notification(_) => None,
+                Call::Invalid { id } => Some(id.clone()),
+            };
+            let handler = self.handler.clone();
+            let meta = meta.clone();
+            let runtime = self.runtime.clone();
+            let parallel_calls = self.parallel_calls.clone();
+            async move {
+                let internal_error = || {
+                    id.map(|id| Output::from(Err(Error::internal_error()), id, Some(Version::V2)))
+                };
+                // The permit is held until the call completes, including its asynchronous part
+                let Ok(_permit) = parallel_calls.acquire_owned().await else {
+                    return internal_error();
+                };
+                // Synchronous rpc methods do all of their work when the call is dispatched, so
+                // the dispatch itself has to happen off the event loop
+                match runtime
+                    .spawn_blocking(move || handler.handle_call(call, meta))
+                    .await
+                {
+                    Ok(output) => output.await,
+                    Err(err) => {
+                        warn!("rpc batch call failed: {err}");
+                        internal_error()
+                    }
+                }
+            }
+        });
+        future::join_all(outputs)
+            .map(|outputs| {
+                let outputs = outputs.into_iter().flatten().collect::<Vec<_>>();
+                (!outputs.is_empty()).then_some(Response::Batch(outputs))
+            })
+            .boxed()
+    }
+}
+
+impl<T: Metadata> Middleware<T> for RpcBatchMiddleware<T> {
+    type Future = BoxFuture<Option<Response>>;
+    type CallFuture = middleware::NoopCallFuture;
+
+    fn on_request<F, X>(&self, request: Request, meta: T, _next: F) -> Either<Self::Future, X>
+    where
+        F: Fn(Request, T) -> X + Send + Sync,
+        X: Future<Output = Option<Response>> + Send + 'static,
+    {
+        Either::Left(match request {
+            Request::Batch(calls) => self.handle_batch(calls, meta),
+            request => self.handler.handle_rpc_request(request, meta).boxed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        jsonrpc_core::{Params, Value},
+        std::{
+            sync::atomic::{AtomicUsize, Ordering},
+            time::{Duration, Instant},
+        },
+        tokio::runtime::Runtime,
+    };
+
+    const BATCH_SIZE: usize = 8;
+
+    fn new_io_handler(
+        runtime: &Runtime,
+        max_batch_size: Option<usize>,
+        max_parallel_calls: usize,
+    ) -> MetaIoHandler<(), RpcBatchMiddleware<()>> {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let mut handler = MetaIoHandler::default();
+        // Returns whether all calls of the batch were in flight at the same time, and the highest
+        // number of calls that were
+        handler.add_method_with_meta("waitForBatch", move |params: Params, _meta: ()| {
+            let num_in_flight = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            max_in_flight.fetch_max(num_in_flight, Ordering::SeqCst);
+            let start = Instant::now();
+            while in_flight.load(Ordering::SeqCst) < BATCH_SIZE
+                && start.elapsed() < Duration::from_millis(500)
+            {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            let all_in_flight = in_flight.load(Ordering::SeqCst) >= BATCH_SIZE;
+            let value = params.parse::<(u64,)>().map(|(value,)| value);
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            future::ready(value.map(|value| {
+                Value::Array(vec![
+                    Value::from(value),
+                    Value::Bool(all_in_flight),
+                    Value::from(max_in_flight.load(Ordering::SeqCst)),
+                ])
+            }))
+        });
+        handler.add_method_with_meta("echo", |params: Params, _meta: ()| {
+            future::ready(params.parse::<Value>())
+        });
+        MetaIoHandler::with_middleware(RpcBatchMiddleware::new(
+            handler,
+            runtime.handle().clone(),
+            max_batch_size,
+            max_parallel_calls,
+        ))
+    }
+
+    fn batch_request(size: usize) -> String {
+        let calls = (0..size)
+            .map(|i| {
+                format!(r#"{{"jsonrpc":"2.0","id":{i},"method":"waitForBatch","params":[{i}]}}"#)
+            })
+            .collect::<Vec<_>>();
+        format!("[{}]", calls.join(","))
+    }
+
+    #[test]
+    fn test_batch_calls_run_in_parallel() {
+        let runtime = Runtime::new().unwrap();
+        let io = new_io_handler(&runtime, None, BATCH_SIZE);
+
+        let response = runtime
+            .block_on(io.handle_request(&batch_request(BATCH_SIZE), ()))
+            .unwrap();
+        let response: Vec<serde_json::Value> = serde_json::from_str(&response).unwrap();
+        assert_eq!(response.len(), BATCH_SIZE);
+        for (i, output) in response.into_iter().enumerate() {
+            assert_eq!(output["id"], json!(i));
+            assert_eq!(output["result"][0], json!(i));
+            assert_eq!(output["result"][1], json!(true));
+        }
+    }
+
+    #[test]
+    fn test_batch_parallel_calls_limit() {
+        let runtime = Runtime::new().unwrap();
+        let io = new_io_handler(&runtime, Some(BATCH_SIZE), 2);
+
+        let response = runtime
+            .block_on(io.handle_request(&batch_request(BATCH_SIZE), ()))
+            .unwrap();
+        let response: Vec<serde_json::Value> = serde_json::from_str(&response).unwrap();
+        assert_eq!(response.len(), BATCH_SIZE);
+        for (i, output) in response.into_iter().enumerate() {
+            assert_eq!(output["id"], json!(i));
+            assert_eq!(output["result"][1], json!(false));
+            assert!(output["result"][2].as_u64().unwrap() <= 2);
+        }
+    }
+
+    #[test]
+    fn test_batch_size_limit() {
+        let runtime = Runtime::new().unwrap();
+        let io = new_io_handler(&runtime, Some(BATCH_SIZE - 1), BATCH_SIZE);
+
+        let response = runtime
+            .block_on(io.handle_request(&batch_request(BATCH_SIZE), ()))
+            .unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(
+            response["error"]["code"],
+            json!(ErrorCode::InvalidRequest.code())
+        );
+
+        // Single calls are still dispatched to the handler
+        let response = runtime
+            .block_on(io.handle_request(
+                r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":[7]}"#,
+                (),
+            ))
+            .unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["result"], json!([7]));
+    }
+}
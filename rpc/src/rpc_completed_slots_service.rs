@@ -1,7 +1,9 @@
 use {
-    crate::rpc_subscriptions::RpcSubscriptions,
+    crate::{
+        rpc_subscriptions::RpcSubscriptions, slot_status_notifier_interface::SlotStatusNotifier,
+    },
     crossbeam_channel::RecvTimeoutError,
-    solana_ledger::blockstore::CompletedSlotsReceiver,
+    solana_ledger::blockstore::{Blockstore, CompletedSlotsReceiver},
     solana_rpc_client_api::response::SlotUpdate,
     solana_sdk::timing::timestamp,
     std::{
@@ -20,7 +22,9 @@ pub struct RpcCompletedSlotsService;
 impl RpcCompletedSlotsService {
     pub fn spawn(
         completed_slots_receiver: CompletedSlotsReceiver,
+        blockstore: Arc<Blockstore>,
         rpc_subscriptions: Arc<RpcSubscriptions>,
+        slot_status_notifier: Option<SlotStatusNotifier>,
         exit: Arc<AtomicBool>,
     ) -> JoinHandle<()> {
         Builder::new()
@@ -41,6 +45,17 @@ impl RpcCompletedSlotsService {
                     }
                     Ok(slots) => {
                         for slot in slots {
+                            if let Some(slot_status_notifier) = &slot_status_notifier {
+                                let parent = blockstore
+                                    .meta(slot)
+                                    .ok()
+                                    .flatten()
+                                    .and_then(|meta| meta.parent_slot);
+                                slot_status_notifier
+                                    .read()
+                                    .unwrap()
+                                    .notify_completed(slot, parent);
+                            }
                             rpc_subscriptions.notify_slot_update(SlotUpdate::Completed {
                                 slot,
                                 timestamp: timestamp(),
@@ -10,6 +10,7 @@ use {
             rpc_deprecated_v1_7::*, rpc_deprecated_v1_9::*, rpc_full::*, rpc_minimal::*,
             rpc_obsolete_v1_7::*, *,
         },
+        rpc_batch::RpcBatchMiddleware,
         rpc_cache::LargestAccountsCache,
         rpc_health::*,
     },
@@ -41,7 +42,7 @@ use {
         native_token::lamports_to_sol,
     },
     solana_send_transaction_service::send_transaction_service::{self, SendTransactionService},
-    solana_storage_bigtable::CredentialType,
+    solana_storage_bigtable::{CredentialType, LongTermLedgerStorage},
     std::{
         net::SocketAddr,
         path::{Path, PathBuf},
@@ -401,35 +402,50 @@ impl JsonRpcService {
 
         let exit_bigtable_ledger_upload_service = Arc::new(AtomicBool::new(false));
 
-        let (bigtable_ledger_storage, _bigtable_ledger_upload_service) =
+        let (long_term_ledger_storage, _bigtable_ledger_upload_service) =
             if let Some(RpcBigtableConfig {
                 enable_bigtable_ledger_upload,
                 ref bigtable_instance_name,
                 ref bigtable_app_profile_id,
                 timeout,
                 max_message_size,
+                ref local_storage_path,
             }) = config.rpc_bigtable_config
             {
-                let bigtable_config = solana_storage_bigtable::LedgerStorageConfig {
-                    read_only: !enable_bigtable_ledger_upload,
-                    timeout,
-                    credential_type: CredentialType::Filepath(None),
-                    instance_name: bigtable_instance_name.clone(),
-                    app_profile_id: bigtable_app_profile_id.clone(),
-                    max_message_size,
+                let ledger_storage: solana_storage_bigtable::Result<
+                    Arc<dyn LongTermLedgerStorage>,
+                > = if let Some(local_storage_path) = local_storage_path {
+                    solana_storage_bigtable::LocalLedgerStorage::new_local(
+                        local_storage_path,
+                        !enable_bigtable_ledger_upload,
+                    )
+                    .map(|ledger_storage| Arc::new(ledger_storage) as _)
+                } else {
+                    let bigtable_config = solana_storage_bigtable::LedgerStorageConfig {
+                        read_only: !enable_bigtable_ledger_upload,
+                        timeout,
+                        credential_type: CredentialType::Filepath(None),
+                        instance_name: bigtable_instance_name.clone(),
+                        app_profile_id: bigtable_app_profile_id.clone(),
+                        max_message_size,
+                        emulator_endpoint: None,
+                    };
+                    runtime
+                        .block_on(solana_storage_bigtable::LedgerStorage::new_with_config(
+                            bigtable_config,
+                        ))
+                        .map(|ledger_storage| Arc::new(ledger_storage) as _)
                 };
-                runtime
-                    .block_on(solana_storage_bigtable::LedgerStorage::new_with_config(
-                        bigtable_config,
-                    ))
-                    .map(|bigtable_ledger_storage| {
-                        info!("BigTable ledger storage initialized");
+                ledger_storage
+                    .map(|long_term_ledger_storage| {
+                        info!("Long-term ledger storage initialized");
 
                         let bigtable_ledger_upload_service = if enable_bigtable_ledger_upload {
                             Some(Arc::new(BigTableUploadService::new_with_config(
                                 runtime.clone(),
-                                bigtable_ledger_storage.clone(),
+                                long_term_ledger_storage.clone(),
                                 blockstore.clone(),
+                                leader_schedule_cache.clone(),
                                 block_commitment_cache.clone(),
                                 max_complete_transaction_status_slot.clone(),
                                 max_complete_rewards_slot.clone(),
@@ -441,12 +457,12 @@ impl JsonRpcService {
                         };
 
                         (
-                            Some(bigtable_ledger_storage),
+                            Some(long_term_ledger_storage),
                             bigtable_ledger_upload_service,
                         )
                     })
                     .unwrap_or_else(|err| {
-                        error!("Failed to initialize BigTable ledger storage: {:?}", err);
+                        error!("Failed to initialize long-term ledger storage: {:?}", err);
                         (None, None)
                     })
             } else {
@@ -458,6 +474,7 @@ impl JsonRpcService {
         let max_request_body_size = config
             .max_request_body_size
             .unwrap_or(MAX_REQUEST_BODY_SIZE);
+        let max_batch_size = config.max_batch_size;
         let (request_processor, receiver) = JsonRpcRequestProcessor::new(
             config,
             snapshot_config.clone(),
@@ -468,7 +485,7 @@ impl JsonRpcService {
             health.clone(),
             cluster_info.clone(),
             genesis_hash,
-            bigtable_ledger_storage,
+            long_term_ledger_storage,
             optimistically_confirmed_bank,
             largest_accounts_cache,
             max_slots,
@@ -516,6 +533,12 @@ impl JsonRpcService {
                 if obsolete_v1_7_api {
                     io.extend_with(rpc_obsolete_v1_7::ObsoleteV1_7Impl.to_delegate());
                 }
+                let io = MetaIoHandler::with_middleware(RpcBatchMiddleware::new(
+                    io,
+                    runtime.handle().clone(),
+                    max_batch_size,
+                    rpc_threads,
+                ));
 
                 let request_middleware = RpcRequestMiddleware::new(
                     ledger_path,
@@ -603,17 +626,17 @@ mod tests {
         solana_runtime::bank::Bank,
         solana_sdk::{
             genesis_config::{ClusterType, DEFAULT_GENESIS_ARCHIVE},
-            signature::Signer,
+            signature::{Keypair, Signer},
         },
         std::{
             io::Write,
             net::{IpAddr, Ipv4Addr},
         },
+        tempfile::TempDir,
         tokio::runtime::Runtime,
     };
 
-    #[test]
-    fn test_rpc_new() {
+    fn start_test_rpc_service() -> (JsonRpcService, SocketAddr, Keypair, TempDir) {
         let GenesisConfigInfo {
             genesis_config,
             mint_keypair,
@@ -635,7 +658,7 @@ mod tests {
         let optimistically_confirmed_bank =
             OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
         let connection_cache = Arc::new(ConnectionCache::new("connection_cache_test"));
-        let mut rpc_service = JsonRpcService::new(
+        let rpc_service = JsonRpcService::new(
             rpc_addr,
             JsonRpcConfig::default(),
             None,
@@ -664,6 +687,12 @@ mod tests {
             Arc::new(PrioritizationFeeCache::default()),
         )
         .expect("assume successful JsonRpcService start");
+        (rpc_service, rpc_addr, mint_keypair, ledger_path)
+    }
+
+    #[test]
+    fn test_rpc_new() {
+        let (mut rpc_service, _rpc_addr, mint_keypair, _ledger_path) = start_test_rpc_service();
         let thread = rpc_service.thread_hdl.thread();
         assert_eq!(thread.name().unwrap(), "solJsonRpcSvc");
 
@@ -679,6 +708,43 @@ mod tests {
         rpc_service.join().unwrap();
     }
 
+    #[test]
+    fn test_rpc_http2_prior_knowledge() {
+        let (rpc_service, rpc_addr, mint_keypair, _ledger_path) = start_test_rpc_service();
+        let url = format!("http://127.0.0.1:{}", rpc_addr.port());
+        let body = format!(
+            r#"[{{"jsonrpc":"2.0","id":1,"method":"getBalance","params":["{}"]}},{{"jsonrpc":"2.0","id":2,"method":"getSlot"}}]"#,
+            mint_keypair.pubkey(),
+        );
+
+        let runtime = Runtime::new().unwrap();
+        let (version, response) = runtime.block_on(async move {
+            // Cleartext HTTP/2 without an upgrade from HTTP/1.1
+            let client = hyper::Client::builder()
+                .http2_only(true)
+                .build_http::<hyper::Body>();
+            let request = hyper::Request::post(url)
+                .header(hyper::header::CONTENT_TYPE, "application/json")
+                .body(hyper::Body::from(body))
+                .unwrap();
+            let response = client.request(request).await.unwrap();
+            assert!(response.status().is_success());
+            let version = response.version();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            (
+                version,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            )
+        });
+        assert_eq!(version, hyper::Version::HTTP_2);
+        assert_eq!(response[0]["id"], 1);
+        assert_eq!(response[0]["result"]["value"], 10_000);
+        assert_eq!(response[1]["id"], 2);
+        assert_eq!(response[1]["result"], 0);
+
+        rpc_service.join().unwrap();
+    }
+
     fn create_bank_forks() -> Arc<RwLock<BankForks>> {
         let GenesisConfigInfo {
             mut genesis_config, ..
@@ -0,0 +1,30 @@
+use {
+    solana_sdk::{clock::Slot, hash::Hash},
+    std::sync::{Arc, RwLock},
+};
+
+pub trait SlotStatusNotifierInterface {
+    /// Notified when a slot is optimistically confirmed
+    fn notify_slot_confirmed(&self, slot: Slot, parent: Option<Slot>);
+
+    /// Notified when a slot is marked frozen.
+    fn notify_slot_processed(&self, slot: Slot, parent: Option<Slot>);
+
+    /// Notified when a slot is rooted.
+    fn notify_slot_rooted(&self, slot: Slot, parent: Option<Slot>);
+
+    /// Notified when a bank is created for a slot, either to replay it or to produce it as leader.
+    fn notify_created_bank(&self, slot: Slot, parent: Slot);
+
+    /// Notified when the blockstore has received all the shreds of a slot, with its parent if
+    /// the blockstore knows it.
+    fn notify_completed(&self, slot: Slot, parent: Option<Slot>);
+
+    /// Notified when the bank of a slot is frozen, with its bank hash.
+    fn notify_frozen(&self, slot: Slot, parent: Slot, bank_hash: &Hash);
+
+    /// Notified when replay marks a slot dead.
+    fn notify_slot_dead(&self, slot: Slot, parent: Slot, error: String);
+}
+
+pub type SlotStatusNotifier = Arc<RwLock<dyn SlotStatusNotifierInterface + Sync + Send>>;
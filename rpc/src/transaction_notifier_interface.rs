@@ -1,4 +1,5 @@
 use {
+    solana_ledger::transaction_account_states::{AccountStatesPrograms, TransactionAccountState},
     solana_sdk::{clock::Slot, signature::Signature, transaction::SanitizedTransaction},
     solana_transaction_status::TransactionStatusMeta,
     std::sync::Arc,
@@ -12,7 +13,15 @@ pub trait TransactionNotifier {
         signature: &Signature,
         transaction_status_meta: &TransactionStatusMeta,
         transaction: &SanitizedTransaction,
+        account_states: &[TransactionAccountState],
     );
+
+    /// Owner programs of the writable accounts whose states before and after each transaction
+    /// are passed to `notify_transaction`, updated by the notifier as they change. Default is
+    /// None -- the account states are never collected.
+    fn account_states_programs(&self) -> Option<AccountStatesPrograms> {
+        None
+    }
 }
 
 pub type TransactionNotifierArc = Arc<dyn TransactionNotifier + Sync + Send>;
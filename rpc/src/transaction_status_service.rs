@@ -6,9 +6,11 @@ use {
         blockstore::Blockstore,
         blockstore_processor::{TransactionStatusBatch, TransactionStatusMessage},
     },
+    solana_sdk::clock::Slot,
     solana_svm::transaction_results::{DurableNonceFee, TransactionExecutionDetails},
     solana_transaction_status::{
-        extract_and_fmt_memos, map_inner_instructions, Reward, TransactionStatusMeta,
+        extract_and_fmt_memos, invoked_program_ids, map_inner_instructions, Reward,
+        TransactionStatusMeta,
     },
     std::{
         sync::{
@@ -20,11 +22,29 @@ use {
     },
 };
 
+// How far the retention cutoff of the transaction logs column has to advance
+// before the expired logs are purged again
+const TRANSACTION_LOGS_PURGE_INTERVAL: u64 = 512;
+
+/// Settings for storing transaction log messages in the separate
+/// `TransactionLogs` blockstore column instead of inline with the status meta
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransactionLogsStorageConfig {
+    /// Maximum number of bytes of log messages stored per transaction. This is
+    /// applied on top of the limit used while executing the transaction; `None`
+    /// stores all of the collected messages.
+    pub bytes_limit: Option<usize>,
+    /// Number of slots behind the latest root for which logs are kept; `None`
+    /// keeps them for as long as the rest of the ledger.
+    pub retention_slots: Option<u64>,
+}
+
 pub struct TransactionStatusService {
     thread_hdl: JoinHandle<()>,
 }
 
 impl TransactionStatusService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         write_transaction_status_receiver: Receiver<TransactionStatusMessage>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
@@ -32,30 +52,41 @@ impl TransactionStatusService {
         transaction_notifier: Option<TransactionNotifierArc>,
         blockstore: Arc<Blockstore>,
         enable_extended_tx_metadata_storage: bool,
+        transaction_logs_storage: Option<TransactionLogsStorageConfig>,
+        enable_program_signatures_index: bool,
         exit: Arc<AtomicBool>,
     ) -> Self {
         let thread_hdl = Builder::new()
             .name("solTxStatusWrtr".to_string())
-            .spawn(move || loop {
-                if exit.load(Ordering::Relaxed) {
-                    break;
-                }
+            .spawn(move || {
+                let mut last_transaction_logs_purge_slot = 0;
+                loop {
+                    if exit.load(Ordering::Relaxed) {
+                        break;
+                    }
 
-                if let Err(RecvTimeoutError::Disconnected) = Self::write_transaction_status_batch(
-                    &write_transaction_status_receiver,
-                    &max_complete_transaction_status_slot,
-                    enable_rpc_transaction_history,
-                    transaction_notifier.clone(),
-                    &blockstore,
-                    enable_extended_tx_metadata_storage,
-                ) {
-                    break;
+                    if let Err(RecvTimeoutError::Disconnected) =
+                        Self::write_transaction_status_batch(
+                            &write_transaction_status_receiver,
+                            &max_complete_transaction_status_slot,
+                            enable_rpc_transaction_history,
+                            transaction_notifier.clone(),
+                            &blockstore,
+                            enable_extended_tx_metadata_storage,
+                            transaction_logs_storage.as_ref(),
+                            enable_program_signatures_index,
+                            &mut last_transaction_logs_purge_slot,
+                        )
+                    {
+                        break;
+                    }
                 }
             })
             .unwrap();
         Self { thread_hdl }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn write_transaction_status_batch(
         write_transaction_status_receiver: &Receiver<TransactionStatusMessage>,
         max_complete_transaction_status_slot: &Arc<AtomicU64>,
@@ -63,6 +94,9 @@ impl TransactionStatusService {
         transaction_notifier: Option<TransactionNotifierArc>,
         blockstore: &Blockstore,
         enable_extended_tx_metadata_storage: bool,
+        transaction_logs_storage: Option<&TransactionLogsStorageConfig>,
+        enable_program_signatures_index: bool,
+        last_transaction_logs_purge_slot: &mut Slot,
     ) -> Result<(), RecvTimeoutError> {
         match write_transaction_status_receiver.recv_timeout(Duration::from_secs(1))? {
             TransactionStatusMessage::Batch(TransactionStatusBatch {
@@ -73,8 +107,10 @@ impl TransactionStatusService {
                 token_balances,
                 rent_debits,
                 transaction_indexes,
+                account_states,
             }) => {
                 let slot = bank.slot();
+                let mut account_states = account_states.map(Vec::into_iter);
                 for (
                     transaction,
                     execution_result,
@@ -94,6 +130,10 @@ impl TransactionStatusService {
                     rent_debits,
                     transaction_indexes,
                 ) {
+                    let account_states = account_states
+                        .as_mut()
+                        .and_then(Iterator::next)
+                        .unwrap_or_default();
                     if let Some(details) = execution_result {
                         let TransactionExecutionDetails {
                             status,
@@ -161,9 +201,32 @@ impl TransactionStatusService {
                                 transaction.signature(),
                                 &transaction_status_meta,
                                 &transaction,
+                                &account_states,
                             );
                         }
 
+                        // Logs that go to their own column are taken out before the
+                        // status meta is stripped, as they are stored regardless of
+                        // the extended metadata setting
+                        let log_messages = transaction_logs_storage.and_then(|config| {
+                            transaction_status_meta
+                                .log_messages
+                                .take()
+                                .map(|log_messages| {
+                                    truncate_log_messages(log_messages, config.bytes_limit)
+                                })
+                        });
+
+                        // The programs invoked through CPIs are collected before the
+                        // inner instructions are stripped from the status meta
+                        let program_ids = enable_program_signatures_index.then(|| {
+                            invoked_program_ids(
+                                &transaction.message().account_keys(),
+                                transaction.message().instructions(),
+                                transaction_status_meta.inner_instructions.as_deref(),
+                            )
+                        });
+
                         if !(enable_extended_tx_metadata_storage || transaction_notifier.is_some())
                         {
                             transaction_status_meta.log_messages.take();
@@ -178,6 +241,28 @@ impl TransactionStatusService {
                                     .expect("Expect database write to succeed: TransactionMemos");
                             }
 
+                            if let Some(log_messages) = log_messages {
+                                blockstore
+                                    .write_transaction_logs(
+                                        slot,
+                                        *transaction.signature(),
+                                        &log_messages,
+                                    )
+                                    .expect("Expect database write to succeed: TransactionLogs");
+                            }
+
+                            if let Some(program_ids) = program_ids {
+                                blockstore
+                                    .write_program_signatures(
+                                        slot,
+                                        *transaction.signature(),
+                                        &program_ids,
+                                        transaction_status_meta.status.is_ok(),
+                                        transaction_index,
+                                    )
+                                    .expect("Expect database write to succeed: ProgramSignatures");
+                            }
+
                             blockstore
                                 .write_transaction_status(
                                     slot,
@@ -194,16 +279,65 @@ impl TransactionStatusService {
             }
             TransactionStatusMessage::Freeze(slot) => {
                 max_complete_transaction_status_slot.fetch_max(slot, Ordering::SeqCst);
+                if let Some(retention_slots) =
+                    transaction_logs_storage.and_then(|config| config.retention_slots)
+                {
+                    Self::purge_expired_transaction_logs(
+                        blockstore,
+                        retention_slots,
+                        last_transaction_logs_purge_slot,
+                    );
+                }
             }
         }
         Ok(())
     }
 
+    /// Purges the logs of the slots more than `retention_slots` behind the
+    /// latest root, once the cutoff has advanced by at least
+    /// `TRANSACTION_LOGS_PURGE_INTERVAL` since the previous purge
+    fn purge_expired_transaction_logs(
+        blockstore: &Blockstore,
+        retention_slots: u64,
+        last_purge_slot: &mut Slot,
+    ) {
+        let Some(purge_slot) = blockstore.max_root().checked_sub(retention_slots) else {
+            return;
+        };
+        if purge_slot.saturating_sub(*last_purge_slot) < TRANSACTION_LOGS_PURGE_INTERVAL {
+            return;
+        }
+        match blockstore.purge_transaction_logs(0, purge_slot) {
+            Ok(()) => *last_purge_slot = purge_slot,
+            Err(err) => warn!("Failed to purge transaction logs up to slot {purge_slot}: {err:?}"),
+        }
+    }
+
     pub fn join(self) -> thread::Result<()> {
         self.thread_hdl.join()
     }
 }
 
+/// Applies the storage byte limit to log messages that were already truncated
+/// during execution, marking the cut in the same way as `LogCollector`. Log
+/// messages of exactly `bytes_limit` bytes are kept whole.
+fn truncate_log_messages(log_messages: Vec<String>, bytes_limit: Option<usize>) -> Vec<String> {
+    let Some(bytes_limit) = bytes_limit else {
+        return log_messages;
+    };
+    let mut bytes_written = 0usize;
+    let mut truncated = vec![];
+    for message in log_messages {
+        bytes_written = bytes_written.saturating_add(message.len());
+        if bytes_written > bytes_limit {
+            truncated.push(String::from("Log truncated"));
+            break;
+        }
+        truncated.push(message);
+    }
+    truncated
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use {
@@ -212,7 +346,10 @@ pub(crate) mod tests {
         crossbeam_channel::unbounded,
         dashmap::DashMap,
         solana_account_decoder::parse_token::token_amount_to_ui_amount,
-        solana_ledger::{genesis_utils::create_genesis_config, get_tmp_ledger_path_auto_delete},
+        solana_ledger::{
+            genesis_utils::create_genesis_config, get_tmp_ledger_path_auto_delete,
+            transaction_account_states::TransactionAccountState,
+        },
         solana_runtime::bank::{Bank, TransactionBalancesSet},
         solana_sdk::{
             account_utils::StateMut,
@@ -278,6 +415,7 @@ pub(crate) mod tests {
             signature: &Signature,
             transaction_status_meta: &TransactionStatusMeta,
             transaction: &SanitizedTransaction,
+            _account_states: &[TransactionAccountState],
         ) {
             self.notifications.insert(
                 TestNotifierKey {
@@ -371,6 +509,7 @@ pub(crate) mod tests {
                 .unwrap(),
             )),
             return_data: None,
+            invocation_trace: None,
             executed_units: 0,
             accounts_data_len_delta: 0,
         });
@@ -414,6 +553,7 @@ pub(crate) mod tests {
             token_balances,
             rent_debits: vec![rent_debits],
             transaction_indexes: vec![transaction_index],
+            account_states: None,
         };
 
         let test_notifier = Arc::new(TestTransactionNotifier::new());
@@ -426,6 +566,8 @@ pub(crate) mod tests {
             Some(test_notifier.clone()),
             blockstore,
             false,
+            None,
+            false,
             exit.clone(),
         );
 
@@ -450,4 +592,40 @@ pub(crate) mod tests {
             result.transaction.signature()
         );
     }
+
+    #[test]
+    fn test_truncate_log_messages() {
+        let log_messages: Vec<_> = (0..4).map(|i| format!("Program log: {i}")).collect();
+        let message_len = log_messages[0].len();
+
+        assert_eq!(
+            truncate_log_messages(log_messages.clone(), None),
+            log_messages
+        );
+        assert_eq!(
+            truncate_log_messages(log_messages.clone(), Some(4 * message_len)),
+            log_messages
+        );
+        assert_eq!(
+            truncate_log_messages(log_messages.clone(), Some(4 * message_len - 1)),
+            vec![
+                log_messages[0].clone(),
+                log_messages[1].clone(),
+                log_messages[2].clone(),
+                String::from("Log truncated"),
+            ]
+        );
+        assert_eq!(
+            truncate_log_messages(log_messages.clone(), Some(2 * message_len)),
+            vec![
+                log_messages[0].clone(),
+                log_messages[1].clone(),
+                String::from("Log truncated"),
+            ]
+        );
+        assert_eq!(
+            truncate_log_messages(log_messages, Some(0)),
+            vec![String::from("Log truncated")]
+        );
+    }
 }
@@ -172,7 +172,8 @@ use {
             TransactionProcessingCallback,
         },
         transaction_results::{
-            TransactionExecutionDetails, TransactionExecutionResult, TransactionResults,
+            InvocationTrace, TransactionExecutionDetails, TransactionExecutionResult,
+            TransactionResults,
         },
     },
     solana_system_program::{get_system_account_kind, SystemAccountKind},
@@ -322,6 +323,7 @@ pub struct TransactionSimulationResult {
     pub units_consumed: u64,
     pub return_data: Option<TransactionReturnData>,
     pub inner_instructions: Option<Vec<InnerInstructions>>,
+    pub invocation_trace: Option<InvocationTrace>,
 }
 pub struct TransactionBalancesSet {
     pub pre_balances: TransactionBalances,
@@ -1494,6 +1496,13 @@ impl Bank {
             .prune_by_deployment_slot(deployment_slot);
     }
 
+    /// Returns the number of program cache hits and misses since the cache
+    /// statistics were last reset, at the creation of the latest bank
+    pub fn get_program_cache_hits_and_misses(&self) -> (u64, u64) {
+        let stats = &self.program_cache.read().unwrap().stats;
+        (stats.hits.load(Relaxed), stats.misses.load(Relaxed))
+    }
+
     pub fn get_runtime_environments_for_slot(&self, slot: Slot) -> ProgramRuntimeEnvironments {
         let epoch = self.epoch_schedule.get_epoch(slot);
         self.program_cache
@@ -4315,8 +4324,85 @@ impl Bank {
         enable_cpi_recording: bool,
     ) -> TransactionSimulationResult {
         let account_keys = transaction.message().account_keys();
-        let number_of_accounts = account_keys.len();
         let account_overrides = self.get_account_overrides_for_simulation(&account_keys);
+        self.simulate_transaction_with_overrides(
+            transaction,
+            enable_cpi_recording,
+            false,
+            &account_overrides,
+        )
+    }
+
+    /// Run an ordered list of transactions against a bank without committing the results. Each
+    /// transaction observes the writable account states, including fee payer debits, left behind
+    /// by the transactions before it. Programs are still executed from the bank's program cache,
+    /// so program deployments and upgrades are not visible to later transactions. Simulation
+    /// stops after the first transaction that fails, so the returned results may be shorter than
+    /// `transactions`.
+    ///
+    /// The accounts in `account_overrides`, which may include sysvars, are used in place of the
+    /// bank's accounts for the whole simulation.
+    ///
+    /// With `enable_invocation_tracing`, each result carries the tree of program invocations the
+    /// transaction made.
+    pub fn simulate_transactions(
+        &self,
+        transactions: &[SanitizedTransaction],
+        enable_cpi_recording: bool,
+        enable_invocation_tracing: bool,
+        account_overrides: HashMap<Pubkey, AccountSharedData>,
+    ) -> Vec<TransactionSimulationResult> {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        // Transactions can not write sysvars, so the sysvar cache that reflects the overridden
+        // sysvars is built once for the whole simulation
+        let sysvar_cache = account_overrides.keys().any(sysvar::is_sysvar_id).then(|| {
+            Arc::new(
+                self.transaction_processor
+                    .sysvar_cache_with_overrides(self, &account_overrides),
+            )
+        });
+        let mut simulated_accounts = account_overrides;
+        let mut results = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            let message = transaction.message();
+            let account_keys = message.account_keys();
+            let mut account_overrides = self.get_account_overrides_for_simulation(&account_keys);
+            for (pubkey, account) in simulated_accounts.iter() {
+                account_overrides.set_account(pubkey, Some(account.clone()));
+            }
+            account_overrides.set_sysvar_cache(sysvar_cache.clone());
+
+            let result = self.simulate_transaction_with_overrides(
+                transaction,
+                enable_cpi_recording,
+                enable_invocation_tracing,
+                &account_overrides,
+            );
+            let failed = result.result.is_err();
+            if !failed {
+                for (i, (pubkey, account)) in result.post_simulation_accounts.iter().enumerate() {
+                    if message.is_writable(i) {
+                        simulated_accounts.insert(*pubkey, account.clone());
+                    }
+                }
+            }
+            results.push(result);
+            if failed {
+                break;
+            }
+        }
+        results
+    }
+
+    fn simulate_transaction_with_overrides(
+        &self,
+        transaction: &SanitizedTransaction,
+        enable_cpi_recording: bool,
+        enable_invocation_tracing: bool,
+        account_overrides: &AccountOverrides,
+    ) -> TransactionSimulationResult {
+        let number_of_accounts = transaction.message().account_keys().len();
         let batch = self.prepare_unlocked_batch_from_single_tx(transaction);
         let mut timings = ExecuteTimings::default();
 
@@ -4334,9 +4420,10 @@ impl Bank {
                 enable_cpi_recording,
                 enable_log_recording: true,
                 enable_return_data_recording: true,
+                enable_invocation_trace_recording: enable_invocation_tracing,
             },
             &mut timings,
-            Some(&account_overrides),
+            Some(account_overrides),
             None,
             true,
         );
@@ -4373,13 +4460,14 @@ impl Bank {
                     TransactionError::InvalidProgramForExecution,
                 ));
         let flattened_result = execution_result.flattened_result();
-        let (logs, return_data, inner_instructions) = match execution_result {
+        let (logs, return_data, inner_instructions, invocation_trace) = match execution_result {
             TransactionExecutionResult::Executed { details, .. } => (
                 details.log_messages,
                 details.return_data,
                 details.inner_instructions,
+                details.invocation_trace,
             ),
-            TransactionExecutionResult::NotExecuted(_) => (None, None, None),
+            TransactionExecutionResult::NotExecuted(_) => (None, None, None, None),
         };
         let logs = logs.unwrap_or_default();
 
@@ -4390,6 +4478,7 @@ impl Bank {
             units_consumed,
             return_data,
             inner_instructions,
+            invocation_trace,
         }
     }
 
@@ -5766,6 +5855,7 @@ impl Bank {
                 enable_cpi_recording: false,
                 enable_log_recording: true,
                 enable_return_data_recording: true,
+                enable_invocation_trace_recording: false,
             },
             &mut ExecuteTimings::default(),
             Some(1000 * 1000),
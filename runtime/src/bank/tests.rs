@@ -104,7 +104,7 @@ use {
             Result, SanitizedTransaction, Transaction, TransactionError,
             TransactionVerificationMode,
         },
-        transaction_context::TransactionAccount,
+        transaction_context::{TransactionAccount, TransactionReturnData},
     },
     solana_stake_program::stake_state::{self, StakeStateV2},
     solana_svm::transaction_results::DurableNonceFee,
@@ -245,6 +245,7 @@ fn new_execution_result(
             inner_instructions: None,
             durable_nonce_fee: nonce.map(DurableNonceFee::from),
             return_data: None,
+            invocation_trace: None,
             executed_units: 0,
             accounts_data_len_delta: 0,
         },
@@ -9210,6 +9211,7 @@ fn test_tx_log_order() {
                 enable_cpi_recording: false,
                 enable_log_recording: true,
                 enable_return_data_recording: false,
+                enable_invocation_trace_recording: false,
             },
             &mut ExecuteTimings::default(),
             None,
@@ -9320,6 +9322,7 @@ fn test_tx_return_data() {
                     enable_cpi_recording: false,
                     enable_log_recording: false,
                     enable_return_data_recording: true,
+                    enable_invocation_trace_recording: false,
                 },
                 &mut ExecuteTimings::default(),
                 None,
@@ -13792,3 +13795,215 @@ fn test_failed_simulation_compute_units() {
     let simulation = bank.simulate_transaction(&sanitized, false);
     assert_eq!(expected_consumed_units, simulation.units_consumed);
 }
+
+#[test]
+fn test_simulate_transactions_chains_account_state() {
+    let (mut genesis_config, mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    genesis_config.fee_rate_governor = FeeRateGovernor::new(5_000, 0);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let alice = Keypair::new();
+    let bob = Pubkey::new_unique();
+    let blockhash = bank.last_blockhash();
+    bank.freeze();
+
+    let transactions = [
+        system_transaction::transfer(
+            &mint_keypair,
+            &alice.pubkey(),
+            LAMPORTS_PER_SOL / 2,
+            blockhash,
+        ),
+        system_transaction::transfer(&alice, &bob, LAMPORTS_PER_SOL / 4, blockhash),
+        system_transaction::transfer(&alice, &bob, LAMPORTS_PER_SOL, blockhash),
+        system_transaction::transfer(&mint_keypair, &bob, 1, blockhash),
+    ]
+    .into_iter()
+    .map(SanitizedTransaction::from_transaction_for_tests)
+    .collect::<Vec<_>>();
+
+    // alice only has funds if the first transfer is visible to the second transaction
+    let results = bank.simulate_transactions(&transactions, false, false, HashMap::new());
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].result, Ok(()));
+    assert_eq!(results[1].result, Ok(()));
+    assert_eq!(
+        results[2].result,
+        Err(TransactionError::InstructionError(
+            0,
+            SystemError::ResultWithNegativeLamports.into()
+        ))
+    );
+    let (_, bob_account) = results[1]
+        .post_simulation_accounts
+        .iter()
+        .find(|(pubkey, _)| *pubkey == bob)
+        .unwrap();
+    assert_eq!(bob_account.lamports(), LAMPORTS_PER_SOL / 4);
+
+    // the fee paid by alice is carried along with the transfer
+    let fee = bank.get_fee_for_message(transactions[1].message()).unwrap();
+    assert!(fee > 0);
+    let (_, alice_account) = results[1]
+        .post_simulation_accounts
+        .iter()
+        .find(|(pubkey, _)| *pubkey == alice.pubkey())
+        .unwrap();
+    assert_eq!(
+        alice_account.lamports(),
+        LAMPORTS_PER_SOL / 2 - LAMPORTS_PER_SOL / 4 - fee
+    );
+
+    // nothing is committed to the bank
+    assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+    assert_eq!(bank.get_balance(&bob), 0);
+}
+
+#[test]
+fn test_simulate_transactions_with_sysvar_overrides() {
+    let (genesis_config, mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let program_id = Pubkey::new_unique();
+    let bank =
+        Bank::new_with_mockup_builtin_for_tests(&genesis_config, program_id, MockBuiltin::vm).0;
+
+    const OVERRIDDEN_UNIX_TIMESTAMP: UnixTimestamp = 1_234_567;
+    declare_process_instruction!(MockBuiltin, 1, |invoke_context| {
+        let clock = invoke_context.get_sysvar_cache().get_clock()?;
+        if clock.unix_timestamp == OVERRIDDEN_UNIX_TIMESTAMP {
+            Ok(())
+        } else {
+            Err(InstructionError::InvalidArgument)
+        }
+    });
+
+    let message = Message::new(
+        &[Instruction::new_with_bincode(program_id, &0, vec![])],
+        Some(&mint_keypair.pubkey()),
+    );
+    let transaction = SanitizedTransaction::from_transaction_for_tests(Transaction::new(
+        &[&mint_keypair],
+        message,
+        bank.last_blockhash(),
+    ));
+    bank.freeze();
+
+    let results = bank.simulate_transactions(
+        std::slice::from_ref(&transaction),
+        false,
+        false,
+        HashMap::new(),
+    );
+    assert_eq!(
+        results[0].result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+
+    let mut clock_account = bank.get_account(&sysvar::clock::id()).unwrap();
+    let mut clock = from_account::<sysvar::clock::Clock, _>(&clock_account).unwrap();
+    clock.unix_timestamp = OVERRIDDEN_UNIX_TIMESTAMP;
+    solana_sdk::account::to_account(&clock, &mut clock_account).unwrap();
+    let account_overrides = HashMap::from([(sysvar::clock::id(), clock_account)]);
+    let results = bank.simulate_transactions(
+        std::slice::from_ref(&transaction),
+        false,
+        false,
+        account_overrides,
+    );
+    assert_eq!(results[0].result, Ok(()));
+
+    // the bank's own clock is unchanged
+    assert_ne!(bank.clock().unix_timestamp, OVERRIDDEN_UNIX_TIMESTAMP);
+}
+
+#[test]
+fn test_simulate_transactions_invocation_trace() {
+    let (genesis_config, mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let program_id = Pubkey::new_unique();
+    let bank =
+        Bank::new_with_mockup_builtin_for_tests(&genesis_config, program_id, MockBuiltin::vm).0;
+
+    declare_process_instruction!(MockBuiltin, 1, |invoke_context| {
+        let (program_id, from, to) = {
+            let transaction_context = &invoke_context.transaction_context;
+            let instruction_context = transaction_context.get_current_instruction_context()?;
+            let (from, to): (Pubkey, Pubkey) =
+                bincode::deserialize(instruction_context.get_instruction_data())
+                    .map_err(|_| InstructionError::InvalidInstructionData)?;
+            (
+                *instruction_context.get_last_program_key(transaction_context)?,
+                from,
+                to,
+            )
+        };
+        let instruction = system_instruction::transfer(&from, &to, LAMPORTS_PER_SOL / 4);
+        invoke_context.native_invoke(instruction.into(), &[])?;
+        invoke_context
+            .transaction_context
+            .set_return_data(program_id, vec![42])
+    });
+
+    let to = Pubkey::new_unique();
+    let instruction = Instruction::new_with_bincode(
+        program_id,
+        &(mint_keypair.pubkey(), to),
+        vec![
+            AccountMeta::new(mint_keypair.pubkey(), true),
+            AccountMeta::new(to, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let message = Message::new(&[instruction], Some(&mint_keypair.pubkey()));
+    let transaction = SanitizedTransaction::from_transaction_for_tests(Transaction::new(
+        &[&mint_keypair],
+        message,
+        bank.last_blockhash(),
+    ));
+    bank.freeze();
+
+    let results = bank.simulate_transactions(
+        std::slice::from_ref(&transaction),
+        false,
+        false,
+        HashMap::new(),
+    );
+    assert_eq!(results[0].result, Ok(()));
+    assert!(results[0].invocation_trace.is_none());
+
+    let results = bank.simulate_transactions(
+        std::slice::from_ref(&transaction),
+        false,
+        true,
+        HashMap::new(),
+    );
+    assert_eq!(results[0].result, Ok(()));
+    let invocation_trace = results[0].invocation_trace.as_ref().unwrap();
+    assert_eq!(invocation_trace.len(), 1);
+    let invocation = &invocation_trace[0];
+    assert_eq!(invocation.program_id, program_id);
+    assert_eq!(
+        invocation.accounts,
+        vec![mint_keypair.pubkey(), to, system_program::id()]
+    );
+    assert_eq!(invocation.stack_height, 1);
+    assert_eq!(invocation.compute_units_consumed, results[0].units_consumed);
+    assert_eq!(
+        invocation.return_data,
+        Some(TransactionReturnData {
+            program_id,
+            data: vec![42],
+        })
+    );
+    assert_eq!(invocation.error, None);
+
+    assert_eq!(invocation.inner_invocations.len(), 1);
+    let inner_invocation = &invocation.inner_invocations[0];
+    assert_eq!(inner_invocation.program_id, system_program::id());
+    assert_eq!(inner_invocation.accounts, vec![mint_keypair.pubkey(), to]);
+    assert_eq!(inner_invocation.stack_height, 2);
+    assert!(inner_invocation.compute_units_consumed > 0);
+    assert!(inner_invocation.compute_units_consumed < invocation.compute_units_consumed);
+    assert_eq!(inner_invocation.return_data, None);
+    assert!(inner_invocation.inner_invocations.is_empty());
+}
@@ -16,7 +16,7 @@ if [[ -n $BIGTABLE_EMULATOR_HOST ]]; then
   cbt+=(-project emulator)
 fi
 
-for table in blocks entries tx tx-by-addr; do
+for table in blocks entries tx tx-by-addr slot-metadata; do
   (
     set -x
     "${cbt[@]}" createtable $table
@@ -17,8 +17,8 @@ use {
 };
 
 #[allow(clippy::derive_partial_eq_without_eq, clippy::enum_variant_names)]
-mod google {
-    mod rpc {
+pub(crate) mod google {
+    pub mod rpc {
         include!(concat!(
             env!("CARGO_MANIFEST_DIR"),
             concat!("/proto/google.rpc.rs")
@@ -82,6 +82,9 @@ pub enum Error {
 
     #[error("Timeout")]
     Timeout,
+
+    #[error("RocksDB: {0}")]
+    RocksDb(rocksdb::Error),
 }
 
 fn to_backoff_err(err: Error) -> BackoffError<Error> {
@@ -99,6 +102,12 @@ impl std::convert::From<std::io::Error> for Error {
     }
 }
 
+impl std::convert::From<rocksdb::Error> for Error {
+    fn from(err: rocksdb::Error) -> Self {
+        Self::RocksDb(err)
+    }
+}
+
 impl std::convert::From<tonic::transport::Error> for Error {
     fn from(err: tonic::transport::Error) -> Self {
         Self::Transport(err)
@@ -739,14 +748,6 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
         Ok(())
     }
 
-    pub async fn get_bincode_cell<T>(&mut self, table: &str, key: RowKey) -> Result<T>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_bincode_cell_data(&row_data, table, key.to_string())
-    }
-
     pub async fn get_bincode_cells<T>(
         &mut self,
         table: &str,
@@ -769,52 +770,6 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
             .collect())
     }
 
-    pub async fn get_protobuf_cell<P>(&mut self, table: &str, key: RowKey) -> Result<P>
-    where
-        P: prost::Message + Default,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_protobuf_cell_data(&row_data, table, key.to_string())
-    }
-
-    pub async fn get_protobuf_or_bincode_cell<B, P>(
-        &mut self,
-        table: &str,
-        key: RowKey,
-    ) -> Result<CellData<B, P>>
-    where
-        B: serde::de::DeserializeOwned,
-        P: prost::Message + Default,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_protobuf_or_bincode_cell_data(&row_data, table, key)
-    }
-
-    pub async fn get_protobuf_or_bincode_cells<'a, B, P>(
-        &mut self,
-        table: &'a str,
-        row_keys: impl IntoIterator<Item = RowKey>,
-    ) -> Result<impl Iterator<Item = (RowKey, CellData<B, P>)> + 'a>
-    where
-        B: serde::de::DeserializeOwned,
-        P: prost::Message + Default,
-    {
-        Ok(self
-            .get_multi_row_data(
-                table,
-                row_keys.into_iter().collect::<Vec<RowKey>>().as_slice(),
-            )
-            .await?
-            .into_iter()
-            .map(|(key, row_data)| {
-                let key_str = key.to_string();
-                (
-                    key,
-                    deserialize_protobuf_or_bincode_cell_data(&row_data, table, key_str).unwrap(),
-                )
-            }))
-    }
-
     pub async fn put_bincode_cells<T>(
         &mut self,
         table: &str,
@@ -826,9 +781,9 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
         let mut bytes_written = 0;
         let mut new_row_data = vec![];
         for (row_key, data) in cells {
-            let data = compress_best(&bincode::serialize(&data).unwrap())?;
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("bin".to_string(), data)]));
+            let cell = serialize_bincode_cell_data(data)?;
+            bytes_written += cell.1.len();
+            new_row_data.push((row_key, vec![cell]));
         }
 
         self.put_row_data(table, "x", &new_row_data).await?;
@@ -846,11 +801,9 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
         let mut bytes_written = 0;
         let mut new_row_data = vec![];
         for (row_key, data) in cells {
-            let mut buf = Vec::with_capacity(data.encoded_len());
-            data.encode(&mut buf).unwrap();
-            let data = compress_best(&buf)?;
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("proto".to_string(), data)]));
+            let cell = serialize_protobuf_cell_data(data)?;
+            bytes_written += cell.1.len();
+            new_row_data.push((row_key, vec![cell]));
         }
 
         self.put_row_data(table, "x", &new_row_data).await?;
@@ -858,6 +811,24 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
     }
 }
 
+pub(crate) fn serialize_bincode_cell_data<T>(data: &T) -> Result<(CellName, CellValue)>
+where
+    T: serde::ser::Serialize,
+{
+    let data = compress_best(&bincode::serialize(data).unwrap())?;
+    Ok(("bin".to_string(), data))
+}
+
+pub(crate) fn serialize_protobuf_cell_data<T>(data: &T) -> Result<(CellName, CellValue)>
+where
+    T: prost::Message,
+{
+    let mut buf = Vec::with_capacity(data.encoded_len());
+    data.encode(&mut buf).unwrap();
+    let data = compress_best(&buf)?;
+    Ok(("proto".to_string(), data))
+}
+
 pub(crate) fn deserialize_protobuf_or_bincode_cell_data<B, P>(
     row_data: RowDataSlice,
     table: &str,
@@ -0,0 +1,219 @@
+// The row reads and writes LedgerStorage is built on, implemented by each of its backends
+
+use {
+    crate::bigtable::{
+        deserialize_bincode_cell_data, deserialize_protobuf_cell_data,
+        deserialize_protobuf_or_bincode_cell_data, BigTableConnection, CellData, Result, RowData,
+        RowKey,
+    },
+    async_trait::async_trait,
+};
+
+#[async_trait]
+pub trait RowStore: Clone + Send + Sync + 'static {
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>>;
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool>;
+
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>>;
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>>;
+
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData>;
+
+    async fn get_bincode_cells_with_retry<T>(
+        &self,
+        table: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, Result<T>)>>
+    where
+        T: serde::de::DeserializeOwned + Send;
+
+    async fn put_bincode_cells_with_retry<T>(
+        &self,
+        table: &str,
+        cells: &[(RowKey, T)],
+    ) -> Result<usize>
+    where
+        T: serde::ser::Serialize + Sync;
+
+    async fn put_protobuf_cells_with_retry<T>(
+        &self,
+        table: &str,
+        cells: &[(RowKey, T)],
+    ) -> Result<usize>
+    where
+        T: prost::Message;
+
+    async fn delete_rows_with_retry(&self, table: &str, row_keys: &[RowKey]) -> Result<()>;
+
+    async fn get_bincode_cell<T>(&self, table: &str, key: RowKey) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let row_data = self.get_single_row_data(table, key.clone()).await?;
+        deserialize_bincode_cell_data(&row_data, table, key)
+    }
+
+    async fn get_bincode_cells<T>(
+        &self,
+        table: &str,
+        keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, Result<T>)>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        Ok(self
+            .get_multi_row_data(table, keys)
+            .await?
+            .into_iter()
+            .map(|(key, row_data)| {
+                let key_str = key.to_string();
+                (
+                    key,
+                    deserialize_bincode_cell_data(&row_data, table, key_str),
+                )
+            })
+            .collect())
+    }
+
+    async fn get_protobuf_cell<P>(&self, table: &str, key: RowKey) -> Result<P>
+    where
+        P: prost::Message + Default,
+    {
+        let row_data = self.get_single_row_data(table, key.clone()).await?;
+        deserialize_protobuf_cell_data(&row_data, table, key)
+    }
+
+    async fn get_protobuf_or_bincode_cell<B, P>(
+        &self,
+        table: &str,
+        key: RowKey,
+    ) -> Result<CellData<B, P>>
+    where
+        B: serde::de::DeserializeOwned,
+        P: prost::Message + Default,
+    {
+        let row_data = self.get_single_row_data(table, key.clone()).await?;
+        deserialize_protobuf_or_bincode_cell_data(&row_data, table, key)
+    }
+
+    async fn get_protobuf_or_bincode_cells<B, P>(
+        &self,
+        table: &str,
+        row_keys: Vec<RowKey>,
+    ) -> Result<Vec<(RowKey, CellData<B, P>)>>
+    where
+        B: serde::de::DeserializeOwned,
+        P: prost::Message + Default,
+    {
+        Ok(self
+            .get_multi_row_data(table, &row_keys)
+            .await?
+            .into_iter()
+            .map(|(key, row_data)| {
+                let key_str = key.to_string();
+                (
+                    key,
+                    deserialize_protobuf_or_bincode_cell_data(&row_data, table, key_str).unwrap(),
+                )
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl RowStore for BigTableConnection {
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        self.client()
+            .get_row_keys(table_name, start_at, end_at, rows_limit)
+            .await
+    }
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        self.client().row_key_exists(table_name, row_key).await
+    }
+
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.client()
+            .get_row_data(table_name, start_at, end_at, rows_limit)
+            .await
+    }
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.client().get_multi_row_data(table_name, row_keys).await
+    }
+
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        self.client().get_single_row_data(table_name, row_key).await
+    }
+
+    async fn get_bincode_cells_with_retry<T>(
+        &self,
+        table: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, Result<T>)>>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        BigTableConnection::get_bincode_cells_with_retry(self, table, row_keys).await
+    }
+
+    async fn put_bincode_cells_with_retry<T>(
+        &self,
+        table: &str,
+        cells: &[(RowKey, T)],
+    ) -> Result<usize>
+    where
+        T: serde::ser::Serialize + Sync,
+    {
+        BigTableConnection::put_bincode_cells_with_retry(self, table, cells).await
+    }
+
+    async fn put_protobuf_cells_with_retry<T>(
+        &self,
+        table: &str,
+        cells: &[(RowKey, T)],
+    ) -> Result<usize>
+    where
+        T: prost::Message,
+    {
+        BigTableConnection::put_protobuf_cells_with_retry(self, table, cells).await
+    }
+
+    async fn delete_rows_with_retry(&self, table: &str, row_keys: &[RowKey]) -> Result<()> {
+        BigTableConnection::delete_rows_with_retry(self, table, row_keys).await
+    }
+}
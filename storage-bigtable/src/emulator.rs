@@ -0,0 +1,649 @@
+// An in-process fake of the BigTable data API, for tests and local runs without Google Cloud.
+//
+// The emulator serves the calls LedgerStorage makes: ReadRows, MutateRows and CheckAndMutateRow.
+// Tables must exist before they are used, like in BigTable; the tables of LedgerStorage are
+// created when the emulator starts. Only the latest version of each cell is kept, which is the
+// only version LedgerStorage reads, and only the row filters LedgerStorage uses are supported.
+
+use {
+    crate::{
+        bigtable::google::{bigtable::v2::*, rpc},
+        local_storage::TABLES,
+        Result, DEFAULT_MAX_MESSAGE_SIZE,
+    },
+    futures::stream,
+    log::*,
+    std::{
+        collections::{BTreeMap, HashMap},
+        convert::Infallible,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+    tokio::{net::TcpListener, sync::oneshot, task::JoinHandle},
+    tonic::{
+        body::BoxBody,
+        codec::ProstCodec,
+        codegen::{http, BoxFuture, Context, Poll, Service},
+        server::{Grpc, NamedService, ServerStreamingService, UnaryService},
+        transport::{Body, Server},
+        Request, Response, Status,
+    },
+};
+
+type ColumnKey = (String, Vec<u8>); // (family name, column qualifier)
+type Row = BTreeMap<ColumnKey, Cell>;
+type Table = BTreeMap<Vec<u8>, Row>;
+type ResponseStream<T> = stream::Iter<std::vec::IntoIter<std::result::Result<T, Status>>>;
+
+#[derive(Clone)]
+struct Cell {
+    timestamp_micros: i64,
+    value: Vec<u8>,
+}
+
+/// A running BigTable emulator, serving until it is shut down or dropped
+pub struct BigTableEmulator {
+    endpoint: SocketAddr,
+    exit_sender: oneshot::Sender<()>,
+    server: JoinHandle<std::result::Result<(), tonic::transport::Error>>,
+}
+
+impl BigTableEmulator {
+    /// Start serving on `bind_address`; port 0 picks a free port, see `endpoint()`
+    pub async fn start(bind_address: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(bind_address).await?;
+        let endpoint = listener.local_addr()?;
+        let incoming = Box::pin(stream::unfold(listener, |listener| async move {
+            let connection = listener.accept().await.map(|(stream, _)| stream);
+            Some((connection, listener))
+        }));
+
+        let service = BigTableEmulatorService {
+            tables: Arc::new(Mutex::new(
+                TABLES
+                    .iter()
+                    .map(|table_name| (table_name.to_string(), Table::default()))
+                    .collect(),
+            )),
+        };
+        let (exit_sender, exit_receiver) = oneshot::channel();
+        let server = tokio::spawn(
+            Server::builder()
+                .add_service(service)
+                .serve_with_incoming_shutdown(incoming, async move {
+                    let _ = exit_receiver.await;
+                }),
+        );
+        info!("BigTable emulator listening on {}", endpoint);
+
+        Ok(Self {
+            endpoint,
+            exit_sender,
+            server,
+        })
+    }
+
+    /// The address to connect to, as expected by `LedgerStorage::new_for_emulator`,
+    /// `LedgerStorageConfig::emulator_endpoint` and the BIGTABLE_EMULATOR_HOST environment variable
+    pub fn endpoint(&self) -> String {
+        self.endpoint.to_string()
+    }
+
+    /// Serve until the server fails
+    pub async fn join(self) -> Result<()> {
+        self.server
+            .await
+            .map_err(crate::Error::TokioJoinError)?
+            .map_err(|err| crate::bigtable::Error::from(err).into())
+    }
+
+    pub async fn shutdown(self) -> Result<()> {
+        let _ = self.exit_sender.send(());
+        self.join().await
+    }
+}
+
+#[derive(Clone)]
+struct BigTableEmulatorService {
+    tables: Arc<Mutex<HashMap<String, Table>>>,
+}
+
+impl NamedService for BigTableEmulatorService {
+    const NAME: &'static str = "google.bigtable.v2.Bigtable";
+}
+
+impl Service<http::Request<Body>> for BigTableEmulatorService {
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+        let service = self.clone();
+        match request.uri().path() {
+            "/google.bigtable.v2.Bigtable/ReadRows" => {
+                Box::pin(
+                    async move { Ok(grpc().server_streaming(ReadRows(service), request).await) },
+                )
+            }
+            "/google.bigtable.v2.Bigtable/MutateRows" => {
+                Box::pin(
+                    async move { Ok(grpc().server_streaming(MutateRows(service), request).await) },
+                )
+            }
+            "/google.bigtable.v2.Bigtable/CheckAndMutateRow" => {
+                Box::pin(async move { Ok(grpc().unary(CheckAndMutateRow(service), request).await) })
+            }
+            path => {
+                let response = Status::unimplemented(format!("{path} is not emulated")).to_http();
+                Box::pin(async move { Ok(response) })
+            }
+        }
+    }
+}
+
+fn grpc<T, U>() -> Grpc<ProstCodec<T, U>>
+where
+    T: prost::Message + Send + 'static,
+    U: prost::Message + Default + Send + 'static,
+{
+    Grpc::new(ProstCodec::default()).apply_max_message_size_config(
+        Some(DEFAULT_MAX_MESSAGE_SIZE),
+        Some(DEFAULT_MAX_MESSAGE_SIZE),
+    )
+}
+
+struct ReadRows(BigTableEmulatorService);
+
+impl ServerStreamingService<ReadRowsRequest> for ReadRows {
+    type Response = ReadRowsResponse;
+    type ResponseStream = ResponseStream<ReadRowsResponse>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<ReadRowsRequest>) -> Self::Future {
+        let result = self
+            .0
+            .read_rows(request.into_inner())
+            .map(|responses| Response::new(stream::iter(responses.into_iter().map(Ok))));
+        Box::pin(async move { result })
+    }
+}
+
+struct MutateRows(BigTableEmulatorService);
+
+impl ServerStreamingService<MutateRowsRequest> for MutateRows {
+    type Response = MutateRowsResponse;
+    type ResponseStream = ResponseStream<MutateRowsResponse>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<MutateRowsRequest>) -> Self::Future {
+        let result = self
+            .0
+            .mutate_rows(request.into_inner())
+            .map(|response| Response::new(stream::iter(vec![Ok(response)])));
+        Box::pin(async move { result })
+    }
+}
+
+struct CheckAndMutateRow(BigTableEmulatorService);
+
+impl UnaryService<CheckAndMutateRowRequest> for CheckAndMutateRow {
+    type Response = CheckAndMutateRowResponse;
+    type Future = BoxFuture<Response<Self::Response>, Status>;
+
+    fn call(&mut self, request: Request<CheckAndMutateRowRequest>) -> Self::Future {
+        let result = self
+            .0
+            .check_and_mutate_row(request.into_inner())
+            .map(Response::new);
+        Box::pin(async move { result })
+    }
+}
+
+impl BigTableEmulatorService {
+    fn with_table<T>(
+        &self,
+        table_name: &str,
+        f: impl FnOnce(&mut Table) -> std::result::Result<T, Status>,
+    ) -> std::result::Result<T, Status> {
+        // Table names are `projects/<project>/instances/<instance>/tables/<table>`; the project
+        // and instance are not checked
+        let name = table_name
+            .rsplit_once("/tables/")
+            .map(|(_, name)| name)
+            .unwrap_or(table_name);
+        let mut tables = self.tables.lock().unwrap();
+        let table = tables
+            .get_mut(name)
+            .ok_or_else(|| Status::not_found(format!("table \"{table_name}\" not found")))?;
+        f(table)
+    }
+
+    fn read_rows(
+        &self,
+        request: ReadRowsRequest,
+    ) -> std::result::Result<Vec<ReadRowsResponse>, Status> {
+        if request.reversed {
+            return Err(Status::unimplemented("reversed reads are not emulated"));
+        }
+        self.with_table(&request.table_name, |table| {
+            let mut responses = vec![];
+            for (row_key, row) in table.iter() {
+                if request.rows_limit > 0 && responses.len() as i64 >= request.rows_limit {
+                    break;
+                }
+                if !row_set_contains(request.rows.as_ref(), row_key) {
+                    continue;
+                }
+                let cells =
+                    filter_cells(request.filter.as_ref(), row.clone().into_iter().collect())?;
+                if cells.is_empty() {
+                    continue;
+                }
+                responses.push(ReadRowsResponse {
+                    chunks: row_chunks(row_key, cells),
+                    ..ReadRowsResponse::default()
+                });
+            }
+            Ok(responses)
+        })
+    }
+
+    fn mutate_rows(
+        &self,
+        request: MutateRowsRequest,
+    ) -> std::result::Result<MutateRowsResponse, Status> {
+        self.with_table(&request.table_name, |table| {
+            let entries = request
+                .entries
+                .into_iter()
+                .enumerate()
+                .map(|(index, entry)| {
+                    mutate_row(table, entry.row_key, entry.mutations);
+                    mutate_rows_response::Entry {
+                        index: index as i64,
+                        status: Some(rpc::Status::default()),
+                    }
+                })
+                .collect();
+            Ok(MutateRowsResponse {
+                entries,
+                ..MutateRowsResponse::default()
+            })
+        })
+    }
+
+    fn check_and_mutate_row(
+        &self,
+        request: CheckAndMutateRowRequest,
+    ) -> std::result::Result<CheckAndMutateRowResponse, Status> {
+        self.with_table(&request.table_name, |table| {
+            let cells = table
+                .get(&request.row_key)
+                .map(|row| row.clone().into_iter().collect())
+                .unwrap_or_default();
+            let predicate_matched =
+                !filter_cells(request.predicate_filter.as_ref(), cells)?.is_empty();
+            let mutations = if predicate_matched {
+                request.true_mutations
+            } else {
+                request.false_mutations
+            };
+            mutate_row(table, request.row_key, mutations);
+            Ok(CheckAndMutateRowResponse { predicate_matched })
+        })
+    }
+}
+
+fn row_set_contains(row_set: Option<&RowSet>, row_key: &[u8]) -> bool {
+    match row_set {
+        Some(row_set) if !row_set.row_keys.is_empty() || !row_set.row_ranges.is_empty() => {
+            row_set.row_keys.iter().any(|key| key == row_key)
+                || row_set
+                    .row_ranges
+                    .iter()
+                    .any(|row_range| row_range_contains(row_range, row_key))
+        }
+        // An empty row set reads the whole table
+        _ => true,
+    }
+}
+
+fn row_range_contains(row_range: &RowRange, row_key: &[u8]) -> bool {
+    // An empty start or end key leaves that side of the range unbounded
+    let after_start = match &row_range.start_key {
+        Some(row_range::StartKey::StartKeyClosed(start)) => row_key >= start.as_slice(),
+        Some(row_range::StartKey::StartKeyOpen(start)) => {
+            start.is_empty() || row_key > start.as_slice()
+        }
+        None => true,
+    };
+    let before_end = match &row_range.end_key {
+        Some(row_range::EndKey::EndKeyClosed(end)) => end.is_empty() || row_key <= end.as_slice(),
+        Some(row_range::EndKey::EndKeyOpen(end)) => end.is_empty() || row_key < end.as_slice(),
+        None => true,
+    };
+    after_start && before_end
+}
+
+fn filter_cells(
+    filter: Option<&RowFilter>,
+    mut cells: Vec<(ColumnKey, Cell)>,
+) -> std::result::Result<Vec<(ColumnKey, Cell)>, Status> {
+    let Some(filter) = filter.and_then(|filter| filter.filter.as_ref()) else {
+        return Ok(cells);
+    };
+    match filter {
+        row_filter::Filter::Chain(chain) => {
+            for filter in &chain.filters {
+                cells = filter_cells(Some(filter), cells)?;
+            }
+        }
+        row_filter::Filter::PassAllFilter(true) => {}
+        row_filter::Filter::BlockAllFilter(true) => cells.clear(),
+        row_filter::Filter::CellsPerRowLimitFilter(limit) => {
+            cells.truncate((*limit).max(0) as usize);
+        }
+        // Only the latest version of each cell is kept
+        row_filter::Filter::CellsPerColumnLimitFilter(limit) if *limit > 0 => {}
+        row_filter::Filter::StripValueTransformer(true) => {
+            cells.iter_mut().for_each(|(_, cell)| cell.value.clear());
+        }
+        filter => {
+            return Err(Status::unimplemented(format!(
+                "row filter {filter:?} is not emulated"
+            )))
+        }
+    }
+    Ok(cells)
+}
+
+fn row_chunks(row_key: &[u8], cells: Vec<(ColumnKey, Cell)>) -> Vec<read_rows_response::CellChunk> {
+    let num_cells = cells.len();
+    cells
+        .into_iter()
+        .enumerate()
+        .map(
+            |(i, ((family_name, qualifier), cell))| read_rows_response::CellChunk {
+                row_key: if i == 0 { row_key.to_vec() } else { vec![] },
+                family_name: Some(family_name),
+                qualifier: Some(qualifier),
+                timestamp_micros: cell.timestamp_micros,
+                value: cell.value,
+                row_status: (i + 1 == num_cells)
+                    .then_some(read_rows_response::cell_chunk::RowStatus::CommitRow(true)),
+                ..read_rows_response::CellChunk::default()
+            },
+        )
+        .collect()
+}
+
+fn mutate_row(table: &mut Table, row_key: Vec<u8>, mutations: Vec<Mutation>) {
+    let row = table.entry(row_key.clone()).or_default();
+    for mutation in mutations
+        .into_iter()
+        .filter_map(|mutation| mutation.mutation)
+    {
+        match mutation {
+            mutation::Mutation::SetCell(set_cell) => {
+                let timestamp_micros = if set_cell.timestamp_micros == -1 {
+                    server_timestamp_micros()
+                } else {
+                    set_cell.timestamp_micros
+                };
+                let column_key = (set_cell.family_name, set_cell.column_qualifier);
+                if row
+                    .get(&column_key)
+                    .map_or(true, |cell| cell.timestamp_micros <= timestamp_micros)
+                {
+                    row.insert(
+                        column_key,
+                        Cell {
+                            timestamp_micros,
+                            value: set_cell.value,
+                        },
+                    );
+                }
+            }
+            mutation::Mutation::DeleteFromColumn(delete) => {
+                let column_key = (delete.family_name, delete.column_qualifier);
+                let in_time_range = row.get(&column_key).is_some_and(|cell| {
+                    delete.time_range.as_ref().map_or(true, |time_range| {
+                        cell.timestamp_micros >= time_range.start_timestamp_micros
+                            && (time_range.end_timestamp_micros == 0
+                                || cell.timestamp_micros < time_range.end_timestamp_micros)
+                    })
+                });
+                if in_time_range {
+                    row.remove(&column_key);
+                }
+            }
+            mutation::Mutation::DeleteFromFamily(delete) => {
+                row.retain(|(family_name, _), _| *family_name != delete.family_name);
+            }
+            mutation::Mutation::DeleteFromRow(_) => row.clear(),
+        }
+    }
+    // BigTable has no empty rows
+    if row.is_empty() {
+        table.remove(&row_key);
+    }
+}
+
+// BigTable assigns timestamps with millisecond granularity
+fn server_timestamp_micros() -> i64 {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+    millis * 1_000
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{Error, LedgerStorage, SlotMetadata},
+        solana_sdk::{
+            clock::Slot, hash::Hash, message::v0::LoadedAddresses, pubkey::Pubkey,
+            signature::Keypair, system_transaction, transaction::VersionedTransaction,
+            transaction_context::TransactionReturnData,
+        },
+        solana_transaction_status::{
+            ConfirmedBlock, TransactionStatusMeta, VersionedConfirmedBlock,
+            VersionedTransactionWithStatusMeta,
+        },
+    };
+
+    fn test_block(parent_slot: Slot, recipient: &Pubkey) -> VersionedConfirmedBlock {
+        let transaction =
+            system_transaction::transfer(&Keypair::new(), recipient, 42, Hash::default());
+        VersionedConfirmedBlock {
+            previous_blockhash: Hash::new_unique().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot,
+            transactions: vec![VersionedTransactionWithStatusMeta {
+                transaction: VersionedTransaction::from(transaction),
+                meta: TransactionStatusMeta {
+                    status: Ok(()),
+                    fee: 5_000,
+                    pre_balances: vec![10_000, 0, 1],
+                    post_balances: vec![4_958, 42, 1],
+                    inner_instructions: Some(vec![]),
+                    log_messages: Some(vec![]),
+                    pre_token_balances: Some(vec![]),
+                    post_token_balances: Some(vec![]),
+                    rewards: Some(vec![]),
+                    loaded_addresses: LoadedAddresses::default(),
+                    return_data: Some(TransactionReturnData::default()),
+                    compute_units_consumed: Some(150),
+                },
+            }],
+            rewards: vec![],
+            block_time: Some(1_700_000_000),
+            block_height: Some(parent_slot + 1),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ledger_storage_round_trip() {
+        let emulator = BigTableEmulator::start("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let ledger_storage =
+            LedgerStorage::new_for_emulator("test", "default", &emulator.endpoint(), None).unwrap();
+
+        assert_eq!(
+            ledger_storage.get_first_available_block().await.unwrap(),
+            None
+        );
+
+        let recipient = Pubkey::new_unique();
+        let block_1 = test_block(0, &recipient);
+        let block_2 = test_block(1, &recipient);
+        let signature_1 = block_1.transactions[0].transaction.signatures[0];
+        ledger_storage
+            .upload_confirmed_block(1, block_1.clone())
+            .await
+            .unwrap();
+        ledger_storage
+            .upload_confirmed_block(2, block_2)
+            .await
+            .unwrap();
+        let slot_metadata = SlotMetadata {
+            parent_slot: 0,
+            blockhash: block_1.blockhash.clone(),
+            bank_hash: Some(Hash::new_unique()),
+            ..SlotMetadata::default()
+        };
+        ledger_storage
+            .upload_slot_metadata(1, slot_metadata.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ledger_storage.get_first_available_block().await.unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            ledger_storage.get_confirmed_blocks(0, 10).await.unwrap(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            ledger_storage.get_confirmed_blocks(2, 10).await.unwrap(),
+            vec![2]
+        );
+        assert_eq!(
+            ledger_storage.get_confirmed_block(1).await.unwrap(),
+            ConfirmedBlock::from(block_1)
+        );
+        assert_eq!(
+            ledger_storage
+                .get_confirmed_transaction(&signature_1)
+                .await
+                .unwrap()
+                .unwrap()
+                .slot,
+            1
+        );
+        assert_eq!(
+            ledger_storage
+                .get_confirmed_signatures_for_address(&recipient, None, None, 10)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            ledger_storage.get_slot_metadata(1).await.unwrap(),
+            slot_metadata
+        );
+
+        ledger_storage
+            .delete_confirmed_block(1, false)
+            .await
+            .unwrap();
+        assert!(matches!(
+            ledger_storage.get_confirmed_block(1).await,
+            Err(Error::BlockNotFound(1))
+        ));
+        assert!(matches!(
+            ledger_storage.get_confirmed_transaction(&signature_1).await,
+            Err(Error::SignatureNotFound)
+        ));
+        assert!(matches!(
+            ledger_storage.get_slot_metadata(1).await,
+            Err(Error::SlotMetadataNotFound(1))
+        ));
+        assert_eq!(
+            ledger_storage
+                .get_confirmed_signatures_for_address(&recipient, None, None, 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            ledger_storage.get_confirmed_blocks(0, 10).await.unwrap(),
+            vec![2]
+        );
+
+        emulator.shutdown().await.unwrap();
+    }
+
+    #[test]
+    fn test_check_and_mutate_row() {
+        let service = BigTableEmulatorService {
+            tables: Arc::new(Mutex::new(HashMap::from([(
+                "blocks".to_string(),
+                Table::default(),
+            )]))),
+        };
+        let set_cell = |value: &[u8]| Mutation {
+            mutation: Some(mutation::Mutation::SetCell(mutation::SetCell {
+                family_name: "x".to_string(),
+                column_qualifier: b"bin".to_vec(),
+                timestamp_micros: -1,
+                value: value.to_vec(),
+            })),
+        };
+        let check_and_mutate_row = |value: &[u8]| {
+            service
+                .check_and_mutate_row(CheckAndMutateRowRequest {
+                    table_name: "projects/emulator/instances/test/tables/blocks".to_string(),
+                    row_key: b"0000000000000001".to_vec(),
+                    false_mutations: vec![set_cell(value)],
+                    ..CheckAndMutateRowRequest::default()
+                })
+                .unwrap()
+                .predicate_matched
+        };
+
+        // Only the first write to a row applies its mutations
+        assert!(!check_and_mutate_row(b"first"));
+        assert!(check_and_mutate_row(b"second"));
+        let responses = service
+            .read_rows(ReadRowsRequest {
+                table_name: "projects/emulator/instances/test/tables/blocks".to_string(),
+                ..ReadRowsRequest::default()
+            })
+            .unwrap();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].chunks[0].value, b"first");
+
+        assert_eq!(
+            service
+                .check_and_mutate_row(CheckAndMutateRowRequest {
+                    table_name: "projects/emulator/instances/test/tables/missing".to_string(),
+                    ..CheckAndMutateRowRequest::default()
+                })
+                .unwrap_err()
+                .code(),
+            tonic::Code::NotFound
+        );
+    }
+}
@@ -1,15 +1,21 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 use {
-    crate::bigtable::RowKey,
+    crate::{
+        bigtable::{BigTableConnection, RowKey},
+        connection::RowStore,
+        local_storage::LocalConnection,
+    },
     log::*,
     serde::{Deserialize, Serialize},
     solana_metrics::datapoint_info,
     solana_sdk::{
         clock::{Slot, UnixTimestamp},
         deserialize_utils::default_on_eof,
+        hash::Hash,
         message::v0::LoadedAddresses,
         pubkey::Pubkey,
+        reward_type::RewardType,
         signature::Signature,
         sysvar::is_sysvar_id,
         timing::AtomicInterval,
@@ -18,7 +24,7 @@ use {
     solana_storage_proto::convert::{entries, generated, tx_by_addr},
     solana_transaction_status::{
         extract_and_fmt_memos, ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
-        ConfirmedTransactionWithStatusMeta, EntrySummary, Reward, TransactionByAddrInfo,
+        ConfirmedTransactionWithStatusMeta, EntrySummary, Reward, Rewards, TransactionByAddrInfo,
         TransactionConfirmationStatus, TransactionStatus, TransactionStatusMeta,
         TransactionWithStatusMeta, VersionedConfirmedBlock, VersionedConfirmedBlockWithEntries,
         VersionedTransactionWithStatusMeta,
@@ -26,6 +32,7 @@ use {
     std::{
         collections::{HashMap, HashSet},
         convert::TryInto,
+        path::Path,
         sync::{
             atomic::{AtomicUsize, Ordering},
             Arc,
@@ -45,8 +52,14 @@ extern crate serde_derive;
 mod access_token;
 mod bigtable;
 mod compression;
+mod connection;
+mod emulator;
+mod local_storage;
+mod long_term_storage;
 mod root_ca_certificate;
 
+pub use {emulator::BigTableEmulator, long_term_storage::LongTermLedgerStorage};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("BigTable: {0}")]
@@ -64,6 +77,9 @@ pub enum Error {
     #[error("Signature not found")]
     SignatureNotFound,
 
+    #[error("Slot metadata not found: {0}")]
+    SlotMetadataNotFound(Slot),
+
     #[error("tokio error")]
     TokioJoinError(JoinError),
 }
@@ -96,6 +112,10 @@ fn slot_to_entries_key(slot: Slot) -> String {
     slot_to_key(slot)
 }
 
+fn slot_to_slot_metadata_key(slot: Slot) -> String {
+    slot_to_key(slot)
+}
+
 fn slot_to_tx_by_addr_key(slot: Slot) -> String {
     slot_to_key(!slot)
 }
@@ -308,6 +328,190 @@ impl From<Reward> for StoredConfirmedBlockReward {
     }
 }
 
+/// Metadata of a rooted slot that is archived next to its block, so that the
+/// bank hash and leader of historical slots can be audited without the blocks
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SlotMetadata {
+    pub parent_slot: Slot,
+    pub blockhash: String,
+    /// Hash of the frozen bank, if the uploading node replayed the slot
+    pub bank_hash: Option<Hash>,
+    /// Identity of the slot leader, if the uploading node knew the leader schedule
+    pub leader: Option<Pubkey>,
+    pub tick_count: u64,
+    pub num_hashes: u64,
+    pub num_entries: u64,
+    pub num_transactions: u64,
+    /// Rewards credited in the slot, including its partition of the epoch's
+    /// stake rewards when they are distributed over several blocks
+    pub rewards: Rewards,
+    pub block_time: Option<UnixTimestamp>,
+    pub block_height: Option<u64>,
+}
+
+impl SlotMetadata {
+    pub fn new(
+        confirmed_block: &VersionedConfirmedBlockWithEntries,
+        bank_hash: Option<Hash>,
+        leader: Option<Pubkey>,
+    ) -> Self {
+        let VersionedConfirmedBlockWithEntries { block, entries } = confirmed_block;
+        Self {
+            parent_slot: block.parent_slot,
+            blockhash: block.blockhash.clone(),
+            bank_hash,
+            leader,
+            tick_count: entries
+                .iter()
+                .filter(|entry| entry.num_transactions == 0)
+                .count() as u64,
+            num_hashes: entries.iter().map(|entry| entry.num_hashes).sum(),
+            num_entries: entries.len() as u64,
+            num_transactions: block.transactions.len() as u64,
+            rewards: block.rewards.clone(),
+            block_time: block.block_time,
+            block_height: block.block_height,
+        }
+    }
+}
+
+// A serialized `StoredSlotMetadata` is stored in the `slot-metadata` table
+//
+// Every version of the row is a variant of the enum, so that the rows written by older versions
+// remain readable. Fields must not be added to an existing variant: add a new variant instead,
+// write it from `From<SlotMetadata>` and convert the older variants with defaults for the fields
+// they lack
+#[derive(Serialize, Deserialize)]
+enum StoredSlotMetadata {
+    V1(StoredSlotMetadataV1),
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredSlotMetadataV1 {
+    parent_slot: Slot,
+    blockhash: String,
+    bank_hash: Option<Hash>,
+    leader: Option<Pubkey>,
+    tick_count: u64,
+    num_hashes: u64,
+    num_entries: u64,
+    num_transactions: u64,
+    rewards: Vec<StoredSlotMetadataReward>,
+    block_time: Option<UnixTimestamp>,
+    block_height: Option<u64>,
+}
+
+impl From<SlotMetadata> for StoredSlotMetadata {
+    fn from(slot_metadata: SlotMetadata) -> Self {
+        let SlotMetadata {
+            parent_slot,
+            blockhash,
+            bank_hash,
+            leader,
+            tick_count,
+            num_hashes,
+            num_entries,
+            num_transactions,
+            rewards,
+            block_time,
+            block_height,
+        } = slot_metadata;
+
+        Self::V1(StoredSlotMetadataV1 {
+            parent_slot,
+            blockhash,
+            bank_hash,
+            leader,
+            tick_count,
+            num_hashes,
+            num_entries,
+            num_transactions,
+            rewards: rewards.into_iter().map(|reward| reward.into()).collect(),
+            block_time,
+            block_height,
+        })
+    }
+}
+
+impl From<StoredSlotMetadata> for SlotMetadata {
+    fn from(slot_metadata: StoredSlotMetadata) -> Self {
+        match slot_metadata {
+            StoredSlotMetadata::V1(StoredSlotMetadataV1 {
+                parent_slot,
+                blockhash,
+                bank_hash,
+                leader,
+                tick_count,
+                num_hashes,
+                num_entries,
+                num_transactions,
+                rewards,
+                block_time,
+                block_height,
+            }) => Self {
+                parent_slot,
+                blockhash,
+                bank_hash,
+                leader,
+                tick_count,
+                num_hashes,
+                num_entries,
+                num_transactions,
+                rewards: rewards.into_iter().map(|reward| reward.into()).collect(),
+                block_time,
+                block_height,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredSlotMetadataReward {
+    pubkey: String,
+    lamports: i64,
+    post_balance: u64,
+    reward_type: Option<RewardType>,
+    commission: Option<u8>,
+}
+
+impl From<StoredSlotMetadataReward> for Reward {
+    fn from(value: StoredSlotMetadataReward) -> Self {
+        let StoredSlotMetadataReward {
+            pubkey,
+            lamports,
+            post_balance,
+            reward_type,
+            commission,
+        } = value;
+        Self {
+            pubkey,
+            lamports,
+            post_balance,
+            reward_type,
+            commission,
+        }
+    }
+}
+
+impl From<Reward> for StoredSlotMetadataReward {
+    fn from(value: Reward) -> Self {
+        let Reward {
+            pubkey,
+            lamports,
+            post_balance,
+            reward_type,
+            commission,
+        } = value;
+        Self {
+            pubkey,
+            lamports,
+            post_balance,
+            reward_type,
+            commission,
+        }
+    }
+}
+
 // A serialized `TransactionInfo` is stored in the `tx` table
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
 struct TransactionInfo {
@@ -397,6 +601,9 @@ pub struct LedgerStorageConfig {
     pub instance_name: String,
     pub app_profile_id: String,
     pub max_message_size: usize,
+    /// Address of a BigTable emulator to connect to instead of the BigTable service, which
+    /// takes precedence over the BIGTABLE_EMULATOR_HOST environment variable
+    pub emulator_endpoint: Option<String>,
 }
 
 impl Default for LedgerStorageConfig {
@@ -408,6 +615,7 @@ impl Default for LedgerStorageConfig {
             instance_name: DEFAULT_INSTANCE_NAME.to_string(),
             app_profile_id: DEFAULT_APP_PROFILE_ID.to_string(),
             max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            emulator_endpoint: None,
         }
     }
 }
@@ -440,12 +648,17 @@ impl LedgerStorageStats {
     }
 }
 
+/// Long-term ledger storage with the BigTable row layout, kept in BigTable by default
 #[derive(Clone)]
-pub struct LedgerStorage {
-    connection: bigtable::BigTableConnection,
+pub struct LedgerStorage<S = BigTableConnection> {
+    connection: S,
     stats: Arc<LedgerStorageStats>,
 }
 
+/// Long-term ledger storage kept in a local embedded store instead of BigTable, for nodes
+/// serving history without a BigTable instance
+pub type LocalLedgerStorage = LedgerStorage<LocalConnection>;
+
 impl LedgerStorage {
     pub async fn new(
         read_only: bool,
@@ -469,7 +682,7 @@ impl LedgerStorage {
     ) -> Result<Self> {
         let stats = Arc::new(LedgerStorageStats::default());
         Ok(Self {
-            connection: bigtable::BigTableConnection::new_for_emulator(
+            connection: BigTableConnection::new_for_emulator(
                 instance_name,
                 app_profile_id,
                 endpoint,
@@ -489,16 +702,28 @@ impl LedgerStorage {
             app_profile_id,
             credential_type,
             max_message_size,
+            emulator_endpoint,
         } = config;
-        let connection = bigtable::BigTableConnection::new(
-            instance_name.as_str(),
-            app_profile_id.as_str(),
-            read_only,
-            timeout,
-            credential_type,
-            max_message_size,
-        )
-        .await?;
+        let connection = match emulator_endpoint {
+            Some(endpoint) => BigTableConnection::new_for_emulator(
+                instance_name.as_str(),
+                app_profile_id.as_str(),
+                &endpoint,
+                timeout,
+                max_message_size,
+            )?,
+            None => {
+                BigTableConnection::new(
+                    instance_name.as_str(),
+                    app_profile_id.as_str(),
+                    read_only,
+                    timeout,
+                    credential_type,
+                    max_message_size,
+                )
+                .await?
+            }
+        };
         Ok(Self { stats, connection })
     }
 
@@ -509,13 +734,29 @@ impl LedgerStorage {
         })
         .await
     }
+}
 
+impl LocalLedgerStorage {
+    /// Open ledger storage kept in a local embedded store at `path`, created if needed unless
+    /// `read_only` is set
+    pub fn new_local(path: &Path, read_only: bool) -> Result<Self> {
+        let stats = Arc::new(LedgerStorageStats::default());
+        Ok(Self {
+            connection: LocalConnection::open(path, read_only)?,
+            stats,
+        })
+    }
+}
+
+impl<S: RowStore> LedgerStorage<S> {
     /// Return the available slot that contains a block
     pub async fn get_first_available_block(&self) -> Result<Option<Slot>> {
         trace!("LedgerStorage::get_first_available_block request received");
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let blocks = bigtable.get_row_keys("blocks", None, None, 1).await?;
+        let blocks = self
+            .connection
+            .get_row_keys("blocks", None, None, 1)
+            .await?;
         if blocks.is_empty() {
             return Ok(None);
         }
@@ -533,8 +774,8 @@ impl LedgerStorage {
             limit
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let blocks = bigtable
+        let blocks = self
+            .connection
             .get_row_keys(
                 "blocks",
                 Some(slot_to_blocks_key(start_slot)),
@@ -555,11 +796,12 @@ impl LedgerStorage {
             slots
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let row_keys = slots.iter().copied().map(slot_to_blocks_key);
-        let data = bigtable
+        let row_keys = slots.iter().copied().map(slot_to_blocks_key).collect();
+        let data = self
+            .connection
             .get_protobuf_or_bincode_cells("blocks", row_keys)
             .await?
+            .into_iter()
             .filter_map(
                 |(row_key, block_cell_data): (
                     RowKey,
@@ -582,8 +824,8 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let block_cell_data = bigtable
+        let block_cell_data = self
+            .connection
             .get_protobuf_or_bincode_cell::<StoredConfirmedBlock, generated::ConfirmedBlock>(
                 "blocks",
                 slot_to_blocks_key(slot),
@@ -608,9 +850,8 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-
-        let block_exists = bigtable
+        let block_exists = self
+            .connection
             .row_key_exists("blocks", slot_to_blocks_key(slot))
             .await?;
 
@@ -624,8 +865,8 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let entry_cell_data = bigtable
+        let entry_cell_data = self
+            .connection
             .get_protobuf_cell::<entries::Entries>("entries", slot_to_entries_key(slot))
             .await
             .map_err(|err| match err {
@@ -636,14 +877,35 @@ impl LedgerStorage {
         Ok(entries)
     }
 
+    /// Fetch the metadata of the desired slot
+    pub async fn get_slot_metadata(&self, slot: Slot) -> Result<SlotMetadata> {
+        trace!(
+            "LedgerStorage::get_slot_metadata request received: {:?}",
+            slot
+        );
+        self.stats.increment_num_queries();
+        let slot_metadata = self
+            .connection
+            .get_bincode_cell::<StoredSlotMetadata>(
+                "slot-metadata",
+                slot_to_slot_metadata_key(slot),
+            )
+            .await
+            .map_err(|err| match err {
+                bigtable::Error::RowNotFound => Error::SlotMetadataNotFound(slot),
+                _ => err.into(),
+            })?;
+        Ok(slot_metadata.into())
+    }
+
     pub async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
         trace!(
             "LedgerStorage::get_signature_status request received: {:?}",
             signature
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let transaction_info = bigtable
+        let transaction_info = self
+            .connection
             .get_bincode_cell::<TransactionInfo>("tx", signature.to_string())
             .await
             .map_err(|err| match err {
@@ -663,11 +925,10 @@ impl LedgerStorage {
             signatures
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-
         // Fetch transactions info
         let keys = signatures.iter().map(|s| s.to_string()).collect::<Vec<_>>();
-        let cells = bigtable
+        let cells = self
+            .connection
             .get_bincode_cells::<TransactionInfo>("tx", &keys)
             .await?;
 
@@ -725,10 +986,9 @@ impl LedgerStorage {
             signature
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-
         // Figure out which block the transaction is located in
-        let TransactionInfo { slot, index, .. } = bigtable
+        let TransactionInfo { slot, index, .. } = self
+            .connection
             .get_bincode_cell("tx", signature.to_string())
             .await
             .map_err(|err| match err {
@@ -785,14 +1045,14 @@ impl LedgerStorage {
             address
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
         let address_prefix = format!("{address}/");
 
         // Figure out where to start listing from based on `before_signature`
         let (first_slot, before_transaction_index) = match before_signature {
             None => (Slot::MAX, 0),
             Some(before_signature) => {
-                let TransactionInfo { slot, index, .. } = bigtable
+                let TransactionInfo { slot, index, .. } = self
+                    .connection
                     .get_bincode_cell("tx", before_signature.to_string())
                     .await?;
 
@@ -804,7 +1064,8 @@ impl LedgerStorage {
         let (last_slot, until_transaction_index) = match until_signature {
             None => (0, u32::MAX),
             Some(until_signature) => {
-                let TransactionInfo { slot, index, .. } = bigtable
+                let TransactionInfo { slot, index, .. } = self
+                    .connection
                     .get_bincode_cell("tx", until_signature.to_string())
                     .await?;
 
@@ -814,7 +1075,7 @@ impl LedgerStorage {
 
         let mut infos = vec![];
 
-        let starting_slot_tx_len = bigtable
+        let starting_slot_tx_len = self.connection
             .get_protobuf_or_bincode_cell::<Vec<LegacyTransactionByAddrInfo>, tx_by_addr::TransactionByAddr>(
                 "tx-by-addr",
                 format!("{}{}", address_prefix, slot_to_tx_by_addr_key(first_slot)),
@@ -830,7 +1091,8 @@ impl LedgerStorage {
 
         // Return the next tx-by-addr data of amount `limit` plus extra to account for the largest
         // number that might be filtered out
-        let tx_by_addr_data = bigtable
+        let tx_by_addr_data = self
+            .connection
             .get_row_data(
                 "tx-by-addr",
                 Some(format!(
@@ -1070,6 +1332,27 @@ impl LedgerStorage {
         Ok(())
     }
 
+    /// Upload the metadata of a slot. The upload service uploads it before the
+    /// block of the slot, without making the block upload depend on it
+    pub async fn upload_slot_metadata(
+        &self,
+        slot: Slot,
+        slot_metadata: SlotMetadata,
+    ) -> Result<()> {
+        trace!(
+            "LedgerStorage::upload_slot_metadata request received: {:?}",
+            slot
+        );
+        let cells = [(
+            slot_to_slot_metadata_key(slot),
+            StoredSlotMetadata::from(slot_metadata),
+        )];
+        self.connection
+            .put_bincode_cells_with_retry::<StoredSlotMetadata>("slot-metadata", &cells)
+            .await?;
+        Ok(())
+    }
+
     // Delete a confirmed block and associated meta data.
     pub async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<()> {
         let mut addresses: HashSet<&Pubkey> = HashSet::new();
@@ -1160,11 +1443,16 @@ impl LedgerStorage {
 
         let entries_exist = self
             .connection
-            .client()
             .row_key_exists("entries", slot_to_entries_key(slot))
             .await
             .is_ok_and(|x| x);
 
+        let slot_metadata_exists = self
+            .connection
+            .row_key_exists("slot-metadata", slot_to_slot_metadata_key(slot))
+            .await
+            .is_ok_and(|x| x);
+
         if !dry_run {
             if !address_slot_rows.is_empty() {
                 self.connection
@@ -1184,18 +1472,25 @@ impl LedgerStorage {
                     .await?;
             }
 
+            if slot_metadata_exists {
+                self.connection
+                    .delete_rows_with_retry("slot-metadata", &[slot_to_slot_metadata_key(slot)])
+                    .await?;
+            }
+
             self.connection
                 .delete_rows_with_retry("blocks", &[slot_to_blocks_key(slot)])
                 .await?;
         }
 
         info!(
-            "{}deleted ledger data for slot {}: {} transaction rows, {} address slot rows, {} entry row",
+            "{}deleted ledger data for slot {}: {} transaction rows, {} address slot rows, {} entry row, {} slot metadata row",
             if dry_run { "[dry run] " } else { "" },
             slot,
             tx_deletion_rows.len(),
             address_slot_rows.len(),
-            if entries_exist { "with" } else {"WITHOUT"}
+            if entries_exist { "with" } else {"WITHOUT"},
+            if slot_metadata_exists { "with" } else {"WITHOUT"}
         );
 
         Ok(())
@@ -1204,11 +1499,175 @@ impl LedgerStorage {
 
 #[cfg(test)]
 mod test {
-    use super::*;
+    use {
+        super::*,
+        solana_sdk::{signature::Keypair, signer::Signer, system_transaction},
+        tempfile::TempDir,
+    };
 
     #[test]
     fn test_slot_to_key() {
         assert_eq!(slot_to_key(0), "0000000000000000");
         assert_eq!(slot_to_key(!0), "ffffffffffffffff");
     }
+
+    #[test]
+    fn test_slot_metadata() {
+        let tick = |num_hashes| EntrySummary {
+            num_hashes,
+            hash: Hash::new_unique(),
+            num_transactions: 0,
+            starting_transaction_index: 0,
+        };
+        let confirmed_block = VersionedConfirmedBlockWithEntries {
+            block: VersionedConfirmedBlock {
+                previous_blockhash: Hash::new_unique().to_string(),
+                blockhash: Hash::new_unique().to_string(),
+                parent_slot: 41,
+                transactions: vec![],
+                rewards: vec![Reward {
+                    pubkey: Pubkey::new_unique().to_string(),
+                    lamports: 42,
+                    post_balance: 1_042,
+                    reward_type: Some(RewardType::Staking),
+                    commission: Some(5),
+                }],
+                block_time: Some(1_700_000_000),
+                block_height: Some(40),
+            },
+            entries: vec![tick(12_500), tick(12_500)],
+        };
+        let bank_hash = Hash::new_unique();
+        let leader = Pubkey::new_unique();
+        let slot_metadata = SlotMetadata::new(&confirmed_block, Some(bank_hash), Some(leader));
+        assert_eq!(slot_metadata.parent_slot, 41);
+        assert_eq!(slot_metadata.bank_hash, Some(bank_hash));
+        assert_eq!(slot_metadata.leader, Some(leader));
+        assert_eq!(slot_metadata.tick_count, 2);
+        assert_eq!(slot_metadata.num_hashes, 25_000);
+        assert_eq!(slot_metadata.num_entries, 2);
+        assert_eq!(slot_metadata.num_transactions, 0);
+        assert_eq!(slot_metadata.rewards, confirmed_block.block.rewards);
+
+        let data = bincode::serialize(&StoredSlotMetadata::from(slot_metadata.clone())).unwrap();
+        let stored_slot_metadata: StoredSlotMetadata = bincode::deserialize(&data).unwrap();
+        assert_eq!(SlotMetadata::from(stored_slot_metadata), slot_metadata);
+    }
+
+    #[tokio::test]
+    async fn test_local_ledger_storage_round_trip() {
+        let ledger_storage_dir = TempDir::new().unwrap();
+        let ledger_storage =
+            LocalLedgerStorage::new_local(ledger_storage_dir.path(), false).unwrap();
+
+        let from = Keypair::new();
+        let to = Pubkey::new_unique();
+        let transaction = VersionedTransaction::from(system_transaction::transfer(
+            &from,
+            &to,
+            42,
+            Hash::new_unique(),
+        ));
+        let signature = transaction.signatures[0];
+        let slot = 42;
+        let confirmed_block = VersionedConfirmedBlockWithEntries {
+            block: VersionedConfirmedBlock {
+                previous_blockhash: Hash::new_unique().to_string(),
+                blockhash: Hash::new_unique().to_string(),
+                parent_slot: 41,
+                transactions: vec![VersionedTransactionWithStatusMeta {
+                    transaction,
+                    meta: TransactionStatusMeta {
+                        fee: 5_000,
+                        pre_balances: vec![1_000_000, 0, 1],
+                        post_balances: vec![994_958, 42, 1],
+                        ..TransactionStatusMeta::default()
+                    },
+                }],
+                rewards: vec![],
+                block_time: Some(1_700_000_000),
+                block_height: Some(40),
+            },
+            entries: vec![EntrySummary {
+                num_hashes: 12_500,
+                hash: Hash::new_unique(),
+                num_transactions: 1,
+                starting_transaction_index: 0,
+            }],
+        };
+        let entry_hash = confirmed_block.entries[0].hash;
+        let slot_metadata = SlotMetadata::new(&confirmed_block, None, None);
+
+        ledger_storage
+            .upload_slot_metadata(slot, slot_metadata.clone())
+            .await
+            .unwrap();
+        ledger_storage
+            .upload_confirmed_block_with_entries(slot, confirmed_block)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ledger_storage.get_first_available_block().await.unwrap(),
+            Some(slot)
+        );
+        assert_eq!(
+            ledger_storage.get_confirmed_blocks(0, 10).await.unwrap(),
+            vec![slot]
+        );
+        assert!(ledger_storage.confirmed_block_exists(slot).await.unwrap());
+
+        let block = ledger_storage.get_confirmed_block(slot).await.unwrap();
+        assert_eq!(block.parent_slot, 41);
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(block.transactions[0].transaction_signature(), &signature);
+
+        let transaction = ledger_storage
+            .get_confirmed_transaction(&signature)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(transaction.slot, slot);
+        assert_eq!(transaction.block_time, Some(1_700_000_000));
+
+        let status = ledger_storage
+            .get_signature_status(&signature)
+            .await
+            .unwrap();
+        assert_eq!(status.slot, slot);
+        assert_eq!(status.err, None);
+
+        for address in [from.pubkey(), to] {
+            let signatures = ledger_storage
+                .get_confirmed_signatures_for_address(&address, None, None, 10)
+                .await
+                .unwrap();
+            assert_eq!(signatures.len(), 1);
+            assert_eq!(signatures[0].0.signature, signature);
+            assert_eq!(signatures[0].0.slot, slot);
+        }
+
+        let entries: Vec<_> = ledger_storage.get_entries(slot).await.unwrap().collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].hash, entry_hash);
+        assert_eq!(entries[0].num_transactions, 1);
+
+        assert_eq!(
+            ledger_storage.get_slot_metadata(slot).await.unwrap(),
+            slot_metadata
+        );
+
+        ledger_storage
+            .delete_confirmed_block(slot, false)
+            .await
+            .unwrap();
+        assert!(matches!(
+            ledger_storage.get_confirmed_block(slot).await,
+            Err(Error::BlockNotFound(42))
+        ));
+        assert!(matches!(
+            ledger_storage.get_signature_status(&signature).await,
+            Err(Error::SignatureNotFound)
+        ));
+    }
 }
@@ -0,0 +1,476 @@
+// An embedded store of the LedgerStorage tables, for nodes serving history without BigTable.
+//
+// The store keeps the BigTable row layout: each table is a RocksDB column family keyed by the
+// BigTable row key, holding the compressed cells of the row. Rows sort the same way in both
+// backends, so range reads return the same rows.
+
+use {
+    crate::{
+        bigtable::{
+            deserialize_bincode_cell_data, serialize_bincode_cell_data,
+            serialize_protobuf_cell_data, Error, Result, RowData, RowKey,
+        },
+        connection::RowStore,
+    },
+    async_trait::async_trait,
+    log::*,
+    rocksdb::{ColumnFamily, Direction, IteratorMode, Options, WriteBatch, DB},
+    std::{
+        collections::BTreeMap,
+        io,
+        path::Path,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// The tables of LedgerStorage, each stored in a column family of the same name
+pub(crate) const TABLES: &[&str] = &["blocks", "entries", "tx", "tx-by-addr", "slot-metadata"];
+
+#[derive(Clone)]
+pub struct LocalConnection {
+    db: Arc<DB>,
+    /// Serializes the writes, which read the cells of the rows they update
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl LocalConnection {
+    /// Open the store at `path`, creating it if needed unless `read_only` is set
+    pub fn open(path: &Path, read_only: bool) -> Result<Self> {
+        let db = if read_only {
+            // Stores created before a table was added lack its column family
+            let existing_tables = DB::list_cf(&Options::default(), path)?;
+            let tables = TABLES
+                .iter()
+                .filter(|table| existing_tables.iter().any(|existing| existing == *table));
+            DB::open_cf_for_read_only(&Options::default(), path, tables, false)?
+        } else {
+            let mut options = Options::default();
+            options.create_if_missing(true);
+            options.create_missing_column_families(true);
+            DB::open_cf(&options, path, TABLES)?
+        };
+        Ok(Self {
+            db: Arc::new(db),
+            write_lock: Arc::default(),
+        })
+    }
+
+    /// Run `f` on the blocking thread pool, so that RocksDB I/O doesn't stall the async runtime
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Self) -> Result<T> + Send + 'static,
+    {
+        let connection = self.clone();
+        tokio::task::spawn_blocking(move || f(&connection))
+            .await
+            .map_err(|err| Error::Io(io::Error::new(io::ErrorKind::Other, err)))?
+    }
+
+    fn table(&self, table_name: &str) -> Result<&ColumnFamily> {
+        self.db
+            .cf_handle(table_name)
+            .ok_or_else(|| Error::ObjectNotFound(format!("table {table_name}")))
+    }
+
+    fn read_row(&self, table_name: &str, row_key: &str) -> Result<Option<RowData>> {
+        self.db
+            .get_cf(self.table(table_name)?, row_key)?
+            .map(|value| deserialize_row_data(table_name, row_key, &value))
+            .transpose()
+    }
+
+    /// Rows between `start_at` and `end_at`, both inclusive, in key order
+    fn read_rows(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, Box<[u8]>)>> {
+        let mode = match &start_at {
+            Some(start_at) => IteratorMode::From(start_at.as_bytes(), Direction::Forward),
+            None => IteratorMode::Start,
+        };
+        let mut rows = vec![];
+        for row in self.db.iterator_cf(self.table(table_name)?, mode) {
+            let (key, value) = row?;
+            if end_at
+                .as_ref()
+                .is_some_and(|end_at| &*key > end_at.as_bytes())
+            {
+                break;
+            }
+            let Ok(row_key) = String::from_utf8(key.into_vec()) else {
+                continue; // Require UTF-8 for row keys
+            };
+            rows.push((row_key, value));
+            if rows.len() as i64 >= rows_limit {
+                break;
+            }
+        }
+        Ok(rows)
+    }
+
+    fn read_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        if rows_limit == 0 {
+            return Ok(vec![]);
+        }
+        let rows = self.read_rows(table_name, start_at, end_at, rows_limit)?;
+        Ok(rows.into_iter().map(|(row_key, _)| row_key).collect())
+    }
+
+    fn read_row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        Ok(self
+            .db
+            .get_pinned_cf(self.table(table_name)?, row_key)?
+            .is_some())
+    }
+
+    fn read_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        if rows_limit == 0 {
+            return Ok(vec![]);
+        }
+        self.read_rows(table_name, start_at, end_at, rows_limit)?
+            .into_iter()
+            .map(|(row_key, value)| {
+                let row_data = deserialize_row_data(table_name, &row_key, &value)?;
+                Ok((row_key, row_data))
+            })
+            .collect()
+    }
+
+    fn read_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let mut rows = vec![];
+        for row_key in row_keys {
+            if let Some(row_data) = self.read_row(table_name, row_key)? {
+                rows.push((row_key.clone(), row_data));
+            }
+        }
+        Ok(rows)
+    }
+
+    fn read_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        self.read_row(table_name, &row_key)?
+            .ok_or(Error::RowNotFound)
+    }
+
+    fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
+        let table = self.table(table_name)?;
+        let mut batch = WriteBatch::default();
+        for row_key in row_keys {
+            batch.delete_cf(table, row_key);
+        }
+        let _write_lock = self.write_lock.lock().unwrap();
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    /// Write the cells of each row, replacing the cells of the same names like a BigTable
+    /// `SetCell` mutation. The rows are written at once, and no other write runs between
+    /// reading the current cells of the rows and writing them.
+    fn put_row_data(&self, table_name: &str, row_data: Vec<(RowKey, RowData)>) -> Result<()> {
+        let table = self.table(table_name)?;
+        let _write_lock = self.write_lock.lock().unwrap();
+        let mut rows = BTreeMap::new();
+        for (row_key, new_cells) in row_data {
+            let mut cells = match rows.remove(&row_key) {
+                Some(cells) => cells,
+                None => self.read_row(table_name, &row_key)?.unwrap_or_default(),
+            };
+            for (cell_name, cell_value) in new_cells {
+                match cells.iter_mut().find(|(name, _)| *name == cell_name) {
+                    Some(cell) => cell.1 = cell_value,
+                    None => cells.push((cell_name, cell_value)),
+                }
+            }
+            rows.insert(row_key, cells);
+        }
+        let mut batch = WriteBatch::default();
+        for (row_key, cells) in rows {
+            batch.put_cf(table, row_key, bincode::serialize(&cells).unwrap());
+        }
+        self.db.write(batch)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RowStore for LocalConnection {
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |connection| {
+            connection.read_row_keys(&table_name, start_at, end_at, rows_limit)
+        })
+        .await
+    }
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |connection| connection.read_row_key_exists(&table_name, row_key))
+            .await
+    }
+
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |connection| {
+            connection.read_row_data(&table_name, start_at, end_at, rows_limit)
+        })
+        .await
+    }
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let table_name = table_name.to_string();
+        let row_keys = row_keys.to_vec();
+        self.run_blocking(move |connection| connection.read_multi_row_data(&table_name, &row_keys))
+            .await
+    }
+
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |connection| connection.read_single_row_data(&table_name, row_key))
+            .await
+    }
+
+    async fn get_bincode_cells_with_retry<T>(
+        &self,
+        table: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, Result<T>)>>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        Ok(self
+            .get_multi_row_data(table, row_keys)
+            .await?
+            .into_iter()
+            .map(|(key, row_data)| {
+                let key_str = key.to_string();
+                (
+                    key,
+                    deserialize_bincode_cell_data(&row_data, table, key_str),
+                )
+            })
+            .collect())
+    }
+
+    async fn put_bincode_cells_with_retry<T>(
+        &self,
+        table: &str,
+        cells: &[(RowKey, T)],
+    ) -> Result<usize>
+    where
+        T: serde::ser::Serialize + Sync,
+    {
+        let mut bytes_written = 0;
+        let mut new_row_data = vec![];
+        for (row_key, data) in cells {
+            let cell = serialize_bincode_cell_data(data)?;
+            bytes_written += cell.1.len();
+            new_row_data.push((row_key.clone(), vec![cell]));
+        }
+
+        let table = table.to_string();
+        self.run_blocking(move |connection| connection.put_row_data(&table, new_row_data))
+            .await?;
+        Ok(bytes_written)
+    }
+
+    async fn put_protobuf_cells_with_retry<T>(
+        &self,
+        table: &str,
+        cells: &[(RowKey, T)],
+    ) -> Result<usize>
+    where
+        T: prost::Message,
+    {
+        let mut bytes_written = 0;
+        let mut new_row_data = vec![];
+        for (row_key, data) in cells {
+            let cell = serialize_protobuf_cell_data(data)?;
+            bytes_written += cell.1.len();
+            new_row_data.push((row_key.clone(), vec![cell]));
+        }
+
+        let table = table.to_string();
+        self.run_blocking(move |connection| connection.put_row_data(&table, new_row_data))
+            .await?;
+        Ok(bytes_written)
+    }
+
+    async fn delete_rows_with_retry(&self, table: &str, row_keys: &[RowKey]) -> Result<()> {
+        let table = table.to_string();
+        let row_keys = row_keys.to_vec();
+        self.run_blocking(move |connection| connection.delete_rows(&table, &row_keys))
+            .await
+    }
+}
+
+fn deserialize_row_data(table_name: &str, row_key: &str, value: &[u8]) -> Result<RowData> {
+    bincode::deserialize(value).map_err(|err| {
+        warn!("Failed to deserialize {}/{}: {}", table_name, row_key, err);
+        Error::ObjectCorrupt(format!("{table_name}/{row_key}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_storage_proto::convert::generated, tempfile::TempDir};
+
+    #[tokio::test]
+    async fn test_local_connection() {
+        let ledger_storage_dir = TempDir::new().unwrap();
+        let connection = LocalConnection::open(ledger_storage_dir.path(), false).unwrap();
+
+        let cells: Vec<_> = ["0000000000000001", "0000000000000002", "0000000000000003"]
+            .into_iter()
+            .map(|row_key| (row_key.to_string(), row_key.to_string()))
+            .collect();
+        connection
+            .put_bincode_cells_with_retry("blocks", &cells)
+            .await
+            .unwrap();
+        connection
+            .put_protobuf_cells_with_retry(
+                "blocks",
+                &[(
+                    "0000000000000002".to_string(),
+                    generated::UnixTimestamp { timestamp: 42 },
+                )],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            connection
+                .get_row_keys("blocks", Some("0000000000000002".to_string()), None, 10)
+                .await
+                .unwrap(),
+            vec!["0000000000000002", "0000000000000003"]
+        );
+        assert_eq!(
+            connection
+                .get_row_keys("blocks", None, Some("0000000000000002".to_string()), 10)
+                .await
+                .unwrap(),
+            vec!["0000000000000001", "0000000000000002"]
+        );
+        assert_eq!(
+            connection
+                .get_row_keys("blocks", None, None, 1)
+                .await
+                .unwrap(),
+            vec!["0000000000000001"]
+        );
+
+        // A write adds cells to the row and keeps its other cells
+        let row_data = connection
+            .get_single_row_data("blocks", "0000000000000002".to_string())
+            .await
+            .unwrap();
+        let cell_names: Vec<_> = row_data.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(cell_names, vec!["bin", "proto"]);
+
+        connection
+            .delete_rows_with_retry("blocks", &["0000000000000001".to_string()])
+            .await
+            .unwrap();
+        assert!(!connection
+            .row_key_exists("blocks", "0000000000000001".to_string())
+            .await
+            .unwrap());
+        assert!(matches!(
+            connection
+                .get_single_row_data("blocks", "0000000000000001".to_string())
+                .await,
+            Err(Error::RowNotFound)
+        ));
+        assert_eq!(
+            connection
+                .get_multi_row_data(
+                    "blocks",
+                    &[
+                        "0000000000000001".to_string(),
+                        "0000000000000003".to_string()
+                    ]
+                )
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(connection
+            .get_row_keys("blocks", None, None, 0)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(connection
+            .get_row_keys("no-such-table", None, None, 1)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_put_row_data_merges_rows() {
+        let ledger_storage_dir = TempDir::new().unwrap();
+        let connection = LocalConnection::open(ledger_storage_dir.path(), false).unwrap();
+
+        // Cells of the same row in one write are merged, the later one winning
+        connection
+            .put_row_data(
+                "tx",
+                vec![
+                    ("a".to_string(), vec![("bin".to_string(), vec![1])]),
+                    ("a".to_string(), vec![("proto".to_string(), vec![2])]),
+                    ("a".to_string(), vec![("bin".to_string(), vec![3])]),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            connection
+                .read_single_row_data("tx", "a".to_string())
+                .unwrap(),
+            vec![("bin".to_string(), vec![3]), ("proto".to_string(), vec![2])]
+        );
+
+        // A write to an unknown table writes none of its rows
+        assert!(connection
+            .put_row_data(
+                "no-such-table",
+                vec![("b".to_string(), vec![("bin".to_string(), vec![4])])],
+            )
+            .is_err());
+    }
+}
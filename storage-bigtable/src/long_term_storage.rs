@@ -0,0 +1,147 @@
+use {
+    crate::{connection::RowStore, LedgerStorage, Result, SlotMetadata},
+    async_trait::async_trait,
+    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
+    solana_transaction_status::{
+        ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
+        ConfirmedTransactionWithStatusMeta, TransactionStatus, VersionedConfirmedBlock,
+        VersionedConfirmedBlockWithEntries,
+    },
+};
+
+/// Historical ledger storage queried by RPC and filled by the upload service once blocks are
+/// rooted and may be purged from the local blockstore
+#[async_trait]
+pub trait LongTermLedgerStorage: Send + Sync {
+    /// Return the available slot that contains a block
+    async fn get_first_available_block(&self) -> Result<Option<Slot>>;
+
+    /// Fetch the next slots after the provided slot that contains a block
+    ///
+    /// start_slot: slot to start the search from (inclusive)
+    /// limit: stop after this many slots have been found
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>>;
+
+    /// Fetch the confirmed block from the desired slot
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock>;
+
+    /// Does the specified block exist
+    async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool>;
+
+    /// Fetch the metadata of the desired slot
+    async fn get_slot_metadata(&self, slot: Slot) -> Result<SlotMetadata>;
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus>;
+
+    /// Fetch a confirmed transaction
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>>;
+
+    /// Get confirmed signatures for the provided address, in descending ledger order
+    ///
+    /// address: address to search for
+    /// before_signature: start with the first signature older than this one
+    /// until_signature: end with the last signature more recent than this one
+    /// limit: stop after this many signatures; if limit==0, all records in the table will be read
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<
+        Vec<(
+            ConfirmedTransactionStatusWithSignature,
+            u32, /*slot index*/
+        )>,
+    >;
+
+    /// Upload a new confirmed block and associated meta data.
+    async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlock,
+    ) -> Result<()>;
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()>;
+
+    /// Upload the metadata of a slot, before its block is uploaded
+    async fn upload_slot_metadata(&self, slot: Slot, slot_metadata: SlotMetadata) -> Result<()>;
+}
+
+#[async_trait]
+impl<S: RowStore> LongTermLedgerStorage for LedgerStorage<S> {
+    async fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        LedgerStorage::<S>::get_first_available_block(self).await
+    }
+
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        LedgerStorage::<S>::get_confirmed_blocks(self, start_slot, limit).await
+    }
+
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        LedgerStorage::<S>::get_confirmed_block(self, slot).await
+    }
+
+    async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool> {
+        LedgerStorage::<S>::confirmed_block_exists(self, slot).await
+    }
+
+    async fn get_slot_metadata(&self, slot: Slot) -> Result<SlotMetadata> {
+        LedgerStorage::<S>::get_slot_metadata(self, slot).await
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        LedgerStorage::<S>::get_signature_status(self, signature).await
+    }
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
+        LedgerStorage::<S>::get_confirmed_transaction(self, signature).await
+    }
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        LedgerStorage::<S>::get_confirmed_signatures_for_address(
+            self,
+            address,
+            before_signature,
+            until_signature,
+            limit,
+        )
+        .await
+    }
+
+    async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlock,
+    ) -> Result<()> {
+        LedgerStorage::<S>::upload_confirmed_block(self, slot, confirmed_block).await
+    }
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()> {
+        LedgerStorage::<S>::upload_confirmed_block_with_entries(self, slot, confirmed_block).await
+    }
+
+    async fn upload_slot_metadata(&self, slot: Slot, slot_metadata: SlotMetadata) -> Result<()> {
+        LedgerStorage::<S>::upload_slot_metadata(self, slot, slot_metadata).await
+    }
+}
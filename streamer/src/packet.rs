@@ -7,7 +7,7 @@ use {
     std::{
         io::Result,
         net::UdpSocket,
-        time::{Duration, Instant},
+        time::{Duration, Instant, SystemTime},
     },
 };
 pub use {
@@ -18,6 +18,24 @@ pub use {
 };
 
 pub fn recv_from(batch: &mut PacketBatch, socket: &UdpSocket, max_wait: Duration) -> Result<usize> {
+    recv_from_impl(batch, socket, max_wait, false)
+}
+
+/// Like [`recv_from`], also recording when the packets were read off the socket.
+pub fn recv_from_with_receive_times(
+    batch: &mut PacketBatch,
+    socket: &UdpSocket,
+    max_wait: Duration,
+) -> Result<usize> {
+    recv_from_impl(batch, socket, max_wait, true)
+}
+
+fn recv_from_impl(
+    batch: &mut PacketBatch,
+    socket: &UdpSocket,
+    max_wait: Duration,
+    record_receive_times: bool,
+) -> Result<usize> {
     let mut i = 0;
     //DOCUMENTED SIDE-EFFECT
     //Performance out of the IO without poll
@@ -48,6 +66,9 @@ pub fn recv_from(batch: &mut PacketBatch, socket: &UdpSocket, max_wait: Duration
                     socket.set_nonblocking(true)?;
                 }
                 trace!("got {} packets", npkts);
+                if record_receive_times {
+                    batch.set_receive_time(i..i + npkts, SystemTime::now());
+                }
                 i += npkts;
                 // Try to batch into big enough buffers
                 // will cause less re-shuffling later on.
@@ -131,6 +152,20 @@ mod tests {
             assert_eq!(m.meta().size, PACKET_DATA_SIZE);
             assert_eq!(m.meta().socket_addr(), saddr);
         }
+        assert_eq!(batch.receive_time(0), None);
+
+        send_to(&batch, &send_socket, &SocketAddrSpace::Unspecified).unwrap();
+        let before = SystemTime::now();
+        let recvd = recv_from_with_receive_times(
+            &mut batch,
+            &recv_socket,
+            Duration::from_millis(1), // max_wait
+        )
+        .unwrap();
+        assert_eq!(recvd, batch.len());
+        for index in 0..recvd {
+            assert!(batch.receive_time(index).unwrap() >= before);
+        }
     }
 
     #[test]
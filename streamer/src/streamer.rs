@@ -110,6 +110,7 @@ fn recv_loop(
     coalesce: Duration,
     use_pinned_memory: bool,
     in_vote_only_mode: Option<Arc<AtomicBool>>,
+    record_receive_times: bool,
 ) -> Result<()> {
     loop {
         let mut packet_batch = if use_pinned_memory {
@@ -131,7 +132,12 @@ fn recv_loop(
                 }
             }
 
-            if let Ok(len) = packet::recv_from(&mut packet_batch, socket, coalesce) {
+            let result = if record_receive_times {
+                packet::recv_from_with_receive_times(&mut packet_batch, socket, coalesce)
+            } else {
+                packet::recv_from(&mut packet_batch, socket, coalesce)
+            };
+            if let Ok(len) = result {
                 if len > 0 {
                     let StreamerReceiveStats {
                         packets_count,
@@ -166,6 +172,60 @@ pub fn receiver(
     coalesce: Duration,
     use_pinned_memory: bool,
     in_vote_only_mode: Option<Arc<AtomicBool>>,
+) -> JoinHandle<()> {
+    spawn_receiver(
+        thread_name,
+        socket,
+        exit,
+        packet_batch_sender,
+        recycler,
+        stats,
+        coalesce,
+        use_pinned_memory,
+        in_vote_only_mode,
+        false, // record_receive_times
+    )
+}
+
+/// Like [`receiver`], also recording when the packets were read off the socket; see
+/// [`PacketBatch::receive_time`].
+pub fn receiver_with_receive_times(
+    thread_name: String,
+    socket: Arc<UdpSocket>,
+    exit: Arc<AtomicBool>,
+    packet_batch_sender: PacketBatchSender,
+    recycler: PacketBatchRecycler,
+    stats: Arc<StreamerReceiveStats>,
+    coalesce: Duration,
+    use_pinned_memory: bool,
+    in_vote_only_mode: Option<Arc<AtomicBool>>,
+) -> JoinHandle<()> {
+    spawn_receiver(
+        thread_name,
+        socket,
+        exit,
+        packet_batch_sender,
+        recycler,
+        stats,
+        coalesce,
+        use_pinned_memory,
+        in_vote_only_mode,
+        true, // record_receive_times
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_receiver(
+    thread_name: String,
+    socket: Arc<UdpSocket>,
+    exit: Arc<AtomicBool>,
+    packet_batch_sender: PacketBatchSender,
+    recycler: PacketBatchRecycler,
+    stats: Arc<StreamerReceiveStats>,
+    coalesce: Duration,
+    use_pinned_memory: bool,
+    in_vote_only_mode: Option<Arc<AtomicBool>>,
+    record_receive_times: bool,
 ) -> JoinHandle<()> {
     let res = socket.set_read_timeout(Some(Duration::new(1, 0)));
     assert!(res.is_ok(), "streamer::receiver set_read_timeout error");
@@ -181,6 +241,7 @@ pub fn receiver(
                 coalesce,
                 use_pinned_memory,
                 in_vote_only_mode,
+                record_receive_times,
             );
         })
         .unwrap()
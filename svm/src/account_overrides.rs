@@ -1,12 +1,14 @@
 use {
+    solana_program_runtime::sysvar_cache::SysvarCache,
     solana_sdk::{account::AccountSharedData, pubkey::Pubkey, sysvar},
-    std::collections::HashMap,
+    std::{collections::HashMap, sync::Arc},
 };
 
 /// Encapsulates overridden accounts, typically used for transaction simulations
 #[derive(Default)]
 pub struct AccountOverrides {
     accounts: HashMap<Pubkey, AccountSharedData>,
+    sysvar_cache: Option<Arc<SysvarCache>>,
 }
 
 impl AccountOverrides {
@@ -29,13 +31,29 @@ impl AccountOverrides {
     pub fn get(&self, pubkey: &Pubkey) -> Option<&AccountSharedData> {
         self.accounts.get(pubkey)
     }
+
+    /// Sets the sysvar cache used in place of the bank's one
+    ///
+    /// Syscalls read sysvars from the sysvar cache rather than from the accounts
+    /// of the transaction, so overriding a sysvar account also requires a sysvar
+    /// cache that reflects it.
+    pub fn set_sysvar_cache(&mut self, sysvar_cache: Option<Arc<SysvarCache>>) {
+        self.sysvar_cache = sysvar_cache;
+    }
+
+    /// Gets the sysvar cache to use in place of the bank's one, if any
+    pub fn sysvar_cache(&self) -> Option<&SysvarCache> {
+        self.sysvar_cache.as_deref()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use {
         crate::account_overrides::AccountOverrides,
+        solana_program_runtime::sysvar_cache::SysvarCache,
         solana_sdk::{account::AccountSharedData, pubkey::Pubkey, sysvar},
+        std::sync::Arc,
     };
 
     #[test]
@@ -60,4 +78,16 @@ mod test {
 
         assert_eq!(accounts.get(&sysvar::slot_history::id()), Some(&data));
     }
+
+    #[test]
+    fn test_sysvar_cache() {
+        let mut accounts = AccountOverrides::default();
+        assert!(accounts.sysvar_cache().is_none());
+
+        accounts.set_sysvar_cache(Some(Arc::new(SysvarCache::default())));
+        assert!(accounts.sysvar_cache().is_some());
+
+        accounts.set_sysvar_cache(None);
+        assert!(accounts.sysvar_cache().is_none());
+    }
 }
@@ -7,7 +7,8 @@ use {
         transaction_account_state_info::TransactionAccountStateInfo,
         transaction_error_metrics::TransactionErrorMetrics,
         transaction_results::{
-            DurableNonceFee, TransactionExecutionDetails, TransactionExecutionResult,
+            DurableNonceFee, InvocationTrace, InvocationTraceNode, TransactionExecutionDetails,
+            TransactionExecutionResult,
         },
     },
     log::debug,
@@ -15,6 +16,7 @@ use {
     solana_measure::measure::Measure,
     solana_program_runtime::{
         compute_budget::ComputeBudget,
+        invocation_recorder::{InvocationRecord, InvocationRecorder},
         loaded_programs::{
             ForkGraph, LoadProgramMetrics, LoadedProgram, LoadedProgramMatchCriteria,
             LoadedProgramType, LoadedProgramsForTxBatch, ProgramCache, ProgramRuntimeEnvironment,
@@ -71,14 +73,18 @@ pub struct ExecutionRecordingConfig {
     pub enable_cpi_recording: bool,
     pub enable_log_recording: bool,
     pub enable_return_data_recording: bool,
+    pub enable_invocation_trace_recording: bool,
 }
 
 impl ExecutionRecordingConfig {
+    /// Applies `option` to everything except the invocation trace, which is
+    /// only meant for simulation and is always disabled
     pub fn new_single_setting(option: bool) -> Self {
         ExecutionRecordingConfig {
             enable_return_data_recording: option,
             enable_log_recording: option,
             enable_cpi_recording: option,
+            enable_invocation_trace_recording: false,
         }
     }
 }
@@ -246,6 +252,8 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
 
         let mut execution_time = Measure::start("execution_time");
 
+        let sysvar_cache_override = account_overrides.and_then(AccountOverrides::sysvar_cache);
+
         let execution_results: Vec<TransactionExecutionResult> = loaded_transactions
             .iter_mut()
             .zip(sanitized_txs.iter())
@@ -285,6 +293,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                         error_counters,
                         log_messages_bytes_limit,
                         &programs_loaded_for_tx_batch.borrow(),
+                        sysvar_cache_override,
                     );
 
                     if let TransactionExecutionResult::Executed {
@@ -561,6 +570,24 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         loaded_programs_for_txs.unwrap()
     }
 
+    /// Build a sysvar cache from the bank's sysvar accounts, with any sysvars in
+    /// `sysvar_overrides` taking precedence, see [`AccountOverrides::set_sysvar_cache`]
+    pub fn sysvar_cache_with_overrides<CB: TransactionProcessingCallback>(
+        &self,
+        callbacks: &CB,
+        sysvar_overrides: &HashMap<Pubkey, AccountSharedData>,
+    ) -> SysvarCache {
+        let mut sysvar_cache = SysvarCache::default();
+        sysvar_cache.fill_missing_entries(|pubkey, set_sysvar| {
+            if let Some(account) = sysvar_overrides.get(pubkey) {
+                set_sysvar(account.data());
+            } else if let Some(account) = callbacks.get_account_shared_data(pubkey) {
+                set_sysvar(account.data());
+            }
+        });
+        sysvar_cache
+    }
+
     /// Execute a transaction using the provided loaded accounts and update
     /// the executors cache if the transaction was successful.
     #[allow(clippy::too_many_arguments)]
@@ -576,6 +603,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         error_counters: &mut TransactionErrorMetrics,
         log_messages_bytes_limit: Option<usize>,
         programs_loaded_for_tx_batch: &LoadedProgramsForTxBatch,
+        sysvar_cache_override: Option<&SysvarCache>,
     ) -> TransactionExecutionResult {
         let transaction_accounts = std::mem::take(&mut loaded_transaction.accounts);
 
@@ -619,6 +647,9 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         } else {
             None
         };
+        let invocation_recorder = recording_config
+            .enable_invocation_trace_recording
+            .then(InvocationRecorder::new_ref);
 
         let (blockhash, lamports_per_signature) =
             callback.get_last_blockhash_and_lamports_per_signature();
@@ -628,23 +659,26 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             self.slot,
             programs_loaded_for_tx_batch.environments.clone(),
         );
+        let sysvar_cache = self.sysvar_cache.read().unwrap();
         let mut process_message_time = Measure::start("process_message_time");
         let process_result = MessageProcessor::process_message(
             tx.message(),
             &loaded_transaction.program_indices,
             &mut transaction_context,
             log_collector.clone(),
+            invocation_recorder.clone(),
             programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             callback.get_feature_set(),
             compute_budget,
             timings,
-            &self.sysvar_cache.read().unwrap(),
+            sysvar_cache_override.unwrap_or(&sysvar_cache),
             blockhash,
             lamports_per_signature,
             &mut executed_units,
         );
         process_message_time.stop();
+        drop(sysvar_cache);
 
         saturating_add_assign!(
             timings.execute_accessories.process_message_us,
@@ -696,6 +730,15 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             None
         };
 
+        let invocation_trace = invocation_recorder
+            .and_then(|invocation_recorder| Rc::try_unwrap(invocation_recorder).ok())
+            .map(|invocation_recorder| {
+                Self::invocation_trace_from_instruction_trace(
+                    &transaction_context,
+                    invocation_recorder.into_inner().into_records(),
+                )
+            });
+
         let ExecutionRecord {
             accounts,
             return_data,
@@ -733,6 +776,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                 inner_instructions,
                 durable_nonce_fee,
                 return_data,
+                invocation_trace,
                 executed_units,
                 accounts_data_len_delta,
             },
@@ -920,6 +964,84 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         }
         outer_instructions
     }
+
+    /// Build the InvocationTrace of a TransactionContext from the records of its invocations
+    fn invocation_trace_from_instruction_trace(
+        transaction_context: &TransactionContext,
+        records: Vec<InvocationRecord>,
+    ) -> InvocationTrace {
+        let instruction_trace_length = transaction_context.get_instruction_trace_length();
+        let mut records_in_trace = vec![None; instruction_trace_length];
+        for record in records {
+            if let Some(entry) = records_in_trace.get_mut(record.index_in_trace) {
+                *entry = Some(record);
+            }
+        }
+
+        fn attach_last(
+            open_invocations: &mut Vec<InvocationTraceNode>,
+            invocation_trace: &mut InvocationTrace,
+        ) {
+            if let Some(node) = open_invocations.pop() {
+                match open_invocations.last_mut() {
+                    Some(parent) => parent.inner_invocations.push(node),
+                    None => invocation_trace.push(node),
+                }
+            }
+        }
+
+        let mut invocation_trace = Vec::new();
+        let mut open_invocations: Vec<InvocationTraceNode> = Vec::new();
+        for (index_in_trace, record) in records_in_trace.into_iter().enumerate() {
+            let Ok(instruction_context) =
+                transaction_context.get_instruction_context_at_index_in_trace(index_in_trace)
+            else {
+                debug_assert!(false);
+                continue;
+            };
+            let stack_height = instruction_context.get_stack_height();
+            while open_invocations.len() >= stack_height.max(TRANSACTION_LEVEL_STACK_HEIGHT) {
+                attach_last(&mut open_invocations, &mut invocation_trace);
+            }
+            let program_id = instruction_context
+                .get_last_program_key(transaction_context)
+                .copied()
+                .unwrap_or_default();
+            let accounts = (0..instruction_context.get_number_of_instruction_accounts())
+                .filter_map(|instruction_account_index| {
+                    instruction_context
+                        .get_index_of_instruction_account_in_transaction(instruction_account_index)
+                        .and_then(|index_in_transaction| {
+                            transaction_context.get_key_of_account_at_index(index_in_transaction)
+                        })
+                        .ok()
+                        .copied()
+                })
+                .collect();
+            let (compute_units_consumed, return_data, error) = record
+                .map(|record| {
+                    (
+                        record.compute_units_consumed,
+                        record.return_data,
+                        record.error,
+                    )
+                })
+                .unwrap_or_default();
+            open_invocations.push(InvocationTraceNode {
+                program_id,
+                accounts,
+                stack_height,
+                compute_units_consumed,
+                return_data,
+                error,
+                inner_invocations: Vec::new(),
+            });
+        }
+        while !open_invocations.is_empty() {
+            attach_last(&mut open_invocations, &mut invocation_trace);
+        }
+        invocation_trace
+    }
 }
 
 #[cfg(test)]
@@ -1039,6 +1161,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_invocation_trace_from_instruction_trace() {
+        let instruction_trace = [1, 2, 1, 1, 2, 3, 2];
+        let mut transaction_context =
+            TransactionContext::new(vec![], Rent::default(), 3, instruction_trace.len());
+        for (index_in_trace, stack_height) in instruction_trace.into_iter().enumerate() {
+            while stack_height <= transaction_context.get_instruction_context_stack_height() {
+                transaction_context.pop().unwrap();
+            }
+            if stack_height > transaction_context.get_instruction_context_stack_height() {
+                transaction_context
+                    .get_next_instruction_context()
+                    .unwrap()
+                    .configure(&[], &[], &[index_in_trace as u8]);
+                transaction_context.push().unwrap();
+            }
+        }
+        // Records arrive in completion order, precompiles (index 2 here) produce none
+        let records = [6, 5, 4, 3, 1, 0]
+            .into_iter()
+            .map(|index_in_trace| InvocationRecord {
+                index_in_trace,
+                compute_units_consumed: index_in_trace as u64 * 10,
+                return_data: None,
+                error: (index_in_trace == 5).then_some(InstructionError::GenericError),
+            })
+            .collect();
+        let invocation_trace =
+            TransactionBatchProcessor::<TestForkGraph>::invocation_trace_from_instruction_trace(
+                &transaction_context,
+                records,
+            );
+
+        let node =
+            |stack_height, compute_units_consumed, error, inner_invocations| InvocationTraceNode {
+                program_id: Pubkey::default(),
+                accounts: vec![],
+                stack_height,
+                compute_units_consumed,
+                return_data: None,
+                error,
+                inner_invocations,
+            };
+        assert_eq!(
+            invocation_trace,
+            vec![
+                node(1, 0, None, vec![node(2, 10, None, vec![])]),
+                node(1, 0, None, vec![]),
+                node(
+                    1,
+                    30,
+                    None,
+                    vec![
+                        node(
+                            2,
+                            40,
+                            None,
+                            vec![node(3, 50, Some(InstructionError::GenericError), vec![])]
+                        ),
+                        node(2, 60, None, vec![]),
+                    ]
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_load_program_accounts_account_not_found() {
         let mut mock_bank = MockBankCallback::default();
@@ -1647,6 +1835,7 @@ mod tests {
             enable_cpi_recording: false,
             enable_log_recording: true,
             enable_return_data_recording: false,
+            enable_invocation_trace_recording: false,
         };
 
         let result = batch_processor.execute_loaded_transaction(
@@ -1660,6 +1849,7 @@ mod tests {
             &mut TransactionErrorMetrics::default(),
             None,
             &loaded_programs,
+            None,
         );
 
         let TransactionExecutionResult::Executed {
@@ -1682,6 +1872,7 @@ mod tests {
             &mut TransactionErrorMetrics::default(),
             Some(2),
             &loaded_programs,
+            None,
         );
 
         let TransactionExecutionResult::Executed {
@@ -1713,6 +1904,7 @@ mod tests {
             &mut TransactionErrorMetrics::default(),
             None,
             &loaded_programs,
+            None,
         );
 
         let TransactionExecutionResult::Executed {
@@ -1787,6 +1979,7 @@ mod tests {
             &mut error_metrics,
             None,
             &loaded_programs,
+            None,
         );
 
         assert_eq!(error_metrics.instruction_error, 1);
@@ -7,7 +7,9 @@ pub use solana_sdk::inner_instruction::{InnerInstruction, InnerInstructionsList}
 use {
     solana_program_runtime::loaded_programs::LoadedProgramsForTxBatch,
     solana_sdk::{
+        instruction::InstructionError,
         nonce_info::{NonceFull, NonceInfo},
+        pubkey::Pubkey,
         rent_debits::RentDebits,
         transaction::{self, TransactionError},
         transaction_context::TransactionReturnData,
@@ -76,12 +78,31 @@ pub struct TransactionExecutionDetails {
     pub inner_instructions: Option<InnerInstructionsList>,
     pub durable_nonce_fee: Option<DurableNonceFee>,
     pub return_data: Option<TransactionReturnData>,
+    pub invocation_trace: Option<InvocationTrace>,
     pub executed_units: u64,
     /// The change in accounts data len for this transaction.
     /// NOTE: This value is valid IFF `status` is `Ok`.
     pub accounts_data_len_delta: i64,
 }
 
+/// The top-level invocations of a transaction, each with its nested invocations
+pub type InvocationTrace = Vec<InvocationTraceNode>;
+
+/// A single program invocation made while executing a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvocationTraceNode {
+    pub program_id: Pubkey,
+    pub accounts: Vec<Pubkey>,
+    pub stack_height: usize,
+    /// Compute units consumed by this invocation, including its inner invocations
+    pub compute_units_consumed: u64,
+    pub return_data: Option<TransactionReturnData>,
+    /// The error this invocation returned, if any. The failing frame is the
+    /// deepest invocation carrying an error.
+    pub error: Option<InstructionError>,
+    pub inner_invocations: Vec<InvocationTraceNode>,
+}
+
 #[derive(Debug, Clone)]
 pub enum DurableNonceFee {
     Valid(u64),
@@ -240,6 +240,7 @@ fn svm_integration() {
         enable_log_recording: true,
         enable_return_data_recording: false,
         enable_cpi_recording: false,
+        enable_invocation_trace_recording: false,
     };
     let mut timings = ExecuteTimings::default();
 
@@ -251,6 +251,32 @@ pub fn map_inner_instructions(
         .filter(|i| !i.instructions.is_empty())
 }
 
+/// Returns the distinct programs invoked by the instructions of a transaction
+/// and, when they were recorded, by its inner instructions. Programs called at
+/// the top level come first in instruction order, followed by the ones only
+/// reached through cross-program invocations
+pub fn invoked_program_ids(
+    account_keys: &AccountKeys,
+    instructions: &[CompiledInstruction],
+    inner_instructions: Option<&[InnerInstructions]>,
+) -> Vec<Pubkey> {
+    let inner_instructions = inner_instructions
+        .into_iter()
+        .flatten()
+        .flat_map(|inner_instructions| &inner_instructions.instructions)
+        .map(|inner_instruction| &inner_instruction.instruction);
+
+    let mut program_ids = vec![];
+    for instruction in instructions.iter().chain(inner_instructions) {
+        if let Some(program_id) = account_keys.get(usize::from(instruction.program_id_index)) {
+            if !program_ids.contains(program_id) {
+                program_ids.push(*program_id);
+            }
+        }
+    }
+    program_ids
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UiInnerInstructions {
@@ -974,6 +1000,16 @@ impl VersionedTransactionWithStatusMeta {
         )
     }
 
+    /// Returns the distinct programs invoked by this transaction, including the
+    /// ones only reached through cross-program invocations
+    pub fn invoked_program_ids(&self) -> Vec<Pubkey> {
+        invoked_program_ids(
+            &self.account_keys(),
+            self.transaction.message.instructions(),
+            self.meta.inner_instructions.as_deref(),
+        )
+    }
+
     fn build_json_accounts(
         self,
         max_supported_transaction_version: Option<u8>,
@@ -1583,4 +1619,41 @@ mod test {
             expected_json_output_value
         );
     }
+
+    #[test]
+    fn test_invoked_program_ids() {
+        let payer = Pubkey::new_unique();
+        let program0 = Pubkey::new_unique();
+        let program1 = Pubkey::new_unique();
+        let cpi_program = Pubkey::new_unique();
+        let account_keys = [payer, program1, program0, cpi_program];
+        let account_keys = AccountKeys::new(&account_keys, None);
+        let instructions = vec![
+            CompiledInstruction::new(2, &(), vec![0]),
+            CompiledInstruction::new(1, &(), vec![0]),
+            CompiledInstruction::new(2, &(), vec![0]),
+        ];
+        assert_eq!(
+            invoked_program_ids(&account_keys, &instructions, None),
+            vec![program0, program1]
+        );
+
+        let inner_instructions = vec![InnerInstructions {
+            index: 0,
+            instructions: vec![
+                InnerInstruction {
+                    instruction: CompiledInstruction::new(3, &(), vec![0]),
+                    stack_height: Some(2),
+                },
+                InnerInstruction {
+                    instruction: CompiledInstruction::new(1, &(), vec![0]),
+                    stack_height: Some(2),
+                },
+            ],
+        }];
+        assert_eq!(
+            invoked_program_ids(&account_keys, &instructions, Some(&inner_instructions)),
+            vec![program0, program1, cpi_program]
+        );
+    }
 }
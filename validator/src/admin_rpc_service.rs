@@ -20,6 +20,7 @@ use {
     solana_rpc::rpc::verify_pubkey,
     solana_rpc_client_api::{config::RpcAccountIndex, custom_error::RpcCustomError},
     solana_sdk::{
+        clock::Slot,
         exit::Exit,
         pubkey::Pubkey,
         signature::{read_keypair_file, Keypair, Signer},
@@ -32,7 +33,7 @@ use {
         path::{Path, PathBuf},
         sync::{Arc, RwLock},
         thread::{self, Builder},
-        time::{Duration, SystemTime},
+        time::{Duration, Instant, SystemTime},
     },
     tokio::runtime::Runtime,
 };
@@ -155,6 +156,14 @@ pub trait AdminRpc {
         config_file: String,
     ) -> BoxFuture<Result<()>>;
 
+    #[rpc(meta, name = "updatePluginConfig")]
+    fn update_plugin_config(
+        &self,
+        meta: Self::Metadata,
+        name: String,
+        config_file: String,
+    ) -> BoxFuture<Result<()>>;
+
     #[rpc(meta, name = "unloadPlugin")]
     fn unload_plugin(&self, meta: Self::Metadata, name: String) -> BoxFuture<Result<()>>;
 
@@ -243,6 +252,14 @@ pub trait AdminRpc {
         meta: Self::Metadata,
         public_tpu_forwards_addr: SocketAddr,
     ) -> Result<()>;
+
+    #[rpc(meta, name = "compactBlockstoreColumn")]
+    fn compact_blockstore_column(
+        &self,
+        meta: Self::Metadata,
+        column: String,
+        slot_range: Option<(Slot, Slot)>,
+    ) -> Result<()>;
 }
 
 pub struct AdminRpcImpl;
@@ -308,6 +325,40 @@ impl AdminRpc for AdminRpcImpl {
         })
     }
 
+    fn update_plugin_config(
+        &self,
+        meta: Self::Metadata,
+        name: String,
+        config_file: String,
+    ) -> BoxFuture<Result<()>> {
+        Box::pin(async move {
+            // Construct channel for plugin to respond to this particular rpc request instance
+            let (response_sender, response_receiver) = oneshot_channel();
+
+            // Send request to plugin manager if there is a geyser service
+            if let Some(ref rpc_to_manager_sender) = meta.rpc_to_plugin_manager_sender {
+                rpc_to_manager_sender
+                    .send(GeyserPluginManagerRequest::UpdatePluginConfig {
+                        name,
+                        config_file,
+                        response_sender,
+                    })
+                    .expect("GeyerPluginService should never drop request receiver");
+            } else {
+                return Err(jsonrpc_core::Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: "No geyser plugin service".to_string(),
+                    data: None,
+                });
+            }
+
+            // Await response from plugin manager
+            response_receiver
+                .await
+                .expect("GeyerPluginService's oneshot sender shouldn't drop early")
+        })
+    }
+
     fn load_plugin(&self, meta: Self::Metadata, config_file: String) -> BoxFuture<Result<String>> {
         Box::pin(async move {
             // Construct channel for plugin to respond to this particular rpc request instance
@@ -674,6 +725,51 @@ impl AdminRpc for AdminRpcImpl {
             Ok(())
         })
     }
+
+    fn compact_blockstore_column(
+        &self,
+        meta: Self::Metadata,
+        column: String,
+        slot_range: Option<(Slot, Slot)>,
+    ) -> Result<()> {
+        debug!("compact_blockstore_column rpc request received: {column} {slot_range:?}");
+
+        if let Some((from_slot, to_slot)) = slot_range {
+            if from_slot > to_slot {
+                return Err(jsonrpc_core::error::Error::invalid_params(format!(
+                    "Starting slot {from_slot} is greater than ending slot {to_slot}"
+                )));
+            }
+        }
+        meta.with_post_init(|post_init| {
+            post_init
+                .blockstore
+                .check_compact_column(&column, slot_range)
+                .map_err(|err| jsonrpc_core::error::Error::invalid_params(format!("{err}")))?;
+
+            // Compacting a large column can take a long time, so it runs in the
+            // background rather than blocking the admin RPC server
+            let blockstore = post_init.blockstore.clone();
+            thread::Builder::new()
+                .name("solCompactCol".into())
+                .spawn(move || {
+                    info!("Compacting blockstore column {column}, slot range {slot_range:?}");
+                    let compact_start = Instant::now();
+                    match blockstore.compact_column(&column, slot_range) {
+                        Ok(()) => info!(
+                            "Compacted blockstore column {column} in {:?}",
+                            compact_start.elapsed()
+                        ),
+                        Err(err) => error!("Failed to compact blockstore column {column}: {err}"),
+                    }
+                })
+                .map_err(|err| {
+                    error!("Failed to spawn the blockstore column compaction thread: {err}");
+                    jsonrpc_core::error::Error::internal_error()
+                })?;
+            Ok(())
+        })
+    }
 }
 
 impl AdminRpcImpl {
@@ -863,7 +959,11 @@ mod tests {
         solana_accounts_db::{accounts_index::AccountSecondaryIndexes, inline_spl_token},
         solana_core::consensus::tower_storage::NullTowerStorage,
         solana_gossip::cluster_info::ClusterInfo,
-        solana_ledger::genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        solana_ledger::{
+            blockstore::Blockstore,
+            genesis_utils::{create_genesis_config, GenesisConfigInfo},
+            get_tmp_ledger_path_auto_delete,
+        },
         solana_rpc::rpc::create_validator_exit,
         solana_runtime::{
             bank::{Bank, BankTestConfig},
@@ -880,6 +980,7 @@ mod tests {
             state::{Account as TokenAccount, AccountState as TokenAccountState, Mint},
         },
         std::{collections::HashSet, sync::atomic::AtomicBool},
+        tempfile::TempDir,
     };
 
     #[derive(Default)]
@@ -891,10 +992,12 @@ mod tests {
         io: MetaIoHandler<AdminRpcRequestMetadata>,
         meta: AdminRpcRequestMetadata,
         bank_forks: Arc<RwLock<BankForks>>,
+        blockstore: Arc<Blockstore>,
+        _ledger_path: TempDir,
     }
 
     impl RpcHandler {
-        fn _start() -> Self {
+        fn start() -> Self {
             Self::start_with_config(TestConfig::default())
         }
 
@@ -917,6 +1020,8 @@ mod tests {
             let vote_account = vote_keypair.pubkey();
             let start_progress = Arc::new(RwLock::new(ValidatorStartProgress::default()));
             let repair_whitelist = Arc::new(RwLock::new(HashSet::new()));
+            let ledger_path = get_tmp_ledger_path_auto_delete!();
+            let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
             let meta = AdminRpcRequestMetadata {
                 rpc_addr: None,
                 start_time: SystemTime::now(),
@@ -937,6 +1042,7 @@ mod tests {
                     cluster_slots: Arc::new(
                         solana_core::cluster_slots_service::cluster_slots::ClusterSlots::default(),
                     ),
+                    blockstore: blockstore.clone(),
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
@@ -948,6 +1054,8 @@ mod tests {
                 io,
                 meta,
                 bank_forks,
+                blockstore,
+                _ledger_path: ledger_path,
             }
         }
 
@@ -1274,4 +1382,38 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_compact_blockstore_column() {
+        let rpc = RpcHandler::start();
+        rpc.blockstore.set_roots([1, 2, 3].iter()).unwrap();
+        let RpcHandler { io, meta, .. } = rpc;
+
+        let compact = |params: &str| -> Value {
+            let req = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"compactBlockstoreColumn","params":{params}}}"#,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization")
+        };
+
+        let result = compact(r#"["root", null]"#);
+        assert!(result["error"].is_null());
+        let result = compact(r#"["root", [1, 2]]"#);
+        assert!(result["error"].is_null());
+
+        for params in [
+            r#"["unknown", null]"#,
+            r#"["address_signatures", [1, 2]]"#,
+            r#"["root", [2, 1]]"#,
+        ] {
+            let result = compact(params);
+            assert_eq!(
+                result["error"]["code"],
+                ErrorCode::InvalidParams.code(),
+                "{params}"
+            );
+        }
+    }
 }
@@ -468,6 +468,7 @@ fn main() {
     genesis.rpc_config(JsonRpcConfig {
         enable_rpc_transaction_history: true,
         enable_extended_tx_metadata_storage: true,
+        enable_program_signatures_index: true,
         rpc_bigtable_config,
         faucet_addr: Some(faucet_addr),
         account_indexes,
@@ -27,7 +27,10 @@ use {
     solana_ledger::use_snapshot_archives_at_startup,
     solana_net_utils::{MINIMUM_VALIDATOR_PORT_RANGE_WIDTH, VALIDATOR_PORT_RANGE},
     solana_rayon_threadlimit::get_thread_count,
-    solana_rpc::{rpc::MAX_REQUEST_BODY_SIZE, rpc_pubsub_service::PubSubConfig},
+    solana_rpc::{
+        rpc::MAX_REQUEST_BODY_SIZE,
+        rpc_pubsub_service::PubSubConfig,
+    },
     solana_rpc_client_api::request::MAX_MULTIPLE_ACCOUNTS,
     solana_runtime::{
         snapshot_bank_utils::{
@@ -250,6 +253,19 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .takes_value(false)
                 .help("Upload new confirmed blocks into a BigTable instance"),
         )
+        .arg(
+            Arg::with_name("rpc_local_ledger_storage")
+                .long("rpc-local-ledger-storage")
+                .requires("enable_rpc_transaction_history")
+                .value_name("DIR")
+                .takes_value(true)
+                .help(
+                    "Keep long-term ledger storage in a local embedded store at DIR instead of \
+                     a BigTable instance. Historical transaction info is served from it, and \
+                     new confirmed blocks are uploaded into it with \
+                     --enable-bigtable-ledger-upload",
+                ),
+        )
         .arg(
             Arg::with_name("enable_extended_tx_metadata_storage")
                 .long("enable-extended-tx-metadata-storage")
@@ -260,6 +276,52 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                      transaction info stored",
                 ),
         )
+        .arg(
+            Arg::with_name("enable_transaction_logs_storage")
+                .long("enable-transaction-logs-storage")
+                .requires("enable_rpc_transaction_history")
+                .takes_value(false)
+                .help(
+                    "Store transaction logs in a separate ledger column instead of inline with \
+                     the historical transaction info, and load them only when a transaction is \
+                     fetched",
+                ),
+        )
+        .arg(
+            Arg::with_name("transaction_logs_storage_bytes_limit")
+                .long("transaction-logs-storage-bytes-limit")
+                .requires("enable_transaction_logs_storage")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .value_name("BYTES")
+                .help(
+                    "Maximum number of bytes of program logs stored per transaction, applied in \
+                     addition to --log-messages-bytes-limit",
+                ),
+        )
+        .arg(
+            Arg::with_name("transaction_logs_retention_slots")
+                .long("transaction-logs-retention-slots")
+                .requires("enable_transaction_logs_storage")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .value_name("SLOTS")
+                .help(
+                    "Keep stored transaction logs only for this many slots behind the latest \
+                     root [default: as long as the rest of the ledger]",
+                ),
+        )
+        .arg(
+            Arg::with_name("enable_program_signatures_index")
+                .long("enable-program-signatures-index")
+                .requires("enable_rpc_transaction_history")
+                .takes_value(false)
+                .help(
+                    "Index historical transactions by the programs they invoke, including \
+                     through cross-program invocations, to serve the getSignaturesForProgram \
+                     RPC method",
+                ),
+        )
         .arg(
             Arg::with_name("rpc_max_multiple_accounts")
                 .long("rpc-max-multiple-accounts")
@@ -634,6 +696,24 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 /* .default_value() intentionally not used here! */
                 .help("Keep this amount of shreds in root slots."),
         )
+        .arg(
+            Arg::with_name("limit_transaction_history_slots")
+                .long("limit-transaction-history-slots")
+                .value_name("SLOTS")
+                .takes_value(true)
+                .requires("limit_ledger_size")
+                .validator(is_parsable::<u64>)
+                .help(
+                    "Keep the signatures and statuses of transactions (statuses, address \
+                     signatures, rewards and block times) for this many slots behind the latest \
+                     root, independently of the shreds limited by --limit-ledger-size. This \
+                     serves getSignaturesForAddress and getSignatureStatuses with \
+                     searchTransactionHistory for longer. The history is never purged before \
+                     the shreds of its slots. Transactions and blocks are still loaded from the \
+                     shreds, so getTransaction and getBlock fall back to long-term ledger \
+                     storage for slots whose shreds are purged.",
+                ),
+        )
         .arg(
             Arg::with_name("rocksdb_shred_compaction")
                 .long("rocksdb-shred-compaction")
@@ -676,6 +756,19 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                      Turning on compression can save ~10% of the ledger size.",
                 ),
         )
+        .arg(
+            Arg::with_name("rocksdb_transaction_logs_compression")
+                .hidden(hidden_unless_forced())
+                .long("rocksdb-transaction-logs-compression")
+                .value_name("COMPRESSION_TYPE")
+                .takes_value(true)
+                .possible_values(&["none", "lz4", "snappy", "zlib"])
+                .default_value(&default_args.rocksdb_transaction_logs_compression)
+                .help(
+                    "The compression algorithm that is used to compress transaction logs stored \
+                     with --enable-transaction-logs-storage",
+                ),
+        )
         .arg(
             Arg::with_name("rocksdb_perf_sample_interval")
                 .hidden(hidden_unless_forced())
@@ -1195,6 +1288,17 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(&default_args.rpc_max_request_body_size)
                 .help("The maximum request body size accepted by rpc service"),
         )
+        .arg(
+            Arg::with_name("rpc_max_batch_size")
+                .long("rpc-max-batch-size")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .help(
+                    "The maximum number of calls in a batch request accepted by rpc service \
+                     [default: unlimited]",
+                ),
+        )
         .arg(
             Arg::with_name("geyser_plugin_config")
                 .long("geyser-plugin-config")
@@ -1643,6 +1747,43 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         ),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("compact-blockstore-column")
+                .about("Compact a column family of the validator's blockstore")
+                .arg(
+                    Arg::with_name("column")
+                        .index(1)
+                        .value_name("COLUMN")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Name of the column family to compact, e.g. address_signatures"),
+                )
+                .arg(
+                    Arg::with_name("starting_slot")
+                        .long("starting-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .requires("ending_slot")
+                        .validator(is_slot)
+                        .help(
+                            "Only compact the entries of the slots from this one. Only supported \
+                             by the columns keyed by slot",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("ending_slot")
+                        .long("ending-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .requires("starting_slot")
+                        .validator(is_slot)
+                        .help("Only compact the entries of the slots up to this one, inclusive"),
+                )
+                .after_help(
+                    "Note: the command returns once the compaction has started in the \
+                     background. Its completion is reported in the validator log",
+                ),
+        )
         .subcommand(
             SubCommand::with_name("contact-info")
                 .about("Display the validator's contact info")
@@ -1758,6 +1899,16 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         .arg(Arg::with_name("name").required(true).takes_value(true))
                         .arg(Arg::with_name("config").required(true).takes_value(true)),
                 )
+                .subcommand(
+                    SubCommand::with_name("update-config")
+                        .about(
+                            "Pass a new config to a particular gesyer plugin, which applies it \
+                             without being unloaded. You must specify the gesyer plugin name and \
+                             the new config path",
+                        )
+                        .arg(Arg::with_name("name").required(true).takes_value(true))
+                        .arg(Arg::with_name("config").required(true).takes_value(true)),
+                )
                 .subcommand(
                     SubCommand::with_name("load")
                         .about(
@@ -2178,6 +2329,7 @@ pub struct DefaultArgs {
 
     pub rocksdb_shred_compaction: String,
     pub rocksdb_ledger_compression: String,
+    pub rocksdb_transaction_logs_compression: String,
     pub rocksdb_perf_sample_interval: String,
 
     pub accounts_shrink_optimize_total_space: String,
@@ -2265,6 +2417,7 @@ impl DefaultArgs {
             snapshot_version: SnapshotVersion::default(),
             rocksdb_shred_compaction: "level".to_string(),
             rocksdb_ledger_compression: "none".to_string(),
+            rocksdb_transaction_logs_compression: "none".to_string(),
             rocksdb_perf_sample_interval: "0".to_string(),
             accounts_shrink_optimize_total_space: DEFAULT_ACCOUNTS_SHRINK_OPTIMIZE_TOTAL_SPACE
                 .to_string(),
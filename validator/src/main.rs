@@ -42,6 +42,7 @@ use {
     solana_rpc::{
         rpc::{JsonRpcConfig, RpcBigtableConfig},
         rpc_pubsub_service::PubSubConfig,
+        transaction_status_service::TransactionLogsStorageConfig,
     },
     solana_rpc_client::rpc_client::RpcClient,
     solana_rpc_client_api::config::RpcLeaderScheduleConfig,
@@ -618,6 +619,28 @@ pub fn main() {
                     }
                     return;
                 }
+                ("update-config", Some(subcommand_matches)) => {
+                    if let Ok(name) = value_t!(subcommand_matches, "name", String) {
+                        if let Ok(config) = value_t!(subcommand_matches, "config", String) {
+                            let admin_client = admin_rpc_service::connect(&ledger_path);
+                            admin_rpc_service::runtime()
+                                .block_on(async {
+                                    admin_client
+                                        .await?
+                                        .update_plugin_config(name.clone(), config.clone())
+                                        .await
+                                })
+                                .unwrap_or_else(|err| {
+                                    println!(
+                                        "Failed to update the config of plugin {name}: {err:?}"
+                                    );
+                                    exit(1);
+                                });
+                            println!("Successfully updated the config of plugin: {name}");
+                        }
+                    }
+                    return;
+                }
                 _ => unreachable!(),
             }
         }
@@ -793,6 +816,25 @@ pub fn main() {
             });
             return;
         }
+        ("compact-blockstore-column", Some(subcommand_matches)) => {
+            let column = value_t_or_exit!(subcommand_matches, "column", String);
+            let slot_range = value_t!(subcommand_matches, "starting_slot", Slot)
+                .ok()
+                .zip(value_t!(subcommand_matches, "ending_slot", Slot).ok());
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move {
+                    admin_client
+                        .await?
+                        .compact_blockstore_column(column, slot_range)
+                        .await
+                })
+                .unwrap_or_else(|err| {
+                    println!("compact blockstore column failed: {err}");
+                    exit(1);
+                });
+            return;
+        }
         ("repair-shred-from-peer", Some(subcommand_matches)) => {
             let pubkey = value_t!(subcommand_matches, "pubkey", Pubkey).ok();
             let slot = value_t_or_exit!(subcommand_matches, "slot", u64);
@@ -1266,6 +1308,7 @@ pub fn main() {
 
     let rpc_bigtable_config = if matches.is_present("enable_rpc_bigtable_ledger_storage")
         || matches.is_present("enable_bigtable_ledger_upload")
+        || matches.is_present("rpc_local_ledger_storage")
     {
         Some(RpcBigtableConfig {
             enable_bigtable_ledger_upload: matches.is_present("enable_bigtable_ledger_upload"),
@@ -1279,6 +1322,7 @@ pub fn main() {
                 .ok()
                 .map(Duration::from_secs),
             max_message_size: value_t_or_exit!(matches, "rpc_bigtable_max_message_size", usize),
+            local_storage_path: value_t!(matches, "rpc_local_ledger_storage", PathBuf).ok(),
         })
     } else {
         None
@@ -1347,6 +1391,15 @@ pub fn main() {
             enable_rpc_transaction_history: matches.is_present("enable_rpc_transaction_history"),
             enable_extended_tx_metadata_storage: matches.is_present("enable_cpi_and_log_storage")
                 || matches.is_present("enable_extended_tx_metadata_storage"),
+            transaction_logs_storage: matches.is_present("enable_transaction_logs_storage").then(
+                || TransactionLogsStorageConfig {
+                    bytes_limit: value_t!(matches, "transaction_logs_storage_bytes_limit", usize)
+                        .ok(),
+                    retention_slots: value_t!(matches, "transaction_logs_retention_slots", u64)
+                        .ok(),
+                },
+            ),
+            enable_program_signatures_index: matches.is_present("enable_program_signatures_index"),
             rpc_bigtable_config,
             faucet_addr: matches.value_of("rpc_faucet_addr").map(|address| {
                 solana_net_utils::parse_host_port(address).expect("failed to parse faucet address")
@@ -1373,6 +1426,7 @@ pub fn main() {
                 "rpc_max_request_body_size",
                 usize
             )),
+            max_batch_size: value_t!(matches, "rpc_max_batch_size", usize).ok(),
         },
         on_start_geyser_plugin_config_files,
         rpc_addrs: value_t!(matches, "rpc_port", u16).ok().map(|rpc_port| {
@@ -1672,6 +1726,8 @@ pub fn main() {
             exit(1);
         }
         validator_config.max_ledger_shreds = Some(limit_ledger_size);
+        validator_config.transaction_history_retention_slots =
+            value_t!(matches, "limit_transaction_history_slots", u64).ok();
     }
 
     configure_banking_trace_dir_byte_limit(&mut validator_config, &matches);
@@ -1690,17 +1746,21 @@ pub fn main() {
     validator_config.unified_scheduler_handler_threads =
         value_t!(matches, "unified_scheduler_handler_threads", usize).ok();
 
-    validator_config.ledger_column_options = LedgerColumnOptions {
-        compression_type: match matches.value_of("rocksdb_ledger_compression") {
-            None => BlockstoreCompressionType::default(),
-            Some(ledger_compression_string) => match ledger_compression_string {
-                "none" => BlockstoreCompressionType::None,
-                "snappy" => BlockstoreCompressionType::Snappy,
-                "lz4" => BlockstoreCompressionType::Lz4,
-                "zlib" => BlockstoreCompressionType::Zlib,
-                _ => panic!("Unsupported ledger_compression: {ledger_compression_string}"),
-            },
+    let compression_type_of = |name: &str| match matches.value_of(name) {
+        None => BlockstoreCompressionType::default(),
+        Some(ledger_compression_string) => match ledger_compression_string {
+            "none" => BlockstoreCompressionType::None,
+            "snappy" => BlockstoreCompressionType::Snappy,
+            "lz4" => BlockstoreCompressionType::Lz4,
+            "zlib" => BlockstoreCompressionType::Zlib,
+            _ => panic!("Unsupported ledger_compression: {ledger_compression_string}"),
         },
+    };
+    validator_config.ledger_column_options = LedgerColumnOptions {
+        compression_type: compression_type_of("rocksdb_ledger_compression"),
+        transaction_logs_compression_type: compression_type_of(
+            "rocksdb_transaction_logs_compression",
+        ),
         shred_storage_type: match matches.value_of("rocksdb_shred_compaction") {
             None => ShredStorageType::default(),
             Some(shred_compaction_string) => match shred_compaction_string {